@@ -2,7 +2,7 @@ use std::time::Duration;
 
 use criterion::*;
 
-use fast_qr::QRBuilder;
+use fast_qr::{Mask, Mode, QRBuilder, Template};
 
 fn bench_fastqr_qrcode(c: &mut Criterion) {
     let bytes: &[u8] = b"https://example.com/";
@@ -60,5 +60,46 @@ fn bench_fastqr_qrcode(c: &mut Criterion) {
     }
 }
 
-criterion_group!(benches, bench_fastqr_qrcode);
+fn bench_template(c: &mut Criterion) {
+    let ids: Vec<String> = (0..100u128)
+        .map(|i| (10_000_000_000_000_000_000 + i * 7_919).to_string())
+        .collect();
+
+    let mut group = c.benchmark_group("Numeric IDs V02M");
+    group.throughput(Throughput::Elements(ids.len() as u64));
+
+    group.bench_function("builder", |b| {
+        b.iter(|| {
+            for id in &ids {
+                black_box(
+                    QRBuilder::new(id.as_str())
+                        .mode(Mode::Numeric)
+                        .version(fast_qr::Version::V02)
+                        .ecl(fast_qr::ECL::M)
+                        .mask(Mask::Checkerboard)
+                        .build()
+                        .unwrap(),
+                );
+            }
+        })
+    });
+
+    let template = Template::new(
+        Mode::Numeric,
+        fast_qr::Version::V02,
+        fast_qr::ECL::M,
+        Mask::Checkerboard,
+    );
+    group.bench_function("template", |b| {
+        b.iter(|| {
+            for id in &ids {
+                black_box(template.build(id).unwrap());
+            }
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_fastqr_qrcode, bench_template);
 criterion_main!(benches);
@@ -0,0 +1,31 @@
+//! Numbered flyers sharing the same version and mask, built in parallel with
+//! the `rayon` feature:
+//!
+//! cargo run --example batch
+//! cargo run --features rayon --example batch
+
+fn main() {
+    use fast_qr::{QRBatchBuilder, ECL};
+
+    let urls: Vec<String> = (1..=20)
+        .map(|i| format!("https://example.com/flyer/{i}"))
+        .collect();
+
+    let mut batch = QRBatchBuilder::new(urls);
+    batch.ecl(ECL::M);
+
+    #[cfg(feature = "rayon")]
+    let qrcodes = batch.par_build().unwrap();
+    #[cfg(not(feature = "rayon"))]
+    let qrcodes = batch.build().unwrap();
+
+    for qrcode in &qrcodes {
+        assert_eq!(qrcode.size, qrcodes[0].size);
+    }
+    println!(
+        "{} codes, {:?} {:?}",
+        qrcodes.len(),
+        qrcodes[0].version,
+        qrcodes[0].mask
+    );
+}
@@ -0,0 +1,28 @@
+//! Every `QRBuilder` option, printed to the terminal:
+//!
+//! cargo run --example builder
+
+fn main() {
+    use fast_qr::{Mask, Mode, QRBuilder, Version, ECL};
+
+    // Smallest version fitting the input, best mask
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    println!("{:?} {:?} {:?}", qrcode.version, qrcode.ecl, qrcode.mask);
+
+    // Forced parameters
+    let qrcode = QRBuilder::new("HELLO WORLD")
+        .mode(Mode::Alphanumeric)
+        .ecl(ECL::H)
+        .version(Version::V03)
+        .mask(Mask::Checkerboard)
+        .build()
+        .unwrap();
+    qrcode.print();
+
+    // Too much data for the largest version allowed
+    let err = QRBuilder::new("https://example.com/".repeat(10))
+        .max_version(Version::V05)
+        .build()
+        .unwrap_err();
+    println!("{err}");
+}
@@ -0,0 +1,23 @@
+//! Encodes then decodes a matrix, as a scanner would once the code is
+//! located:
+//!
+//! cargo run --example decode -- "Hello World"
+
+fn main() {
+    use fast_qr::{decode::decode, QRBuilder};
+
+    let input = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| String::from("https://example.com/"));
+    let qrcode = QRBuilder::new(input.as_str()).build().unwrap();
+
+    let decoded = decode(&qrcode).unwrap();
+    assert_eq!(decoded.data, input.as_bytes());
+    println!(
+        "{:?} {:?} {:?}: {}",
+        decoded.version,
+        decoded.ecl,
+        decoded.mask,
+        String::from_utf8_lossy(&decoded.data)
+    );
+}
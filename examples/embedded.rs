@@ -0,0 +1,69 @@
+//! Draws a code on a 128x64 monochrome framebuffer, as found on SSD1306
+//! displays, then prints the framebuffer:
+//!
+//! cargo run --features embedded-graphics --example embedded
+
+use core::convert::Infallible;
+
+use embedded_graphics_core::draw_target::DrawTarget;
+use embedded_graphics_core::geometry::{Dimensions, OriginDimensions, Point, Size};
+use embedded_graphics_core::pixelcolor::BinaryColor;
+use embedded_graphics_core::{Drawable, Pixel};
+use fast_qr::embedded::QRDrawable;
+use fast_qr::QRBuilder;
+
+struct Framebuffer {
+    pixels: [[bool; 128]; 64],
+}
+
+impl OriginDimensions for Framebuffer {
+    fn size(&self) -> Size {
+        Size::new(128, 64)
+    }
+}
+
+impl DrawTarget for Framebuffer {
+    type Color = BinaryColor;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            if self.bounding_box().contains(point) {
+                self.pixels[point.y as usize][point.x as usize] = color.is_on();
+            }
+        }
+        Ok(())
+    }
+}
+
+fn main() {
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    let mut framebuffer = Framebuffer {
+        pixels: [[false; 128]; 64],
+    };
+
+    // 25 modules + 2 of margin, twice as large: 54 pixels
+    QRDrawable::new(&qrcode, BinaryColor::On, BinaryColor::Off)
+        .top_left(Point::new(37, 4))
+        .scale(2)
+        .margin(1)
+        .draw(&mut framebuffer)
+        .unwrap();
+
+    for row in framebuffer.pixels.chunks(2) {
+        let line: String = row[0]
+            .iter()
+            .zip(row[1].iter())
+            .map(|(&top, &bottom)| match (top, bottom) {
+                (true, true) => '█',
+                (true, false) => '▀',
+                (false, true) => '▄',
+                (false, false) => ' ',
+            })
+            .collect();
+        println!("{line}");
+    }
+}
@@ -0,0 +1,35 @@
+//! Sizes a logo with the overlay planner so the code stays decodable, writes
+//! `logo.svg`:
+//!
+//! cargo run --features svg --example logo
+
+fn main() {
+    use fast_qr::{
+        convert::{svg::SvgBuilder, Builder, ImageBackgroundShape, Shape},
+        overlay::OverlayPlanner,
+        QRBuilder,
+    };
+
+    let qrcode = QRBuilder::new("https://example.com/")
+        .image_embedding()
+        .build()
+        .unwrap();
+
+    let (version, ecl) = (qrcode.version.unwrap(), qrcode.ecl.unwrap());
+    let overlay = OverlayPlanner::new(version, ecl)
+        .verify(true)
+        .plan()
+        .unwrap();
+    println!(
+        "{version:?} {ecl:?}: logo up to {}x{} modules",
+        overlay.width, overlay.height
+    );
+
+    SvgBuilder::default()
+        .shape(Shape::RoundedSquare)
+        .image(String::from("./assets/example.com.svg"))
+        .image_size(overlay.width.min(overlay.height) as f64)
+        .image_background_shape(ImageBackgroundShape::RoundedSquare)
+        .to_file(&qrcode, "logo.svg")
+        .unwrap();
+}
@@ -0,0 +1,26 @@
+//! Calls every function checked by `fast_qr_no_panic`, fails to link if one
+//! of them may panic:
+//!
+//! RUSTFLAGS="--cfg fast_qr_no_panic" cargo run --profile no-panic --example no_panic
+
+fn main() {
+    use fast_qr::{Mode, ModuleType, QRBuilder};
+
+    let input = std::env::args().nth(1).unwrap_or_default();
+    for mode in [Mode::Numeric, Mode::Alphanumeric, Mode::Byte] {
+        println!("{mode:?}: {}", mode.can_encode(input.as_bytes()));
+    }
+
+    let qrcode = match QRBuilder::new(input).build() {
+        Ok(qrcode) => qrcode,
+        Err(err) => return println!("{err}"),
+    };
+    let position = std::env::args().count() * 7;
+    if let Some(module) = qrcode.try_get(position, position) {
+        println!(
+            "{:?} {:?}",
+            module.module_type(),
+            ModuleType::from(module.0 >> 1)
+        );
+    }
+}
@@ -0,0 +1,28 @@
+//! Brand colors: a gradient on data modules, rounded finder patterns in
+//! their own color, writes `svg_theme.svg`:
+//!
+//! cargo run --features svg --example svg_theme
+
+fn main() {
+    use fast_qr::{
+        convert::{svg::SvgBuilder, Builder, Gradient, Shape},
+        ModuleType, QRBuilder, ECL,
+    };
+
+    let qrcode = QRBuilder::new("https://example.com/")
+        .ecl(ECL::Q)
+        .build()
+        .unwrap();
+
+    SvgBuilder::default()
+        .shape(Shape::Circle)
+        .gradient(Gradient::Linear {
+            start: "#7B2FF7".into(),
+            end: "#F107A3".into(),
+            angle: 45.0,
+        })
+        .module_shape_color(ModuleType::FinderPattern, Shape::RoundedSquare, "#2B1055")
+        .background_color("#FFF8F0")
+        .to_file(&qrcode, "svg_theme.svg")
+        .unwrap();
+}
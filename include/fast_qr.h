@@ -0,0 +1,158 @@
+/* Generated with cbindgen --config cbindgen.toml --output include/fast_qr.h */
+
+#ifndef FAST_QR_H
+#define FAST_QR_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+/**
+ * Highest ABI version implemented, see [`fastqr_v1_abi_version`]
+ */
+#define FAST_QR_ABI_VERSION 1
+
+/**
+ * Success
+ */
+#define FAST_QR_OK 0
+
+/**
+ * A pointer argument is null
+ */
+#define FAST_QR_NULL_POINTER -1
+
+/**
+ * `ecl` is not one of the `FAST_QR_ECL_*` values
+ */
+#define FAST_QR_INVALID_ECL -2
+
+/**
+ * The content does not fit in a version 40 code
+ */
+#define FAST_QR_DATA_TOO_BIG -3
+
+/**
+ * `out_buf` is too small, `*out_size` holds the required length
+ */
+#define FAST_QR_BUFFER_TOO_SMALL -4
+
+/**
+ * The encoder refused the content for another reason than its size
+ */
+#define FAST_QR_INVALID_INPUT -5
+
+/**
+ * Low error correction, 7%
+ */
+#define FAST_QR_ECL_L 0
+
+/**
+ * Medium error correction, 15%
+ */
+#define FAST_QR_ECL_M 1
+
+/**
+ * Quartile error correction, 25%
+ */
+#define FAST_QR_ECL_Q 2
+
+/**
+ * High error correction, 30%
+ */
+#define FAST_QR_ECL_H 3
+
+/**
+ * Bytes needed by the largest code, version 40 (177 x 177)
+ */
+#define FAST_QR_MAX_MODULES (177 * 177)
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Returns [`FAST_QR_ABI_VERSION`], the highest `fastqr_vN_` prefix the
+ * library exports
+ */
+uint32_t fastqr_v1_abi_version(void);
+
+/**
+ * Encodes `len` bytes of `content` and writes its modules to `out_buf`, row
+ * by row, `1` for dark and `0` for light. `len` may be 0, giving a version 1
+ * code holding no data.
+ *
+ * On input, `*out_size` is the length of `out_buf` in bytes. On success it
+ * is set to the number of modules per side, `out_buf` then holds
+ * `*out_size * *out_size` bytes. [`FAST_QR_MAX_MODULES`] bytes fit any code.
+ *
+ * Returns [`FAST_QR_OK`] or a negative error code. With
+ * [`FAST_QR_BUFFER_TOO_SMALL`], `*out_size` is set to the required length
+ * in bytes and nothing is written.
+ *
+ * # Safety
+ * - `content` must be valid for reads of `len` bytes, it may be null if `len` is 0
+ * - `out_size` must be valid for reads and writes
+ * - `out_buf` must be valid for writes of `*out_size` bytes
+ */
+int32_t fastqr_v1_generate(const uint8_t *content,
+                           size_t len,
+                           uint8_t ecl,
+                           uint8_t *out_buf,
+                           size_t *out_size);
+
+#if defined(FAST_QR_SVG)
+/**
+ * Encodes `len` bytes of `content` and writes it as a NUL-terminated SVG
+ * document to `out_buf`, with the default [`SvgBuilder`](crate::convert::svg::SvgBuilder) options.
+ * `len` may be 0, giving a version 1 code holding no data.
+ *
+ * On input, `*out_size` is the length of `out_buf` in bytes. On success it
+ * is set to the length of the document, NUL excluded.
+ *
+ * Returns [`FAST_QR_OK`] or a negative error code. With
+ * [`FAST_QR_BUFFER_TOO_SMALL`], `*out_size` is set to the required length
+ * in bytes, NUL included, and nothing is written.
+ *
+ * # Safety
+ * - `content` must be valid for reads of `len` bytes, it may be null if `len` is 0
+ * - `out_size` must be valid for reads and writes
+ * - `out_buf` must be valid for writes of `*out_size` bytes
+ */
+int32_t fastqr_v1_generate_svg(const uint8_t *content,
+                               size_t len,
+                               uint8_t ecl,
+                               char *out_buf,
+                               size_t *out_size);
+#endif
+
+/**
+ * Unversioned name of [`fastqr_v1_generate`], from before the ABI was versioned
+ *
+ * # Safety
+ * See [`fastqr_v1_generate`]
+ */
+int32_t fast_qr_generate(const uint8_t *content,
+                         size_t len,
+                         uint8_t ecl,
+                         uint8_t *out_buf,
+                         size_t *out_size);
+
+#if defined(FAST_QR_SVG)
+/**
+ * Unversioned name of [`fastqr_v1_generate_svg`], from before the ABI was versioned
+ *
+ * # Safety
+ * See [`fastqr_v1_generate_svg`]
+ */
+int32_t fast_qr_generate_svg(const uint8_t *content,
+                             size_t len,
+                             uint8_t ecl,
+                             char *out_buf,
+                             size_t *out_size);
+#endif
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* FAST_QR_H */
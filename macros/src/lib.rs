@@ -0,0 +1,163 @@
+//! Generates [`fast_qr`] QR codes at compile time, for static site generators
+//! and embedded UIs: the encoder runs in the compiler, the binary only holds
+//! the result.
+//!
+//! - [`qr!`] expands to a `&'static str` SVG
+//! - [`qr_matrix!`] expands to a [`fast_qr::baked::BakedQR`], usable in a `const`
+//!
+//! Both take the content as a string literal, then optional `ecl = L|M|Q|H`
+//! and `version = 1..=40`. Content that does not fit is a compile error.
+//!
+//! ```rust
+//! use fast_qr::baked::BakedQR;
+//! use fast_qr_macros::{qr, qr_matrix};
+//!
+//! const LOGO: &str = qr!("https://example.com/", ecl = H, margin = 2);
+//! const MATRIX: BakedQR = qr_matrix!("https://example.com/", version = 3);
+//!
+//! assert!(LOGO.starts_with("<svg"));
+//! assert_eq!(MATRIX.size(), 29);
+//! ```
+//!
+//! This crate depends on `fast_qr` to run the encoder, add both crates to
+//! `Cargo.toml`: `fast_qr` cannot re-export macros built on top of itself.
+
+#![deny(unsafe_code)]
+#![warn(missing_docs)]
+
+use fast_qr::convert::svg::SvgBuilder;
+use fast_qr::convert::Builder;
+use fast_qr::{QRBuilder, QRCode, Version, ECL};
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::{parse_macro_input, Error, Ident, LitInt, LitStr, Token};
+
+/// Content and options of a macro call
+struct Args {
+    content: LitStr,
+    ecl: Option<ECL>,
+    version: Option<Version>,
+    /// Only read by `qr!`, with its span to reject it elsewhere
+    margin: Option<(usize, proc_macro2::Span)>,
+}
+
+impl Parse for Args {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut args = Args {
+            content: input.parse()?,
+            ecl: None,
+            version: None,
+            margin: None,
+        };
+
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            // Trailing comma
+            if input.is_empty() {
+                break;
+            }
+
+            let key: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            match key.to_string().as_str() {
+                "ecl" => {
+                    let value: Ident = input.parse()?;
+                    args.ecl = Some(match value.to_string().as_str() {
+                        "L" => ECL::L,
+                        "M" => ECL::M,
+                        "Q" => ECL::Q,
+                        "H" => ECL::H,
+                        _ => return Err(Error::new(value.span(), "expected L, M, Q or H")),
+                    });
+                }
+                "version" => {
+                    let value: LitInt = input.parse()?;
+                    let version = Version::from_number(value.base10_parse()?).ok_or_else(|| {
+                        Error::new(value.span(), "expected a version from 1 to 40")
+                    })?;
+                    args.version = Some(version);
+                }
+                "margin" => {
+                    let value: LitInt = input.parse()?;
+                    args.margin = Some((value.base10_parse()?, value.span()));
+                }
+                _ => {
+                    return Err(Error::new(
+                        key.span(),
+                        "unknown option, expected ecl, version or margin",
+                    ))
+                }
+            }
+        }
+
+        Ok(args)
+    }
+}
+
+impl Args {
+    /// Runs the encoder, errors point at the content
+    fn build(&self) -> syn::Result<QRCode> {
+        let mut builder = QRBuilder::new(self.content.value());
+        if let Some(ecl) = self.ecl {
+            builder.ecl(ecl);
+        }
+        if let Some(version) = self.version {
+            builder.version(version);
+        }
+        builder
+            .build()
+            .map_err(|err| Error::new(self.content.span(), err))
+    }
+}
+
+/// Expands to the SVG of a QR code, as a `&'static str`
+///
+/// Also takes `margin = N`, in modules (default: 4).
+///
+/// ```rust
+/// const SVG: &str = fast_qr_macros::qr!("https://example.com/");
+/// ```
+#[proc_macro]
+pub fn qr(input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(input as Args);
+    let qrcode = match args.build() {
+        Ok(qrcode) => qrcode,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    let mut builder = SvgBuilder::default();
+    if let Some((margin, _)) = args.margin {
+        builder.margin(margin);
+    }
+    let svg = builder.to_str(&qrcode);
+
+    quote!(#svg).into()
+}
+
+/// Expands to a [`fast_qr::baked::BakedQR`] expression, one bit per module
+///
+/// ```rust
+/// use fast_qr::baked::BakedQR;
+///
+/// const QR: BakedQR = fast_qr_macros::qr_matrix!("https://example.com/", ecl = L);
+/// assert!(QR.get(0, 0));
+/// ```
+#[proc_macro]
+pub fn qr_matrix(input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(input as Args);
+    if let Some((_, span)) = args.margin {
+        return Error::new(span, "margin only applies to qr!")
+            .to_compile_error()
+            .into();
+    }
+    let qrcode = match args.build() {
+        Ok(qrcode) => qrcode,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    let size = qrcode.size;
+    let bits = fast_qr::baked::pack(&qrcode);
+
+    quote!(::fast_qr::baked::BakedQR::new(#size, &[#(#bits),*])).into()
+}
@@ -0,0 +1,46 @@
+use fast_qr::baked::BakedQR;
+use fast_qr::convert::svg::SvgBuilder;
+use fast_qr::convert::Builder;
+use fast_qr::{QRBuilder, Version, ECL};
+use fast_qr_macros::{qr, qr_matrix};
+
+#[test]
+fn svg_matches_runtime() {
+    let qrcode = QRBuilder::new("https://example.com/")
+        .ecl(ECL::H)
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        qr!("https://example.com/", ecl = H),
+        SvgBuilder::default().to_str(&qrcode)
+    );
+    assert_eq!(
+        qr!("https://example.com/", ecl = H, margin = 1,),
+        SvgBuilder::default().margin(1).to_str(&qrcode)
+    );
+}
+
+#[test]
+fn matrix_matches_runtime() {
+    const QR: BakedQR = qr_matrix!("Hello", version = 2, ecl = L);
+
+    let qrcode = QRBuilder::new("Hello")
+        .version(Version::V02)
+        .ecl(ECL::L)
+        .build()
+        .unwrap();
+    assert_eq!(QR.size(), qrcode.size);
+    for y in 0..qrcode.size {
+        for x in 0..qrcode.size {
+            assert_eq!(QR.get(y, x), qrcode[y][x].value());
+        }
+    }
+}
+
+#[test]
+fn escaped_content() {
+    let qrcode = QRBuilder::new("a\"b\n").build().unwrap();
+    assert_eq!(qr!("a\"b\n"), SvgBuilder::default().to_str(&qrcode));
+    assert_eq!(qr!(r#"a"b"#), qr!("a\"b"));
+}
@@ -0,0 +1,610 @@
+//! Codes computed ahead of time and stored in a `const`, for firmware keeping
+//! a fixed [`QRCode`](crate::QRCode) in flash
+//!
+//! [`generate_const`] runs the whole pipeline (encoding, error correction,
+//! placement and masking) in const context. It passes its buffers by value,
+//! as `&mut` references are only allowed in `const fn` from Rust 1.83, above
+//! the MSRV of this crate. The [`qr_const!`](crate::qr_const) macro wraps it
+//! in a [`BakedQR`]:
+//!
+//! ```rust
+//! use fast_qr::baked::BakedQR;
+//! use fast_qr::{qr_const, Mask, Mode, QRBuilder, Version, ECL};
+//!
+//! const QR: BakedQR = qr_const!(
+//!     b"https://example.com/",
+//!     Mode::Byte,
+//!     Version::V02,
+//!     ECL::M,
+//!     Mask::Checkerboard
+//! );
+//!
+//! let qrcode = QRBuilder::new("https://example.com/")
+//!     .mode(Mode::Byte)
+//!     .version(Version::V02)
+//!     .ecl(ECL::M)
+//!     .mask(Mask::Checkerboard)
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(QR.bits(), &fast_qr::baked::pack(&qrcode)[..]);
+//! ```
+//!
+//! The mode, version, ECL and mask are given, as masks are not scored in
+//! const context. Kanji input is Shift JIS, UTF-8 text is not converted.
+//!
+//! [`to_rust`] writes a code as Rust source declaring a [`BakedQR`], for the
+//! `fast_qr` command with `--output rust`. The `fast_qr_macros` crate picks
+//! the mode, version and mask like [`QRBuilder`](crate::QRBuilder), in a single
+//! macro call: `const QR: BakedQR = qr_matrix!("https://example.com/");`.
+//!
+//! Modules are stored on one bit each, their
+//! [`ModuleType`](crate::ModuleType) is not kept: renderers see data modules
+//! only.
+
+#![deny(unsafe_code)]
+#![warn(missing_docs)]
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use crate::datamasking::Mask;
+use crate::encode::{self, Mode};
+use crate::galois::{self, MAX_ECC_CODEWORDS};
+use crate::matrix::Matrix;
+use crate::{hardcode, Module, Version, ECL};
+
+/// Matrix stored in a `&'static` slice, one bit per module, row-major, the
+/// first module in the most significant bit
+///
+/// ```rust
+/// use fast_qr::baked::BakedQR;
+///
+/// // 3x3 checkerboard
+/// const TINY: BakedQR = BakedQR::new(3, &[0b1010_1010, 0b1000_0000]);
+/// const CORNER: bool = TINY.get(0, 0);
+///
+/// assert!(CORNER);
+/// assert!(!TINY.get(0, 1));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BakedQR {
+    size: usize,
+    bits: &'static [u8],
+}
+
+impl BakedQR {
+    /// Creates a matrix of `size` x `size` modules from packed `bits`
+    ///
+    /// # Panics
+    /// If `bits` does not hold exactly `size` x `size` modules, at compile
+    /// time in a `const`
+    #[must_use]
+    pub const fn new(size: usize, bits: &'static [u8]) -> Self {
+        assert!(
+            bits.len() == (size * size + 7) / 8,
+            "bits do not hold size x size modules"
+        );
+        BakedQR { size, bits }
+    }
+
+    /// Returns the number of modules per side
+    #[must_use]
+    pub const fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the packed modules
+    #[must_use]
+    pub const fn bits(&self) -> &'static [u8] {
+        self.bits
+    }
+
+    /// Returns whether the module at row `y`, column `x` is dark, modules
+    /// outside of the matrix are light
+    #[must_use]
+    pub const fn get(&self, y: usize, x: usize) -> bool {
+        if y >= self.size || x >= self.size {
+            return false;
+        }
+        let index = y * self.size + x;
+        self.bits[index / 8] & (0x80 >> (index % 8)) != 0
+    }
+}
+
+impl Matrix for BakedQR {
+    fn size(&self) -> usize {
+        self.size
+    }
+
+    fn module(&self, y: usize, x: usize) -> Module {
+        Module::data(self.get(y, x))
+    }
+}
+
+/// Packs the modules of `matrix` as expected by [`BakedQR::new`]
+pub fn pack<M: Matrix>(matrix: &M) -> Vec<u8> {
+    let n = matrix.size();
+    let mut bits = alloc::vec![0; (n * n + 7) / 8];
+    for y in 0..n {
+        for x in (0..n).filter(|&x| matrix.module(y, x).value()) {
+            let index = y * n + x;
+            bits[index / 8] |= 0x80 >> (index % 8);
+        }
+    }
+    bits
+}
+
+/// Returns Rust source declaring `matrix` as a public [`BakedQR`] constant
+/// named `name`
+pub fn to_rust<M: Matrix>(matrix: &M, name: &str) -> String {
+    let bits = pack(matrix);
+
+    let mut out = String::with_capacity(bits.len() * 6 + 128);
+    let _ = writeln!(
+        out,
+        "/// Generated by fast_qr, {0}x{0} modules",
+        matrix.size()
+    );
+    let _ = writeln!(
+        out,
+        "pub const {name}: fast_qr::baked::BakedQR = fast_qr::baked::BakedQR::new({}, &[",
+        matrix.size()
+    );
+    for line in bits.chunks(12) {
+        out.push_str("   ");
+        for byte in line {
+            let _ = write!(out, " 0x{byte:02X},");
+        }
+        out.push('\n');
+    }
+    out.push_str("]);\n");
+    out
+}
+
+/// Data codewords of a version 40-L, the most of any version / ECL
+const MAX_DATA_CODEWORDS: usize = 2956;
+
+/// Codewords of a version 40
+const MAX_CODEWORDS: usize = 3706;
+
+/// Blocks of a version 40-H, the most of any version / ECL
+const MAX_BLOCKS: usize = 81;
+
+/// Modules per side of a version 40
+const MAX_SIZE: usize = 177;
+
+/// No alignment pattern around a row or column
+const NO_ALIGNMENT: u8 = u8::MAX;
+
+/// Returns the number of bytes packing the modules of `version`, the length
+/// of the array returned by [`generate_const`]
+#[must_use]
+pub const fn packed_len(version: Version) -> usize {
+    let size = version.size();
+    (size * size + 7) / 8
+}
+
+/// Expands to a [`BakedQR`](crate::baked::BakedQR) of `input` computed at
+/// compile time, see [`generate_const`](crate::baked::generate_const)
+///
+/// ```rust
+/// use fast_qr::baked::BakedQR;
+/// use fast_qr::{qr_const, Mask, Mode, Version, ECL};
+///
+/// const QR: BakedQR = qr_const!(b"HELLO WORLD", Mode::Alphanumeric, Version::V01, ECL::M, Mask::Fields);
+/// assert_eq!(QR.size(), 21);
+/// ```
+#[macro_export]
+macro_rules! qr_const {
+    ($input:expr, $mode:expr, $version:expr, $ecl:expr, $mask:expr $(,)?) => {{
+        const BITS: [u8; $crate::baked::packed_len($version)] =
+            $crate::baked::generate_const($input, $mode, $version, $ecl, $mask);
+        $crate::baked::BakedQR::new($version.size(), &BITS)
+    }};
+}
+
+/// Appends the `count` low bits of `value` to `data`, holding `len` bits
+macro_rules! push_bits {
+    ($data:ident, $len:ident, $value:expr, $count:expr) => {{
+        let value = $value;
+        let mut bit = $count;
+        while bit > 0 {
+            bit -= 1;
+            if (value >> bit) & 1 == 1 {
+                $data[$len / 8] |= 0x80 >> ($len % 8);
+            }
+            $len += 1;
+        }
+    }};
+}
+
+/// Computes the modules of `input` in `mode`, `version`, `ecl` and `mask`,
+/// packed as expected by [`BakedQR::new`]. Evaluable at compile time, the
+/// modules are the ones of a [`QRBuilder`](crate::QRBuilder) given the same
+/// parameters.
+///
+/// `N` is [`packed_len(version)`](packed_len). Kanji `input` is Shift JIS.
+///
+/// ```rust
+/// use fast_qr::baked::{generate_const, packed_len, BakedQR};
+/// use fast_qr::{Mask, Mode, Version, ECL};
+///
+/// const BITS: [u8; packed_len(Version::V01)] =
+///     generate_const(b"01234567", Mode::Numeric, Version::V01, ECL::H, Mask::Meadow);
+/// const QR: BakedQR = BakedQR::new(21, &BITS);
+///
+/// // Top left finder pattern
+/// assert!(QR.get(0, 0) && !QR.get(1, 1) && QR.get(3, 3));
+/// ```
+///
+/// # Panics
+/// At compile time in a `const`, if `N` does not match the version, if `mode`
+/// cannot encode `input` or if `input` does not fit the version
+#[must_use]
+pub const fn generate_const<const N: usize>(
+    input: &[u8],
+    mode: Mode,
+    version: Version,
+    ecl: ECL,
+    mask: Mask,
+) -> [u8; N] {
+    assert!(N == packed_len(version), "N is not packed_len(version)");
+
+    let data = data_codewords(input, mode, version, ecl);
+    let codewords = codewords(&data, version, ecl);
+    let codeword_bits = version.max_bytes() * 8;
+
+    let size = version.size();
+    let near = alignment_rows(version);
+    let format = hardcode::ecm_to_format_information(ecl, mask);
+    let mut bits = [0; N];
+
+    let mut y = 0;
+    while y < size {
+        let mut x = 0;
+        while x < size {
+            if let Some(true) = function_module(version, format, &near, y, x) {
+                let index = y * size + x;
+                bits[index / 8] |= 0x80 >> (index % 8);
+            }
+            x += 1;
+        }
+        y += 1;
+    }
+
+    // Codewords go up and down two columns at a time from the right,
+    // skipping the vertical timing pattern
+    let mut right = size - 1;
+    let mut upward = true;
+    let mut i = 0;
+    loop {
+        let mut step = 0;
+        while step < size {
+            let y = if upward { size - 1 - step } else { step };
+            let mut x = right + 1;
+            while x > right - 1 {
+                x -= 1;
+                if function_module(version, format, &near, y, x).is_some() {
+                    continue;
+                }
+                // Remainder bits past the last codeword are light
+                let bit = i < codeword_bits && codewords[i / 8] & (0x80 >> (i % 8)) != 0;
+                i += 1;
+                if bit != mask_bit(mask, y, x) {
+                    let index = y * size + x;
+                    bits[index / 8] |= 0x80 >> (index % 8);
+                }
+            }
+            step += 1;
+        }
+
+        if right == 1 {
+            break;
+        }
+        upward = !upward;
+        right -= 2;
+        if right == 6 {
+            right = 5;
+        }
+    }
+
+    bits
+}
+
+/// Encodes `input` on the data codewords of `version` and `ecl`, padded
+const fn data_codewords(
+    input: &[u8],
+    mode: Mode,
+    version: Version,
+    ecl: ECL,
+) -> [u8; MAX_DATA_CODEWORDS] {
+    let (indicator, count, payload_bits) = match mode {
+        Mode::Numeric => (
+            0b0001,
+            input.len(),
+            input.len() / 3 * 10 + [0, 4, 7][input.len() % 3],
+        ),
+        Mode::Alphanumeric => (
+            0b0010,
+            input.len(),
+            input.len() / 2 * 11 + input.len() % 2 * 6,
+        ),
+        Mode::Byte => (0b0100, input.len(), input.len() * 8),
+        Mode::Kanji => (0b1000, input.len() / 2, input.len() / 2 * 13),
+    };
+    let cci_bits = hardcode::cci_bits(version, mode);
+    let data_bits = hardcode::data_bits(version, ecl);
+    assert!(
+        4 + cci_bits + payload_bits <= data_bits,
+        "input does not fit the version"
+    );
+
+    let mut data = [0; MAX_DATA_CODEWORDS];
+    let mut len = 0;
+    push_bits!(data, len, indicator, 4);
+    push_bits!(data, len, count, cci_bits);
+
+    let mut i = 0;
+    while i < input.len() {
+        match mode {
+            Mode::Numeric => {
+                let mut number = 0;
+                let mut digits = 0;
+                while digits < 3 && i < input.len() {
+                    assert!(input[i].is_ascii_digit(), "input is not numeric");
+                    number = number * 10 + (input[i] - b'0') as usize;
+                    digits += 1;
+                    i += 1;
+                }
+                push_bits!(data, len, number, digits * 3 + 1);
+            }
+            Mode::Alphanumeric if i + 1 < input.len() => {
+                let pair = alphanumeric_value(input[i]) * 45 + alphanumeric_value(input[i + 1]);
+                push_bits!(data, len, pair, 11);
+                i += 2;
+            }
+            Mode::Alphanumeric => {
+                push_bits!(data, len, alphanumeric_value(input[i]), 6);
+                i += 1;
+            }
+            Mode::Byte => {
+                push_bits!(data, len, input[i], 8);
+                i += 1;
+            }
+            Mode::Kanji => {
+                assert!(i + 1 < input.len(), "input is not Shift JIS");
+                let value = match encode::kanji_value(u16::from_be_bytes([input[i], input[i + 1]]))
+                {
+                    Some(value) => value,
+                    None => panic!("input is not Shift JIS"),
+                };
+                push_bits!(data, len, value, 13);
+                i += 2;
+            }
+        }
+    }
+
+    // Terminator, then zeros up to the next codeword
+    len += if data_bits - len < 4 {
+        data_bits - len
+    } else {
+        4
+    };
+    len = (len + 7) / 8 * 8;
+
+    let mut pad = len / 8;
+    while pad < hardcode::data_codewords(version, ecl) {
+        data[pad] = if (pad - len / 8) % 2 == 0 { 0xEC } else { 0x11 };
+        pad += 1;
+    }
+
+    data
+}
+
+/// Splits `data` in blocks and interleaves them, followed by their error
+/// correction codewords
+const fn codewords(
+    data: &[u8; MAX_DATA_CODEWORDS],
+    version: Version,
+    ecl: ECL,
+) -> [u8; MAX_CODEWORDS] {
+    let [(g1_count, g1_size), (g2_count, g2_size)] = hardcode::ecc_to_groups(ecl, version);
+    let blocks = g1_count + g2_count;
+    let ecc_len = galois::ecc_codewords(version, ecl);
+
+    let mut ecc = [[0; MAX_ECC_CODEWORDS]; MAX_BLOCKS];
+    let mut block = 0;
+    while block < blocks {
+        let (start, size) = block_range(block, g1_count, g1_size, g2_size);
+        ecc[block] = galois::remainder_of(data, start, size, version, ecl);
+        block += 1;
+    }
+
+    let mut codewords = [0; MAX_CODEWORDS];
+    let mut len = 0;
+    // Group 2 blocks are one codeword longer
+    let longest = if g2_count > 0 { g2_size } else { g1_size };
+    let mut i = 0;
+    while i < longest {
+        let mut block = 0;
+        while block < blocks {
+            let (start, size) = block_range(block, g1_count, g1_size, g2_size);
+            if i < size {
+                codewords[len] = data[start + i];
+                len += 1;
+            }
+            block += 1;
+        }
+        i += 1;
+    }
+
+    let mut i = 0;
+    while i < ecc_len {
+        let mut block = 0;
+        while block < blocks {
+            codewords[len] = ecc[block][i];
+            len += 1;
+            block += 1;
+        }
+        i += 1;
+    }
+
+    codewords
+}
+
+/// Returns the start and length in the data codewords of block `block`,
+/// group 2 blocks come after group 1 blocks
+const fn block_range(
+    block: usize,
+    g1_count: usize,
+    g1_size: usize,
+    g2_size: usize,
+) -> (usize, usize) {
+    if block < g1_count {
+        (block * g1_size, g1_size)
+    } else {
+        (g1_count * g1_size + (block - g1_count) * g2_size, g2_size)
+    }
+}
+
+/// Returns the value of an alphanumeric character, referring to 7.1 of the spec
+const fn alphanumeric_value(c: u8) -> usize {
+    match c {
+        b'0'..=b'9' => (c - b'0') as usize,
+        b'A'..=b'Z' => (c - b'A') as usize + 10,
+        b' ' => 36,
+        b'$' => 37,
+        b'%' => 38,
+        b'*' => 39,
+        b'+' => 40,
+        b'-' => 41,
+        b'.' => 42,
+        b'/' => 43,
+        b':' => 44,
+        _ => panic!("input is not alphanumeric"),
+    }
+}
+
+/// Returns, for each row or column, the index in
+/// [`Version::alignment_positions`] of the alignment patterns covering it
+const fn alignment_rows(version: Version) -> [u8; MAX_SIZE] {
+    let positions = version.alignment_positions();
+    let mut near = [NO_ALIGNMENT; MAX_SIZE];
+    let mut i = 0;
+    while i < positions.len() {
+        let mut row = positions[i] - 2;
+        while row <= positions[i] + 2 {
+            near[row] = i as u8;
+            row += 1;
+        }
+        i += 1;
+    }
+    near
+}
+
+/// Returns the value of the function module at row `y`, column `x`, `None`
+/// for data modules. Follows `default::create_matrix`, where later patterns
+/// overwrite earlier ones.
+const fn function_module(
+    version: Version,
+    format: u16,
+    near: &[u8; MAX_SIZE],
+    y: usize,
+    x: usize,
+) -> Option<bool> {
+    let n = version.size();
+
+    // Finder patterns with their separators and the format information
+    if y < 9 && x < 9 {
+        return Some(match (y, x) {
+            (6, 8) | (8, 6) => true,
+            (8, 8) => format & (1 << 7) != 0,
+            (8, 7) => format & (1 << 8) != 0,
+            (7, 8) => format & (1 << 6) != 0,
+            (8, _) => format & (1 << (14 - x)) != 0,
+            (_, 8) => format & (1 << y) != 0,
+            (7, _) | (_, 7) => false,
+            _ => ring(y, x, 3, 3) != 2,
+        });
+    }
+    if y < 9 && x >= n - 8 {
+        return Some(match y {
+            8 => format & (1 << (n - 1 - x)) != 0,
+            7 => false,
+            _ => x != n - 8 && ring(y, x, 3, n - 4) != 2,
+        });
+    }
+    if y >= n - 8 && x < 9 {
+        return Some(match x {
+            8 if y == n - 8 => true,
+            8 => format & (1 << (y + 15 - n)) != 0,
+            7 => false,
+            _ => y != n - 8 && ring(y, x, n - 4, 3) != 2,
+        });
+    }
+
+    // Version information
+    if version as usize >= Version::V07 as usize {
+        let info = hardcode::VERSION_INFORMATION[version as usize];
+        if y < 6 && x >= n - 11 && x < n - 8 {
+            return Some(info & (1 << (y * 3 + x - (n - 11))) != 0);
+        }
+        if x < 6 && y >= n - 11 && y < n - 8 {
+            return Some(info & (1 << (x * 3 + y - (n - 11))) != 0);
+        }
+    }
+
+    // Alignment patterns, except the ones on finder patterns
+    let positions = version.alignment_positions();
+    let (i, j) = (near[y], near[x]);
+    let last = positions.len().saturating_sub(1) as u8;
+    let on_finder = (i == 0 && (j == 0 || j == last)) || (i == last && j == 0);
+    if i != NO_ALIGNMENT && j != NO_ALIGNMENT && !on_finder {
+        return Some(ring(y, x, positions[i as usize], positions[j as usize]) != 1);
+    }
+
+    // Timing patterns
+    if y == 6 {
+        return Some(x % 2 == 0);
+    }
+    if x == 6 {
+        return Some(y % 2 == 0);
+    }
+
+    None
+}
+
+/// Returns the ring of the square centered on row `center_y`, column
+/// `center_x` holding the module at row `y`, column `x`, 0 for the center
+const fn ring(y: usize, x: usize, center_y: usize, center_x: usize) -> usize {
+    let dy = if y > center_y {
+        y - center_y
+    } else {
+        center_y - y
+    };
+    let dx = if x > center_x {
+        x - center_x
+    } else {
+        center_x - x
+    };
+    if dy > dx {
+        dy
+    } else {
+        dx
+    }
+}
+
+/// Returns whether mask `mask` flips the data module at row `y`, column `x`
+const fn mask_bit(mask: Mask, y: usize, x: usize) -> bool {
+    match mask {
+        Mask::Checkerboard => (y + x) % 2 == 0,
+        Mask::HorizontalLines => y % 2 == 0,
+        Mask::VerticalLines => x % 3 == 0,
+        Mask::DiagonalLines => (y + x) % 3 == 0,
+        Mask::LargeCheckerboard => (y / 2 + x / 3) % 2 == 0,
+        Mask::Fields => (y * x) % 2 + (y * x) % 3 == 0,
+        Mask::Diamonds => ((y * x) % 2 + (y * x) % 3) % 2 == 0,
+        Mask::Meadow => ((y + x) % 2 + (y * x) % 3) % 2 == 0,
+    }
+}
@@ -0,0 +1,168 @@
+//! Generates several `QRCode`s sharing the same look
+
+#![deny(unsafe_code)]
+#![warn(missing_docs)]
+
+use crate::datamasking::Mask;
+use crate::encode::{self, Mode};
+use crate::qr::{check_len, Overflow, QRCodeError};
+use crate::{QRCode, Version, ECL};
+use alloc::vec::Vec;
+
+/// Builder struct, creates a batch of [`QRCode`]s with different payloads but
+/// identical version, ECL and mask, so printed variants (A/B tests, numbered
+/// flyers, ...) look uniform once rendered with the same converter.
+///
+/// The version is the smallest one fitting every payload, smaller payloads are
+/// padded to it. The mask is the best one for the first payload.
+///
+/// # Example
+/// ```rust
+/// use fast_qr::QRBatchBuilder;
+///
+/// let qrcodes = QRBatchBuilder::new(["https://example.com/a", "https://example.com/bb"])
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(qrcodes[0].size, qrcodes[1].size);
+/// ```
+pub struct QRBatchBuilder {
+    inputs: Vec<Vec<u8>>,
+    ecl: Option<ECL>,
+    version: Option<Version>,
+    mask: Option<Mask>,
+}
+
+impl QRBatchBuilder {
+    /// Creates an instance of `QRBatchBuilder` with default parameters
+    #[must_use]
+    pub fn new<I, T>(inputs: I) -> QRBatchBuilder
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<Vec<u8>>,
+    {
+        QRBatchBuilder {
+            inputs: inputs.into_iter().map(Into::into).collect(),
+            ecl: None,
+            version: None,
+            mask: None,
+        }
+    }
+
+    /// Forces the Encoding Level
+    pub fn ecl(&mut self, ecl: ECL) -> &mut Self {
+        self.ecl = Some(ecl);
+        self
+    }
+
+    /// Forces the version, must fit the largest payload
+    pub fn version(&mut self, version: Version) -> &mut Self {
+        self.version = Some(version);
+        self
+    }
+
+    /// Forces the mask, should very rarely be used
+    pub fn mask(&mut self, mask: Mask) -> &mut Self {
+        self.mask = Some(mask);
+        self
+    }
+
+    /// Returns the smallest version fitting every payload
+    fn shared_version(&self, ecl: ECL) -> Result<Version, QRCodeError> {
+        let mut shared = Version::V01;
+
+        for input in &self.inputs {
+            if input.is_empty() {
+                return Err(QRCodeError::EmptyPayload);
+            }
+            check_len(input.len(), None, ecl)?;
+
+            let mode = encode::best_encoding(input);
+            // Kanji is measured once converted to Shift JIS
+            let sjis = match mode {
+                Mode::Kanji => encode::kanji_from_utf8(input),
+                _ => None,
+            };
+            let input = sjis.as_deref().unwrap_or(input);
+            match Version::get(mode, ecl, input.len()) {
+                Some(version) if version > shared => shared = version,
+                Some(_) => {}
+                None => {
+                    return Err(QRCodeError::EncodedData(Overflow::new(
+                        mode,
+                        ecl,
+                        input.len(),
+                    )))
+                }
+            }
+        }
+
+        match self.version {
+            Some(user_version) if user_version >= shared => Ok(user_version),
+            None => Ok(shared),
+            Some(_) => Err(QRCodeError::SpecifiedVersion),
+        }
+    }
+
+    /// Computes every [`QRCode`] of the batch, `qrcodes[i]` encodes `inputs[i]`
+    ///
+    /// # Errors
+    /// - `QRCodeError::EncodedData` if any input is too large to be encoded
+    /// - `QRCodeError::SpecifiedVersion` if specified `version` is too small to contain the largest input
+    /// - `QRCodeError::EmptyPayload` if any input is empty
+    pub fn build(&self) -> Result<Vec<QRCode>, QRCodeError> {
+        let ecl = self.ecl.unwrap_or(ECL::Q);
+        let version = self.shared_version(ecl)?;
+
+        let mut mask = self.mask;
+        let mut qrcodes = Vec::with_capacity(self.inputs.len());
+
+        for input in &self.inputs {
+            // The first code picks the mask (if not forced), the others reuse it
+            let qrcode = QRCode::new(input, Some(ecl), Some(version), None, mask)?;
+            mask = qrcode.mask;
+            qrcodes.push(qrcode);
+        }
+
+        Ok(qrcodes)
+    }
+
+    /// Same as [`build`](QRBatchBuilder::build), computed on the rayon thread pool.
+    ///
+    /// The output is in input order and bitwise identical to `build`, whatever
+    /// the number of threads.
+    ///
+    /// # Errors
+    /// - `QRCodeError::EncodedData` if any input is too large to be encoded
+    /// - `QRCodeError::SpecifiedVersion` if specified `version` is too small to contain the largest input
+    /// - `QRCodeError::EmptyPayload` if any input is empty
+    #[cfg(feature = "rayon")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rayon")))]
+    pub fn par_build(&self) -> Result<Vec<QRCode>, QRCodeError> {
+        use rayon::prelude::*;
+
+        let ecl = self.ecl.unwrap_or(ECL::Q);
+        let version = self.shared_version(ecl)?;
+
+        let (first, rest) = match self.inputs.split_first() {
+            Some(split) => split,
+            None => return Ok(Vec::new()),
+        };
+
+        // The mask depends on the first code, it has to be computed first
+        let first = QRCode::new(first, Some(ecl), Some(version), None, self.mask)?;
+        let mask = first.mask;
+
+        // Indexed parallel iterators collect in order
+        let rest: Vec<QRCode> = rest
+            .par_iter()
+            .map(|input| QRCode::new(input, Some(ecl), Some(version), None, mask))
+            .collect::<Result<_, _>>()?;
+
+        let mut qrcodes = Vec::with_capacity(self.inputs.len());
+        qrcodes.push(first);
+        qrcodes.extend(rest);
+
+        Ok(qrcodes)
+    }
+}
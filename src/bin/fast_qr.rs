@@ -0,0 +1,213 @@
+//! `fast_qr` command, encodes its argument or stdin as a `QRCode`
+//!
+//! ```sh
+//! fast_qr "https://example.com/"
+//! echo -n "https://example.com/" | fast_qr --ecl H --output svg -o code.svg
+//! ```
+
+use std::io::{self, Read, Write};
+use std::process;
+use std::{env, fs};
+
+use fast_qr::convert::svg::SvgBuilder;
+use fast_qr::convert::Builder;
+use fast_qr::{Mask, QRBuilder, QRCode, Version, ECL};
+
+const USAGE: &str = "\
+Usage: fast_qr [OPTIONS] [CONTENT]
+
+Encodes CONTENT as a QR code, reads it from stdin when omitted.
+
+Options:
+  -e, --ecl <L|M|Q|H>          Error correction level [default: Q]
+  -v, --version <1-40>         Forces the version
+  -m, --mask <0-7>             Forces the mask pattern
+  -O, --output <FORMAT>        svg, png, term or rust (a `BakedQR` const) [default: term]
+      --margin <MODULES>       Quiet zone [default: 4, 1 for term]
+  -o <FILE>                    Writes to FILE instead of stdout
+  -h, --help                   Prints this help";
+
+/// Every option takes a value
+const OPTIONS: [&str; 10] = [
+    "-e",
+    "--ecl",
+    "-v",
+    "--version",
+    "-m",
+    "--mask",
+    "-O",
+    "--output",
+    "--margin",
+    "-o",
+];
+
+#[derive(Clone, Copy)]
+enum Output {
+    Svg,
+    Png,
+    Term,
+    Rust,
+}
+
+struct Args {
+    content: Option<String>,
+    ecl: Option<ECL>,
+    version: Option<Version>,
+    mask: Option<Mask>,
+    output: Output,
+    margin: Option<usize>,
+    file: Option<String>,
+}
+
+fn parse_value<T>(flag: &str, value: &str, parse: impl Fn(&str) -> Option<T>) -> Result<T, String> {
+    parse(value).ok_or_else(|| format!("invalid value '{value}' for {flag}"))
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<Args>, String> {
+    let mut parsed = Args {
+        content: None,
+        ecl: None,
+        version: None,
+        mask: None,
+        output: Output::Term,
+        margin: None,
+        file: None,
+    };
+
+    while let Some(arg) = args.next() {
+        // Accepts both `--flag value` and `--flag=value`
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => {
+                (flag.to_owned(), Some(value.to_owned()))
+            }
+            _ => (arg, None),
+        };
+
+        if flag == "-h" || flag == "--help" {
+            return Ok(None);
+        }
+        if !flag.starts_with('-') || flag == "-" {
+            if parsed.content.replace(flag).is_some() {
+                return Err("expected a single CONTENT argument".to_owned());
+            }
+            continue;
+        }
+
+        if !OPTIONS.contains(&flag.as_str()) {
+            return Err(format!("unknown option {flag}"));
+        }
+        let value = match inline.or_else(|| args.next()) {
+            Some(value) => value,
+            None => return Err(format!("missing value for {flag}")),
+        };
+
+        match flag.as_str() {
+            "-e" | "--ecl" => {
+                parsed.ecl = Some(parse_value(&flag, &value, |v| match v {
+                    "L" | "l" => Some(ECL::L),
+                    "M" | "m" => Some(ECL::M),
+                    "Q" | "q" => Some(ECL::Q),
+                    "H" | "h" => Some(ECL::H),
+                    _ => None,
+                })?);
+            }
+            "-v" | "--version" => {
+                parsed.version = Some(parse_value(&flag, &value, |v| {
+                    v.parse().ok().and_then(Version::from_number)
+                })?);
+            }
+            "-m" | "--mask" => {
+                parsed.mask = Some(parse_value(&flag, &value, |v| {
+                    v.parse().ok().and_then(Mask::from_pattern)
+                })?);
+            }
+            "-O" | "--output" => {
+                parsed.output = parse_value(&flag, &value, |v| match v {
+                    "svg" => Some(Output::Svg),
+                    "png" => Some(Output::Png),
+                    "term" => Some(Output::Term),
+                    "rust" => Some(Output::Rust),
+                    _ => None,
+                })?;
+            }
+            "--margin" => parsed.margin = Some(parse_value(&flag, &value, |v| v.parse().ok())?),
+            "-o" => parsed.file = Some(value),
+            _ => unreachable!(),
+        }
+    }
+
+    Ok(Some(parsed))
+}
+
+fn render(qrcode: &QRCode, output: Output, margin: Option<usize>) -> Result<Vec<u8>, String> {
+    match output {
+        Output::Term => {
+            let mut out = qrcode.to_str_with_margin(margin.unwrap_or(1));
+            out.push('\n');
+            Ok(out.into_bytes())
+        }
+        Output::Svg => Ok(SvgBuilder::default()
+            .margin(margin.unwrap_or(4))
+            .to_str(qrcode)
+            .into_bytes()),
+        #[cfg(feature = "image")]
+        Output::Png => fast_qr::convert::image::ImageBuilder::default()
+            .margin(margin.unwrap_or(4))
+            .fit_width(512)
+            .to_bytes(qrcode)
+            .map_err(|e| e.to_string()),
+        #[cfg(not(feature = "image"))]
+        Output::Png => Err("png output needs the `image` feature".to_owned()),
+        Output::Rust => Ok(fast_qr::baked::to_rust(qrcode, "QR").into_bytes()),
+    }
+}
+
+fn run() -> Result<(), String> {
+    let args = match parse_args(env::args().skip(1)) {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            // Nothing to do if stdout is already closed
+            let _ = writeln!(io::stdout(), "{USAGE}");
+            return Ok(());
+        }
+        Err(message) => return Err(format!("{message}\n\n{USAGE}")),
+    };
+
+    let content = match args.content {
+        Some(content) if content != "-" => content.into_bytes(),
+        _ => {
+            let mut content = Vec::new();
+            io::stdin()
+                .read_to_end(&mut content)
+                .map_err(|e| format!("cannot read stdin: {e}"))?;
+            content
+        }
+    };
+
+    let mut builder = QRBuilder::new(content);
+    if let Some(ecl) = args.ecl {
+        builder.ecl(ecl);
+    }
+    if let Some(version) = args.version {
+        builder.version(version);
+    }
+    if let Some(mask) = args.mask {
+        builder.mask(mask);
+    }
+    let qrcode = builder.build().map_err(|e| e.to_string())?;
+
+    let bytes = render(&qrcode, args.output, args.margin)?;
+    match args.file {
+        Some(file) => fs::write(&file, bytes).map_err(|e| format!("cannot write {file}: {e}")),
+        None => io::stdout()
+            .write_all(&bytes)
+            .map_err(|e| format!("cannot write to stdout: {e}")),
+    }
+}
+
+fn main() {
+    if let Err(message) = run() {
+        eprintln!("fast_qr: {message}");
+        process::exit(1);
+    }
+}
@@ -3,8 +3,11 @@
 #![deny(unsafe_code)]
 #![warn(missing_docs)]
 
+use alloc::string::String;
+use alloc::{vec, vec::Vec};
 use core::fmt::{Display, Formatter};
 
+use crate::compat::PadCodewords;
 use crate::Version;
 
 /// Values to keep last X bits of a u8
@@ -63,6 +66,22 @@ pub struct CompactQR {
     pub data: Vec<u8>,
 }
 
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for CompactQR {
+    fn zeroize(&mut self) {
+        self.len = 0;
+        self.data.zeroize();
+    }
+}
+
+/// Encoded data may be secret, it is wiped once the `CompactQR` is dropped
+#[cfg(feature = "zeroize")]
+impl Drop for CompactQR {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
 /// Returns a string visualization of the `CompactQR`. \
 /// `CompactQR { len: 4, data: [0b1111_1010] }.to_string()` => `"1010"`
 impl Display for CompactQR {
@@ -207,18 +226,15 @@ impl CompactQR {
         self.len += remaining;
     }
 
-    /// Fills the `CompactQR`'s remaining space with `[236, 17]`.
+    /// Fills the `CompactQR`'s remaining space with `padding`, alternating `[236, 17]` in the spec.
     /// Expects the `CompactQR` `len` to be a multiple of 8.
     #[inline(always)]
-    pub fn fill(&mut self) {
-        const PAD_BYTES: [u8; 2] = [0b1110_1100, 0b0001_0001]; //[236, 17]
-
+    pub fn fill(&mut self, padding: PadCodewords) {
         #[cfg(debug_assertions)]
         assert_eq!(self.len % 8, 0);
 
         for (i, _) in (self.len..self.data.len()).step_by(8).enumerate() {
-            let bits = PAD_BYTES[i % 2];
-            self.push_u8(bits);
+            self.push_u8(padding.byte(i));
         }
     }
 }
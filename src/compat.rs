@@ -0,0 +1,102 @@
+//! Reproduces known quirks of older encoders, to re-print codes that must
+//! stay bit-identical to archived ones.
+//!
+//! Every quirk defaults to the spec behavior, [`LegacyCompat::default`]
+//! produces the same `QRCode` as not setting anything.
+//!
+//! ```rust
+//! use fast_qr::compat::{LegacyCompat, MaskTieBreak, PadCodewords};
+//! use fast_qr::QRBuilder;
+//!
+//! let qrcode = QRBuilder::new("https://example.com/")
+//!     .legacy_compat(LegacyCompat {
+//!         mask_tie_break: MaskTieBreak::Last,
+//!         padding: PadCodewords::Zeros,
+//!     })
+//!     .build();
+//! ```
+
+#![deny(unsafe_code)]
+#![warn(missing_docs)]
+
+/// Which mask is applied when several share the lowest penalty
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum MaskTieBreak {
+    /// Lowest pattern reference wins, the default
+    First,
+    /// Highest pattern reference wins, as encoders comparing with `<=` do
+    Last,
+}
+
+/// Codewords filling the data capacity left after the terminator
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum PadCodewords {
+    /// `0xEC`, `0x11`, `0xEC`, ... as required by section 7.4.10, the default
+    Alternating,
+    /// `0x11`, `0xEC`, `0x11`, ... starting with the wrong byte
+    Swapped,
+    /// `0x00` only
+    Zeros,
+    /// Byte returned for each pad codeword, numbered from `0` after the
+    /// terminator. Decoders stop at the terminator, so any pattern scans, but
+    /// codes are no longer bit-identical to other encoders
+    ///
+    /// Functions cannot be serialized, serializing this variant fails
+    #[cfg_attr(feature = "serde", serde(skip))]
+    #[cfg_attr(feature = "arbitrary", arbitrary(skip))]
+    Custom(fn(usize) -> u8),
+}
+
+/// Custom paddings are equal when they point to the same function, which
+/// the compiler may or may not merge
+impl PartialEq for PadCodewords {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (PadCodewords::Custom(a), PadCodewords::Custom(b)) => *a as usize == *b as usize,
+            _ => core::mem::discriminant(self) == core::mem::discriminant(other),
+        }
+    }
+}
+
+impl Eq for PadCodewords {}
+
+impl PadCodewords {
+    /// Returns the pad codeword numbered `index`
+    pub(crate) fn byte(self, index: usize) -> u8 {
+        let [first, second] = match self {
+            PadCodewords::Alternating => [0b1110_1100, 0b0001_0001],
+            PadCodewords::Swapped => [0b0001_0001, 0b1110_1100],
+            PadCodewords::Zeros => [0, 0],
+            PadCodewords::Custom(pad) => return pad(index),
+        };
+        if index % 2 == 0 {
+            first
+        } else {
+            second
+        }
+    }
+}
+
+/// Set of legacy quirks, see the [module documentation](self)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct LegacyCompat {
+    /// Default is `MaskTieBreak::First`
+    pub mask_tie_break: MaskTieBreak,
+    /// Default is `PadCodewords::Alternating`
+    pub padding: PadCodewords,
+}
+
+impl Default for LegacyCompat {
+    fn default() -> Self {
+        LegacyCompat {
+            mask_tie_break: MaskTieBreak::First,
+            padding: PadCodewords::Alternating,
+        }
+    }
+}
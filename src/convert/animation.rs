@@ -0,0 +1,276 @@
+//! Converts [`QRCode`](crate::QRCode)s to animated GIF or APNG
+//!
+//! Screens at check-in desks often show several linked codes in turn, or
+//! reveal a code little by little. [`AnimationBuilder`] either shows a list
+//! of codes one after the other, or fades a single code in.
+//!
+//! ```rust
+//! use fast_qr::convert::animation::{AnimationBuilder, AnimationFormat};
+//! use fast_qr::qr::QRBuilder;
+//!
+//! let parts = [
+//!     QRBuilder::new("first part").build().unwrap(),
+//!     QRBuilder::new("second part").build().unwrap(),
+//! ];
+//!
+//! let gif = AnimationBuilder::default()
+//!     .module_size(4)
+//!     .frame_delay(1500)
+//!     .cycle(&parts, AnimationFormat::Gif)
+//!     .unwrap();
+//! assert!(gif.starts_with(b"GIF89a"));
+//! ```
+
+use core::fmt::{Display, Formatter};
+
+use crate::matrix::Matrix;
+
+/// Output formats of an [`AnimationBuilder`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnimationFormat {
+    /// GIF89a, read everywhere, up to 65535 pixels wide
+    Gif,
+    /// Animated PNG, falls back to its first frame in viewers without APNG support
+    Apng,
+}
+
+/// Possible errors when rendering an animation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AnimationError {
+    /// No code to render
+    NoFrames,
+    /// The frames are wider than the format allows
+    TooLarge,
+    /// Error from the GIF or PNG encoder
+    EncodingError(String),
+}
+
+impl std::error::Error for AnimationError {}
+
+impl Display for AnimationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            AnimationError::NoFrames => f.write_str("No frame to render"),
+            AnimationError::TooLarge => f.write_str("Frames are too large for the format"),
+            AnimationError::EncodingError(error) => f.write_str(error),
+        }
+    }
+}
+
+/// Builder for animations, can set margin, module size, colors and timing
+#[derive(Debug, Clone)]
+pub struct AnimationBuilder {
+    /// The quiet zone around the codes, in modules, default is 4
+    margin: usize,
+    /// Width of a module, in pixels, default is 8
+    module_size: usize,
+    /// Time each frame is shown, in milliseconds, default is 1000
+    frame_delay: u16,
+    /// Number of times the animation is played, 0 loops forever (default)
+    loops: u16,
+    /// The color of dark modules, default is black
+    module_color: [u8; 3],
+    /// The color of light modules and of the margin, default is white
+    background_color: [u8; 3],
+}
+
+/// Creates a Builder instance
+impl Default for AnimationBuilder {
+    fn default() -> Self {
+        AnimationBuilder {
+            margin: 4,
+            module_size: 8,
+            frame_delay: 1000,
+            loops: 0,
+            module_color: [0, 0, 0],
+            background_color: [255, 255, 255],
+        }
+    }
+}
+
+impl AnimationBuilder {
+    /// Updates margin (default: 4)
+    pub fn margin(&mut self, margin: usize) -> &mut Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Updates the width of a module in pixels (default: 8)
+    pub fn module_size(&mut self, module_size: usize) -> &mut Self {
+        self.module_size = module_size.max(1);
+        self
+    }
+
+    /// Updates the time each frame is shown, in milliseconds (default: 1000).
+    /// GIF counts in hundredths of a second, the delay is rounded down.
+    pub fn frame_delay(&mut self, frame_delay: u16) -> &mut Self {
+        self.frame_delay = frame_delay;
+        self
+    }
+
+    /// Updates the number of times the animation is played, `0` loops
+    /// forever (default: 0)
+    pub fn loops(&mut self, loops: u16) -> &mut Self {
+        self.loops = loops;
+        self
+    }
+
+    /// Updates module color (default: black)
+    pub fn module_color(&mut self, module_color: [u8; 3]) -> &mut Self {
+        self.module_color = module_color;
+        self
+    }
+
+    /// Updates background color (default: white)
+    pub fn background_color(&mut self, background_color: [u8; 3]) -> &mut Self {
+        self.background_color = background_color;
+        self
+    }
+
+    /// Shows each code of `codes` in turn, i.e. the parts of a structured
+    /// append sequence. Codes of different sizes are centered in the frame of
+    /// the largest one.
+    ///
+    /// # Errors
+    /// - `AnimationError::NoFrames` if `codes` is empty
+    /// - `AnimationError::TooLarge` if a GIF would be over 65535 pixels wide
+    /// - `AnimationError::EncodingError` if the encoder fails
+    pub fn cycle<M: Matrix>(
+        &self,
+        codes: &[M],
+        format: AnimationFormat,
+    ) -> Result<Vec<u8>, AnimationError> {
+        let modules = match codes.iter().map(Matrix::size).max() {
+            Some(modules) => modules,
+            None => return Err(AnimationError::NoFrames),
+        };
+        let width = self.width(modules, format)?;
+        let frames: Vec<Vec<u8>> = codes
+            .iter()
+            .map(|qr| self.frame(qr, width, |_, _| true))
+            .collect();
+
+        self.encode(&frames, width, format)
+    }
+
+    /// Reveals the dark modules of `qr` in `steps` frames, in a scattered
+    /// order that is the same for every render. The last frame shows the
+    /// whole code, set [`loops`](AnimationBuilder::loops) to `1` to stop there.
+    ///
+    /// # Errors
+    /// - `AnimationError::TooLarge` if a GIF would be over 65535 pixels wide
+    /// - `AnimationError::EncodingError` if the encoder fails
+    pub fn fade_in<M: Matrix>(
+        &self,
+        qr: &M,
+        steps: usize,
+        format: AnimationFormat,
+    ) -> Result<Vec<u8>, AnimationError> {
+        let n = qr.size();
+        let width = self.width(n, format)?;
+        let steps = steps.max(1);
+        // Step at which each module appears, scattered by a multiplicative hash
+        let step =
+            |y: usize, x: usize| ((y * n + x) as u32).wrapping_mul(0x9E37_79B9) as usize >> 8;
+
+        let frames: Vec<Vec<u8>> = (0..steps)
+            .map(|frame| self.frame(qr, width, |y, x| step(y, x) % steps <= frame))
+            .collect();
+
+        self.encode(&frames, width, format)
+    }
+
+    /// Width of frames holding codes of up to `modules` modules, in pixels,
+    /// checked before any frame is allocated
+    fn width(&self, modules: usize, format: AnimationFormat) -> Result<usize, AnimationError> {
+        let max = match format {
+            AnimationFormat::Gif => usize::from(u16::MAX),
+            AnimationFormat::Apng => u32::MAX as usize,
+        };
+        (modules + self.margin * 2)
+            .checked_mul(self.module_size)
+            .filter(|&width| width <= max)
+            .ok_or(AnimationError::TooLarge)
+    }
+
+    /// Palette indices of a `width` pixels wide frame, `1` for the dark
+    /// modules of `qr` which are `visible`, centered
+    fn frame<M: Matrix, F: Fn(usize, usize) -> bool>(
+        &self,
+        qr: &M,
+        width: usize,
+        visible: F,
+    ) -> Vec<u8> {
+        let n = qr.size();
+        let offset = (width - n * self.module_size) / 2;
+
+        let mut pixels = vec![0; width * width];
+        for y in 0..n {
+            for x in (0..n).filter(|&x| qr.module(y, x).value() && visible(y, x)) {
+                let (top, left) = (offset + y * self.module_size, offset + x * self.module_size);
+                for row in top..top + self.module_size {
+                    pixels[row * width + left..row * width + left + self.module_size].fill(1);
+                }
+            }
+        }
+        pixels
+    }
+
+    fn encode(
+        &self,
+        frames: &[Vec<u8>],
+        width: usize,
+        format: AnimationFormat,
+    ) -> Result<Vec<u8>, AnimationError> {
+        let mut palette = [0; 6];
+        palette[..3].copy_from_slice(&self.background_color);
+        palette[3..].copy_from_slice(&self.module_color);
+
+        let mut out = Vec::new();
+        match format {
+            AnimationFormat::Gif => {
+                let width = width as u16;
+                let error =
+                    |err: gif::EncodingError| AnimationError::EncodingError(err.to_string());
+
+                let mut encoder =
+                    gif::Encoder::new(&mut out, width, width, &palette).map_err(error)?;
+                let repeat = match self.loops {
+                    0 => gif::Repeat::Infinite,
+                    loops => gif::Repeat::Finite(loops),
+                };
+                encoder.set_repeat(repeat).map_err(error)?;
+
+                for pixels in frames {
+                    let mut frame = gif::Frame::from_indexed_pixels(width, width, pixels, None);
+                    frame.delay = self.frame_delay / 10;
+                    encoder.write_frame(&frame).map_err(error)?;
+                }
+            }
+            AnimationFormat::Apng => {
+                let width = width as u32;
+                let error =
+                    |err: png::EncodingError| AnimationError::EncodingError(err.to_string());
+
+                let mut encoder = png::Encoder::new(&mut out, width, width);
+                encoder.set_color(png::ColorType::Indexed);
+                encoder.set_depth(png::BitDepth::Eight);
+                encoder.set_palette(&palette[..]);
+                encoder
+                    .set_animated(frames.len() as u32, u32::from(self.loops))
+                    .map_err(error)?;
+                encoder
+                    .set_frame_delay(self.frame_delay, 1000)
+                    .map_err(error)?;
+
+                let mut writer = encoder.write_header().map_err(error)?;
+                for pixels in frames {
+                    writer.write_image_data(pixels).map_err(error)?;
+                }
+                writer.finish().map_err(error)?;
+            }
+        }
+
+        Ok(out)
+    }
+}
@@ -0,0 +1,184 @@
+//! Converts [`QRCode`](crate::QRCode) to PBM, PGM or XBM bitmaps, without
+//! any dependency
+//!
+//! These formats are read by most image tools (netpbm, ImageMagick, GIMP,
+//! X11), which is enough to produce a bitmap on embedded targets or in CI.
+//!
+//! ```rust
+//! use fast_qr::convert::bitmap::{BitmapBuilder, BitmapFormat};
+//! use fast_qr::qr::QRBuilder;
+//!
+//! let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+//!
+//! let pbm = BitmapBuilder::default()
+//!     .module_size(4)
+//!     .to_bytes(&qrcode, BitmapFormat::Pbm);
+//! assert!(pbm.starts_with(b"P4\n132 132\n"));
+//! ```
+
+use std::fmt::Write;
+
+use super::vector::{clamp_bleed, is_dark};
+use crate::matrix::Matrix;
+
+/// Longest line of plain PBM files
+const PLAIN_LINE: usize = 70;
+
+/// Output formats of a [`BitmapBuilder`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitmapFormat {
+    /// Plain PBM (`P1`), one ASCII digit per pixel
+    PbmAscii,
+    /// Raw PBM (`P4`), one bit per pixel
+    Pbm,
+    /// Raw PGM (`P5`), one byte per pixel
+    Pgm,
+    /// X BitMap, a C source file
+    Xbm,
+}
+
+/// Builder for bitmaps, can set margin, module size and the XBM name
+#[derive(Debug, Clone)]
+pub struct BitmapBuilder {
+    /// The quiet zone around the code, in modules, default is 4
+    margin: usize,
+    /// Width of a module, in pixels, default is 1
+    module_size: usize,
+    /// Growth of dark modules on every side, in modules, default is 0
+    bleed: f64,
+    /// Prefix of the XBM identifiers, default is `qrcode`
+    xbm_name: String,
+}
+
+/// Creates a Builder instance
+impl Default for BitmapBuilder {
+    fn default() -> Self {
+        BitmapBuilder {
+            margin: 4,
+            module_size: 1,
+            bleed: 0.0,
+            xbm_name: String::from("qrcode"),
+        }
+    }
+}
+
+impl BitmapBuilder {
+    /// Updates margin (default: 4)
+    pub fn margin(&mut self, margin: usize) -> &mut Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Updates the width of a module in pixels (default: 1)
+    pub fn module_size(&mut self, module_size: usize) -> &mut Self {
+        self.module_size = module_size.max(1);
+        self
+    }
+
+    /// Grows dark modules by `bleed` modules on every side, or shrinks them
+    /// when negative, from -0.5 to 0.5 (default: 0). Pixels are dark when
+    /// their center is, so bleed needs a `module_size` of a few pixels.
+    pub fn bleed(&mut self, bleed: f64) -> &mut Self {
+        self.bleed = clamp_bleed(bleed);
+        self
+    }
+
+    /// Updates the prefix of the `_width`, `_height` and `_bits` XBM
+    /// identifiers (default: `qrcode`)
+    pub fn xbm_name<S: Into<String>>(&mut self, name: S) -> &mut Self {
+        self.xbm_name = name.into();
+        self
+    }
+
+    /// Returns the width of the bitmap, in pixels
+    pub fn width<M: Matrix>(&self, qr: &M) -> usize {
+        (qr.size() + self.margin * 2) * self.module_size
+    }
+
+    /// Returns the pixels of every row, `true` for dark
+    fn rows<'a, M: Matrix>(&'a self, qr: &'a M) -> impl Iterator<Item = Vec<bool>> + 'a {
+        let width = self.width(qr);
+        // Center of a pixel, in modules from the top left corner of the code
+        let center =
+            move |pixel: usize| (pixel as f64 + 0.5) / self.module_size as f64 - self.margin as f64;
+        (0..width).map(move |y| {
+            (0..width)
+                .map(|x| is_dark(qr, center(y), center(x), self.bleed))
+                .collect()
+        })
+    }
+
+    /// Packs a row in bytes, the first pixel in the most significant bit
+    /// (`msb_first`) or the least significant one, padding with light pixels
+    fn pack(row: &[bool], msb_first: bool) -> impl Iterator<Item = u8> + '_ {
+        row.chunks(8).map(move |chunk| {
+            chunk.iter().enumerate().fold(0, |byte, (i, &dark)| {
+                let shift = if msb_first { 7 - i } else { i };
+                byte | (u8::from(dark) << shift)
+            })
+        })
+    }
+
+    /// Return the bitmap for a qr code, in `format`
+    pub fn to_bytes<M: Matrix>(&self, qr: &M, format: BitmapFormat) -> Vec<u8> {
+        let width = self.width(qr);
+        match format {
+            BitmapFormat::PbmAscii => {
+                let mut out = format!("P1\n{width} {width}\n");
+                for row in self.rows(qr) {
+                    for line in row.chunks(PLAIN_LINE) {
+                        out.extend(line.iter().map(|&dark| if dark { '1' } else { '0' }));
+                        out.push('\n');
+                    }
+                }
+                out.into_bytes()
+            }
+            BitmapFormat::Pbm => {
+                let mut out = format!("P4\n{width} {width}\n").into_bytes();
+                for row in self.rows(qr) {
+                    out.extend(Self::pack(&row, true));
+                }
+                out
+            }
+            BitmapFormat::Pgm => {
+                let mut out = format!("P5\n{width} {width}\n255\n").into_bytes();
+                for row in self.rows(qr) {
+                    out.extend(row.iter().map(|&dark| if dark { 0 } else { 255 }));
+                }
+                out
+            }
+            BitmapFormat::Xbm => {
+                let name = &self.xbm_name;
+                let mut out = format!(
+                    "#define {name}_width {width}\n#define {name}_height {width}\nstatic unsigned char {name}_bits[] = {{\n"
+                );
+                let bytes: Vec<u8> = self
+                    .rows(qr)
+                    .flat_map(|row| Self::pack(&row, false).collect::<Vec<_>>())
+                    .collect();
+                for line in bytes.chunks(12) {
+                    out.push(' ');
+                    for byte in line {
+                        let _ = write!(out, " 0x{byte:02x},");
+                    }
+                    out.push('\n');
+                }
+                out.push_str("};\n");
+                out.into_bytes()
+            }
+        }
+    }
+
+    /// Saves the bitmap for a qr code to a file, in `format`
+    ///
+    /// # Errors
+    /// - if the file cannot be written
+    pub fn to_file<M: Matrix>(
+        &self,
+        qr: &M,
+        file: &str,
+        format: BitmapFormat,
+    ) -> std::io::Result<()> {
+        std::fs::write(file, self.to_bytes(qr, format))
+    }
+}
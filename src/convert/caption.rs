@@ -0,0 +1,290 @@
+//! Renders a [`QRCode`](crate::QRCode) with a text caption below it to a grayscale raster,
+//! without a separate compositing step.
+//!
+//! Text layout goes through the [`TextShaper`] trait: enable the `font`
+//! feature to use `FontShaper` with your own TTF / OTF bytes, or implement
+//! the trait for another text engine.
+//!
+//! ```rust
+//! use fast_qr::convert::caption::{CaptionBuilder, TextShaper};
+//! use fast_qr::qr::QRBuilder;
+//!
+//! /// Draws each character as a filled box
+//! struct Boxes;
+//!
+//! impl TextShaper for Boxes {
+//!     fn measure(&self, text: &str, size: f32) -> (u32, u32) {
+//!         (text.chars().count() as u32 * size as u32, size as u32)
+//!     }
+//!
+//!     fn draw(&self, text: &str, size: f32, draw: &mut dyn FnMut(u32, u32, f32)) {
+//!         let (width, height) = self.measure(text, size);
+//!         for y in 0..height {
+//!             for x in (0..width).filter(|x| x % size as u32 != 0) {
+//!                 draw(x, y, 1.0);
+//!             }
+//!         }
+//!     }
+//! }
+//!
+//! let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+//!
+//! let image = CaptionBuilder::new(&Boxes)
+//!     .text("Scan me")
+//!     .module_size(8)
+//!     .to_gray(&qrcode);
+//! assert!(image.height > image.width);
+//! ```
+
+use core::fmt::{Display, Formatter};
+
+use crate::matrix::Matrix;
+
+/// Lays out and rasterizes a single line of text
+///
+/// Shapers are `Sync` so a [`CaptionBuilder`] borrowing one can be sent to
+/// another thread.
+pub trait TextShaper: Sync {
+    /// Returns the `(width, height)` in pixels of `text` drawn at `size` pixels
+    fn measure(&self, text: &str, size: f32) -> (u32, u32);
+
+    /// Rasterizes `text` at `size` pixels, calling `draw(x, y, coverage)` for
+    /// every covered pixel, relative to the top left of the measured box.
+    /// `coverage` goes from `0.0` (empty) to `1.0` (fully covered).
+    fn draw(&self, text: &str, size: f32, draw: &mut dyn FnMut(u32, u32, f32));
+}
+
+/// Possible errors when loading a font
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CaptionError {
+    /// The font bytes could not be parsed
+    InvalidFont,
+}
+
+impl std::error::Error for CaptionError {}
+
+impl Display for CaptionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            CaptionError::InvalidFont => f.write_str("Invalid font data"),
+        }
+    }
+}
+
+/// Grayscale raster, `0` is black and `255` white
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GrayImage {
+    /// Width in pixels
+    pub width: u32,
+    /// Height in pixels
+    pub height: u32,
+    /// Row-major pixels, `width * height` long
+    pub pixels: Vec<u8>,
+}
+
+impl GrayImage {
+    /// Returns the pixel at `(x, y)`
+    #[must_use]
+    pub fn get(&self, x: u32, y: u32) -> u8 {
+        self.pixels[(y * self.width + x) as usize]
+    }
+}
+
+/// Builder for a [`QRCode`](crate::QRCode) with a caption, can set the text, sizes and margin
+pub struct CaptionBuilder<'a> {
+    shaper: &'a dyn TextShaper,
+    text: String,
+    /// Pixels per module, default is 10
+    module_size: u32,
+    /// Text height in pixels, default is twice the module size
+    font_size: Option<f32>,
+    /// The quiet zone around the code, in modules, default is 4
+    margin: u32,
+}
+
+impl<'a> CaptionBuilder<'a> {
+    /// Creates an instance of `CaptionBuilder` drawing text with `shaper`
+    #[must_use]
+    pub fn new(shaper: &'a dyn TextShaper) -> Self {
+        CaptionBuilder {
+            shaper,
+            text: String::new(),
+            module_size: 10,
+            font_size: None,
+            margin: 4,
+        }
+    }
+
+    /// Updates the caption (default: empty, no caption area)
+    pub fn text<S: Into<String>>(&mut self, text: S) -> &mut Self {
+        self.text = text.into();
+        self
+    }
+
+    /// Updates the number of pixels per module (default: 10)
+    pub fn module_size(&mut self, module_size: u32) -> &mut Self {
+        self.module_size = module_size.max(1);
+        self
+    }
+
+    /// Updates the text size in pixels (default: twice the module size)
+    pub fn font_size(&mut self, font_size: f32) -> &mut Self {
+        self.font_size = Some(font_size);
+        self
+    }
+
+    /// Updates margin (default: 4)
+    pub fn margin(&mut self, margin: u32) -> &mut Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Renders the [`QRCode`](crate::QRCode) with the caption centered below its quiet zone.
+    /// Text wider than the code is clipped.
+    #[must_use]
+    pub fn to_gray<M: Matrix>(&self, qr: &M) -> GrayImage {
+        let n = qr.size();
+        let size = n as u32;
+        let margin_px = self.margin * self.module_size;
+        let width = (size + self.margin * 2) * self.module_size;
+
+        let font_size = self.font_size.unwrap_or((self.module_size * 2) as f32);
+        let (text_width, text_height) = if self.text.is_empty() {
+            (0, 0)
+        } else {
+            self.shaper.measure(&self.text, font_size)
+        };
+
+        // The caption starts after the bottom quiet zone and gets its own
+        // bottom padding, as large as the margin
+        let caption_height = match text_height {
+            0 => 0,
+            _ => text_height + margin_px,
+        };
+        let height = width + caption_height;
+
+        let mut pixels = vec![255u8; (width * height) as usize];
+
+        for y in 0..n {
+            for x in 0..n {
+                if !qr.module(y, x).value() {
+                    continue;
+                }
+
+                let left = margin_px + x as u32 * self.module_size;
+                let top = margin_px + y as u32 * self.module_size;
+                for py in top..top + self.module_size {
+                    let row = (py * width) as usize;
+                    pixels[row + left as usize..row + (left + self.module_size) as usize].fill(0);
+                }
+            }
+        }
+
+        if caption_height != 0 {
+            let left = width.saturating_sub(text_width) / 2;
+            let top = width;
+
+            self.shaper
+                .draw(&self.text, font_size, &mut |x, y, coverage| {
+                    let (px, py) = (left + x, top + y);
+                    if px >= width || py >= height {
+                        return;
+                    }
+
+                    let pixel = &mut pixels[(py * width + px) as usize];
+                    let ink = (coverage.clamp(0.0, 1.0) * 255.0).round() as u8;
+                    *pixel = (*pixel).min(255 - ink);
+                });
+        }
+
+        GrayImage {
+            width,
+            height,
+            pixels,
+        }
+    }
+}
+
+/// [`TextShaper`] backed by a TTF / OTF font, using `ab_glyph`
+///
+/// ```rust,no_run
+/// use fast_qr::convert::caption::{CaptionBuilder, FontShaper};
+/// use fast_qr::qr::QRBuilder;
+///
+/// let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+/// let font = FontShaper::from_ttf(std::fs::read("Roboto-Regular.ttf").unwrap()).unwrap();
+///
+/// let image = CaptionBuilder::new(&font).text("Scan me").to_gray(&qrcode);
+/// ```
+#[cfg(feature = "font")]
+#[cfg_attr(docsrs, doc(cfg(feature = "font")))]
+pub struct FontShaper {
+    font: ab_glyph::FontVec,
+}
+
+#[cfg(feature = "font")]
+impl FontShaper {
+    /// Loads a font from its file content
+    ///
+    /// # Errors
+    /// - `CaptionError::InvalidFont` if `bytes` is not a valid font
+    pub fn from_ttf(bytes: Vec<u8>) -> Result<Self, CaptionError> {
+        ab_glyph::FontVec::try_from_vec(bytes)
+            .map(|font| FontShaper { font })
+            .map_err(|_| CaptionError::InvalidFont)
+    }
+
+    /// Positions every glyph of `text` on the baseline, from the left edge
+    fn layout(&self, text: &str, size: f32) -> (Vec<ab_glyph::Glyph>, f32) {
+        use ab_glyph::{Font, ScaleFont};
+
+        let font = self.font.as_scaled(size);
+        let mut glyphs = Vec::with_capacity(text.len());
+        let mut caret = 0f32;
+        let mut previous = None;
+
+        for c in text.chars() {
+            let id = font.glyph_id(c);
+            if let Some(previous) = previous {
+                caret += font.kern(previous, id);
+            }
+            glyphs.push(id.with_scale_and_position(size, ab_glyph::point(caret, font.ascent())));
+            caret += font.h_advance(id);
+            previous = Some(id);
+        }
+
+        (glyphs, caret)
+    }
+}
+
+#[cfg(feature = "font")]
+impl TextShaper for FontShaper {
+    fn measure(&self, text: &str, size: f32) -> (u32, u32) {
+        use ab_glyph::{Font, ScaleFont};
+
+        let font = self.font.as_scaled(size);
+        let (_, width) = self.layout(text, size);
+        (
+            width.ceil() as u32,
+            (font.ascent() - font.descent()).ceil() as u32,
+        )
+    }
+
+    fn draw(&self, text: &str, size: f32, draw: &mut dyn FnMut(u32, u32, f32)) {
+        use ab_glyph::Font;
+
+        let (glyphs, _) = self.layout(text, size);
+        for glyph in glyphs {
+            if let Some(outlined) = self.font.outline_glyph(glyph) {
+                let bounds = outlined.px_bounds();
+                outlined.draw(|x, y, coverage| {
+                    let px = bounds.min.x as i32 + x as i32;
+                    let py = bounds.min.y as i32 + y as i32;
+                    if px >= 0 && py >= 0 {
+                        draw(px as u32, py as u32, coverage);
+                    }
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,256 @@
+//! Converts [`QRCode`](crate::QRCode) to SVG for dark interfaces on small
+//! screens
+//!
+//! Apps with a dark theme often draw light modules on their dark background,
+//! or no quiet zone at all, which many scanners cannot read. This renderer
+//! always draws dark modules inside a light ring, itself on the page color,
+//! and refuses colors whose contrast is too low once blended.
+//!
+//! ```rust
+//! use fast_qr::convert::dark_mode::DarkModeBuilder;
+//! use fast_qr::qr::QRBuilder;
+//!
+//! let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+//!
+//! let svg = DarkModeBuilder::default()
+//!     .page_color("#1E1E1E")
+//!     .module_color("#101010")
+//!     .ring(3)
+//!     .to_str(&qrcode)
+//!     .unwrap();
+//!
+//! // Light modules on a dark ring are rejected
+//! let inverted = DarkModeBuilder::default()
+//!     .module_color("#FFFFFF")
+//!     .ring_color("#000000")
+//!     .to_str(&qrcode);
+//! assert!(inverted.is_err());
+//! ```
+
+use core::fmt::{Display, Formatter};
+
+use super::Color;
+use crate::matrix::Matrix;
+
+/// Possible errors when rendering with a [`DarkModeBuilder`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum DarkModeError {
+    /// The color is not `#rgb`, `#rgba`, `#rrggbb` or `#rrggbbaa`
+    Color(String),
+    /// Modules are lighter than the ring, which most scanners cannot read
+    Inverted,
+    /// The contrast ratio between modules and ring is under the minimum
+    Contrast {
+        /// Contrast ratio of the blended colors, from 1 to 21
+        ratio: f64,
+        /// Minimum set with [`DarkModeBuilder::min_contrast`]
+        min: f64,
+    },
+}
+
+impl std::error::Error for DarkModeError {}
+
+impl Display for DarkModeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DarkModeError::Color(color) => write!(f, "Unsupported color {color}"),
+            DarkModeError::Inverted => f.write_str("Modules are lighter than the quiet ring"),
+            DarkModeError::Contrast { ratio, min } => {
+                write!(f, "Contrast ratio {ratio:.2} is under {min:.2}")
+            }
+        }
+    }
+}
+
+/// Builder for dark interfaces, can set the ring, padding, colors and
+/// minimum contrast
+pub struct DarkModeBuilder {
+    /// Width of the light ring around the code, in modules, default is 4
+    ring: usize,
+    /// Page colored space around the ring, in modules, default is 1
+    padding: usize,
+    /// Radius of the ring corners, in modules, default is 1
+    corner_radius: f64,
+    /// The color of dark modules, default is #000000
+    module_color: Color,
+    /// The color of the ring and of light modules, default is #FFFFFF
+    ring_color: Color,
+    /// The color of the interface around the ring, default is #121212
+    page_color: Color,
+    /// Minimum contrast ratio between modules and ring, default is 7
+    min_contrast: f64,
+}
+
+/// Creates a Builder instance
+impl Default for DarkModeBuilder {
+    fn default() -> Self {
+        DarkModeBuilder {
+            ring: 4,
+            padding: 1,
+            corner_radius: 1.0,
+            module_color: [0, 0, 0, 255].into(),
+            ring_color: [255; 4].into(),
+            page_color: [0x12, 0x12, 0x12, 255].into(),
+            min_contrast: 7.0,
+        }
+    }
+}
+
+/// Parses `#rgb`, `#rgba`, `#rrggbb` and `#rrggbbaa` colors
+pub(crate) fn parse_color(color: &Color) -> Result<[u8; 4], DarkModeError> {
+    color
+        .to_rgba()
+        .ok_or_else(|| DarkModeError::Color(String::from(color.to_str())))
+}
+
+/// Blends `color` over the opaque `under`, returning linear RGB
+pub(crate) fn blend(color: [u8; 4], under: [f64; 3]) -> [f64; 3] {
+    let alpha = f64::from(color[3]) / 255.0;
+    let mut out = [0.0; 3];
+    for (i, channel) in out.iter_mut().enumerate() {
+        // sRGB to linear, as defined by WCAG 2
+        let c = f64::from(color[i]) / 255.0;
+        let linear = if c <= 0.04045 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        };
+        *channel = linear * alpha + under[i] * (1.0 - alpha);
+    }
+    out
+}
+
+/// Relative luminance of a linear RGB color
+pub(crate) fn luminance(rgb: [f64; 3]) -> f64 {
+    0.2126 * rgb[0] + 0.7152 * rgb[1] + 0.0722 * rgb[2]
+}
+
+impl DarkModeBuilder {
+    /// Updates the width of the light ring, in modules, at least 1 (default: 4)
+    pub fn ring(&mut self, ring: usize) -> &mut Self {
+        self.ring = ring.max(1);
+        self
+    }
+
+    /// Updates the page colored space around the ring, in modules (default: 1)
+    pub fn padding(&mut self, padding: usize) -> &mut Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Updates the radius of the ring corners, in modules, up to the ring
+    /// width so that the code itself is never cut (default: 1)
+    pub fn corner_radius(&mut self, corner_radius: f64) -> &mut Self {
+        self.corner_radius = corner_radius.max(0.0);
+        self
+    }
+
+    /// Updates module color (default: #000000)
+    pub fn module_color<C: Into<Color>>(&mut self, module_color: C) -> &mut Self {
+        self.module_color = module_color.into();
+        self
+    }
+
+    /// Updates the color of the ring and of light modules (default: #FFFFFF)
+    pub fn ring_color<C: Into<Color>>(&mut self, ring_color: C) -> &mut Self {
+        self.ring_color = ring_color.into();
+        self
+    }
+
+    /// Updates the color around the ring, usually the app background
+    /// (default: #121212)
+    pub fn page_color<C: Into<Color>>(&mut self, page_color: C) -> &mut Self {
+        self.page_color = page_color.into();
+        self
+    }
+
+    /// Updates the minimum contrast ratio between modules and ring, from 1
+    /// to 21 as defined by WCAG 2 (default: 7)
+    pub fn min_contrast(&mut self, min_contrast: f64) -> &mut Self {
+        self.min_contrast = min_contrast;
+        self
+    }
+
+    /// Returns the contrast ratio between modules and ring once blended, the
+    /// ring over the page and the modules over the ring
+    ///
+    /// # Errors
+    /// - [`DarkModeError::Color`] if a color is not hexadecimal
+    /// - [`DarkModeError::Inverted`] if modules are lighter than the ring
+    pub fn contrast(&self) -> Result<f64, DarkModeError> {
+        // The page is drawn over whatever is behind, taken as opaque
+        let mut page = parse_color(&self.page_color)?;
+        page[3] = 255;
+        let page = blend(page, [0.0; 3]);
+        let ring = blend(parse_color(&self.ring_color)?, page);
+        let module = blend(parse_color(&self.module_color)?, ring);
+
+        let (ring, module) = (luminance(ring), luminance(module));
+        if module > ring {
+            return Err(DarkModeError::Inverted);
+        }
+        Ok((ring + 0.05) / (module + 0.05))
+    }
+
+    /// Return a string containing the svg for a qr code
+    ///
+    /// # Errors
+    /// - [`DarkModeError::Color`] if a color is not hexadecimal
+    /// - [`DarkModeError::Inverted`] if modules are lighter than the ring
+    /// - [`DarkModeError::Contrast`] if the contrast is under the minimum
+    pub fn to_str<M: Matrix>(&self, qr: &M) -> Result<String, DarkModeError> {
+        let ratio = self.contrast()?;
+        if ratio < self.min_contrast {
+            return Err(DarkModeError::Contrast {
+                ratio,
+                min: self.min_contrast,
+            });
+        }
+
+        let n = qr.size();
+        let ring_width = n + self.ring * 2;
+        let offset = self.padding + self.ring;
+        let width = ring_width + self.padding * 2;
+        let radius = self.corner_radius.min(self.ring as f64);
+
+        let mut path = String::with_capacity(10 * n * n);
+        for y in 0..n {
+            for x in (0..n).filter(|&x| qr.module(y, x).value()) {
+                path.push_str(&format!("M{},{}h1v1h-1", x + offset, y + offset));
+            }
+        }
+
+        let mut out = String::with_capacity(path.len() + 300);
+        out.push_str(&format!(
+            r#"<svg viewBox="0 0 {width} {width}" xmlns="http://www.w3.org/2000/svg">"#
+        ));
+        out.push_str(&format!(
+            r#"<rect width="{width}px" height="{width}px" fill="{}"/>"#,
+            self.page_color.to_str()
+        ));
+        out.push_str(&format!(
+            r#"<rect x="{0}" y="{0}" width="{ring_width}" height="{ring_width}" rx="{radius}" fill="{1}"/>"#,
+            self.padding,
+            self.ring_color.to_str()
+        ));
+        out.push_str(&format!(
+            r#"<path d="{path}" fill="{}"/>"#,
+            self.module_color.to_str()
+        ));
+        out.push_str("</svg>");
+
+        Ok(out)
+    }
+
+    /// Saves the svg for a qr code to a file
+    ///
+    /// # Errors
+    /// - if the colors are rejected, see [`to_str`](DarkModeBuilder::to_str)
+    /// - if the file cannot be written
+    pub fn to_file<M: Matrix>(&self, qr: &M, file: &str) -> Result<(), super::ConvertError> {
+        let svg = self
+            .to_str(qr)
+            .map_err(|err| super::ConvertError::Svg(err.to_string()))?;
+        std::fs::write(file, svg).map_err(super::ConvertError::Io)
+    }
+}
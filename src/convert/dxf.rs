@@ -0,0 +1,160 @@
+//! Converts [`QRCode`](crate::QRCode) to DXF, for laser cutters, engravers
+//! and CNC routers
+//!
+//! Every dark area is one closed polyline at its physical size, holes
+//! included, so CAM software can fill or cut it without tracing an image.
+//! The file is AutoCAD R12 (`AC1009`), which every CAM tool reads.
+//!
+//! ```rust
+//! use fast_qr::convert::dxf::{DxfBuilder, DxfUnit};
+//! use fast_qr::qr::QRBuilder;
+//!
+//! let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+//!
+//! // 2 inches wide, quiet zone included
+//! let dxf = DxfBuilder::default()
+//!     .unit(DxfUnit::Inch)
+//!     .width(2.0)
+//!     .layer("ENGRAVE")
+//!     .to_str(&qrcode);
+//! assert!(dxf.ends_with("0\nEOF\n"));
+//! ```
+
+use std::fmt::Write;
+
+use super::vector::{dark_outlines, number};
+use super::Renderer;
+use crate::matrix::Matrix;
+use crate::QRCode;
+
+/// Unit of the drawing of a [`DxfBuilder`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DxfUnit {
+    /// Millimeters
+    Millimeter,
+    /// Inches
+    Inch,
+}
+
+impl DxfUnit {
+    /// Value of the `$INSUNITS` header variable
+    fn insunits(self) -> u8 {
+        match self {
+            DxfUnit::Inch => 1,
+            DxfUnit::Millimeter => 4,
+        }
+    }
+}
+
+/// Builder for dxf, can set the unit, physical size and layer
+#[derive(Debug, Clone)]
+pub struct DxfBuilder {
+    /// Unit of the drawing, default is `DxfUnit::Millimeter`
+    unit: DxfUnit,
+    /// Width of the code, quiet zone included, default is 30
+    width: f64,
+    /// The quiet zone around the code, in modules, default is 4
+    margin: usize,
+    /// Layer of the polylines, default is `QR`
+    layer: String,
+}
+
+impl Default for DxfBuilder {
+    fn default() -> Self {
+        DxfBuilder {
+            unit: DxfUnit::Millimeter,
+            width: 30.0,
+            margin: 4,
+            layer: String::from("QR"),
+        }
+    }
+}
+
+impl DxfBuilder {
+    /// Updates the unit of the drawing and of [`width`](DxfBuilder::width)
+    /// (default: `DxfUnit::Millimeter`)
+    pub fn unit(&mut self, unit: DxfUnit) -> &mut Self {
+        self.unit = unit;
+        self
+    }
+
+    /// Updates the width of the code, quiet zone included (default: 30)
+    pub fn width(&mut self, width: f64) -> &mut Self {
+        self.width = width;
+        self
+    }
+
+    /// Updates margin (default: 4). Nothing is drawn in the quiet zone, it
+    /// only offsets the code from the origin.
+    pub fn margin(&mut self, margin: usize) -> &mut Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Updates the layer of the polylines (default: `QR`)
+    pub fn layer(&mut self, layer: &str) -> &mut Self {
+        self.layer = String::from(layer);
+        self
+    }
+
+    /// Return a string containing the dxf for a qr code
+    pub fn to_str<M: Matrix>(&self, qr: &M) -> String {
+        let n = qr.size();
+        let modules = n + self.margin * 2;
+        let module_size = self.width / modules as f64;
+
+        let mut out = String::with_capacity(n * n * 16);
+        let _ = write!(
+            out,
+            "0\nSECTION\n2\nHEADER\n\
+             9\n$ACADVER\n1\nAC1009\n\
+             9\n$INSUNITS\n70\n{}\n\
+             9\n$EXTMIN\n10\n0\n20\n0\n\
+             9\n$EXTMAX\n10\n{}\n20\n{}\n\
+             0\nENDSEC\n\
+             0\nSECTION\n2\nENTITIES\n",
+            self.unit.insunits(),
+            number(self.width),
+            number(self.width),
+        );
+
+        // The origin is bottom left, rows go down from the top
+        let point = |(x, y): (usize, usize)| {
+            (
+                number((x + self.margin) as f64 * module_size),
+                number((n + self.margin - y) as f64 * module_size),
+            )
+        };
+        let layer = &self.layer;
+        for outline in dark_outlines(qr) {
+            let _ = write!(
+                out,
+                "0\nPOLYLINE\n8\n{layer}\n66\n1\n70\n1\n10\n0\n20\n0\n30\n0\n"
+            );
+            for corner in outline {
+                let (x, y) = point(corner);
+                let _ = write!(out, "0\nVERTEX\n8\n{layer}\n10\n{x}\n20\n{y}\n30\n0\n");
+            }
+            let _ = write!(out, "0\nSEQEND\n8\n{layer}\n");
+        }
+        out.push_str("0\nENDSEC\n0\nEOF\n");
+
+        out
+    }
+
+    /// Saves the dxf for a qr code to a file
+    ///
+    /// # Errors
+    /// - if the file cannot be written
+    pub fn to_file<M: Matrix>(&self, qr: &M, file: &str) -> std::io::Result<()> {
+        std::fs::write(file, self.to_str(qr))
+    }
+}
+
+impl Renderer for DxfBuilder {
+    type Output = String;
+
+    fn render(&self, qr: &QRCode) -> Self::Output {
+        self.to_str(qr)
+    }
+}
@@ -0,0 +1,105 @@
+//! Debug renderer shading each module of a [`QRCode`] by the error correction
+//! left in its block once the area around it is damaged.
+//!
+//! Green areas keep the most correction and are the safest to cover with a
+//! sticker or a label, red areas the least. Function modules are drawn in
+//! black and white.
+//!
+//! ```rust
+//! use fast_qr::convert::ecc_map::EccMapBuilder;
+//! use fast_qr::qr::QRBuilder;
+//!
+//! let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+//!
+//! let _svg = EccMapBuilder::default()
+//!     .damage(5)
+//!     .to_str(&qrcode);
+//! ```
+
+use std::collections::BTreeMap;
+
+use crate::overlay::correction_map;
+use crate::QRCode;
+
+/// Builder for the error correction map, can set the damage size and margin
+pub struct EccMapBuilder {
+    /// Side of the damaged square centered on each module, default is 3
+    damage: usize,
+    /// The quiet zone around the code, in modules, default is 4
+    margin: usize,
+}
+
+impl Default for EccMapBuilder {
+    fn default() -> Self {
+        EccMapBuilder {
+            damage: 3,
+            margin: 4,
+        }
+    }
+}
+
+impl EccMapBuilder {
+    /// Updates the side of the square damaged around each module (default: 3)
+    pub fn damage(&mut self, damage: usize) -> &mut Self {
+        self.damage = damage;
+        self
+    }
+
+    /// Updates margin (default: 4)
+    pub fn margin(&mut self, margin: usize) -> &mut Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Return a string containing the svg of the error correction map
+    pub fn to_str(&self, qr: &QRCode) -> String {
+        let n = qr.size;
+        // Version and ECL are always set on a built `QRCode`
+        let map = match (qr.version, qr.ecl) {
+            (Some(version), Some(ecl)) => Some(correction_map(version, ecl, self.damage)),
+            _ => None,
+        };
+
+        // Groups modules by fill color, one path per color
+        let mut paths: BTreeMap<String, String> = BTreeMap::new();
+        for y in 0..n {
+            for x in 0..n {
+                let module = qr[y][x];
+                let fill = match map
+                    .as_ref()
+                    .and_then(|map| map.get(y, x).zip(Some(map.capacity)))
+                {
+                    Some((remaining, capacity)) => {
+                        // Red (nothing left) to green (intact block)
+                        let hue = 120 * remaining / capacity.max(1);
+                        let lightness = if module.value() { 30 } else { 75 };
+                        format!("hsl({hue},80%,{lightness}%)")
+                    }
+                    None if module.value() => String::from("#000000"),
+                    None => String::from("#ffffff"),
+                };
+
+                paths.entry(fill).or_default().push_str(&format!(
+                    "M{},{}h1v1h-1",
+                    x + self.margin,
+                    y + self.margin
+                ));
+            }
+        }
+
+        let mut out = String::with_capacity(12 * n * n);
+        out.push_str(&format!(
+            r#"<svg viewBox="0 0 {0} {0}" xmlns="http://www.w3.org/2000/svg">"#,
+            self.margin * 2 + n
+        ));
+        out.push_str(&format!(
+            r##"<rect width="{0}px" height="{0}px" fill="#ffffff"/>"##,
+            self.margin * 2 + n
+        ));
+        for (fill, path) in paths {
+            out.push_str(&format!(r#"<path d="{path}" fill="{fill}"/>"#));
+        }
+        out.push_str("</svg>");
+        out
+    }
+}
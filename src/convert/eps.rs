@@ -0,0 +1,242 @@
+//! Converts [`QRCode`](crate::QRCode) to Encapsulated PostScript, for label
+//! printers and RIPs which only accept EPS
+//!
+//! ```rust
+//! use fast_qr::convert::eps::{EpsBuilder, EpsUnit};
+//! use fast_qr::qr::QRBuilder;
+//!
+//! let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+//!
+//! // 1 inch wide, quiet zone included, on a 2 x 1.5 inches label
+//! let eps = EpsBuilder::default()
+//!     .unit(EpsUnit::Inch)
+//!     .width(1.0)
+//!     .bounding_box(2.0, 1.5)
+//!     .to_str(&qrcode);
+//! assert!(eps.starts_with("%!PS-Adobe-3.0 EPSF-3.0\n"));
+//! ```
+
+use std::fmt::Write;
+
+use super::vector::{clamp_bleed, dark_rects, number};
+use super::Renderer;
+use crate::matrix::Matrix;
+use crate::QRCode;
+
+/// Unit of the sizes given to an [`EpsBuilder`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EpsUnit {
+    /// PostScript points, 1/72 inch
+    Point,
+    /// Millimeters
+    Millimeter,
+    /// Inches
+    Inch,
+}
+
+impl EpsUnit {
+    /// Converts `value` from this unit to points
+    #[must_use]
+    pub fn to_points(self, value: f64) -> f64 {
+        match self {
+            EpsUnit::Point => value,
+            EpsUnit::Millimeter => value * 72.0 / 25.4,
+            EpsUnit::Inch => value * 72.0,
+        }
+    }
+}
+
+/// Fill color of an [`EpsBuilder`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EpsColor {
+    /// Gray level, from 0 (black) to 255 (white)
+    Gray(u8),
+    /// Red, green and blue, from 0 to 255
+    Rgb([u8; 3]),
+    /// Cyan, magenta, yellow and black percentages, from 0 to 100
+    Cmyk([u8; 4]),
+}
+
+impl EpsColor {
+    /// Appends the operator setting the current color
+    fn write_set(self, out: &mut String) {
+        let (components, operator): (Vec<f64>, _) = match self {
+            EpsColor::Gray(gray) => (vec![f64::from(gray) / 255.0], "setgray"),
+            EpsColor::Rgb(rgb) => (
+                rgb.iter().map(|&c| f64::from(c) / 255.0).collect(),
+                "setrgbcolor",
+            ),
+            EpsColor::Cmyk(cmyk) => (
+                cmyk.iter()
+                    .map(|&c| f64::from(c.min(100)) / 100.0)
+                    .collect(),
+                "setcmykcolor",
+            ),
+        };
+
+        for component in components {
+            out.push_str(&number(component));
+            out.push(' ');
+        }
+        out.push_str(operator);
+        out.push('\n');
+    }
+}
+
+/// Builder for eps, can set the unit, physical size, bounding box and colors
+#[derive(Debug, Clone)]
+pub struct EpsBuilder {
+    /// Unit of `width` and `bounding_box`, default is `EpsUnit::Millimeter`
+    unit: EpsUnit,
+    /// Width of the code, quiet zone included, default is 30
+    width: f64,
+    /// The quiet zone around the code, in modules, default is 4
+    margin: usize,
+    /// Growth of dark modules on every side, in modules, default is 0
+    bleed: f64,
+    /// Size of the bounding box, default is the size of the code
+    bounding_box: Option<(f64, f64)>,
+    /// The color of dark modules, default is `EpsColor::Gray(0)`
+    module_color: EpsColor,
+    /// Background of the code and its quiet zone, default is none
+    background_color: Option<EpsColor>,
+}
+
+impl Default for EpsBuilder {
+    fn default() -> Self {
+        EpsBuilder {
+            unit: EpsUnit::Millimeter,
+            width: 30.0,
+            margin: 4,
+            bleed: 0.0,
+            bounding_box: None,
+            module_color: EpsColor::Gray(0),
+            background_color: None,
+        }
+    }
+}
+
+impl EpsBuilder {
+    /// Updates the unit of [`width`](EpsBuilder::width) and
+    /// [`bounding_box`](EpsBuilder::bounding_box) (default: `EpsUnit::Millimeter`)
+    pub fn unit(&mut self, unit: EpsUnit) -> &mut Self {
+        self.unit = unit;
+        self
+    }
+
+    /// Updates the printed width of the code, quiet zone included (default: 30)
+    pub fn width(&mut self, width: f64) -> &mut Self {
+        self.width = width;
+        self
+    }
+
+    /// Updates margin (default: 4)
+    pub fn margin(&mut self, margin: usize) -> &mut Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Grows dark modules by `bleed` modules on every side, or shrinks them
+    /// when negative, from -0.5 to 0.5 (default: 0)
+    pub fn bleed(&mut self, bleed: f64) -> &mut Self {
+        self.bleed = clamp_bleed(bleed);
+        self
+    }
+
+    /// Centers the code in a bounding box of `width` x `height`
+    /// (default: the bounding box is the code, quiet zone included)
+    pub fn bounding_box(&mut self, width: f64, height: f64) -> &mut Self {
+        self.bounding_box = Some((width, height));
+        self
+    }
+
+    /// Updates the color of dark modules (default: `EpsColor::Gray(0)`)
+    pub fn module_color(&mut self, color: EpsColor) -> &mut Self {
+        self.module_color = color;
+        self
+    }
+
+    /// Fills the code and its quiet zone (default: nothing is drawn)
+    pub fn background_color(&mut self, color: EpsColor) -> &mut Self {
+        self.background_color = Some(color);
+        self
+    }
+
+    /// Return a string containing the eps for a qr code
+    pub fn to_str<M: Matrix>(&self, qr: &M) -> String {
+        let code_width = self.unit.to_points(self.width);
+        let (width, height) = self
+            .bounding_box
+            .map_or((code_width, code_width), |(w, h)| {
+                (self.unit.to_points(w), self.unit.to_points(h))
+            });
+
+        let modules = qr.size() + self.margin * 2;
+        let module_size = code_width / modules as f64;
+        // The origin is bottom left, rows are drawn going down from the top
+        let left = (width - code_width) / 2.0;
+        let top = (height + code_width) / 2.0;
+
+        let mut out = String::with_capacity(qr.size() * qr.size() * 4);
+        // The integer bounding box must contain the whole drawing
+        let _ = write!(
+            out,
+            "%!PS-Adobe-3.0 EPSF-3.0\n\
+             %%BoundingBox: 0 0 {} {}\n\
+             %%HiResBoundingBox: 0 0 {} {}\n\
+             %%Creator: fast_qr {}\n\
+             %%LanguageLevel: 2\n\
+             %%Pages: 0\n\
+             %%EndComments\n\
+             gsave\n\
+             {} {} translate\n\
+             {} -{} scale\n",
+            width.ceil(),
+            height.ceil(),
+            number(width),
+            number(height),
+            env!("CARGO_PKG_VERSION"),
+            number(left),
+            number(top),
+            number(module_size),
+            number(module_size),
+        );
+
+        if let Some(background) = self.background_color {
+            background.write_set(&mut out);
+            let _ = writeln!(out, "0 0 {modules} {modules} rectfill");
+        }
+
+        self.module_color.write_set(&mut out);
+        let margin = self.margin as f64;
+        for (x, y, width, height) in dark_rects(qr, self.bleed) {
+            let _ = writeln!(
+                out,
+                "{} {} {} {} rectfill",
+                number(x + margin),
+                number(y + margin),
+                number(width),
+                number(height)
+            );
+        }
+        out.push_str("grestore\nshowpage\n%%EOF\n");
+
+        out
+    }
+
+    /// Saves the eps for a qr code to a file
+    ///
+    /// # Errors
+    /// - if the file cannot be written
+    pub fn to_file<M: Matrix>(&self, qr: &M, file: &str) -> std::io::Result<()> {
+        std::fs::write(file, self.to_str(qr))
+    }
+}
+
+impl Renderer for EpsBuilder {
+    type Output = String;
+
+    fn render(&self, qr: &QRCode) -> Self::Output {
+        self.to_str(qr)
+    }
+}
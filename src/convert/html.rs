@@ -0,0 +1,184 @@
+//! Converts [`QRCode`](crate::QRCode) to HTML cells with inline styles, for
+//! email clients which strip images and SVG
+//!
+//! Horizontal runs of same-colored modules are merged in a single cell, and
+//! colors are repeated in `bgcolor` attributes for clients ignoring CSS.
+//!
+//! ```rust
+//! use fast_qr::convert::html::{HtmlBuilder, HtmlLayout};
+//! use fast_qr::qr::QRBuilder;
+//!
+//! let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+//!
+//! let html = HtmlBuilder::default()
+//!     .cell_size(3)
+//!     .module_color("#1A1A1A")
+//!     .to_str(&qrcode);
+//! assert!(html.starts_with("<table"));
+//!
+//! let html = HtmlBuilder::default()
+//!     .layout(HtmlLayout::Div)
+//!     .to_str(&qrcode);
+//! assert!(html.starts_with("<div"));
+//! ```
+
+use std::fmt::Write;
+
+use super::{Color, Renderer};
+use crate::matrix::Matrix;
+use crate::QRCode;
+
+/// Markup of an [`HtmlBuilder`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HtmlLayout {
+    /// A `<table>` of `<td>` cells, the most widely supported in emails
+    Table,
+    /// Rows of `<div>` holding inline-block `<span>` cells
+    Div,
+}
+
+/// Builder for html, can set the layout, cell size, margin and colors
+pub struct HtmlBuilder {
+    /// Markup of the cells, default is `HtmlLayout::Table`
+    layout: HtmlLayout,
+    /// Width and height of a module, in pixels, default is 4
+    cell_size: usize,
+    /// The quiet zone around the code, in modules, default is 4
+    margin: usize,
+    /// The color of dark modules, default is #000000
+    module_color: Color,
+    /// The color of light modules and of the quiet zone, default is #FFFFFF
+    background_color: Color,
+}
+
+/// Creates a Builder instance
+impl Default for HtmlBuilder {
+    fn default() -> Self {
+        HtmlBuilder {
+            layout: HtmlLayout::Table,
+            cell_size: 4,
+            margin: 4,
+            module_color: [0, 0, 0, 255].into(),
+            background_color: [255; 4].into(),
+        }
+    }
+}
+
+impl HtmlBuilder {
+    /// Updates the markup (default: `HtmlLayout::Table`)
+    pub fn layout(&mut self, layout: HtmlLayout) -> &mut Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Updates the width and height of a module, in pixels (default: 4)
+    pub fn cell_size(&mut self, cell_size: usize) -> &mut Self {
+        self.cell_size = cell_size.max(1);
+        self
+    }
+
+    /// Updates margin (default: 4)
+    pub fn margin(&mut self, margin: usize) -> &mut Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Updates module color (default: #000000)
+    pub fn module_color<C: Into<Color>>(&mut self, module_color: C) -> &mut Self {
+        self.module_color = module_color.into();
+        self
+    }
+
+    /// Updates background color (default: #FFFFFF)
+    pub fn background_color<C: Into<Color>>(&mut self, background_color: C) -> &mut Self {
+        self.background_color = background_color.into();
+        self
+    }
+
+    /// Runs of same-colored modules in row `y` of the code with its quiet
+    /// zone, as `(dark, length)`
+    fn runs<M: Matrix>(&self, qr: &M, y: usize) -> Vec<(bool, usize)> {
+        let size = qr.size();
+        let n = size + self.margin * 2;
+
+        let mut runs: Vec<(bool, usize)> = Vec::new();
+        for x in 0..n {
+            let dark = y >= self.margin
+                && x >= self.margin
+                && y < self.margin + size
+                && x < self.margin + size
+                && qr.module(y - self.margin, x - self.margin).value();
+
+            match runs.last_mut() {
+                Some(run) if run.0 == dark => run.1 += 1,
+                _ => runs.push((dark, 1)),
+            }
+        }
+        runs
+    }
+
+    /// Return a string containing the html for a qr code
+    pub fn to_str<M: Matrix>(&self, qr: &M) -> String {
+        let n = qr.size() + self.margin * 2;
+        let cell = self.cell_size;
+        let width = n * cell;
+        let dark = self.module_color.to_str();
+        let light = self.background_color.to_str();
+
+        let mut out = String::with_capacity(n * n * 16);
+        match self.layout {
+            HtmlLayout::Table => {
+                let _ = write!(
+                    out,
+                    r#"<table role="presentation" width="{width}" cellpadding="0" cellspacing="0" border="0" bgcolor="{light}" style="border-collapse:collapse;border-spacing:0;width:{width}px;background:{light}">"#
+                );
+                for y in 0..n {
+                    let _ = write!(out, r#"<tr style="height:{cell}px">"#);
+                    for (is_dark, length) in self.runs(qr, y) {
+                        let color = if is_dark { dark } else { light };
+                        out.push_str("<td");
+                        if length > 1 {
+                            let _ = write!(out, r#" colspan="{length}""#);
+                        }
+                        let _ = write!(
+                            out,
+                            r#" width="{0}" height="{cell}" bgcolor="{color}" style="width:{0}px;height:{cell}px;padding:0;font-size:0;line-height:0;background:{color}"></td>"#,
+                            length * cell
+                        );
+                    }
+                    out.push_str("</tr>");
+                }
+                out.push_str("</table>");
+            }
+            HtmlLayout::Div => {
+                let _ = write!(
+                    out,
+                    r#"<div style="width:{width}px;font-size:0;line-height:0;background:{light}">"#
+                );
+                for y in 0..n {
+                    let _ = write!(out, r#"<div style="height:{cell}px;white-space:nowrap">"#);
+                    for (is_dark, length) in self.runs(qr, y) {
+                        let color = if is_dark { dark } else { light };
+                        let _ = write!(
+                            out,
+                            r#"<span style="display:inline-block;width:{}px;height:{cell}px;background:{color}"></span>"#,
+                            length * cell
+                        );
+                    }
+                    out.push_str("</div>");
+                }
+                out.push_str("</div>");
+            }
+        }
+
+        out
+    }
+}
+
+impl Renderer for HtmlBuilder {
+    type Output = String;
+
+    fn render(&self, qr: &QRCode) -> Self::Output {
+        self.to_str(qr)
+    }
+}
@@ -1,4 +1,4 @@
-//! Converts [`QRCode`] to an image
+//! Converts [`QRCode`](crate::QRCode) to an image
 //!
 //! ```rust
 //! use fast_qr::convert::ConvertError;
@@ -25,10 +25,13 @@
 use std::fmt::Formatter;
 use std::io;
 
-use crate::QRCode;
+use crate::fingerprint::{self, Fingerprint};
+use crate::matrix::Matrix;
+use crate::{ModuleType, QRCode};
 
-use super::Color;
-use super::{svg::SvgBuilder, Builder, Shape};
+use super::dark_mode::{blend, luminance};
+use super::{svg::SvgBuilder, Builder, Renderer, Shape};
+use super::{Color, Gradient};
 
 use resvg::tiny_skia::{self, Pixmap};
 use resvg::usvg;
@@ -40,6 +43,7 @@ pub struct ImageBuilder {
     fit_height: Option<u32>,
     fit_width: Option<u32>,
     svg_builder: SvgBuilder,
+    qr_fingerprint: Option<u64>,
 }
 
 /// Error when converting to image
@@ -55,6 +59,33 @@ pub enum ImageError {
 
 impl std::error::Error for ImageError {}
 
+/// Contrast ratio under which [`ImageBuilder::background_warning`] warns
+pub const MIN_BACKGROUND_CONTRAST: f64 = 3.0;
+
+/// Reasons why a code drawn over a background image is likely unscannable
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BackgroundWarning {
+    /// The darkest light modules are darker than the lightest dark modules
+    Inverted,
+    /// The contrast ratio between the darkest light modules and the lightest
+    /// dark modules, from 1 to 21, is under [`MIN_BACKGROUND_CONTRAST`]
+    LowContrast(f64),
+}
+
+impl std::fmt::Display for BackgroundWarning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BackgroundWarning::Inverted => {
+                f.write_str("Some light modules are darker than dark modules")
+            }
+            BackgroundWarning::LowContrast(ratio) => write!(
+                f,
+                "Contrast ratio {ratio:.2} is under {MIN_BACKGROUND_CONTRAST:.2}"
+            ),
+        }
+    }
+}
+
 impl std::fmt::Display for ImageError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -72,6 +103,7 @@ impl Default for ImageBuilder {
             fit_height: None,
             fit_width: None,
             svg_builder: Default::default(),
+            qr_fingerprint: None,
         }
     }
 }
@@ -138,6 +170,60 @@ impl Builder for ImageBuilder {
     }
 }
 
+impl ImageBuilder {
+    /// Fills dark modules with a gradient instead of the module color
+    pub fn gradient(&mut self, gradient: Gradient) -> &mut Self {
+        self.svg_builder.gradient(gradient);
+        self
+    }
+
+    /// Adds a shape used only for modules of `module_type` (i.e. finder patterns).
+    /// Modules of that type then ignore the shapes added with `.shape()`
+    pub fn module_shape(&mut self, module_type: ModuleType, shape: Shape) -> &mut Self {
+        self.svg_builder.module_shape(module_type, shape);
+        self
+    }
+
+    /// Same as `.module_shape()` with a specific color
+    pub fn module_shape_color<C: Into<Color>>(
+        &mut self,
+        module_type: ModuleType,
+        shape: Shape,
+        color: C,
+    ) -> &mut Self {
+        self.svg_builder
+            .module_shape_color(module_type, shape, color);
+        self
+    }
+
+    /// Updates the color of every module of `module_type` (i.e. brand-colored finder patterns).
+    /// Colors given with `.module_shape_color()` still take precedence
+    pub fn module_type_color<C: Into<Color>>(
+        &mut self,
+        module_type: ModuleType,
+        color: C,
+    ) -> &mut Self {
+        self.svg_builder.module_type_color(module_type, color);
+        self
+    }
+
+    /// Draws each finder pattern as an `outer` ring around an `inner` square,
+    /// whatever the shapes of the other modules. Shapes given with
+    /// `.module_shape()` no longer apply to finder patterns
+    pub fn eye_shape(&mut self, outer: super::EyeShape, inner: super::EyeShape) -> &mut Self {
+        self.svg_builder.eye_shape(outer, inner);
+        self
+    }
+
+    /// Updates the image size as a percentage of the [`crate::QRCode`] width (margin excluded).
+    /// On `ECL::L` and `ECL::M` codes, images are shrunk to what the code can
+    /// recover, see [`crate::QRBuilder::image_embedding`]
+    pub fn image_size_percent(&mut self, percent: f64) -> &mut Self {
+        self.svg_builder.image_size_percent(percent);
+        self
+    }
+}
+
 impl ImageBuilder {
     /// Add a max-height boundary
     pub fn fit_height(&mut self, height: u32) -> &mut Self {
@@ -151,9 +237,106 @@ impl ImageBuilder {
         self
     }
 
+    /// Hashes every render option, the ones of the inner [`SvgBuilder`]
+    /// (see [`SvgBuilder::options_fingerprint`]) and the size boundaries
+    #[must_use]
+    pub fn options_fingerprint(&self) -> u64 {
+        Fingerprint::new("ImageBuilder")
+            .u64(self.svg_builder.options_fingerprint())
+            .debug(&self.fit_width)
+            .debug(&self.fit_height)
+            .finish()
+    }
+
+    /// Combines the fingerprint of the [`QRBuilder`](crate::QRBuilder) options
+    /// with [`options_fingerprint`](ImageBuilder::options_fingerprint)
+    #[must_use]
+    pub fn output_fingerprint(&self, qr_options: u64) -> u64 {
+        fingerprint::combine(qr_options, self.options_fingerprint())
+    }
+
+    /// Writes the [`output_fingerprint`](ImageBuilder::output_fingerprint) in
+    /// a `fingerprint` PNG text chunk, as 16 hexadecimal digits
+    pub fn fingerprint(&mut self, qr_options: u64) -> &mut Self {
+        self.qr_fingerprint = Some(qr_options);
+        self
+    }
+
+    /// Grows or shrinks dark modules, see [`SvgBuilder::bleed`]
+    pub fn bleed(&mut self, bleed: f64) -> &mut Self {
+        self.svg_builder.bleed(bleed);
+        self
+    }
+
+    /// Draws `image` (a path or a data URI) under the modules, see
+    /// [`SvgBuilder::background_image`]
+    pub fn background_image(&mut self, image: String) -> &mut Self {
+        self.svg_builder.background_image(image);
+        self
+    }
+
+    /// Updates the opacity of dark modules, see [`SvgBuilder::dark_opacity`]
+    pub fn dark_opacity(&mut self, opacity: f64) -> &mut Self {
+        self.svg_builder.dark_opacity(opacity);
+        self
+    }
+
+    /// Renders the code and compares the luminance of light and dark modules
+    /// at their center, the way a scanner thresholds them. A tenth of the
+    /// modules may be off (a busy background, the embedded image), the
+    /// darkest light modules and lightest dark modules past them are compared.
+    ///
+    /// Returns `None` when the contrast is at least [`MIN_BACKGROUND_CONTRAST`].
+    pub fn background_warning<M: Matrix>(&self, qr: &M) -> Option<BackgroundWarning> {
+        let pixmap = self.to_pixmap(qr);
+        let n = qr.size();
+        let margin = self.svg_builder.margin;
+        let scale = f64::from(pixmap.width()) / (n + 2 * margin) as f64;
+
+        let center = |i: usize| ((i + margin) as f64 + 0.5) * scale;
+
+        let mut light = Vec::with_capacity(n * n);
+        let mut dark = Vec::with_capacity(n * n);
+        for y in 0..n {
+            for x in 0..n {
+                let pixel = match pixmap.pixel(center(x) as u32, center(y) as u32) {
+                    Some(pixel) => pixel.demultiply(),
+                    None => continue,
+                };
+                // Transparent parts are printed on white
+                let rgba = [pixel.red(), pixel.green(), pixel.blue(), pixel.alpha()];
+                let value = luminance(blend(rgba, [1.0; 3]));
+                if qr.module(y, x).value() {
+                    dark.push(value);
+                } else {
+                    light.push(value);
+                }
+            }
+        }
+        if light.is_empty() || dark.is_empty() {
+            return None;
+        }
+
+        let order = |a: &f64, b: &f64| a.partial_cmp(b).unwrap_or(core::cmp::Ordering::Equal);
+        light.sort_by(order);
+        dark.sort_by(order);
+        let light = light[light.len() / 10];
+        let dark = dark[dark.len() - 1 - dark.len() / 10];
+
+        if light <= dark {
+            return Some(BackgroundWarning::Inverted);
+        }
+        let ratio = (light + 0.05) / (dark + 0.05);
+        if ratio < MIN_BACKGROUND_CONTRAST {
+            Some(BackgroundWarning::LowContrast(ratio))
+        } else {
+            None
+        }
+    }
+
     // From https://github.com/RazrFalcon/resvg/blob/374a25f/crates/resvg/tests/integration/main.rs
     /// Return a pixmap containing the svg for a QRCode
-    pub fn to_pixmap(&self, qr: &QRCode) -> Pixmap {
+    pub fn to_pixmap<M: Matrix>(&self, qr: &M) -> Pixmap {
         let opt = usvg::Options::default();
 
         // Do not unwrap on the from_data line, because panic will poison GLOBAL_OPT.
@@ -187,18 +370,39 @@ impl ImageBuilder {
     }
 
     /// Saves the image for a QRCode to a file
-    pub fn to_file(&self, qr: &QRCode, file: &str) -> Result<(), ImageError> {
-        use io::{Error, ErrorKind};
+    pub fn to_file<M: Matrix>(&self, qr: &M, file: &str) -> Result<(), ImageError> {
+        let png = self.to_bytes(qr)?;
+        std::fs::write(file, png).map_err(ImageError::IoError)
+    }
 
-        self.to_pixmap(qr)
-            .save_png(file)
-            .map_err(|err| ImageError::IoError(Error::new(ErrorKind::Other, err.to_string())))
+    /// Returns the png for a QRCode as a `data:image/png;base64,` URI, to
+    /// inline it in an `<img src>`
+    pub fn to_png_data_uri<M: Matrix>(&self, qr: &M) -> Result<String, ImageError> {
+        Ok(super::data_uri("image/png", &self.to_bytes(qr)?))
     }
 
     /// Saves the image for a QRCode in a byte buffer
-    pub fn to_bytes(&self, qr: &QRCode) -> Result<Vec<u8>, ImageError> {
+    pub fn to_bytes<M: Matrix>(&self, qr: &M) -> Result<Vec<u8>, ImageError> {
         let out = self.to_pixmap(qr);
-        out.encode_png()
-            .map_err(|err| ImageError::EncodingError(err.to_string()))
+        let png = out
+            .encode_png()
+            .map_err(|err| ImageError::EncodingError(err.to_string()))?;
+
+        Ok(match self.qr_fingerprint {
+            Some(qr_options) => fingerprint::png_with_text(
+                &png,
+                "fingerprint",
+                &format!("{:016x}", self.output_fingerprint(qr_options)),
+            ),
+            None => png,
+        })
+    }
+}
+
+impl Renderer for ImageBuilder {
+    type Output = Result<Vec<u8>, ImageError>;
+
+    fn render(&self, qr: &QRCode) -> Self::Output {
+        self.to_bytes(qr)
     }
 }
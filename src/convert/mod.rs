@@ -14,8 +14,64 @@ pub mod image;
 #[cfg(feature = "image")]
 use image::ImageError;
 
+#[cfg(feature = "animation")]
+#[cfg_attr(docsrs, doc(cfg(feature = "animation")))]
+pub mod animation;
+
+#[cfg(feature = "skia")]
+#[cfg_attr(docsrs, doc(cfg(feature = "skia")))]
+pub mod skia;
+
+pub mod bitmap;
+
+pub mod caption;
+
+pub mod html;
+
+pub mod sixel;
+
+#[cfg(feature = "svg")]
+#[cfg_attr(docsrs, doc(cfg(feature = "svg")))]
+pub mod dark_mode;
+
+#[cfg(feature = "svg")]
+#[cfg_attr(docsrs, doc(cfg(feature = "svg")))]
+pub mod ecc_map;
+
+#[cfg(feature = "svg")]
+#[cfg_attr(docsrs, doc(cfg(feature = "svg")))]
+pub mod test_sheet;
+
+#[cfg(feature = "svg")]
+#[cfg_attr(docsrs, doc(cfg(feature = "svg")))]
+pub mod style;
+
+#[cfg(feature = "pdf")]
+#[cfg_attr(docsrs, doc(cfg(feature = "pdf")))]
+pub mod pdf;
+
+#[cfg(feature = "eps")]
+#[cfg_attr(docsrs, doc(cfg(feature = "eps")))]
+pub mod eps;
+
+#[cfg(feature = "dxf")]
+#[cfg_attr(docsrs, doc(cfg(feature = "dxf")))]
+pub mod dxf;
+
+#[cfg(feature = "zpl")]
+#[cfg_attr(docsrs, doc(cfg(feature = "zpl")))]
+pub mod zpl;
+
+pub(crate) mod vector;
+
+#[cfg(not(feature = "wasm-bindgen"))]
+pub mod terminal;
+
 use crate::Module;
 
+#[cfg(feature = "svg")]
+use vector::number;
+
 /// Converts a position to a module svg
 /// # Example
 ///
@@ -54,6 +110,7 @@ pub enum Shape {
 
 /// Different possible Shapes to represent modules in a [`crate::QRCode`]
 #[cfg(not(feature = "wasm-bindgen"))]
+#[allow(unknown_lints, unpredictable_function_pointer_comparisons)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Ord, PartialOrd)]
 pub enum Shape {
     /// Square Shape
@@ -199,6 +256,101 @@ pub enum ImageBackgroundShape {
     RoundedSquare,
 }
 
+/// Shapes of a finder pattern ("eye"), drawn whole instead of module by module
+#[cfg_attr(feature = "wasm-bindgen", repr(C), wasm_bindgen)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Ord, PartialOrd)]
+pub enum EyeShape {
+    /// Square shape, same outline as square modules
+    Square,
+    /// Square shape with rounded corners
+    Rounded,
+    /// Circle shape
+    Circle,
+    /// Two opposite corners rounded, the sharp ones on the diagonal through the center of the code
+    Leaf,
+}
+
+#[cfg(feature = "svg")]
+impl EyeShape {
+    /// Corner radii (top left, top right, bottom right, bottom left) of a
+    /// `size` wide eye, `flip` mirrors the leaf for the right and bottom eyes
+    pub(crate) fn radii(self, size: f64, flip: bool) -> [f64; 4] {
+        match self {
+            EyeShape::Square => [0.0; 4],
+            EyeShape::Rounded => [size / 4.0; 4],
+            EyeShape::Circle => [size / 2.0; 4],
+            EyeShape::Leaf if flip => [size / 2.0, 0.0, size / 2.0, 0.0],
+            EyeShape::Leaf => [0.0, size / 2.0, 0.0, size / 2.0],
+        }
+    }
+
+    /// Closed path of a `size` wide square at `x`, `y` with rounded corners
+    pub(crate) fn outline(x: f64, y: f64, size: f64, radii: [f64; 4]) -> String {
+        let [tl, tr, br, bl] = radii;
+        let (right, bottom) = (x + size, y + size);
+        let arc = |r: f64, x: f64, y: f64| {
+            if r > 0.0 {
+                format!("A{0},{0} 0 0 1 {1},{2}", number(r), number(x), number(y))
+            } else {
+                String::new()
+            }
+        };
+
+        format!(
+            "M{},{}H{}{}V{}{}H{}{}V{}{}z",
+            number(x + tl),
+            number(y),
+            number(right - tr),
+            arc(tr, right, y + tr),
+            number(bottom - br),
+            arc(br, right - br, bottom),
+            number(x + bl),
+            arc(bl, x, bottom - bl),
+            number(y + tl),
+            arc(tl, x + tl, y),
+        )
+    }
+}
+
+/// Gradient used to fill dark modules, light modules keep the background color
+pub enum Gradient {
+    /// Linear gradient, `angle` in degrees (`0` goes from left to right, `90` from top to bottom)
+    Linear {
+        /// Color at the start of the gradient
+        start: Color,
+        /// Color at the end of the gradient
+        end: Color,
+        /// Direction of the gradient, in degrees
+        angle: f64,
+    },
+    /// Radial gradient, from the center of the [`crate::QRCode`] to its corners
+    Radial {
+        /// Color at the center
+        inner: Color,
+        /// Color at the corners
+        outer: Color,
+    },
+}
+
+impl Gradient {
+    /// Creates a linear gradient, `angle` in degrees
+    pub fn linear<C1: Into<Color>, C2: Into<Color>>(start: C1, end: C2, angle: f64) -> Self {
+        Gradient::Linear {
+            start: start.into(),
+            end: end.into(),
+            angle,
+        }
+    }
+
+    /// Creates a radial gradient, from the center to the corners
+    pub fn radial<C1: Into<Color>, C2: Into<Color>>(inner: C1, outer: C2) -> Self {
+        Gradient::Radial {
+            inner: inner.into(),
+            outer: outer.into(),
+        }
+    }
+}
+
 /// Contains possible errors for a conversion
 #[derive(Debug)]
 pub enum ConvertError {
@@ -260,6 +412,32 @@ pub fn rgba2hex(color: [u8; 4]) -> String {
     hex
 }
 
+/// Returns `bytes` as a `data:` URI of type `mime`, in standard padded base64
+#[cfg(feature = "svg")]
+pub(crate) fn data_uri(mime: &str, bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut out = String::with_capacity(mime.len() + 13 + (bytes.len() + 2) / 3 * 4);
+    out.push_str("data:");
+    out.push_str(mime);
+    out.push_str(";base64,");
+
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (i, &byte)| {
+            group | u32::from(byte) << (16 - 8 * i)
+        });
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(group >> (18 - 6 * i) & 0x3F) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+
+    out
+}
+
 /// Allows to take String, string slices, arrays or slices of u8 (3 or 4) to create a [Color]
 pub struct Color(pub String);
 
@@ -269,6 +447,42 @@ impl Color {
     pub fn to_str(&self) -> &str {
         &self.0
     }
+
+    /// Parses `#rgb`, `#rgba`, `#rrggbb` and `#rrggbbaa` colors, `None` for
+    /// any other (i.e. named) color
+    ///
+    /// ```rust
+    /// use fast_qr::convert::Color;
+    ///
+    /// assert_eq!(Color::from("#1A2B3C").to_rgba(), Some([0x1A, 0x2B, 0x3C, 255]));
+    /// assert_eq!(Color::from("#fff8").to_rgba(), Some([255, 255, 255, 0x88]));
+    /// assert_eq!(Color::from("white").to_rgba(), None);
+    /// ```
+    #[must_use]
+    pub fn to_rgba(&self) -> Option<[u8; 4]> {
+        let hex = self.0.strip_prefix('#')?;
+        if !hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            return None;
+        }
+
+        let digit = |i: usize| u8::from_str_radix(&hex[i..=i], 16).unwrap_or(0);
+        let pair = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).unwrap_or(0);
+        let mut rgba = [255; 4];
+        match hex.len() {
+            3 | 4 => {
+                for (i, channel) in rgba.iter_mut().take(hex.len()).enumerate() {
+                    *channel = digit(i) * 17;
+                }
+            }
+            6 | 8 => {
+                for (i, channel) in rgba.iter_mut().take(hex.len() / 2).enumerate() {
+                    *channel = pair(i * 2);
+                }
+            }
+            _ => return None,
+        }
+        Some(rgba)
+    }
 }
 
 impl From<String> for Color {
@@ -315,7 +529,8 @@ impl From<Vec<u8>> for Color {
 
 /// Trait for `SvgBuilder` and `ImageBuilder`
 pub trait Builder {
-    /// Updates margin (default: 4)
+    /// Updates margin, in modules (default: 4 as per spec).
+    /// Can be `0` when the surrounding layout already provides whitespace.
     fn margin(&mut self, margin: usize) -> &mut Self;
     /// Updates module color (default: #000000)
     fn module_color<C: Into<Color>>(&mut self, module_color: C) -> &mut Self;
@@ -343,3 +558,45 @@ pub trait Builder {
     /// Updates the image position, anchor is the center of the image. Default is the center of the [`crate::QRCode`]
     fn image_position(&mut self, x: f64, y: f64) -> &mut Self;
 }
+
+/// Turns a [`crate::QRCode`] into some output: implemented by the built-in
+/// builders, and by third-party backends (meshes, plotters, e-paper
+/// drivers...) which only need the modules, read with
+/// [`crate::QRCode::iter_rows`] or the [`crate::matrix::Matrix`] methods.
+///
+/// `render` takes no generic parameter, so renderers can be stored as trait
+/// objects and picked at runtime.
+///
+/// ```rust
+/// use fast_qr::convert::html::HtmlBuilder;
+/// use fast_qr::convert::Renderer;
+/// use fast_qr::{QRBuilder, QRCode};
+///
+/// /// One character per module
+/// struct Ascii;
+///
+/// impl Renderer for Ascii {
+///     type Output = String;
+///
+///     fn render(&self, qr: &QRCode) -> String {
+///         qr.iter_rows()
+///             .map(|row| row.iter().map(|m| if m.value() { '#' } else { ' ' }).collect())
+///             .collect::<Vec<String>>()
+///             .join("\n")
+///     }
+/// }
+///
+/// let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+/// let renderers: [&dyn Renderer<Output = String>; 2] = [&Ascii, &HtmlBuilder::default()];
+/// for renderer in renderers {
+///     assert!(!renderer.render(&qrcode).is_empty());
+/// }
+/// ```
+pub trait Renderer {
+    /// What the renderer produces, i.e. a `String` for text formats or a
+    /// `Result` when rendering can fail
+    type Output;
+
+    /// Renders `qr`
+    fn render(&self, qr: &crate::QRCode) -> Self::Output;
+}
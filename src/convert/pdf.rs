@@ -0,0 +1,239 @@
+//! Converts [`QRCode`](crate::QRCode) to a single page vector PDF, at a
+//! physical size, for print shops which refuse raster input
+//!
+//! Dark modules are filled rectangles, one per horizontal run, in a single
+//! process color: pure black (`K` 100%) by default, which does not suffer
+//! from plate misregistration.
+//!
+//! ```rust
+//! use fast_qr::convert::pdf::PdfBuilder;
+//! use fast_qr::qr::QRBuilder;
+//!
+//! let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+//!
+//! // 25mm wide, quiet zone included, centered on an A6 page
+//! let pdf = PdfBuilder::default()
+//!     .width(25.0)
+//!     .page_size(105.0, 148.0)
+//!     .to_bytes(&qrcode);
+//! assert!(pdf.starts_with(b"%PDF-1.4"));
+//! ```
+
+use std::fmt::Write;
+
+use super::vector::{clamp_bleed, dark_rects, number};
+use super::Renderer;
+use crate::matrix::Matrix;
+use crate::QRCode;
+
+/// Points per millimeter, PDF user space units are 1/72 inch
+const POINTS_PER_MM: f64 = 72.0 / 25.4;
+
+/// Fill color of a [`PdfBuilder`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PdfColor {
+    /// Red, green and blue, from 0 to 255
+    Rgb([u8; 3]),
+    /// Cyan, magenta, yellow and black percentages, from 0 to 100
+    Cmyk([u8; 4]),
+}
+
+impl PdfColor {
+    /// Appends the operator setting the fill color
+    fn write_fill(self, out: &mut String) {
+        let (components, operator) = match self {
+            PdfColor::Rgb(rgb) => (rgb.iter().map(|&c| f64::from(c) / 255.0).collect(), "rg"),
+            PdfColor::Cmyk(cmyk) => (
+                cmyk.iter()
+                    .map(|&c| f64::from(c.min(100)) / 100.0)
+                    .collect::<Vec<_>>(),
+                "k",
+            ),
+        };
+
+        for component in components {
+            out.push_str(&number(component));
+            out.push(' ');
+        }
+        out.push_str(operator);
+        out.push('\n');
+    }
+}
+
+/// Builder for pdf, can set the physical size, page size and colors
+#[derive(Debug, Clone)]
+pub struct PdfBuilder {
+    /// Width of the code, quiet zone included, in millimeters, default is 30
+    width: f64,
+    /// The quiet zone around the code, in modules, default is 4
+    margin: usize,
+    /// Growth of dark modules on every side, in modules, default is 0
+    bleed: f64,
+    /// Page size in millimeters, default is the size of the code
+    page_size: Option<(f64, f64)>,
+    /// The color of dark modules, default is `Cmyk([0, 0, 0, 100])`
+    module_color: PdfColor,
+    /// Background of the code and its quiet zone, default is none (paper color)
+    background_color: Option<PdfColor>,
+}
+
+impl Default for PdfBuilder {
+    fn default() -> Self {
+        PdfBuilder {
+            width: 30.0,
+            margin: 4,
+            bleed: 0.0,
+            page_size: None,
+            module_color: PdfColor::Cmyk([0, 0, 0, 100]),
+            background_color: None,
+        }
+    }
+}
+
+impl PdfBuilder {
+    /// Updates the printed width of the code, quiet zone included, in
+    /// millimeters (default: 30). See [`PrintSize`](crate::physical::PrintSize)
+    /// to check modules stay readable.
+    pub fn width(&mut self, width: f64) -> &mut Self {
+        self.width = width;
+        self
+    }
+
+    /// Updates margin (default: 4)
+    pub fn margin(&mut self, margin: usize) -> &mut Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Grows dark modules by `bleed` modules on every side, or shrinks them
+    /// when negative, from -0.5 to 0.5 (default: 0)
+    pub fn bleed(&mut self, bleed: f64) -> &mut Self {
+        self.bleed = clamp_bleed(bleed);
+        self
+    }
+
+    /// Centers the code on a page of `width` x `height` millimeters
+    /// (default: the page is the size of the code)
+    pub fn page_size(&mut self, width: f64, height: f64) -> &mut Self {
+        self.page_size = Some((width, height));
+        self
+    }
+
+    /// Updates the color of dark modules (default: `Cmyk([0, 0, 0, 100])`)
+    pub fn module_color(&mut self, color: PdfColor) -> &mut Self {
+        self.module_color = color;
+        self
+    }
+
+    /// Fills the code and its quiet zone (default: nothing is drawn, the
+    /// paper shows through)
+    pub fn background_color(&mut self, color: PdfColor) -> &mut Self {
+        self.background_color = Some(color);
+        self
+    }
+
+    /// Returns the content stream drawing the code
+    fn content<M: Matrix>(&self, qr: &M, page: (f64, f64)) -> String {
+        let n = qr.size();
+        let modules = n + self.margin * 2;
+        let module_size = self.width * POINTS_PER_MM / modules as f64;
+
+        // Code centered on the page, the origin of PDF pages is bottom left
+        let left = (page.0 - self.width * POINTS_PER_MM) / 2.0;
+        let top = (page.1 + self.width * POINTS_PER_MM) / 2.0;
+
+        let mut out = String::with_capacity(n * n * 4);
+        // Module units from the top left corner of the quiet zone, rows going down
+        let _ = writeln!(
+            out,
+            "q\n{0} 0 0 -{0} {1} {2} cm",
+            number(module_size),
+            number(left),
+            number(top)
+        );
+
+        if let Some(background) = self.background_color {
+            background.write_fill(&mut out);
+            let _ = writeln!(out, "0 0 {modules} {modules} re\nf");
+        }
+
+        self.module_color.write_fill(&mut out);
+        let margin = self.margin as f64;
+        for (x, y, width, height) in dark_rects(qr, self.bleed) {
+            let _ = writeln!(
+                out,
+                "{} {} {} {} re",
+                number(x + margin),
+                number(y + margin),
+                number(width),
+                number(height)
+            );
+        }
+        out.push_str("f\nQ\n");
+
+        out
+    }
+
+    /// Return the bytes of a single page pdf containing the code
+    pub fn to_bytes<M: Matrix>(&self, qr: &M) -> Vec<u8> {
+        let code_width = self.width * POINTS_PER_MM;
+        let page = self.page_size.map_or((code_width, code_width), |(w, h)| {
+            (w * POINTS_PER_MM, h * POINTS_PER_MM)
+        });
+        let media_box = format!("[0 0 {} {}]", number(page.0), number(page.1));
+        let content = self.content(qr, page);
+
+        let objects = [
+            String::from("<< /Type /Catalog /Pages 2 0 R >>"),
+            String::from("<< /Type /Pages /Kids [3 0 R] /Count 1 >>"),
+            format!("<< /Type /Page /Parent 2 0 R /MediaBox {media_box} /TrimBox {media_box} /Resources << >> /Contents 4 0 R >>"),
+            format!(
+                "<< /Length {} >>\nstream\n{content}endstream",
+                content.len()
+            ),
+            format!(
+                "<< /Producer (fast_qr {}) >>",
+                env!("CARGO_PKG_VERSION")
+            ),
+        ];
+
+        // Binary comment line, marks the file as binary for transfer tools
+        let mut out = String::from("%PDF-1.4\n%\u{e2}\u{e3}\u{cf}\u{d3}\n").into_bytes();
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(out.len());
+            out.extend_from_slice(format!("{} 0 obj\n{object}\nendobj\n", i + 1).as_bytes());
+        }
+
+        // Cross-reference entries are exactly 20 bytes long
+        let xref = out.len();
+        let mut trailer = format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+        for offset in offsets {
+            let _ = writeln!(trailer, "{offset:010} 00000 n ");
+        }
+        let _ = write!(
+            trailer,
+            "trailer\n<< /Size {} /Root 1 0 R /Info 5 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+            objects.len() + 1
+        );
+        out.extend_from_slice(trailer.as_bytes());
+
+        out
+    }
+
+    /// Saves the pdf for a qr code to a file
+    ///
+    /// # Errors
+    /// - if the file cannot be written
+    pub fn to_file<M: Matrix>(&self, qr: &M, file: &str) -> std::io::Result<()> {
+        std::fs::write(file, self.to_bytes(qr))
+    }
+}
+
+impl Renderer for PdfBuilder {
+    type Output = Vec<u8>;
+
+    fn render(&self, qr: &QRCode) -> Self::Output {
+        self.to_bytes(qr)
+    }
+}
@@ -0,0 +1,195 @@
+//! Converts [`QRCode`](crate::QRCode) to Sixel, a raster image printed
+//! inline by terminals supporting it (xterm with `-ti vt340`, iTerm2,
+//! WezTerm, foot, mlterm...)
+//!
+//! Unlike [`terminal`](super::terminal), modules are pixels instead of
+//! character cells: the code stays square and sharp whatever the font.
+//!
+//! ```rust
+//! use fast_qr::convert::sixel::SixelBuilder;
+//! use fast_qr::qr::QRBuilder;
+//!
+//! let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+//!
+//! let sixel = SixelBuilder::default()
+//!     .module_size(6)
+//!     .module_color("#1A2B6D")
+//!     .to_str(&qrcode)
+//!     .unwrap();
+//! assert!(sixel.starts_with("\x1bP0;1;0q\"1;1;198;198"));
+//! print!("{sixel}");
+//! ```
+
+use core::fmt::{Display, Formatter};
+use std::fmt::Write;
+
+use super::{Color, Renderer};
+use crate::matrix::Matrix;
+use crate::QRCode;
+
+/// Pixels drawn by a sixel character, stacked vertically
+const BAND: usize = 6;
+
+/// Possible errors when rendering with a [`SixelBuilder`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SixelError {
+    /// The color is not `#rgb`, `#rgba`, `#rrggbb` or `#rrggbbaa`
+    Color(String),
+}
+
+impl std::error::Error for SixelError {}
+
+impl Display for SixelError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SixelError::Color(color) => write!(f, "Unsupported color {color}"),
+        }
+    }
+}
+
+/// Builder for Sixel images, can set margin, module size and colors
+pub struct SixelBuilder {
+    /// The quiet zone around the code, in modules, default is 4
+    margin: usize,
+    /// Width of a module, in pixels, default is 4
+    module_size: usize,
+    /// The color of dark modules, default is #000000
+    module_color: Color,
+    /// The color of light modules and of the margin, default is #FFFFFF
+    background_color: Color,
+}
+
+/// Creates a Builder instance
+impl Default for SixelBuilder {
+    fn default() -> Self {
+        SixelBuilder {
+            margin: 4,
+            module_size: 4,
+            module_color: [0, 0, 0, 255].into(),
+            background_color: [255; 4].into(),
+        }
+    }
+}
+
+impl SixelBuilder {
+    /// Updates margin (default: 4)
+    pub fn margin(&mut self, margin: usize) -> &mut Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Updates the width of a module in pixels (default: 4)
+    pub fn module_size(&mut self, module_size: usize) -> &mut Self {
+        self.module_size = module_size.max(1);
+        self
+    }
+
+    /// Updates module color (default: #000000). Sixel has no transparency,
+    /// alpha is ignored.
+    pub fn module_color<C: Into<Color>>(&mut self, module_color: C) -> &mut Self {
+        self.module_color = module_color.into();
+        self
+    }
+
+    /// Updates background color (default: #FFFFFF)
+    pub fn background_color<C: Into<Color>>(&mut self, background_color: C) -> &mut Self {
+        self.background_color = background_color.into();
+        self
+    }
+
+    /// Returns the width of the image, in pixels
+    pub fn width<M: Matrix>(&self, qr: &M) -> usize {
+        (qr.size() + self.margin * 2) * self.module_size
+    }
+
+    /// Returns the Sixel escape sequence of the code, to print as is. The
+    /// cursor ends on the line below the image.
+    ///
+    /// # Errors
+    /// - `SixelError::Color` if a color is not a hexadecimal color
+    pub fn to_str<M: Matrix>(&self, qr: &M) -> Result<String, SixelError> {
+        let n = qr.size();
+        let width = self.width(qr);
+        // Module of a pixel, `None` in the quiet zone
+        let module = |pixel: usize| {
+            (pixel / self.module_size)
+                .checked_sub(self.margin)
+                .filter(|&i| i < n)
+        };
+        let dark = |x: usize, y: usize| match (module(x), module(y)) {
+            (Some(x), Some(y)) => qr.module(y, x).value(),
+            _ => false,
+        };
+
+        let mut out = String::with_capacity(width * width / 2);
+        // Pixels left at 0 keep the background of the terminal, every pixel
+        // is drawn below anyway
+        let _ = write!(out, "\x1bP0;1;0q\"1;1;{width};{width}");
+        for (register, color) in [&self.background_color, &self.module_color]
+            .into_iter()
+            .enumerate()
+        {
+            let [r, g, b] = percent(color)?;
+            let _ = write!(out, "#{register};2;{r};{g};{b}");
+        }
+
+        let mut sixels = Vec::with_capacity(width);
+        for top in (0..width).step_by(BAND) {
+            let rows = top..(top + BAND).min(width);
+            for register in 0..2 {
+                sixels.clear();
+                sixels.extend((0..width).map(|x| {
+                    rows.clone()
+                        .filter(|&y| dark(x, y) == (register == 1))
+                        .fold(0, |bits, y| bits | 1 << (y - top))
+                }));
+                if register == 1 && sixels.iter().all(|&bits| bits == 0) {
+                    continue;
+                }
+                if register == 1 {
+                    // Back to the start of the band
+                    out.push('$');
+                }
+                let _ = write!(out, "#{register}");
+                push_run_length(&mut out, &sixels);
+            }
+            out.push('-');
+        }
+        out.push_str("\x1b\\");
+
+        Ok(out)
+    }
+}
+
+impl Renderer for SixelBuilder {
+    type Output = Result<String, SixelError>;
+
+    fn render(&self, qr: &QRCode) -> Self::Output {
+        self.to_str(qr)
+    }
+}
+
+/// Red, green and blue of `color` from 0 to 100, as Sixel color registers
+fn percent(color: &Color) -> Result<[u32; 3], SixelError> {
+    let [r, g, b, _] = color
+        .to_rgba()
+        .ok_or_else(|| SixelError::Color(String::from(color.to_str())))?;
+    Ok([r, g, b].map(|channel| (u32::from(channel) * 100 + 127) / 255))
+}
+
+/// Writes one sixel character per column, `!count` prefixing runs longer
+/// than 3
+fn push_run_length(out: &mut String, sixels: &[u8]) {
+    let mut i = 0;
+    while i < sixels.len() {
+        let bits = sixels[i];
+        let run = sixels[i..].iter().take_while(|&&b| b == bits).count();
+        let c = char::from(b'?' + bits);
+        if run > 3 {
+            let _ = write!(out, "!{run}{c}");
+        } else {
+            (0..run).for_each(|_| out.push(c));
+        }
+        i += run;
+    }
+}
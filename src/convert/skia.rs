@@ -0,0 +1,299 @@
+//! Converts [`QRCode`](crate::QRCode) to anti-aliased PNG with [tiny-skia](https://docs.rs/tiny-skia),
+//! without the SVG parsing and rendering stack of the `image` feature
+//!
+//! Modules are drawn as vector shapes straight on a pixmap, so round and
+//! diagonal shapes get smooth edges at any module size.
+//!
+//! ```rust
+//! use fast_qr::convert::skia::SkiaBuilder;
+//! use fast_qr::convert::{Gradient, Shape};
+//! use fast_qr::qr::QRBuilder;
+//!
+//! let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+//!
+//! let png = SkiaBuilder::default()
+//!     .module_size(12.0)
+//!     .shape(Shape::Circle)
+//!     .gradient(Gradient::linear("#1A2B6D", "#7A1FA2", 45.0))
+//!     .to_bytes(&qrcode)
+//!     .unwrap();
+//! assert!(png.starts_with(b"\x89PNG"));
+//! ```
+
+use core::fmt::{Display, Formatter};
+
+use tiny_skia::{
+    FillRule, GradientStop, LinearGradient, Paint, PathBuilder, Pixmap, Point, RadialGradient,
+    Shader, SpreadMode, Transform,
+};
+
+use super::dark_mode::parse_color;
+use super::{Color, Gradient, Renderer, Shape};
+use crate::matrix::Matrix;
+use crate::QRCode;
+
+/// Possible errors when rendering with a [`SkiaBuilder`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SkiaError {
+    /// The color is not `#rgb`, `#rgba`, `#rrggbb` or `#rrggbbaa`
+    Color(String),
+    /// The image would be empty or too large for a pixmap
+    Size,
+    /// Error from the PNG encoder
+    EncodingError(String),
+}
+
+impl std::error::Error for SkiaError {}
+
+impl Display for SkiaError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SkiaError::Color(color) => write!(f, "Unsupported color {color}"),
+            SkiaError::Size => f.write_str("Invalid image size"),
+            SkiaError::EncodingError(error) => f.write_str(error),
+        }
+    }
+}
+
+/// Builder for anti-aliased rasters, can set margin, module size, colors,
+/// shapes and gradient
+pub struct SkiaBuilder {
+    /// The quiet zone around the code, in modules, default is 4
+    margin: usize,
+    /// Width of a module, in pixels, default is 10
+    module_size: f32,
+    /// The color of dark modules, default is #000000
+    module_color: Color,
+    /// The color of light modules and of the margin, default is #FFFFFF
+    background_color: Color,
+    /// Gradient filling dark modules, overrides `module_color`
+    gradient: Option<Gradient>,
+    /// Shapes drawn on top of each other, default is a square
+    shapes: Vec<Shape>,
+    /// Whether edges are anti-aliased, default is true
+    anti_alias: bool,
+}
+
+/// Creates a Builder instance
+impl Default for SkiaBuilder {
+    fn default() -> Self {
+        SkiaBuilder {
+            margin: 4,
+            module_size: 10.0,
+            module_color: [0, 0, 0, 255].into(),
+            background_color: [255; 4].into(),
+            gradient: None,
+            shapes: Vec::new(),
+            anti_alias: true,
+        }
+    }
+}
+
+impl SkiaBuilder {
+    /// Updates margin (default: 4)
+    pub fn margin(&mut self, margin: usize) -> &mut Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Updates the width of a module in pixels, fractions included (default: 10)
+    pub fn module_size(&mut self, module_size: f32) -> &mut Self {
+        self.module_size = module_size;
+        self
+    }
+
+    /// Updates module color (default: #000000)
+    pub fn module_color<C: Into<Color>>(&mut self, module_color: C) -> &mut Self {
+        self.module_color = module_color.into();
+        self
+    }
+
+    /// Updates background color (default: #FFFFFF)
+    pub fn background_color<C: Into<Color>>(&mut self, background_color: C) -> &mut Self {
+        self.background_color = background_color.into();
+        self
+    }
+
+    /// Fills dark modules with a gradient instead of the module color
+    pub fn gradient(&mut self, gradient: Gradient) -> &mut Self {
+        self.gradient = Some(gradient);
+        self
+    }
+
+    /// Adds a shape to the shapes list, drawn over the previous ones.
+    /// [`Shape::Command`] returns SVG paths, which are not parsed: it is
+    /// drawn as a square.
+    pub fn shape(&mut self, shape: Shape) -> &mut Self {
+        self.shapes.push(shape);
+        self
+    }
+
+    /// Enables anti-aliasing (default: true). Square modules with a whole
+    /// `module_size` are pixel-exact either way.
+    pub fn anti_alias(&mut self, anti_alias: bool) -> &mut Self {
+        self.anti_alias = anti_alias;
+        self
+    }
+
+    /// Returns a [`Pixmap`] of the code, quiet zone included
+    ///
+    /// # Errors
+    /// - `SkiaError::Color` if a color is not a hexadecimal color
+    /// - `SkiaError::Size` if the pixmap would be empty or too large
+    pub fn to_pixmap<M: Matrix>(&self, qr: &M) -> Result<Pixmap, SkiaError> {
+        let n = qr.size();
+        let modules = (n + self.margin * 2) as f32;
+        let width = (modules * self.module_size).ceil();
+        if !(width >= 1.0 && width <= u32::MAX as f32) {
+            return Err(SkiaError::Size);
+        }
+        let mut pixmap = Pixmap::new(width as u32, width as u32).ok_or(SkiaError::Size)?;
+
+        let [r, g, b, a] = color(&self.background_color)?;
+        pixmap.fill(tiny_skia::Color::from_rgba8(r, g, b, a));
+
+        let paint = Paint {
+            shader: self.shader(n)?,
+            anti_alias: self.anti_alias,
+            ..Paint::default()
+        };
+        let transform = Transform::from_scale(self.module_size, self.module_size);
+
+        let default = [Shape::Square];
+        let shapes = if self.shapes.is_empty() {
+            &default[..]
+        } else {
+            &self.shapes[..]
+        };
+        for &shape in shapes {
+            let mut path = PathBuilder::new();
+            for y in 0..n {
+                for x in (0..n).filter(|&x| qr.module(y, x).value()) {
+                    let (x, y) = ((x + self.margin) as f32, (y + self.margin) as f32);
+                    push_shape(&mut path, shape, x, y);
+                }
+            }
+            // No dark module at all
+            if let Some(path) = path.finish() {
+                pixmap.fill_path(&path, &paint, FillRule::Winding, transform, None);
+            }
+        }
+
+        Ok(pixmap)
+    }
+
+    /// Returns the PNG bytes of the code
+    ///
+    /// # Errors
+    /// - same as [`to_pixmap`](SkiaBuilder::to_pixmap)
+    /// - `SkiaError::EncodingError` if the encoder fails
+    pub fn to_bytes<M: Matrix>(&self, qr: &M) -> Result<Vec<u8>, SkiaError> {
+        self.to_pixmap(qr)?
+            .encode_png()
+            .map_err(|err| SkiaError::EncodingError(err.to_string()))
+    }
+
+    /// Saves the PNG of the code to a file
+    ///
+    /// # Errors
+    /// - same as [`to_bytes`](SkiaBuilder::to_bytes)
+    /// - `SkiaError::EncodingError` if the file cannot be written
+    pub fn to_file<M: Matrix>(&self, qr: &M, file: &str) -> Result<(), SkiaError> {
+        std::fs::write(file, self.to_bytes(qr)?)
+            .map_err(|err| SkiaError::EncodingError(err.to_string()))
+    }
+
+    /// Paint of dark modules, gradients spanning the code without its
+    /// margin like in [`SvgBuilder`](super::svg::SvgBuilder)
+    fn shader(&self, n: usize) -> Result<Shader<'static>, SkiaError> {
+        let start = self.margin as f32;
+        let center = start + n as f32 / 2.0;
+
+        let shader = match &self.gradient {
+            None => {
+                let [r, g, b, a] = color(&self.module_color)?;
+                return Ok(Shader::SolidColor(tiny_skia::Color::from_rgba8(r, g, b, a)));
+            }
+            Some(Gradient::Linear {
+                start: start_color,
+                end: end_color,
+                angle,
+            }) => {
+                let (sin, cos) = (*angle as f32).to_radians().sin_cos();
+                let half = n as f32 / 2.0;
+                LinearGradient::new(
+                    Point::from_xy(center - half * cos, center - half * sin),
+                    Point::from_xy(center + half * cos, center + half * sin),
+                    vec![stop(0.0, start_color)?, stop(1.0, end_color)?],
+                    SpreadMode::Pad,
+                    Transform::identity(),
+                )
+            }
+            Some(Gradient::Radial { inner, outer }) => RadialGradient::new(
+                Point::from_xy(center, center),
+                Point::from_xy(center, center),
+                n as f32 / core::f32::consts::SQRT_2,
+                vec![stop(0.0, inner)?, stop(1.0, outer)?],
+                SpreadMode::Pad,
+                Transform::identity(),
+            ),
+        };
+
+        shader.ok_or(SkiaError::Size)
+    }
+}
+
+impl Renderer for SkiaBuilder {
+    type Output = Result<Vec<u8>, SkiaError>;
+
+    fn render(&self, qr: &QRCode) -> Self::Output {
+        self.to_bytes(qr)
+    }
+}
+
+fn color(color: &Color) -> Result<[u8; 4], SkiaError> {
+    parse_color(color).map_err(|_| SkiaError::Color(String::from(color.to_str())))
+}
+
+fn stop(position: f32, stop_color: &Color) -> Result<GradientStop, SkiaError> {
+    let [r, g, b, a] = color(stop_color)?;
+    Ok(GradientStop::new(
+        position,
+        tiny_skia::Color::from_rgba8(r, g, b, a),
+    ))
+}
+
+/// Adds the outline of `shape` for the module at (`x`, `y`), matching the
+/// SVG paths of [`Shape`]
+fn push_shape(path: &mut PathBuilder, shape: Shape, x: f32, y: f32) {
+    match shape {
+        Shape::Circle => path.push_circle(x + 0.5, y + 0.5, 0.5),
+        // A .6 square stroked .3 wide with round joins
+        Shape::RoundedSquare => {
+            let (left, top, right, bottom) = (x + 0.05, y + 0.05, x + 0.95, y + 0.95);
+            let r = 0.15;
+            path.move_to(left + r, top);
+            path.line_to(right - r, top);
+            path.quad_to(right, top, right, top + r);
+            path.line_to(right, bottom - r);
+            path.quad_to(right, bottom, right - r, bottom);
+            path.line_to(left + r, bottom);
+            path.quad_to(left, bottom, left, bottom - r);
+            path.line_to(left, top + r);
+            path.quad_to(left, top, left + r, top);
+            path.close();
+        }
+        Shape::Vertical => path.push_rect(x + 0.1, y, 0.8, 1.0),
+        Shape::Horizontal => path.push_rect(x, y + 0.1, 1.0, 0.8),
+        Shape::Diamond => {
+            path.move_to(x + 0.5, y);
+            path.line_to(x + 1.0, y + 0.5);
+            path.line_to(x + 0.5, y + 1.0);
+            path.line_to(x, y + 0.5);
+            path.close();
+        }
+        Shape::Square => path.push_rect(x, y, 1.0, 1.0),
+        #[cfg(not(feature = "wasm-bindgen"))]
+        Shape::Command(_) => path.push_rect(x, y, 1.0, 1.0),
+    }
+}
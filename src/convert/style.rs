@@ -0,0 +1,267 @@
+//! Checks a styled [`SvgBuilder`] before a [`QRCode`] is exported with it
+//!
+//! Colors, logos and margins make codes prettier, and often harder to scan.
+//! [`StyleValidator`] looks at the contrast of every module color, the
+//! codewords hidden behind the logo and the quiet zone, and reports what is
+//! likely to fail as [`StyleIssue`]s.
+//!
+//! ```rust
+//! use fast_qr::convert::style::{Severity, StyleIssue, StyleValidator};
+//! use fast_qr::convert::{svg::SvgBuilder, Builder};
+//! use fast_qr::qr::QRBuilder;
+//!
+//! let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+//!
+//! let mut builder = SvgBuilder::default();
+//! builder.module_color("#BBBBBB").margin(2);
+//!
+//! let issues = StyleValidator::default().validate(&builder, &qrcode);
+//! assert!(matches!(issues[0], StyleIssue::Contrast { .. }));
+//! assert_eq!(issues[0].severity(), Severity::Error);
+//! assert_eq!(issues[1], StyleIssue::QuietZone { margin: 2, min: 4 });
+//! ```
+
+use core::fmt::{Display, Formatter};
+
+use super::dark_mode::{blend, luminance, parse_color};
+use super::svg::SvgBuilder;
+use crate::overlay::{correctable_per_block, Overlay, OverlayPlanner};
+use crate::QRCode;
+
+/// How likely a [`StyleIssue`] is to make the code unscannable
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Some scanners or some print conditions may fail
+    Warning,
+    /// Most scanners will fail, the code should not be exported
+    Error,
+}
+
+/// Problem found by a [`StyleValidator`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum StyleIssue {
+    /// The color is not `#rgb`, `#rgba`, `#rrggbb` or `#rrggbbaa`, its
+    /// contrast cannot be checked
+    Color(String),
+    /// Dark modules are lighter than the background, which some scanners
+    /// cannot read
+    Inverted,
+    /// The lowest contrast ratio between a module color and the background
+    /// is under the minimum
+    Contrast {
+        /// Contrast ratio, from 1 to 21
+        ratio: f64,
+        /// Minimum set with [`StyleValidator::min_contrast`]
+        min: f64,
+    },
+    /// The contrast depends on the background image, check the rendered
+    /// pixels instead
+    BackgroundImage,
+    /// The logo hides part of a finder pattern
+    LogoOverFinder,
+    /// The logo damages more codewords than a block can recover, or close
+    /// to it (over three quarters)
+    LogoCoverage {
+        /// Damaged codewords of the block that suffers the most
+        damaged: usize,
+        /// Codewords each block can recover
+        capacity: usize,
+    },
+    /// The logo goes past the code, over the quiet zone
+    LogoInQuietZone,
+    /// The margin is narrower than the minimum
+    QuietZone {
+        /// Margin of the builder, in modules
+        margin: usize,
+        /// Minimum set with [`StyleValidator::min_margin`]
+        min: usize,
+    },
+}
+
+impl StyleIssue {
+    /// Returns how likely the issue is to make the code unscannable
+    #[must_use]
+    pub fn severity(&self) -> Severity {
+        match self {
+            StyleIssue::Contrast { .. } | StyleIssue::LogoOverFinder => Severity::Error,
+            StyleIssue::LogoCoverage { damaged, capacity } if damaged > capacity => Severity::Error,
+            StyleIssue::LogoInQuietZone | StyleIssue::QuietZone { margin: 0, .. } => {
+                Severity::Error
+            }
+            _ => Severity::Warning,
+        }
+    }
+}
+
+impl std::error::Error for StyleIssue {}
+
+impl Display for StyleIssue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            StyleIssue::Color(color) => write!(f, "Unsupported color {color}"),
+            StyleIssue::Inverted => f.write_str("Modules are lighter than the background"),
+            StyleIssue::Contrast { ratio, min } => {
+                write!(f, "Contrast ratio {ratio:.2} is under {min:.2}")
+            }
+            StyleIssue::BackgroundImage => {
+                f.write_str("Contrast cannot be checked over a background image")
+            }
+            StyleIssue::LogoOverFinder => f.write_str("The logo covers a finder pattern"),
+            StyleIssue::LogoCoverage { damaged, capacity } => write!(
+                f,
+                "The logo damages {damaged} codewords of a block recovering {capacity}"
+            ),
+            StyleIssue::LogoInQuietZone => f.write_str("The logo covers the quiet zone"),
+            StyleIssue::QuietZone { margin, min } => {
+                write!(f, "Margin of {margin} modules is under {min}")
+            }
+        }
+    }
+}
+
+/// Validates the style of a [`SvgBuilder`], can set the minimum contrast
+/// and margin
+pub struct StyleValidator {
+    /// Minimum contrast ratio between module colors and background, default is 3
+    min_contrast: f64,
+    /// Minimum margin, in modules, default is 4 as per spec
+    min_margin: usize,
+}
+
+/// Creates a Validator instance
+impl Default for StyleValidator {
+    fn default() -> Self {
+        StyleValidator {
+            min_contrast: 3.0,
+            min_margin: 4,
+        }
+    }
+}
+
+impl StyleValidator {
+    /// Updates the minimum contrast ratio between module colors and the
+    /// background, from 1 to 21 as defined by WCAG 2 (default: 3)
+    pub fn min_contrast(&mut self, min_contrast: f64) -> &mut Self {
+        self.min_contrast = min_contrast;
+        self
+    }
+
+    /// Updates the minimum margin, in modules (default: 4)
+    pub fn min_margin(&mut self, min_margin: usize) -> &mut Self {
+        self.min_margin = min_margin;
+        self
+    }
+
+    /// Returns the issues of `builder` when rendering `qr`, colors first,
+    /// then the logo and the quiet zone. An empty list means nothing was found.
+    #[must_use]
+    pub fn validate(&self, builder: &SvgBuilder, qr: &QRCode) -> Vec<StyleIssue> {
+        let mut issues = Vec::new();
+        self.check_colors(builder, &mut issues);
+        Self::check_logo(builder, qr, &mut issues);
+        if builder.margin < self.min_margin {
+            issues.push(StyleIssue::QuietZone {
+                margin: builder.margin,
+                min: self.min_margin,
+            });
+        }
+        issues
+    }
+
+    fn check_colors(&self, builder: &SvgBuilder, issues: &mut Vec<StyleIssue>) {
+        if builder.background_image.is_some() {
+            issues.push(StyleIssue::BackgroundImage);
+            return;
+        }
+
+        // The background is drawn on white paper
+        let background = match parse_color(&builder.background_color) {
+            Ok(color) => blend(color, [1.0; 3]),
+            Err(_) => {
+                issues.push(StyleIssue::Color(String::from(
+                    builder.background_color.to_str(),
+                )));
+                return;
+            }
+        };
+        let light = luminance(background);
+
+        let mut inverted = false;
+        let mut lowest: Option<f64> = None;
+        for color in builder.dark_colors() {
+            let mut rgba = match parse_color(color) {
+                Ok(rgba) => rgba,
+                Err(_) => {
+                    issues.push(StyleIssue::Color(String::from(color.to_str())));
+                    continue;
+                }
+            };
+            rgba[3] = (f64::from(rgba[3]) * builder.dark_opacity).round() as u8;
+
+            let dark = luminance(blend(rgba, background));
+            if dark > light {
+                inverted = true;
+                continue;
+            }
+            let ratio = (light + 0.05) / (dark + 0.05);
+            lowest = Some(lowest.map_or(ratio, |lowest| lowest.min(ratio)));
+        }
+
+        if inverted {
+            issues.push(StyleIssue::Inverted);
+        }
+        if let Some(ratio) = lowest.filter(|&ratio| ratio < self.min_contrast) {
+            issues.push(StyleIssue::Contrast {
+                ratio,
+                min: self.min_contrast,
+            });
+        }
+    }
+
+    fn check_logo(builder: &SvgBuilder, qr: &QRCode, issues: &mut Vec<StyleIssue>) {
+        let n = qr.size;
+        let (x, y, size, _) = match builder.image_area(n, builder.margin, qr.ecl) {
+            Some(area) => area,
+            None => return,
+        };
+
+        // Image background, relative to the top left module
+        let (left, top) = (x - builder.margin as f64, y - builder.margin as f64);
+        let (right, bottom) = (left + size, top + size);
+        let end = n as f64;
+        if left < 0.0 || top < 0.0 || right > end || bottom > end {
+            issues.push(StyleIssue::LogoInQuietZone);
+        }
+
+        // Modules even partially hidden
+        let first = |start: f64| start.max(0.0).min(end).floor() as usize;
+        let last = |stop: f64| stop.max(0.0).min(end).ceil() as usize;
+        let overlay = Overlay {
+            x: first(left),
+            y: first(top),
+            width: last(right).saturating_sub(first(left)),
+            height: last(bottom).saturating_sub(first(top)),
+        };
+        if overlay.width == 0 || overlay.height == 0 {
+            return;
+        }
+
+        let finders = [(0, 0), (0, n - 7), (n - 7, 0)];
+        if finders.iter().any(|&(fy, fx)| {
+            overlay.x < fx + 7
+                && fx < overlay.x + overlay.width
+                && overlay.y < fy + 7
+                && fy < overlay.y + overlay.height
+        }) {
+            issues.push(StyleIssue::LogoOverFinder);
+        }
+
+        if let (Some(version), Some(ecl)) = (qr.version, qr.ecl) {
+            let damaged = OverlayPlanner::new(version, ecl).worst_block_damage(&overlay);
+            let capacity = correctable_per_block(version, ecl);
+            if damaged * 4 > capacity * 3 {
+                issues.push(StyleIssue::LogoCoverage { damaged, capacity });
+            }
+        }
+    }
+}
@@ -1,4 +1,4 @@
-//! Converts [`QRCode`] to SVG
+//! Converts [`QRCode`](crate::QRCode) to SVG
 //!
 //! ```rust
 //! use fast_qr::convert::ConvertError;
@@ -21,9 +21,38 @@
 //! # }
 //! ```
 
-use crate::{QRCode, Version};
+use core::fmt::{self, Write};
 
-use super::{Builder, Color, ImageBackgroundShape, ModuleFunction, Shape};
+use crate::fingerprint::{self, Fingerprint};
+use crate::matrix::Matrix;
+use crate::overlay::OverlayPlanner;
+use crate::{ModuleType, QRCode, Version, ECL};
+
+use super::vector::{clamp_bleed, number};
+use super::{
+    Builder, Color, EyeShape, Gradient, ImageBackgroundShape, ModuleFunction, Renderer, Shape,
+};
+
+/// Id of the gradient definition referenced by dark modules
+const GRADIENT_ID: &str = "fast_qr_gradient";
+
+/// Adapts an `io::Write` to the `fmt::Write` the svg is rendered to,
+/// keeping the error `fmt::Error` cannot carry
+#[cfg(not(feature = "wasm-bindgen"))]
+struct IoWriter<W> {
+    inner: W,
+    error: Option<std::io::Error>,
+}
+
+#[cfg(not(feature = "wasm-bindgen"))]
+impl<W: std::io::Write> Write for IoWriter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.write_all(s.as_bytes()).map_err(|err| {
+            self.error = Some(err);
+            fmt::Error
+        })
+    }
+}
 
 /// Builder for svg, can set shape, margin, background_color, dot_color
 pub struct SvgBuilder {
@@ -34,12 +63,31 @@ pub struct SvgBuilder {
     /// The default is `dot_color`, commands with specific colors can be
     /// added using `.shape_color()`
     command_colors: Vec<Option<Color>>,
+    /// Commands can be restricted to a single module type
+    /// Modules of a type with dedicated commands skip the generic ones
+    command_module_types: Vec<Option<ModuleType>>,
+    /// Module types can have their own color (indexed by `ModuleType as u8 >> 1`)
+    module_type_colors: [Option<Color>; 8],
+    /// Outer and inner shapes of the finder patterns, drawn module by module when `None`
+    eye_shapes: Option<(EyeShape, EyeShape)>,
     /// The margin for the svg, default is 4
-    margin: usize,
+    pub(crate) margin: usize,
+    /// Growth of dark modules on every side, in modules, default is 0
+    bleed: f64,
     /// The background color for the svg, default is #FFFFFF
-    background_color: Color,
+    pub(crate) background_color: Color,
     /// The color for each module, default is #000000
     dot_color: Color,
+    /// Gradient filling dark modules, overrides `dot_color`
+    gradient: Option<Gradient>,
+    /// Image drawn under the modules, margin included, can be a path or a base64 string
+    pub(crate) background_image: Option<String>,
+    /// Opacity of dark modules, from 0 to 1, default is 1
+    pub(crate) dark_opacity: f64,
+    /// Stroke width when dark modules are outlined instead of filled, in modules
+    outline: Option<f64>,
+    /// Whether adjacent square modules are merged into rectangles, default is false
+    shape_optimization: bool,
 
     // Image Embedding
     /// Image to embed in the svg, can be a path or a base64 string
@@ -50,10 +98,128 @@ pub struct SvgBuilder {
     image_background_shape: ImageBackgroundShape,
     /// Size of the image (in module size), default is ~1/3 of the svg
     image_size: Option<f64>,
+    /// Size of the image (in percent of the QRCode width), overrides `image_size`
+    image_size_percent: Option<f64>,
     /// Gap between the image and the border (in module size), default is calculated
     image_gap: Option<f64>,
     /// Position of the image, default is center
     image_position: Option<(f64, f64)>,
+
+    /// `QRBuilder::options_fingerprint` of the rendered code, written with
+    /// the render options in a `data-fingerprint` attribute
+    qr_fingerprint: Option<u64>,
+}
+
+/// Paths of a [`QRCode`](crate::QRCode) for a given set of shapes, margin and bleed, without colors.
+///
+/// Computing the paths is the expensive part of a render: keep the geometry
+/// and call [`SvgBuilder::to_str_with_geometry`] to render the same code in
+/// several themes.
+///
+/// ```rust
+/// use fast_qr::convert::{svg::SvgBuilder, Builder};
+/// use fast_qr::qr::QRBuilder;
+///
+/// let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+/// let mut builder = SvgBuilder::default();
+/// let geometry = builder.geometry(&qrcode);
+///
+/// let light = builder.to_str_with_geometry(&geometry);
+/// let dark = builder
+///     .module_color("#FFFFFF")
+///     .background_color("#000000")
+///     .to_str_with_geometry(&geometry);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SvgGeometry {
+    size: usize,
+    margin: usize,
+    commands: Vec<GeometryCommand>,
+    bleed: f64,
+    /// Outlines of the finder patterns when they have eye shapes
+    eyes: String,
+    /// Outlines of light modules and of the code, stroked to shrink dark
+    /// modules when `bleed` is negative
+    light_path: String,
+    /// Error correction level of the code, which bounds the image size
+    ecl: Option<ECL>,
+}
+
+/// Path drawn by one command, split in runs of same-typed modules
+#[derive(Debug, Clone)]
+struct GeometryCommand {
+    function: ModuleFunction,
+    /// Index of the command in the builder, `None` for the default square
+    color_index: Option<usize>,
+    module_type: Option<ModuleType>,
+    path: String,
+    /// `(ModuleType as u8 >> 1, end of the run in path)`
+    runs: Vec<(usize, usize)>,
+}
+
+impl GeometryCommand {
+    fn new(
+        function: ModuleFunction,
+        color_index: Option<usize>,
+        module_type: Option<ModuleType>,
+        size: usize,
+    ) -> Self {
+        GeometryCommand {
+            function,
+            color_index,
+            module_type,
+            path: String::with_capacity(10 * size * size),
+            runs: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, module_type: ModuleType, segment: &str) {
+        self.path.push_str(segment);
+
+        let type_index = module_type as usize >> 1;
+        match self.runs.last_mut() {
+            Some(run) if run.0 == type_index => run.1 = self.path.len(),
+            _ => self.runs.push((type_index, self.path.len())),
+        }
+    }
+
+    /// Pushes the modules of `cells` (their type, row by row, `None` when
+    /// not drawn) as rectangles: each run of a row of same-typed modules
+    /// goes down as long as the rows below repeat it
+    fn push_rects(&mut self, mut cells: Vec<Option<ModuleType>>, n: usize, margin: usize) {
+        for y in 0..n {
+            let mut x = 0;
+            while x < n {
+                let cell_type = match cells[y * n + x] {
+                    Some(cell_type) => cell_type,
+                    None => {
+                        x += 1;
+                        continue;
+                    }
+                };
+
+                let mut width = 1;
+                while x + width < n && cells[y * n + x + width] == Some(cell_type) {
+                    width += 1;
+                }
+                let mut height = 1;
+                while y + height < n
+                    && cells[(y + height) * n + x..][..width]
+                        .iter()
+                        .all(|&cell| cell == Some(cell_type))
+                {
+                    height += 1;
+                }
+                for row in y..y + height {
+                    cells[row * n + x..][..width].fill(None);
+                }
+
+                let segment = format!("M{},{}h{width}v{height}h-{width}", x + margin, y + margin);
+                self.push(cell_type, &segment);
+                x += width;
+            }
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -72,17 +238,29 @@ impl Default for SvgBuilder {
         SvgBuilder {
             background_color: [255; 4].into(),
             dot_color: [0, 0, 0, 255].into(),
+            gradient: None,
+            background_image: None,
+            dark_opacity: 1.0,
+            outline: None,
+            shape_optimization: false,
             margin: 4,
+            bleed: 0.0,
             commands: Vec::new(),
             command_colors: Vec::new(),
+            command_module_types: Vec::new(),
+            module_type_colors: Default::default(),
+            eye_shapes: None,
 
             // Image Embedding
             image: None,
             image_background_color: [255; 4].into(),
             image_background_shape: ImageBackgroundShape::Square,
             image_size: None,
+            image_size_percent: None,
             image_gap: None,
             image_position: None,
+
+            qr_fingerprint: None,
         }
     }
 }
@@ -106,12 +284,14 @@ impl Builder for SvgBuilder {
     fn shape(&mut self, shape: Shape) -> &mut Self {
         self.commands.push(*shape);
         self.command_colors.push(None);
+        self.command_module_types.push(None);
         self
     }
 
     fn shape_color<C: Into<Color>>(&mut self, shape: Shape, color: C) -> &mut Self {
         self.commands.push(*shape);
         self.command_colors.push(Some(color.into()));
+        self.command_module_types.push(None);
         self
     }
 
@@ -149,6 +329,63 @@ impl Builder for SvgBuilder {
     }
 }
 
+impl SvgBuilder {
+    /// Fills dark modules with a gradient instead of the module color
+    pub fn gradient(&mut self, gradient: Gradient) -> &mut Self {
+        self.gradient = Some(gradient);
+        self
+    }
+
+    /// Adds a shape used only for modules of `module_type` (i.e. finder patterns).
+    /// Modules of that type then ignore the shapes added with `.shape()`
+    pub fn module_shape(&mut self, module_type: ModuleType, shape: Shape) -> &mut Self {
+        self.commands.push(*shape);
+        self.command_colors.push(None);
+        self.command_module_types.push(Some(module_type));
+        self
+    }
+
+    /// Same as `.module_shape()` with a specific color
+    pub fn module_shape_color<C: Into<Color>>(
+        &mut self,
+        module_type: ModuleType,
+        shape: Shape,
+        color: C,
+    ) -> &mut Self {
+        self.commands.push(*shape);
+        self.command_colors.push(Some(color.into()));
+        self.command_module_types.push(Some(module_type));
+        self
+    }
+
+    /// Updates the color of every module of `module_type` (i.e. brand-colored finder patterns).
+    /// Colors given with `.module_shape_color()` still take precedence
+    pub fn module_type_color<C: Into<Color>>(
+        &mut self,
+        module_type: ModuleType,
+        color: C,
+    ) -> &mut Self {
+        self.module_type_colors[module_type as usize >> 1] = Some(color.into());
+        self
+    }
+
+    /// Draws each finder pattern as an `outer` ring around an `inner` square,
+    /// whatever the shapes of the other modules. Shapes given with
+    /// `.module_shape()` no longer apply to finder patterns
+    pub fn eye_shape(&mut self, outer: EyeShape, inner: EyeShape) -> &mut Self {
+        self.eye_shapes = Some((outer, inner));
+        self
+    }
+
+    /// Updates the image size as a percentage of the [`crate::QRCode`] width (margin excluded).
+    /// On `ECL::L` and `ECL::M` codes, images are shrunk to what the code can
+    /// recover, see [`crate::QRBuilder::image_embedding`]
+    pub fn image_size_percent(&mut self, percent: f64) -> &mut Self {
+        self.image_size_percent = Some(percent);
+        self
+    }
+}
+
 impl SvgBuilder {
     fn image_placement(image_background_shape: ImageBackgroundShape, n: usize) -> (f64, f64) {
         use ImageBackgroundShape::{Circle, RoundedSquare, Square};
@@ -167,8 +404,9 @@ impl SvgBuilder {
         const ROUNDED_SQUARE: [f64; 40] = SQUARE;
         const CIRCLE: [f64; 40] = SQUARE;
 
-        // Using hardcoded values
-        let version = Version::from_n(n) as usize;
+        // Using hardcoded values, indexed by version from 0 (V01). Custom
+        // matrices of other sizes get the closest version
+        let version = (n.saturating_sub(17) / 4).clamp(1, 40) - 1;
         let border_size = match image_background_shape {
             Square => SQUARE[version],
             RoundedSquare => ROUNDED_SQUARE[version],
@@ -185,18 +423,55 @@ impl SvgBuilder {
         (border_size, (border_size - gap).round())
     }
 
-    fn image(&self, n: usize) -> String {
-        if self.image.is_none() {
-            return String::new();
+    /// Every color dark modules can be drawn in: the module color or the
+    /// gradient stops, then the shape and module type colors
+    pub(crate) fn dark_colors(&self) -> Vec<&Color> {
+        let mut colors = match &self.gradient {
+            None => vec![&self.dot_color],
+            Some(Gradient::Linear { start, end, .. }) => vec![start, end],
+            Some(Gradient::Radial { inner, outer }) => vec![inner, outer],
+        };
+        colors.extend(self.command_colors.iter().flatten());
+        colors.extend(self.module_type_colors.iter().flatten());
+        colors
+    }
+
+    /// Largest image background an `ECL::L` or `ECL::M` code of size `n`
+    /// can recover from, in modules, `None` for other levels
+    fn recoverable_size(n: usize, ecl: Option<ECL>) -> Option<f64> {
+        let ecl = ecl.filter(|&ecl| matches!(ecl, ECL::L | ECL::M))?;
+        let version = Version::from_number(n.checked_sub(17)? / 4)?;
+        if version.size() != n {
+            return None;
         }
 
-        let image = self.image.as_ref().unwrap();
-        let mut out = String::with_capacity(image.len() + 100);
+        let side = OverlayPlanner::new(version, ecl)
+            .verify(true)
+            .plan()
+            .map_or(0, |overlay| overlay.width.min(overlay.height));
+        Some(side as f64)
+    }
+
+    /// Top left corner and size of the image background, then size of the
+    /// image itself, in svg units (margin included), `None` without image.
+    /// Images too large for the `ecl` of the code are shrunk, or dropped when
+    /// nothing fits
+    pub(crate) fn image_area(
+        &self,
+        n: usize,
+        margin: usize,
+        ecl: Option<ECL>,
+    ) -> Option<(f64, f64, f64, f64)> {
+        self.image.as_ref()?;
 
         let (mut border_size, mut image_size) =
             Self::image_placement(self.image_background_shape, n);
 
-        if let Some(override_size) = self.image_size {
+        let percent_size = self
+            .image_size_percent
+            .map(|percent| (n as f64 * percent / 100f64).round());
+
+        if let Some(override_size) = percent_size.or(self.image_size) {
             let gap = -(image_size - border_size);
             border_size = override_size + gap;
             image_size = override_size;
@@ -206,7 +481,18 @@ impl SvgBuilder {
             border_size = image_size + override_gap * 2f64;
         }
 
-        let mut placed_coord_x = (self.margin * 2 + n) as f64 - border_size;
+        // Low error correction cannot make up for a large logo
+        if let Some(max_size) = Self::recoverable_size(n, ecl) {
+            if border_size > max_size {
+                image_size -= border_size - max_size;
+                border_size = max_size;
+            }
+            if image_size <= 0f64 {
+                return None;
+            }
+        }
+
+        let mut placed_coord_x = (margin * 2 + n) as f64 - border_size;
 
         // Adjust for non-integer initial x coordinates so as not to partially cover bits by rounding down.
         if placed_coord_x % 2f64 != 0f64 {
@@ -214,7 +500,7 @@ impl SvgBuilder {
             border_size -= 1f64;
         }
 
-        placed_coord_x = placed_coord_x / 2f64;
+        placed_coord_x /= 2f64;
 
         let mut placed_coord = (placed_coord_x, placed_coord_x);
 
@@ -222,6 +508,18 @@ impl SvgBuilder {
             placed_coord = (x - border_size / 2f64, y - border_size / 2f64);
         }
 
+        Some((placed_coord.0, placed_coord.1, border_size, image_size))
+    }
+
+    fn image(&self, n: usize, margin: usize, ecl: Option<ECL>) -> String {
+        let (image, (x, y, border_size, image_size)) =
+            match (&self.image, self.image_area(n, margin, ecl)) {
+                (Some(image), Some(area)) => (image, area),
+                _ => return String::new(),
+            };
+        let placed_coord = (x, y);
+        let mut out = String::with_capacity(image.len() + 100);
+
         let format = match self.image_background_shape {
             ImageBackgroundShape::Square => {
                 r#"<rect x="{0}" y="{1}" width="{2}" height="{2}" fill="{3}"/>"#
@@ -238,7 +536,7 @@ impl SvgBuilder {
             .replace("{0}", &placed_coord.0.to_string())
             .replace("{1}", &placed_coord.1.to_string())
             .replace("{2}", &border_size.to_string())
-            .replace("{3}", &self.image_background_color.to_str());
+            .replace("{3}", self.image_background_color.to_str());
 
         out.push_str(&format);
 
@@ -253,91 +551,620 @@ impl SvgBuilder {
         out
     }
 
-    fn path(&self, qr: &QRCode) -> String {
-        const DEFAULT_COMMAND: [ModuleFunction; 1] = [Shape::square];
-        const DEFAULT_COMMAND_COLOR: [Option<Color>; 1] = [None];
+    /// Grows dark modules by `bleed` modules on every side, or shrinks them
+    /// when negative, from -0.5 to 0.5 (default: 0)
+    ///
+    /// Dark paths are stroked in their own color to grow, which suits every
+    /// shape. To shrink, light modules and the border of the code are stroked
+    /// in the background color, which suits square modules on an opaque
+    /// background.
+    pub fn bleed(&mut self, bleed: f64) -> &mut Self {
+        self.bleed = clamp_bleed(bleed);
+        self
+    }
 
-        // TODO: cleanup this basic logic
-        let command_colors: &[Option<Color>] = if !self.commands.is_empty() {
-            &self.command_colors
-        } else {
-            &DEFAULT_COMMAND_COLOR
-        };
-        let commands: &[ModuleFunction] = if !self.commands.is_empty() {
-            &self.commands
+    /// Draws `image` (a path or a data URI) over the background color and
+    /// under the modules, scaled to cover the whole svg, margin included.
+    /// Light modules let it show through, check the result with
+    /// `ImageBuilder::background_warning` when rendering to png.
+    pub fn background_image(&mut self, image: String) -> &mut Self {
+        self.background_image = Some(image);
+        self
+    }
+
+    /// Updates the opacity of dark modules, from 0 to 1 (default: 1).
+    /// Lower values blend them with the background image.
+    pub fn dark_opacity(&mut self, opacity: f64) -> &mut Self {
+        self.dark_opacity = if opacity.is_nan() {
+            1.0
         } else {
-            &DEFAULT_COMMAND
+            opacity.clamp(0.0, 1.0)
         };
+        self
+    }
 
-        let mut paths = vec![String::with_capacity(10 * qr.size * qr.size); commands.len()];
-        for path in paths.iter_mut() {
-            path.push_str(r#"<path d=""#);
+    /// Outlines dark modules with a `stroke_width` modules wide stroke in
+    /// their color, without filling them, for laser cutters and plotters
+    /// (default: filled). A width of `0` fills them again.
+    ///
+    /// Every shape is outlined on its own, so adjacent square modules share
+    /// an edge that is drawn twice. [`bleed`](SvgBuilder::bleed) is ignored.
+    ///
+    /// ```rust
+    /// use fast_qr::convert::svg::SvgBuilder;
+    /// use fast_qr::qr::QRBuilder;
+    ///
+    /// let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    /// let svg = SvgBuilder::default().outline(0.1).to_str(&qrcode);
+    /// assert!(svg.contains(r##"fill="none" stroke-width="0.1" stroke="#000000""##));
+    /// ```
+    pub fn outline(&mut self, stroke_width: f64) -> &mut Self {
+        self.outline = Some(stroke_width).filter(|&width| width > 0.0);
+        self
+    }
+
+    /// Merges adjacent square modules of the same type into rectangles
+    /// (default: false). The code looks the same with far fewer path
+    /// commands, which halves the size of large versions.
+    ///
+    /// ```rust
+    /// use fast_qr::convert::svg::SvgBuilder;
+    /// use fast_qr::qr::QRBuilder;
+    /// use fast_qr::Version;
+    ///
+    /// let qrcode = QRBuilder::new("https://example.com/")
+    ///     .version(Version::V30)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let plain = SvgBuilder::default().to_str(&qrcode);
+    /// let merged = SvgBuilder::default()
+    ///     .shape_optimization(true)
+    ///     .to_str(&qrcode);
+    /// assert!(merged.len() * 2 < plain.len());
+    /// ```
+    pub fn shape_optimization(&mut self, shape_optimization: bool) -> &mut Self {
+        self.shape_optimization = shape_optimization;
+        self
+    }
+
+    /// Computes the path of every command, see [`SvgGeometry`]
+    pub fn geometry<M: Matrix>(&self, qr: &M) -> SvgGeometry {
+        let n = qr.size();
+
+        // Without generic commands (only typed ones or none at all), the
+        // remaining modules are drawn with the default square
+        let default_square = self.command_module_types.iter().all(Option::is_some);
+
+        let mut commands: Vec<GeometryCommand> = Vec::with_capacity(self.commands.len() + 1);
+        if default_square {
+            commands.push(GeometryCommand::new(Shape::square, None, None, n));
+        }
+        for (i, &command) in self.commands.iter().enumerate() {
+            commands.push(GeometryCommand::new(
+                command,
+                Some(i),
+                self.command_module_types[i],
+                n,
+            ));
+        }
+
+        // Module types which have dedicated commands (indexed by `ModuleType as u8 >> 1`)
+        let mut typed = [false; 8];
+        for command in &commands {
+            if let Some(module_type) = command.module_type {
+                typed[module_type as usize >> 1] = true;
+            }
         }
 
-        for y in 0..qr.size {
-            let line = &qr[y];
-            for (x, &cell) in line.iter().enumerate() {
-                if !cell.value() {
+        // Top left corner of the finder patterns, with whether the eye is on
+        // the right or bottom side. Matrices too small to hold them are drawn
+        // with plain modules.
+        let finders = match self.eye_shapes {
+            Some(eye_shapes) if n >= 7 => Some((
+                eye_shapes,
+                [(0, 0, false), (0, n - 7, true), (n - 7, 0, true)],
+            )),
+            _ => None,
+        };
+        let in_eye = |y: usize, x: usize| {
+            finders.map_or(false, |(_, finders)| {
+                finders.iter().any(|&(top, left, _)| {
+                    (top..top + 7).contains(&y) && (left..left + 7).contains(&x)
+                })
+            })
+        };
+
+        // Modules of the square commands to merge, drawn once all are known
+        let square: ModuleFunction = Shape::square;
+        let mut merged: Vec<Option<Vec<Option<ModuleType>>>> = commands
+            .iter()
+            .map(|command| {
+                let merge = self.shape_optimization && command.function as usize == square as usize;
+                merge.then(|| vec![None; n * n])
+            })
+            .collect();
+
+        for y in 0..n {
+            for x in 0..n {
+                let cell = qr.module(y, x);
+                if !cell.value() || in_eye(y, x) {
                     continue;
                 }
 
-                for (i, command) in commands.iter().enumerate() {
-                    paths[i].push_str(&command(y + self.margin, x + self.margin, cell));
+                let cell_type = cell.module_type();
+                let cell_typed = typed[cell_type as usize >> 1];
+
+                for (command, cells) in commands.iter_mut().zip(&mut merged) {
+                    let applies = match command.module_type {
+                        Some(module_type) => module_type == cell_type,
+                        None => !cell_typed,
+                    };
+                    if !applies {
+                        continue;
+                    }
+                    if let Some(cells) = cells {
+                        cells[y * n + x] = Some(cell_type);
+                        continue;
+                    }
+
+                    let segment = (command.function)(y + self.margin, x + self.margin, cell);
+                    command.push(cell_type, &segment);
                 }
             }
         }
 
-        for (i, &command) in commands.iter().enumerate() {
-            let command_color = command_colors[i].as_ref().unwrap_or(&self.dot_color);
-            // Allows to compare if two function pointers are the same
-            // This works because there is no notion of Generics for `rounded_square`
-            if command as usize == Shape::rounded_square as usize {
-                paths[i].push_str(&format!(
-                    r##"" stroke-width=".3" stroke-linejoin="round" stroke="{}"##,
-                    command_color.to_str()
+        for (command, cells) in commands.iter_mut().zip(merged) {
+            if let Some(cells) = cells {
+                command.push_rects(cells, n, self.margin);
+            }
+        }
+
+        let mut eyes = String::new();
+        if let Some(((outer, inner), finders)) = finders {
+            for &(top, left, flip) in &finders {
+                let (y, x) = ((top + self.margin) as f64, (left + self.margin) as f64);
+                let radii = outer.radii(7.0, flip);
+                eyes.push_str(&EyeShape::outline(x, y, 7.0, radii));
+                // The hole follows the outer outline, one module in
+                let hole = radii.map(|r| (r - 1.0).max(0.0));
+                eyes.push_str(&EyeShape::outline(x + 1.0, y + 1.0, 5.0, hole));
+                eyes.push_str(&EyeShape::outline(
+                    x + 2.0,
+                    y + 2.0,
+                    3.0,
+                    inner.radii(3.0, flip),
                 ));
             }
+        }
+
+        let mut light_path = String::new();
+        if self.bleed < 0.0 {
+            let margin = self.margin;
+            light_path.push_str(&format!("M{margin},{margin}h{n}v{n}h-{n}z"));
+            for y in 0..n {
+                for x in (0..n).filter(|&x| !qr.module(y, x).value()) {
+                    light_path.push_str(&format!("M{},{}h1v1h-1z", x + margin, y + margin));
+                }
+            }
+        }
+
+        SvgGeometry {
+            size: n,
+            margin: self.margin,
+            commands,
+            bleed: self.bleed,
+            eyes,
+            light_path,
+            ecl: qr.ecl(),
+        }
+    }
+
+    fn write_paths<W: Write>(&self, out: &mut W, geometry: &SvgGeometry) -> fmt::Result {
+        let gradient_color = Color::from(format!("url(#{GRADIENT_ID})"));
+        let dot_color = match self.gradient {
+            Some(_) => &gradient_color,
+            None => &self.dot_color,
+        };
+
+        for command in &geometry.commands {
+            let command_color = command
+                .color_index
+                .and_then(|i| self.command_colors.get(i))
+                .and_then(Option::as_ref);
+
+            // Modules whose type has its own color get their own path, unless
+            // the command is restricted to their type with an explicit color
+            let explicit = command.module_type.is_some() && command_color.is_some();
+            let own_path =
+                |type_index: usize| !explicit && self.module_type_colors[type_index].is_some();
+            // Segments of the runs whose type is `type_index`, or `None` for
+            // the runs drawn with the command color
+            let segments = |type_index: Option<usize>| {
+                let mut start = 0;
+                command.runs.iter().filter_map(move |&(run_type, end)| {
+                    let segment = &command.path[start..end];
+                    start = end;
+                    let selected = match type_index {
+                        Some(type_index) => own_path(run_type) && run_type == type_index,
+                        None => !own_path(run_type),
+                    };
+                    Some(segment).filter(|_| selected)
+                })
+            };
+
+            let command_color = command_color.unwrap_or(dot_color);
+            Self::write_path(
+                out,
+                command.function,
+                segments(None),
+                command_color,
+                geometry.bleed,
+                self.outline,
+            )?;
+
+            for (type_index, color) in self.module_type_colors.iter().enumerate() {
+                if let Some(color) = color {
+                    let mut typed = segments(Some(type_index)).peekable();
+                    if typed.peek().is_some() {
+                        Self::write_path(
+                            out,
+                            command.function,
+                            typed,
+                            color,
+                            geometry.bleed,
+                            self.outline,
+                        )?;
+                    }
+                }
+            }
+        }
+
+        if !geometry.eyes.is_empty() {
+            let color = self.module_type_colors[ModuleType::FinderPattern as usize >> 1]
+                .as_ref()
+                .unwrap_or(dot_color);
+            write!(out, r#"<path d="{}" fill-rule="evenodd"#, geometry.eyes)?;
+            if let Some(width) = self.outline {
+                write!(
+                    out,
+                    r#"" fill="none" stroke-width="{}" stroke="{}"/>"#,
+                    number(width),
+                    color.to_str()
+                )?;
+            } else {
+                if geometry.bleed > 0.0 {
+                    write!(
+                        out,
+                        r#"" stroke-width="{}" stroke="{}"#,
+                        number(2.0 * geometry.bleed),
+                        color.to_str()
+                    )?;
+                }
+                write!(out, r#"" fill="{}"/>"#, color.to_str())?;
+            }
+        }
+
+        if !geometry.light_path.is_empty() && self.outline.is_none() {
+            write!(
+                out,
+                r#"<path d="{}" fill="none" stroke-width="{}" stroke="{}"/>"#,
+                geometry.light_path,
+                number(-2.0 * geometry.bleed),
+                self.background_color.to_str()
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn write_path<'a, W: Write, I: Iterator<Item = &'a str>>(
+        out: &mut W,
+        command: ModuleFunction,
+        segments: I,
+        color: &Color,
+        bleed: f64,
+        outline: Option<f64>,
+    ) -> fmt::Result {
+        out.write_str(r#"<path d=""#)?;
+        for segment in segments {
+            out.write_str(segment)?;
+        }
+
+        // Allows to compare if two function pointers are the same
+        // This works because there is no notion of Generics for `rounded_square`
+        let rounded = command as usize == Shape::rounded_square as ModuleFunction as usize;
+        if let Some(width) = outline {
+            write!(out, r#"" fill="none" stroke-width="{}"#, number(width))?;
+            if rounded {
+                out.write_str(r#"" stroke-linejoin="round"#)?;
+            }
+            return write!(out, r#"" stroke="{}"/>"#, color.to_str());
+        }
 
-            paths[i].push_str(&format!(r#"" fill="{}"/>"#, command_color.to_str()));
+        let stroke_width = match (rounded, bleed > 0.0) {
+            (true, false) => Some(String::from(".3")),
+            (true, true) => Some(number(0.3 + 2.0 * bleed)),
+            (false, true) => Some(number(2.0 * bleed)),
+            (false, false) => None,
+        };
+        if let Some(stroke_width) = stroke_width {
+            write!(out, r#"" stroke-width="{stroke_width}"#)?;
+            if rounded {
+                out.write_str(r#"" stroke-linejoin="round"#)?;
+            }
+            write!(out, r#"" stroke="{}"#, color.to_str())?;
         }
 
-        paths.join("")
+        write!(out, r#"" fill="{}"/>"#, color.to_str())
+    }
+
+    fn gradient_definition(&self, n: usize, margin: usize) -> String {
+        let gradient = match &self.gradient {
+            Some(gradient) => gradient,
+            None => return String::new(),
+        };
+
+        // Gradient spans the QRCode itself, not the margin
+        let start = margin as f64;
+        let end = (margin + n) as f64;
+        let center = start + n as f64 / 2f64;
+
+        match gradient {
+            Gradient::Linear {
+                start: start_color,
+                end: end_color,
+                angle,
+            } => format!(
+                r#"<defs><linearGradient id="{GRADIENT_ID}" gradientUnits="userSpaceOnUse" x1="{start}" y1="{center}" x2="{end}" y2="{center}" gradientTransform="rotate({angle} {center} {center})"><stop offset="0" stop-color="{}"/><stop offset="1" stop-color="{}"/></linearGradient></defs>"#,
+                start_color.to_str(),
+                end_color.to_str(),
+            ),
+            Gradient::Radial { inner, outer } => format!(
+                r#"<defs><radialGradient id="{GRADIENT_ID}" gradientUnits="userSpaceOnUse" cx="{center}" cy="{center}" r="{:.2}"><stop offset="0" stop-color="{}"/><stop offset="1" stop-color="{}"/></radialGradient></defs>"#,
+                n as f64 / core::f64::consts::SQRT_2,
+                inner.to_str(),
+                outer.to_str(),
+            ),
+        }
+    }
+
+    /// Hashes every render option: margin, colors, gradient, shapes and
+    /// image. Custom [`Shape::Command`]s are `fn` pointers, only their
+    /// position among the shapes is hashed. The embedded image is hashed by
+    /// its path or data, a file changed in place keeps the same fingerprint.
+    #[must_use]
+    pub fn options_fingerprint(&self) -> u64 {
+        let mut fingerprint = Fingerprint::new("SvgBuilder");
+        fingerprint
+            .u64(self.margin as u64)
+            .str(self.background_color.to_str())
+            .str(self.dot_color.to_str());
+        match &self.gradient {
+            None => fingerprint.str("none"),
+            Some(Gradient::Linear { start, end, angle }) => fingerprint
+                .str("linear")
+                .str(start.to_str())
+                .str(end.to_str())
+                .u64(angle.to_bits()),
+            Some(Gradient::Radial { inner, outer }) => fingerprint
+                .str("radial")
+                .str(inner.to_str())
+                .str(outer.to_str()),
+        };
+
+        // Hashed only when set, fingerprints from before bleed stay the same
+        if self.bleed != 0.0 {
+            fingerprint.str("bleed").u64(self.bleed.to_bits());
+        }
+        if let Some(image) = &self.background_image {
+            fingerprint.str("background_image").str(image);
+        }
+        if self.dark_opacity < 1.0 {
+            fingerprint
+                .str("dark_opacity")
+                .u64(self.dark_opacity.to_bits());
+        }
+        if let Some(width) = self.outline {
+            fingerprint.str("outline").u64(width.to_bits());
+        }
+        if self.shape_optimization {
+            fingerprint.str("shape_optimization");
+        }
+
+        fingerprint.u64(self.commands.len() as u64);
+        for (i, &command) in self.commands.iter().enumerate() {
+            match Shape::FUNCTIONS
+                .iter()
+                .position(|&function| function as usize == command as usize)
+            {
+                Some(shape) => fingerprint.u64(shape as u64),
+                None => fingerprint.str("custom"),
+            };
+            fingerprint
+                .debug(&self.command_colors[i].as_ref().map(Color::to_str))
+                .debug(&self.command_module_types[i]);
+        }
+        for color in &self.module_type_colors {
+            fingerprint.debug(&color.as_ref().map(Color::to_str));
+        }
+        if let Some(eye_shapes) = self.eye_shapes {
+            fingerprint.str("eyes").debug(&eye_shapes);
+        }
+
+        fingerprint
+            .debug(&self.image)
+            .str(self.image_background_color.to_str())
+            .debug(&self.image_background_shape)
+            .debug(&self.image_size)
+            .debug(&self.image_size_percent)
+            .debug(&self.image_gap)
+            .debug(&self.image_position);
+        fingerprint.finish()
+    }
+
+    /// Combines the fingerprint of the [`QRBuilder`](crate::QRBuilder) options
+    /// with [`options_fingerprint`](SvgBuilder::options_fingerprint): the
+    /// same input gives the same svg as long as this value does not change
+    #[must_use]
+    pub fn output_fingerprint(&self, qr_options: u64) -> u64 {
+        fingerprint::combine(qr_options, self.options_fingerprint())
+    }
+
+    /// Writes the [`output_fingerprint`](SvgBuilder::output_fingerprint) in a
+    /// `data-fingerprint` attribute of the `<svg>` element, as 16 hexadecimal digits
+    ///
+    /// # Example
+    /// ```rust
+    /// use fast_qr::convert::{svg::SvgBuilder, Builder};
+    /// use fast_qr::qr::QRBuilder;
+    ///
+    /// let builder = QRBuilder::new("https://example.com/");
+    /// let qrcode = builder.build().unwrap();
+    ///
+    /// let svg = SvgBuilder::default()
+    ///     .fingerprint(builder.options_fingerprint())
+    ///     .to_str(&qrcode);
+    /// assert!(svg.contains("data-fingerprint="));
+    /// ```
+    pub fn fingerprint(&mut self, qr_options: u64) -> &mut Self {
+        self.qr_fingerprint = Some(qr_options);
+        self
     }
 
     /// Return a string containing the svg for a qr code
-    pub fn to_str(&self, qr: &QRCode) -> String {
-        let n = qr.size;
+    pub fn to_str<M: Matrix>(&self, qr: &M) -> String {
+        self.to_str_with_geometry(&self.geometry(qr))
+    }
 
+    /// Return a string containing the svg for a precomputed [`SvgGeometry`].
+    ///
+    /// Shapes and margin come from the geometry, colors, gradient and image
+    /// from this builder, so one geometry can be rendered in many themes.
+    pub fn to_str_with_geometry(&self, geometry: &SvgGeometry) -> String {
+        let n = geometry.size;
         let mut out = String::with_capacity(11 * n * n / 2);
-        out.push_str(&format!(
-            r#"<svg viewBox="0 0 {0} {0}" xmlns="http://www.w3.org/2000/svg">"#,
-            self.margin * 2 + n
-        ));
+        // Writing to a `String` cannot fail
+        let _ = self.write_svg(&mut out, geometry);
+        out
+    }
 
-        out.push_str(&format!(
+    fn write_svg<W: Write>(&self, out: &mut W, geometry: &SvgGeometry) -> fmt::Result {
+        let n = geometry.size;
+        let margin = geometry.margin;
+
+        write!(
+            out,
+            r#"<svg viewBox="0 0 {0} {0}" xmlns="http://www.w3.org/2000/svg""#,
+            margin * 2 + n
+        )?;
+        if let Some(qr_options) = self.qr_fingerprint {
+            write!(
+                out,
+                r#" data-fingerprint="{:016x}""#,
+                self.output_fingerprint(qr_options)
+            )?;
+        }
+        out.write_char('>')?;
+
+        write!(
+            out,
             r#"<rect width="{0}px" height="{0}px" fill="{1}"/>"#,
-            self.margin * 2 + n,
+            margin * 2 + n,
             self.background_color.to_str()
-        ));
+        )?;
+
+        if let Some(image) = &self.background_image {
+            write!(
+                out,
+                r#"<image width="{0}" height="{0}" preserveAspectRatio="xMidYMid slice" href="{image}"/>"#,
+                margin * 2 + n
+            )?;
+        }
 
-        out.push_str(&self.path(qr));
-        out.push_str(&self.image(n));
+        out.write_str(&self.gradient_definition(n, margin))?;
+        if self.dark_opacity < 1.0 {
+            write!(out, r#"<g opacity="{}">"#, number(self.dark_opacity))?;
+            self.write_paths(out, geometry)?;
+            out.write_str("</g>")?;
+        } else {
+            self.write_paths(out, geometry)?;
+        }
+        out.write_str(&self.image(n, margin, geometry.ecl))?;
 
-        out.push_str("</svg>");
-        out
+        out.write_str("</svg>")
+    }
+
+    /// Writes the svg for a qr code to `writer`, piece by piece instead of
+    /// building the whole document first. Wrap unbuffered writers (files,
+    /// sockets) in a [`BufWriter`](std::io::BufWriter).
+    ///
+    /// ```rust
+    /// use fast_qr::convert::svg::SvgBuilder;
+    /// use fast_qr::qr::QRBuilder;
+    ///
+    /// let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    /// let builder = SvgBuilder::default();
+    ///
+    /// let mut out = Vec::new();
+    /// builder.write_to(&qrcode, &mut out).unwrap();
+    /// assert_eq!(out, builder.to_str(&qrcode).into_bytes());
+    /// ```
+    ///
+    /// # Errors
+    /// - `SvgError::IoError` if `writer` fails
+    #[cfg(not(feature = "wasm-bindgen"))]
+    pub fn write_to<M: Matrix, W: std::io::Write>(
+        &self,
+        qr: &M,
+        writer: W,
+    ) -> Result<(), SvgError> {
+        self.write_to_with_geometry(&self.geometry(qr), writer)
+    }
+
+    /// Same as [`write_to`](SvgBuilder::write_to) for a precomputed [`SvgGeometry`]
+    ///
+    /// # Errors
+    /// - `SvgError::IoError` if `writer` fails
+    #[cfg(not(feature = "wasm-bindgen"))]
+    pub fn write_to_with_geometry<W: std::io::Write>(
+        &self,
+        geometry: &SvgGeometry,
+        writer: W,
+    ) -> Result<(), SvgError> {
+        let mut out = IoWriter {
+            inner: writer,
+            error: None,
+        };
+        match (self.write_svg(&mut out, geometry), out.error) {
+            (Ok(()), _) => Ok(()),
+            (Err(_), Some(err)) => Err(SvgError::IoError(err)),
+            (Err(_), None) => Err(SvgError::SvgError(String::from("Formatting failed"))),
+        }
+    }
+
+    /// Return the svg for a qr code as a `data:image/svg+xml;base64,` URI,
+    /// to inline it in an `<img src>` or a CSS `url()`
+    pub fn to_data_uri<M: Matrix>(&self, qr: &M) -> String {
+        super::data_uri("image/svg+xml", self.to_str(qr).as_bytes())
     }
 
     /// Saves the svg for a qr code to a file
     #[cfg(not(feature = "wasm-bindgen"))]
-    pub fn to_file(&self, qr: &QRCode, file: &str) -> Result<(), SvgError> {
-        use std::fs::File;
+    pub fn to_file<M: Matrix>(&self, qr: &M, file: &str) -> Result<(), SvgError> {
         use std::io::Write;
 
-        let out = self.to_str(qr);
+        let f = std::fs::File::create(file).map_err(SvgError::IoError)?;
+        let mut writer = std::io::BufWriter::new(f);
+        self.write_to(qr, &mut writer)?;
+        writer.flush().map_err(SvgError::IoError)
+    }
+}
 
-        let mut f = File::create(file).map_err(SvgError::IoError)?;
-        f.write_all(out.as_bytes()).map_err(SvgError::IoError)?;
+impl Renderer for SvgBuilder {
+    type Output = String;
 
-        Ok(())
+    fn render(&self, qr: &QRCode) -> Self::Output {
+        self.to_str(qr)
     }
 }
@@ -0,0 +1,117 @@
+//! Converts [`QRCode`](crate::QRCode) to ANSI-colored terminal output
+//!
+//! Each module is drawn as two spaces with an ANSI background color, which
+//! keeps modules square on most terminal fonts.
+//!
+//! ```rust
+//! use fast_qr::convert::terminal::TerminalBuilder;
+//! use fast_qr::qr::QRBuilder;
+//!
+//! // QRBuilde::new can fail if content is too big for version,
+//! // please check before unwrapping.
+//! let qrcode = QRBuilder::new("https://example.com/")
+//!     .build()
+//!     .unwrap();
+//!
+//! let _str = TerminalBuilder::default()
+//!     .invert(true)
+//!     .margin(2)
+//!     .to_str(&qrcode);
+//! ```
+
+use super::Renderer;
+use crate::matrix::Matrix;
+use crate::QRCode;
+
+/// ANSI escape for a black background
+const BACKGROUND_BLACK: &str = "\x1b[40m";
+/// ANSI escape for a white background
+const BACKGROUND_WHITE: &str = "\x1b[47m";
+/// ANSI escape resetting all attributes
+const RESET: &str = "\x1b[0m";
+/// Characters used to draw one module, two columns keep it square
+const MODULE: &str = "  ";
+
+/// Builder for terminal output, can set margin and inverted colors
+pub struct TerminalBuilder {
+    /// The quiet zone around the code, in modules, default is 4
+    margin: usize,
+    /// Draws dark modules white and light modules black, default is false
+    invert: bool,
+}
+
+/// Creates a Builder instance
+impl Default for TerminalBuilder {
+    fn default() -> Self {
+        TerminalBuilder {
+            margin: 4,
+            invert: false,
+        }
+    }
+}
+
+impl TerminalBuilder {
+    /// Updates margin (default: 4)
+    pub fn margin(&mut self, margin: usize) -> &mut Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Swaps dark and light colors, some terminals scan better with
+    /// white-on-black (default: false)
+    pub fn invert(&mut self, invert: bool) -> &mut Self {
+        self.invert = invert;
+        self
+    }
+
+    fn background(&self, dark: bool) -> &'static str {
+        if dark != self.invert {
+            BACKGROUND_BLACK
+        } else {
+            BACKGROUND_WHITE
+        }
+    }
+
+    /// Return a string containing the ANSI-colored qr code
+    pub fn to_str<M: Matrix>(&self, qr: &M) -> String {
+        let size = qr.size();
+        let n = size + self.margin * 2;
+
+        let mut out = String::with_capacity(n * (n * 2 + 16));
+
+        for y in 0..n {
+            let mut current = None;
+            for x in 0..n {
+                let dark = y >= self.margin
+                    && x >= self.margin
+                    && y < self.margin + size
+                    && x < self.margin + size
+                    && qr.module(y - self.margin, x - self.margin).value();
+
+                // Only emit an escape when the color actually changes
+                if current != Some(dark) {
+                    out.push_str(self.background(dark));
+                    current = Some(dark);
+                }
+                out.push_str(MODULE);
+            }
+            out.push_str(RESET);
+            out.push('\n');
+        }
+
+        out
+    }
+
+    /// Prints the ANSI-colored qr code to the terminal
+    pub fn print<M: Matrix>(&self, qr: &M) {
+        print!("{}", self.to_str(qr));
+    }
+}
+
+impl Renderer for TerminalBuilder {
+    type Output = String;
+
+    fn render(&self, qr: &QRCode) -> Self::Output {
+        self.to_str(qr)
+    }
+}
@@ -0,0 +1,178 @@
+//! Generates a printable SVG sheet of the same content encoded with every
+//! ECL × version × module size combination, each code labelled, to validate
+//! scanners against a standardized target.
+//!
+//! ```rust
+//! use fast_qr::convert::test_sheet::TestSheetBuilder;
+//! use fast_qr::{Version, ECL};
+//!
+//! let _svg = TestSheetBuilder::new("https://fast-qr.com/")
+//!     .ecls(vec![ECL::L, ECL::H])
+//!     .versions(vec![Version::V02, Version::V05])
+//!     .module_sizes(vec![0.25, 0.5])
+//!     .to_str();
+//! ```
+
+use crate::convert::svg::SvgBuilder;
+#[cfg(not(feature = "wasm-bindgen"))]
+use crate::convert::svg::SvgError;
+use crate::convert::Builder;
+use crate::{QRBuilder, QRCode, Version, ECL};
+
+/// Height of the label under each code, in millimeters
+const LABEL_HEIGHT: f64 = 5.0;
+/// Font size of the labels, in millimeters
+const LABEL_FONT_SIZE: f64 = 3.0;
+
+/// Builder for a test sheet, can set the swept ECLs, versions, module sizes
+pub struct TestSheetBuilder {
+    content: Vec<u8>,
+    /// Default is every ECL
+    ecls: Vec<ECL>,
+    /// Default is the smallest version fitting the content, for each ECL
+    versions: Vec<Version>,
+    /// Width of a module in millimeters, default is 0.25, 0.33 and 0.5
+    module_sizes: Vec<f64>,
+    /// Quiet zone of each code, in modules, default is 4
+    margin: usize,
+    /// Space between codes, in millimeters, default is 5
+    gap: f64,
+}
+
+impl TestSheetBuilder {
+    /// Creates an instance of `TestSheetBuilder` encoding `content` in every code
+    #[must_use]
+    pub fn new<I: Into<Vec<u8>>>(content: I) -> Self {
+        TestSheetBuilder {
+            content: content.into(),
+            ecls: vec![ECL::L, ECL::M, ECL::Q, ECL::H],
+            versions: Vec::new(),
+            module_sizes: vec![0.25, 0.33, 0.5],
+            margin: 4,
+            gap: 5.0,
+        }
+    }
+
+    /// Updates the swept ECLs, one row per ECL and version (default: every ECL)
+    pub fn ecls(&mut self, ecls: Vec<ECL>) -> &mut Self {
+        self.ecls = ecls;
+        self
+    }
+
+    /// Updates the swept versions, combinations too small for the content
+    /// are left out (default: smallest fitting version)
+    pub fn versions(&mut self, versions: Vec<Version>) -> &mut Self {
+        self.versions = versions;
+        self
+    }
+
+    /// Updates the swept module widths in millimeters, one column per size
+    /// (default: 0.25, 0.33 and 0.5)
+    pub fn module_sizes(&mut self, module_sizes: Vec<f64>) -> &mut Self {
+        self.module_sizes = module_sizes;
+        self
+    }
+
+    /// Updates the quiet zone of each code, in modules (default: 4)
+    pub fn margin(&mut self, margin: usize) -> &mut Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Updates the space between codes, in millimeters (default: 5)
+    pub fn gap(&mut self, gap: f64) -> &mut Self {
+        self.gap = gap;
+        self
+    }
+
+    /// Returns one row of codes per ECL / version combination that fits
+    fn rows(&self) -> Vec<(ECL, QRCode)> {
+        let mut rows = Vec::new();
+
+        for &ecl in &self.ecls {
+            if self.versions.is_empty() {
+                if let Ok(qrcode) = QRBuilder::new(self.content.clone()).ecl(ecl).build() {
+                    rows.push((ecl, qrcode));
+                }
+                continue;
+            }
+
+            for &version in &self.versions {
+                let qrcode = QRBuilder::new(self.content.clone())
+                    .ecl(ecl)
+                    .version(version)
+                    .build();
+                if let Ok(qrcode) = qrcode {
+                    rows.push((ecl, qrcode));
+                }
+            }
+        }
+
+        rows
+    }
+
+    /// Return a string containing the svg of the sheet, sized in millimeters
+    #[must_use]
+    pub fn to_str(&self) -> String {
+        let rows = self.rows();
+        let mut svg_builder = SvgBuilder::default();
+        svg_builder.margin(self.margin);
+
+        // Every column is as wide as its largest code
+        let max_size = rows.iter().map(|(_, qr)| qr.size).max().unwrap_or(0);
+        let max_width = (max_size + self.margin * 2) as f64;
+
+        let column_widths: Vec<f64> = self.module_sizes.iter().map(|s| s * max_width).collect();
+        let width = column_widths.iter().sum::<f64>() + self.gap * (column_widths.len() + 1) as f64;
+
+        let mut out = String::new();
+        let mut y = self.gap;
+
+        for (ecl, qr) in &rows {
+            let qr_width = (qr.size + self.margin * 2) as f64;
+            let svg = svg_builder.to_str(qr);
+            let version = qr.version.map_or(0, Version::number);
+
+            let mut x = self.gap;
+            let mut row_height = 0f64;
+            for (&module_size, &column_width) in self.module_sizes.iter().zip(&column_widths) {
+                let code_width = qr_width * module_size;
+
+                // Nested svg elements keep each code in its own coordinates
+                out.push_str(&svg.replacen(
+                    "<svg ",
+                    &format!(r#"<svg x="{x:.2}" y="{y:.2}" width="{code_width:.2}" height="{code_width:.2}" "#),
+                    1,
+                ));
+                out.push_str(&format!(
+                    r#"<text x="{:.2}" y="{:.2}" font-size="{LABEL_FONT_SIZE}" font-family="monospace">V{version:02} {ecl} {module_size}mm</text>"#,
+                    x,
+                    y + code_width + LABEL_FONT_SIZE + 1.0,
+                ));
+
+                row_height = row_height.max(code_width + LABEL_HEIGHT);
+                x += column_width + self.gap;
+            }
+
+            y += row_height + self.gap;
+        }
+
+        format!(
+            r##"<svg width="{width:.2}mm" height="{y:.2}mm" viewBox="0 0 {width:.2} {y:.2}" xmlns="http://www.w3.org/2000/svg"><rect width="{width:.2}" height="{y:.2}" fill="#ffffff"/>{out}</svg>"##
+        )
+    }
+
+    /// Saves the svg of the sheet to a file
+    #[cfg(not(feature = "wasm-bindgen"))]
+    pub fn to_file(&self, file: &str) -> Result<(), SvgError> {
+        use std::fs::File;
+        use std::io::Write;
+
+        let out = self.to_str();
+
+        let mut f = File::create(file).map_err(SvgError::IoError)?;
+        f.write_all(out.as_bytes()).map_err(SvgError::IoError)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,212 @@
+//! Shared by the renderers drawing modules as rectangles or pixels
+//!
+//! Bleed grows dark modules by a fraction of a module on every side, to
+//! compensate ink spread when negative or laser kerf when positive. Grown
+//! modules overlap their neighbours, shrunk ones only shrink on the sides
+//! facing a light module or the quiet zone so that dark areas stay joined.
+
+use crate::matrix::Matrix;
+
+/// Formats `value` with at most 4 decimals, without trailing zeros
+#[cfg(any(feature = "pdf", feature = "eps", feature = "svg", feature = "dxf"))]
+pub(crate) fn number(value: f64) -> String {
+    let out = format!("{value:.4}");
+    let out = out.trim_end_matches('0').trim_end_matches('.');
+    match out {
+        "-0" | "" => String::from("0"),
+        out => String::from(out),
+    }
+}
+
+/// Bleed is kept between half a module less and half a module more
+pub(crate) fn clamp_bleed(bleed: f64) -> f64 {
+    if bleed.is_nan() {
+        return 0.0;
+    }
+    bleed.clamp(-0.5, 0.5)
+}
+
+/// Returns whether module (`y`, `x`) is dark, modules outside of `qr` are light
+fn is_dark_module<M: Matrix>(qr: &M, y: isize, x: isize) -> bool {
+    let n = qr.size() as isize;
+    (0..n).contains(&y) && (0..n).contains(&x) && qr.module(y as usize, x as usize).value()
+}
+
+/// Horizontal runs of dark modules, as `(y, x, length)`, row by row
+#[cfg(any(feature = "pdf", feature = "eps"))]
+pub(crate) fn dark_runs<M: Matrix>(qr: &M) -> Vec<(usize, usize, usize)> {
+    let n = qr.size();
+    let mut runs = Vec::new();
+
+    for y in 0..n {
+        let mut x = 0;
+        while x < n {
+            if !qr.module(y, x).value() {
+                x += 1;
+                continue;
+            }
+
+            let start = x;
+            while x < n && qr.module(y, x).value() {
+                x += 1;
+            }
+            runs.push((y, start, x - start));
+        }
+    }
+
+    runs
+}
+
+/// Rectangles covering the dark modules once bled, as `(x, y, width, height)`
+/// in modules from the top left corner of the code
+#[cfg(any(feature = "pdf", feature = "eps"))]
+pub(crate) fn dark_rects<M: Matrix>(qr: &M, bleed: f64) -> Vec<(f64, f64, f64, f64)> {
+    let mut rects = Vec::new();
+
+    for (y, x, length) in dark_runs(qr) {
+        if bleed >= 0.0 {
+            rects.push((
+                x as f64 - bleed,
+                y as f64 - bleed,
+                length as f64 + 2.0 * bleed,
+                1.0 + 2.0 * bleed,
+            ));
+            continue;
+        }
+
+        // Splits the run where the modules above or below change
+        let shrink = -bleed;
+        let (iy, end) = (y as isize, x + length);
+        let neighbours = |x: usize| {
+            (
+                is_dark_module(qr, iy - 1, x as isize),
+                is_dark_module(qr, iy + 1, x as isize),
+            )
+        };
+        let mut start = x;
+        while start < end {
+            let (above, below) = neighbours(start);
+            let mut stop = start + 1;
+            while stop < end && neighbours(stop) == (above, below) {
+                stop += 1;
+            }
+
+            let left = if start == x { shrink } else { 0.0 };
+            let right = if stop == end { shrink } else { 0.0 };
+            let top = if above { 0.0 } else { shrink };
+            let bottom = if below { 0.0 } else { shrink };
+            let (width, height) = ((stop - start) as f64 - left - right, 1.0 - top - bottom);
+            if width > 0.0 && height > 0.0 {
+                rects.push((start as f64 + left, y as f64 + top, width, height));
+            }
+            start = stop;
+        }
+    }
+
+    rects
+}
+
+/// Closed outlines of the dark areas, as corners `(x, y)` in modules from
+/// the top left corner of the code. Outer borders go clockwise, borders of
+/// holes counterclockwise; areas touching by a corner are kept apart.
+#[cfg(feature = "dxf")]
+pub(crate) fn dark_outlines<M: Matrix>(qr: &M) -> Vec<Vec<(usize, usize)>> {
+    let n = qr.size();
+    let dark = |y: isize, x: isize| is_dark_module(qr, y, x);
+    let vertex = |x: usize, y: usize| y * (n + 1) + x;
+
+    // Sides of dark modules facing a light one, with the dark module on the
+    // right hand side
+    let mut edges: Vec<((usize, usize), (usize, usize))> = Vec::new();
+    for y in 0..n {
+        for x in (0..n).filter(|&x| qr.module(y, x).value()) {
+            let (iy, ix) = (y as isize, x as isize);
+            if !dark(iy - 1, ix) {
+                edges.push(((x, y), (x + 1, y)));
+            }
+            if !dark(iy, ix + 1) {
+                edges.push(((x + 1, y), (x + 1, y + 1)));
+            }
+            if !dark(iy + 1, ix) {
+                edges.push(((x + 1, y + 1), (x, y + 1)));
+            }
+            if !dark(iy, ix - 1) {
+                edges.push(((x, y + 1), (x, y)));
+            }
+        }
+    }
+
+    // At most two edges leave a corner, when dark modules touch diagonally
+    let mut outgoing = vec![Vec::with_capacity(1); (n + 1) * (n + 1)];
+    for (i, &((x, y), _)) in edges.iter().enumerate() {
+        outgoing[vertex(x, y)].push(i);
+    }
+
+    let direction = |i: usize| {
+        let ((x0, y0), (x1, y1)) = edges[i];
+        (x1 as isize - x0 as isize, y1 as isize - y0 as isize)
+    };
+    let mut used = vec![false; edges.len()];
+    let mut outlines = Vec::new();
+    for first in 0..edges.len() {
+        if used[first] {
+            continue;
+        }
+
+        let mut outline = Vec::new();
+        let mut edge = first;
+        loop {
+            used[edge] = true;
+            let (dx, dy) = direction(edge);
+            let (_, (x, y)) = edges[edge];
+            // Turning right first follows the module the edge belongs to
+            let next = *outgoing[vertex(x, y)]
+                .iter()
+                .min_by_key(|&&next| match direction(next) {
+                    turn if turn == (-dy, dx) => 0,
+                    turn if turn == (dx, dy) => 1,
+                    _ => 2,
+                })
+                .expect("every corner of an outline is left by an edge");
+            if direction(next) != (dx, dy) {
+                outline.push((x, y));
+            }
+            if next == first {
+                break;
+            }
+            edge = next;
+        }
+        outlines.push(outline);
+    }
+
+    outlines
+}
+
+/// Returns whether the point (`y`, `x`), in modules from the top left corner
+/// of the code, is dark once modules are bled, matching [`dark_rects`]
+pub(crate) fn is_dark<M: Matrix>(qr: &M, y: f64, x: f64, bleed: f64) -> bool {
+    let (cy, cx) = (y.floor() as isize, x.floor() as isize);
+
+    if bleed >= 0.0 {
+        // Bleed is at most half a module, only neighbours can reach the point
+        return (cy - 1..=cy + 1).any(|my| {
+            (cx - 1..=cx + 1).any(|mx| {
+                is_dark_module(qr, my, mx)
+                    && y >= my as f64 - bleed
+                    && y < (my + 1) as f64 + bleed
+                    && x >= mx as f64 - bleed
+                    && x < (mx + 1) as f64 + bleed
+            })
+        });
+    }
+
+    if !is_dark_module(qr, cy, cx) {
+        return false;
+    }
+    let shrink = -bleed;
+    let (fy, fx) = (y - cy as f64, x - cx as f64);
+    !((fx < shrink && !is_dark_module(qr, cy, cx - 1))
+        || (fx >= 1.0 - shrink && !is_dark_module(qr, cy, cx + 1))
+        || (fy < shrink && !is_dark_module(qr, cy - 1, cx))
+        || (fy >= 1.0 - shrink && !is_dark_module(qr, cy + 1, cx)))
+}
@@ -0,0 +1,249 @@
+//! Converts [`QRCode`](crate::QRCode) to ZPL, the label language of Zebra
+//! printers
+//!
+//! [`to_str`](ZplBuilder::to_str) draws the modules as a `^GF` graphic
+//! field, so the printed code is exactly the one built by fast_qr.
+//! [`to_barcode_command`](ZplBuilder::to_barcode_command) sends a `^BQ`
+//! command instead, leaving the encoding to the printer firmware.
+//!
+//! ```rust
+//! use fast_qr::convert::zpl::ZplBuilder;
+//! use fast_qr::qr::QRBuilder;
+//! use fast_qr::ECL;
+//!
+//! let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+//!
+//! let mut builder = ZplBuilder::default();
+//! builder.origin(50, 50).magnification(6).darkness(20);
+//!
+//! let graphic = builder.to_str(&qrcode);
+//! assert!(graphic.starts_with("~SD20^XA^FO74,74^GFA,"));
+//!
+//! let barcode = builder.to_barcode_command(b"https://example.com/", ECL::M);
+//! assert_eq!(
+//!     barcode,
+//!     "~SD20^XA^FO74,74^BQN,2,6^FDMA,https://example.com/^FS^XZ\n"
+//! );
+//! ```
+
+use std::fmt::Write;
+
+use super::Renderer;
+use crate::matrix::Matrix;
+use crate::{QRCode, ECL};
+
+/// Largest magnification of `^BQ`
+const MAX_BARCODE_MAGNIFICATION: usize = 10;
+
+/// Largest value of `~SD`
+const MAX_DARKNESS: u8 = 30;
+
+/// Builder for ZPL labels, can set the position, module size and darkness
+#[derive(Debug, Clone)]
+pub struct ZplBuilder {
+    /// Top left corner of the quiet zone, in dots, default is (0, 0)
+    origin: (usize, usize),
+    /// Width of a module, in dots, default is 4
+    magnification: usize,
+    /// The quiet zone around the code, in modules, default is 4
+    margin: usize,
+    /// Print darkness, from 0 to 30, default is the printer setting
+    darkness: Option<u8>,
+    /// Whether the field is wrapped in `^XA` / `^XZ`, default is true
+    label: bool,
+}
+
+impl Default for ZplBuilder {
+    fn default() -> Self {
+        ZplBuilder {
+            origin: (0, 0),
+            magnification: 4,
+            margin: 4,
+            darkness: None,
+            label: true,
+        }
+    }
+}
+
+impl ZplBuilder {
+    /// Updates the top left corner of the quiet zone, in dots from the top
+    /// left of the label (default: (0, 0))
+    pub fn origin(&mut self, x: usize, y: usize) -> &mut Self {
+        self.origin = (x, y);
+        self
+    }
+
+    /// Updates the width of a module in dots, at least 1 (default: 4).
+    /// `^BQ` goes up to 10.
+    pub fn magnification(&mut self, magnification: usize) -> &mut Self {
+        self.magnification = magnification.max(1);
+        self
+    }
+
+    /// Updates margin (default: 4). Labels are blank around the code, the
+    /// quiet zone only offsets it from the origin.
+    pub fn margin(&mut self, margin: usize) -> &mut Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Sets the darkness of the printer with `~SD`, from 0 to 30 (default:
+    /// the printer setting). The printer keeps it for the following labels.
+    pub fn darkness(&mut self, darkness: u8) -> &mut Self {
+        self.darkness = Some(darkness.min(MAX_DARKNESS));
+        self
+    }
+
+    /// Wraps the field in `^XA` / `^XZ` to make a whole label (default:
+    /// true). Without them, the field can be inserted in a larger label.
+    pub fn label(&mut self, label: bool) -> &mut Self {
+        self.label = label;
+        self
+    }
+
+    /// Writes the darkness, label start and field origin
+    fn start(&self, out: &mut String, magnification: usize) {
+        if let Some(darkness) = self.darkness {
+            let _ = write!(out, "~SD{darkness:02}");
+        }
+        if self.label {
+            out.push_str("^XA");
+        }
+        let offset = self.margin * magnification;
+        let (x, y) = (self.origin.0 + offset, self.origin.1 + offset);
+        let _ = write!(out, "^FO{x},{y}");
+    }
+
+    fn end(&self, out: &mut String) {
+        out.push_str("^FS");
+        if self.label {
+            out.push_str("^XZ");
+        }
+        out.push('\n');
+    }
+
+    /// Returns the ZPL drawing `qr` as a `^GF` graphic field, one dot per
+    /// bit. The data uses the ZPL ASCII compression: runs of a hex digit
+    /// are counted, and rows equal to the previous one are a single `:`.
+    pub fn to_str<M: Matrix>(&self, qr: &M) -> String {
+        let n = qr.size();
+        let scale = self.magnification;
+        let width = n * scale;
+        let stride = (width + 7) / 8;
+
+        let mut rows = String::with_capacity(n * stride);
+        let mut previous = String::new();
+        let mut row = vec![0u8; stride];
+        let mut hex = String::with_capacity(stride * 2);
+        for y in 0..n {
+            row.iter_mut().for_each(|byte| *byte = 0);
+            for x in (0..n).filter(|&x| qr.module(y, x).value()) {
+                for dot in x * scale..(x + 1) * scale {
+                    row[dot / 8] |= 0x80 >> (dot % 8);
+                }
+            }
+
+            hex.clear();
+            for byte in &row {
+                let _ = write!(hex, "{byte:02X}");
+            }
+            // Scaled rows repeat `scale` times
+            for i in 0..scale {
+                if i > 0 || hex == previous {
+                    rows.push(':');
+                } else {
+                    compress_row(&mut rows, &hex);
+                }
+            }
+            core::mem::swap(&mut previous, &mut hex);
+        }
+
+        let mut out = String::with_capacity(rows.len() + 64);
+        self.start(&mut out, scale);
+        let total = stride * width;
+        let _ = write!(out, "^GFA,{total},{total},{stride},{rows}");
+        self.end(&mut out);
+        out
+    }
+
+    /// Returns the ZPL printing `data` with the `^BQ` command at `ecl`. The
+    /// printer encodes it with its own choice of version and mask, so the
+    /// code may differ from the one of a [`QRBuilder`](crate::QRBuilder).
+    ///
+    /// The magnification is capped at 10. `^`, `~`, `_`, control characters
+    /// and bytes above 127 are escaped with `^FH`, non-ASCII bytes are
+    /// encoded in byte mode as they are.
+    #[must_use]
+    pub fn to_barcode_command(&self, data: &[u8], ecl: ECL) -> String {
+        let magnification = self.magnification.min(MAX_BARCODE_MAGNIFICATION);
+        let escape = |byte: u8| matches!(byte, b'^' | b'~' | b'_') || !(0x20..0x7F).contains(&byte);
+
+        let mut out = String::with_capacity(data.len() + 64);
+        self.start(&mut out, magnification);
+        let _ = write!(out, "^BQN,2,{magnification}");
+        if data.iter().any(|&byte| escape(byte)) {
+            out.push_str("^FH");
+        }
+        let _ = write!(out, "^FD{ecl}A,");
+        for &byte in data {
+            if escape(byte) {
+                let _ = write!(out, "_{byte:02X}");
+            } else {
+                out.push(char::from(byte));
+            }
+        }
+        self.end(&mut out);
+        out
+    }
+
+    /// Saves the ZPL graphic field of a qr code to a file
+    ///
+    /// # Errors
+    /// - if the file cannot be written
+    pub fn to_file<M: Matrix>(&self, qr: &M, file: &str) -> std::io::Result<()> {
+        std::fs::write(file, self.to_str(qr))
+    }
+}
+
+impl Renderer for ZplBuilder {
+    type Output = String;
+
+    fn render(&self, qr: &QRCode) -> Self::Output {
+        self.to_str(qr)
+    }
+}
+
+/// Writes a row of hex digits with the ZPL repeat counts: `G` to `Y` for 1
+/// to 19, `g` to `z` for 20 to 400. A trailing run of `0` or `F` is `,` or
+/// `!`, which fill the rest of the row.
+fn compress_row(out: &mut String, hex: &str) {
+    let hex = hex.as_bytes();
+    let mut i = 0;
+    while i < hex.len() {
+        let digit = hex[i];
+        let run = hex[i..].iter().take_while(|&&d| d == digit).count();
+        i += run;
+
+        if i == hex.len() && digit == b'0' {
+            out.push(',');
+        } else if i == hex.len() && digit == b'F' {
+            out.push('!');
+        } else if run < 3 {
+            (0..run).for_each(|_| out.push(char::from(digit)));
+        } else {
+            let mut count = run;
+            while count >= 400 {
+                out.push('z');
+                count -= 400;
+            }
+            if count >= 20 {
+                out.push(char::from(b'g' + (count / 20 - 1) as u8));
+                count %= 20;
+            }
+            if count > 0 {
+                out.push(char::from(b'G' + (count - 1) as u8));
+            }
+            out.push(char::from(digit));
+        }
+    }
+}
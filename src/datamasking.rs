@@ -8,6 +8,8 @@ use crate::QRCode;
 /// The different mask patterns. The mask pattern should only be applied to
 /// the data and error correction portion of the QR code.
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum Mask {
     /// QR code pattern n°0: `(x + y) % 2 == 0`.
     Checkerboard = 0,
@@ -27,6 +29,150 @@ pub enum Mask {
     Meadow = 7,
 }
 
+/// How the 8 masks are compared, the mask with the lowest penalty is applied
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum MaskScoring {
+    /// Every penalty rule of the spec (section 7.8.3), the default
+    Spec,
+    /// Only the dark / light balance rule, favors evenly spread modules
+    DarkRatio,
+    /// Every penalty rule of the spec, with custom weights
+    Weighted(PenaltyWeights),
+    /// User penalty, called on each masked matrix (format information not
+    /// written yet), the lowest result wins
+    ///
+    /// Functions cannot be serialized, serializing this variant fails
+    #[cfg_attr(feature = "serde", serde(skip))]
+    #[cfg_attr(feature = "arbitrary", arbitrary(skip))]
+    Custom(fn(&QRCode) -> u32),
+}
+
+impl Default for MaskScoring {
+    fn default() -> Self {
+        MaskScoring::Spec
+    }
+}
+
+/// Points given by each penalty rule (section 7.8.3.1, `N1` to `N4`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct PenaltyWeights {
+    /// `N1`, for a run of 5 same-colored modules, each extra module adds 1,
+    /// `0` disables the rule
+    pub runs: u32,
+    /// `N2`, for each 2x2 block of same-colored modules
+    pub squares: u32,
+    /// `N3`, for each finder-like `1011101` pattern
+    pub finder_patterns: u32,
+    /// `N4`, for each 5% step of dark modules away from 50%
+    pub balance: u32,
+}
+
+impl PenaltyWeights {
+    /// Weights of the spec: 3, 3, 40 and 10
+    pub const SPEC: PenaltyWeights = PenaltyWeights {
+        runs: 3,
+        squares: 3,
+        finder_patterns: 40,
+        balance: 10,
+    };
+}
+
+impl Default for PenaltyWeights {
+    fn default() -> Self {
+        PenaltyWeights::SPEC
+    }
+}
+
+/// Points given to a mask by each penalty rule, see [`MaskReport`]
+#[derive(Debug, Clone, Copy)]
+pub struct MaskPenalty {
+    /// The scored mask
+    pub mask: Mask,
+    /// `N1`, runs of 5 or more same-colored modules
+    pub runs: u32,
+    /// `N2`, 2x2 blocks of same-colored modules
+    pub squares: u32,
+    /// `N3`, finder-like `1011101` patterns
+    pub finder_patterns: u32,
+    /// `N4`, dark / light balance
+    pub balance: u32,
+}
+
+impl MaskPenalty {
+    /// Returns the sum of every rule, the lowest total wins
+    #[must_use]
+    pub const fn total(&self) -> u32 {
+        self.runs + self.squares + self.finder_patterns + self.balance
+    }
+}
+
+/// Penalties of the 8 masks of a `QRCode`, with the weights of the spec,
+/// returned by [`QRCode::mask_report`](crate::QRCode::mask_report)
+///
+/// Columns are scored before masking, as the encoder does when it compares
+/// masks, so totals may differ from other encoders.
+#[derive(Debug, Clone, Copy)]
+pub struct MaskReport {
+    /// Penalties, indexed by pattern reference
+    pub candidates: [MaskPenalty; 8],
+    /// Mask applied to the `QRCode`, forced or selected
+    pub applied: Mask,
+}
+
+impl MaskReport {
+    /// Returns the mask with the lowest total, the first one on ties
+    #[must_use]
+    pub fn best(&self) -> Mask {
+        let mut best = self.candidates[0];
+        for candidate in &self.candidates[1..] {
+            if candidate.total() < best.total() {
+                best = *candidate;
+            }
+        }
+        best.mask
+    }
+}
+
+impl Mask {
+    /// Every mask, in pattern reference order
+    pub const ALL: [Mask; 8] = [
+        Mask::Checkerboard,
+        Mask::HorizontalLines,
+        Mask::VerticalLines,
+        Mask::DiagonalLines,
+        Mask::LargeCheckerboard,
+        Mask::Fields,
+        Mask::Diamonds,
+        Mask::Meadow,
+    ];
+
+    /// Returns the mask with the given pattern reference, `None` if greater than 7
+    #[must_use]
+    pub const fn from_pattern(pattern: usize) -> Option<Mask> {
+        match pattern {
+            0 => Some(Mask::Checkerboard),
+            1 => Some(Mask::HorizontalLines),
+            2 => Some(Mask::VerticalLines),
+            3 => Some(Mask::DiagonalLines),
+            4 => Some(Mask::LargeCheckerboard),
+            5 => Some(Mask::Fields),
+            6 => Some(Mask::Diamonds),
+            7 => Some(Mask::Meadow),
+            _ => None,
+        }
+    }
+
+    /// Returns the pattern reference, from 0 to 7
+    #[must_use]
+    pub const fn pattern(self) -> usize {
+        self as usize
+    }
+}
+
 /// Mask function nb°**0**, `Mask::Checkerboard`.
 fn mask_checkerboard(qr: &mut QRCode) {
     for row in 0..qr.size {
@@ -159,7 +305,7 @@ fn mask_meadow(qr: &mut QRCode) {
     }
 }
 
-/// Applies the function at `mask_nb` on `mat`
+/// Applies `mask` on the data modules of `qr`
 pub fn mask(qr: &mut QRCode, mask: Mask) {
     match mask {
         Mask::Checkerboard => mask_checkerboard(qr),
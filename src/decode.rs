@@ -0,0 +1,494 @@
+//! Reads a grid of modules back into bytes, like a scanner would once the
+//! code is located: format and version information are read from the
+//! modules, the mask is removed, codewords are de-interleaved and every block
+//! goes through Reed-Solomon error correction.
+//!
+//! There is no image detection, the input is a [`Matrix`] (a [`QRCode`](crate::QRCode)
+//! or a [`QRMatrix`](crate::QRMatrix)). This is meant for golden tests and
+//! round-trip fuzzing of the encoder.
+//!
+//! ```rust
+//! use fast_qr::{decode, QRBuilder};
+//!
+//! let mut qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+//! // Bottom right module, a data module in every version
+//! let last = qrcode.size - 1;
+//! qrcode[last][last].toggle();
+//!
+//! let decoded = decode::decode(&qrcode).unwrap();
+//! assert_eq!(decoded.data, b"https://example.com/");
+//! assert_eq!(decoded.corrected_codewords, 1);
+//! ```
+
+#![deny(unsafe_code)]
+#![warn(missing_docs)]
+
+use core::fmt::{Display, Formatter};
+
+use alloc::vec::Vec;
+
+use crate::matrix::Matrix;
+use crate::structured_append::Header;
+use crate::{galois, hardcode, placement, Mask, Version, ECL};
+
+/// Contains the different errors when a matrix cannot be decoded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    /// Size is not `version * 4 + 17`, for a version from 1 to 40
+    Size,
+    /// Neither copy of the format information is close enough to a valid one
+    FormatInformation,
+    /// Neither copy of the version information is close enough to the one
+    /// of the size (version 7 and up)
+    VersionInformation,
+    /// A block of codewords has more errors than it can correct
+    ErrorCorrection,
+    /// Data codewords are not a valid sequence of segments
+    Bitstream,
+}
+
+impl std::error::Error for DecodeError {}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DecodeError::Size => f.write_str("Size does not match any version"),
+            DecodeError::FormatInformation => f.write_str("Unreadable format information"),
+            DecodeError::VersionInformation => f.write_str("Unreadable version information"),
+            DecodeError::ErrorCorrection => f.write_str("Too many errors to correct"),
+            DecodeError::Bitstream => f.write_str("Invalid data segments"),
+        }
+    }
+}
+
+/// Content and parameters read from a matrix
+#[derive(Debug, Clone)]
+pub struct Decoded {
+    /// Decoded data, Kanji characters as Shift JIS, ECI headers left out
+    pub data: Vec<u8>,
+    /// Version, from the size
+    pub version: Version,
+    /// Error correction level, from the format information
+    pub ecl: ECL,
+    /// Mask, from the format information
+    pub mask: Mask,
+    /// Number of codewords fixed by error correction
+    pub corrected_codewords: usize,
+    /// Number of wrong bits in both copies of the format and version
+    /// information, `0` for a pristine code
+    pub information_errors: u32,
+    /// Structured append header, if the code is part of a sequence
+    pub structured_append: Option<Header>,
+}
+
+/// Section 7.8.2, `y` being the row and `x` the column
+const fn mask_bit(mask: Mask, y: usize, x: usize) -> bool {
+    match mask {
+        Mask::Checkerboard => (y + x) % 2 == 0,
+        Mask::HorizontalLines => y % 2 == 0,
+        Mask::VerticalLines => x % 3 == 0,
+        Mask::DiagonalLines => (y + x) % 3 == 0,
+        Mask::LargeCheckerboard => (y / 2 + x / 3) % 2 == 0,
+        Mask::Fields => (y * x) % 2 + (y * x) % 3 == 0,
+        Mask::Diamonds => ((y * x) % 2 + (y * x) % 3) % 2 == 0,
+        Mask::Meadow => ((y + x) % 2 + (y * x) % 3) % 2 == 0,
+    }
+}
+
+/// Reads bits `0..` at `positions`, bit 0 being the least significant
+fn read_bits<M: Matrix>(matrix: &M, positions: impl Iterator<Item = (usize, usize)>) -> u32 {
+    positions.enumerate().fold(0, |bits, (i, (y, x))| {
+        bits | u32::from(matrix.module(y, x).value()) << i
+    })
+}
+
+/// Returns the candidate closest to either copy if it is at most 3 bits
+/// away (both codes have a minimum distance of 7 or 8), with the number of
+/// wrong bits of both copies
+fn closest<T: Copy>(
+    copies: [u32; 2],
+    candidates: impl Iterator<Item = (T, u32)>,
+) -> Option<(T, u32)> {
+    let distance = |copy: u32, bits: u32| (copy ^ bits).count_ones();
+    let (value, bits) = candidates
+        .min_by_key(|&(_, bits)| distance(copies[0], bits).min(distance(copies[1], bits)))?;
+
+    let errors = [distance(copies[0], bits), distance(copies[1], bits)];
+    if errors[0].min(errors[1]) > 3 {
+        return None;
+    }
+    Some((value, errors[0] + errors[1]))
+}
+
+/// Reads both copies of the format information (section 7.9.1)
+fn format_information<M: Matrix>(matrix: &M) -> Result<((ECL, Mask), u32), DecodeError> {
+    let size = matrix.size();
+    let first = read_bits(
+        matrix,
+        (0..=5)
+            .map(|i| (i, 8))
+            .chain([(7, 8), (8, 8), (8, 7)])
+            .chain((9..15).map(|i| (8, 14 - i))),
+    );
+    let second = read_bits(
+        matrix,
+        (0..8)
+            .map(|i| (8, size - 1 - i))
+            .chain((8..15).map(|i| (size - 15 + i, 8))),
+    );
+
+    let candidates = [ECL::L, ECL::M, ECL::Q, ECL::H].iter().flat_map(|&ecl| {
+        Mask::ALL.iter().map(move |&mask| {
+            let bits = hardcode::ecm_to_format_information(ecl, mask);
+            ((ecl, mask), u32::from(bits))
+        })
+    });
+    closest([first, second], candidates).ok_or(DecodeError::FormatInformation)
+}
+
+/// Checks both copies of the version information (section 7.10), the
+/// version itself comes from the size
+fn version_information<M: Matrix>(matrix: &M, version: Version) -> Result<u32, DecodeError> {
+    let expected = hardcode::VERSION_INFORMATION[version as usize];
+    if expected == 0 {
+        return Ok(0);
+    }
+
+    let size = matrix.size();
+    let positions = || (0..18).map(move |i| (i / 3, size - 11 + i % 3));
+    let top_right = read_bits(matrix, positions());
+    let bottom_left = read_bits(matrix, positions().map(|(y, x)| (x, y)));
+
+    let candidates = hardcode::VERSION_INFORMATION
+        .iter()
+        .filter(|&&bits| bits != 0)
+        .map(|&bits| (bits, bits));
+    match closest([top_right, bottom_left], candidates) {
+        Some((bits, distance)) if bits == expected => Ok(distance),
+        _ => Err(DecodeError::VersionInformation),
+    }
+}
+
+/// Reads the interleaved codewords of `version`, removing `mask`
+pub(crate) fn read_codewords<M: Matrix>(matrix: &M, version: Version, mask: Mask) -> Vec<u8> {
+    let mut codewords = vec![0; version.max_bytes()];
+    for (i, (y, x)) in placement::data_positions(version)
+        .into_iter()
+        .take(codewords.len() * 8)
+        .enumerate()
+    {
+        let bit = matrix.module(y, x).value() ^ mask_bit(mask, y, x);
+        codewords[i / 8] |= u8::from(bit) << (7 - i % 8);
+    }
+    codewords
+}
+
+/// Codewords of each block, data then error correction (section 7.6)
+pub(crate) fn blocks(codewords: &[u8], version: Version, ecl: ECL) -> Vec<Vec<u8>> {
+    let [(g1_count, g1_size), (g2_count, g2_size)] = hardcode::ecc_to_groups(ecl, version);
+    let ecc_len = galois::ecc_codewords(version, ecl);
+    let sizes: Vec<usize> = core::iter::repeat(g1_size)
+        .take(g1_count)
+        .chain(core::iter::repeat(g2_size).take(g2_count))
+        .collect();
+
+    let mut blocks: Vec<Vec<u8>> = sizes
+        .iter()
+        .map(|size| Vec::with_capacity(size + ecc_len))
+        .collect();
+    let mut codewords = codewords.iter();
+    for i in 0..g1_size.max(g2_size) {
+        for (block, &size) in blocks.iter_mut().zip(&sizes) {
+            if i < size {
+                block.extend(codewords.next());
+            }
+        }
+    }
+    for _ in 0..ecc_len {
+        for block in &mut blocks {
+            block.extend(codewords.next());
+        }
+    }
+
+    blocks
+}
+
+/// `a^power`
+fn exp(power: usize) -> u8 {
+    galois::ANTILOG[power % 255]
+}
+
+/// Power of `a` equal to `value`, which must not be 0
+fn log(value: u8) -> usize {
+    usize::from(galois::LOG[usize::from(value)])
+}
+
+fn divide(a: u8, b: u8) -> u8 {
+    if a == 0 {
+        return 0;
+    }
+    exp(log(a) + 255 - log(b))
+}
+
+/// Coefficient of `x^power` in `poly`
+fn coefficient(poly: &[u8], power: usize) -> u8 {
+    poly.get(power).copied().unwrap_or(0)
+}
+
+/// Evaluates `poly` at `x`, coefficients from the constant term up
+fn evaluate(poly: &[u8], x: u8) -> u8 {
+    poly.iter().rev().fold(0, |value, &coefficient| {
+        galois::multiply(value, x) ^ coefficient
+    })
+}
+
+/// Corrects `block` in place, returns the number of corrected codewords.
+///
+/// The generator polynomial has roots `a^0` to `a^(ecc_len - 1)`: syndromes
+/// give the error locator (Berlekamp-Massey), its roots give the positions
+/// (Chien search) and the error values come from Forney's formula.
+fn correct(block: &mut [u8], ecc_len: usize) -> Result<usize, DecodeError> {
+    // The first codeword is the coefficient of the highest power
+    let syndromes: Vec<u8> = (0..ecc_len)
+        .map(|i| {
+            block.iter().fold(0, |value, &codeword| {
+                galois::multiply(value, exp(i)) ^ codeword
+            })
+        })
+        .collect();
+    if syndromes.iter().all(|&syndrome| syndrome == 0) {
+        return Ok(0);
+    }
+
+    // Berlekamp-Massey, coefficients from the constant term up
+    let mut locator = vec![1_u8];
+    let mut previous = vec![1_u8];
+    let (mut len, mut shift, mut previous_discrepancy) = (0, 1, 1);
+    for n in 0..ecc_len {
+        let discrepancy = (1..=len).fold(syndromes[n], |d, i| {
+            d ^ galois::multiply(coefficient(&locator, i), syndromes[n - i])
+        });
+        if discrepancy == 0 {
+            shift += 1;
+            continue;
+        }
+
+        let factor = divide(discrepancy, previous_discrepancy);
+        let mut next = locator.clone();
+        next.resize(next.len().max(previous.len() + shift), 0);
+        for (i, &coefficient) in previous.iter().enumerate() {
+            next[i + shift] ^= galois::multiply(factor, coefficient);
+        }
+
+        if 2 * len <= n {
+            previous = core::mem::replace(&mut locator, next);
+            len = n + 1 - len;
+            previous_discrepancy = discrepancy;
+            shift = 1;
+        } else {
+            locator = next;
+            shift += 1;
+        }
+    }
+    if 2 * len > ecc_len {
+        return Err(DecodeError::ErrorCorrection);
+    }
+
+    // Chien search, an error at power p of the codeword makes a^-p a root
+    let n = block.len();
+    let positions: Vec<usize> = (0..n)
+        .filter(|&power| evaluate(&locator, exp(255 - power % 255)) == 0)
+        .collect();
+    if positions.len() != len {
+        return Err(DecodeError::ErrorCorrection);
+    }
+
+    // Forney, the error evaluator is syndromes * locator mod x^ecc_len
+    let evaluator: Vec<u8> = (0..ecc_len)
+        .map(|i| {
+            (0..=i.min(len)).fold(0, |value, j| {
+                value ^ galois::multiply(coefficient(&locator, j), syndromes[i - j])
+            })
+        })
+        .collect();
+    // Formal derivative, only odd powers remain in characteristic 2
+    let derivative: Vec<u8> = locator
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(i, &coefficient)| if i % 2 == 1 { coefficient } else { 0 })
+        .collect();
+
+    for &power in &positions {
+        let inverse = exp(255 - power % 255);
+        let denominator = evaluate(&derivative, inverse);
+        if denominator == 0 {
+            return Err(DecodeError::ErrorCorrection);
+        }
+        let magnitude = galois::multiply(
+            exp(power),
+            divide(evaluate(&evaluator, inverse), denominator),
+        );
+        block[n - 1 - power] ^= magnitude;
+    }
+
+    Ok(len)
+}
+
+struct Bits<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl Bits<'_> {
+    fn remaining(&self) -> usize {
+        self.data.len() * 8 - self.position
+    }
+
+    fn read(&mut self, count: usize) -> Result<usize, DecodeError> {
+        if count > self.remaining() {
+            return Err(DecodeError::Bitstream);
+        }
+
+        let mut value = 0;
+        for _ in 0..count {
+            let bit = self.data[self.position / 8] >> (7 - self.position % 8) & 1;
+            value = value << 1 | usize::from(bit);
+            self.position += 1;
+        }
+        Ok(value)
+    }
+}
+
+/// Parses the segments of `data` (section 7.4), returns their content and
+/// the structured append header
+fn segments(data: &[u8], version: Version) -> Result<(Vec<u8>, Option<Header>), DecodeError> {
+    const ALPHANUMERIC: &[u8; 45] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ $%*+-./:";
+
+    let number = version.number();
+    let cci_bits = |bits: [usize; 3]| match number {
+        1..=9 => bits[0],
+        10..=26 => bits[1],
+        _ => bits[2],
+    };
+
+    let mut bits = Bits { data, position: 0 };
+    let mut out = Vec::new();
+    let mut header = None;
+    while bits.remaining() >= 4 {
+        match bits.read(4)? {
+            0b0000 => break,
+            0b0001 => {
+                let mut count = bits.read(cci_bits([10, 12, 14]))?;
+                while count > 0 {
+                    let (digits, len, max) = match count {
+                        1 => (1, 4, 9),
+                        2 => (2, 7, 99),
+                        _ => (3, 10, 999),
+                    };
+                    let value = bits.read(len)?;
+                    if value > max {
+                        return Err(DecodeError::Bitstream);
+                    }
+                    let text = alloc::format!("{value:0digits$}");
+                    out.extend_from_slice(text.as_bytes());
+                    count -= digits;
+                }
+            }
+            0b0010 => {
+                let mut count = bits.read(cci_bits([9, 11, 13]))?;
+                while count > 0 {
+                    if count == 1 {
+                        let value = bits.read(6)?;
+                        out.push(*ALPHANUMERIC.get(value).ok_or(DecodeError::Bitstream)?);
+                        break;
+                    }
+                    let value = bits.read(11)?;
+                    let (first, second) = (value / 45, value % 45);
+                    out.push(*ALPHANUMERIC.get(first).ok_or(DecodeError::Bitstream)?);
+                    out.push(ALPHANUMERIC[second]);
+                    count -= 2;
+                }
+            }
+            0b0100 => {
+                let count = bits.read(cci_bits([8, 16, 16]))?;
+                for _ in 0..count {
+                    out.push(bits.read(8)? as u8);
+                }
+            }
+            0b1000 => {
+                let count = bits.read(cci_bits([8, 10, 12]))?;
+                for _ in 0..count {
+                    let value = bits.read(13)?;
+                    let c = (value / 0xC0) << 8 | (value % 0xC0);
+                    let sjis = if c + 0x8140 <= 0x9FFC {
+                        c + 0x8140
+                    } else {
+                        c + 0xC140
+                    };
+                    out.extend_from_slice(&(sjis as u16).to_be_bytes());
+                }
+            }
+            0b0111 => {
+                let len = match bits.read(8)? {
+                    first if first >> 7 == 0 => 0,
+                    first if first >> 6 == 0b10 => 8,
+                    first if first >> 5 == 0b110 => 16,
+                    _ => return Err(DecodeError::Bitstream),
+                };
+                bits.read(len)?;
+            }
+            // Structured append: part index, part count and parity
+            0b0011 => {
+                header = Some(Header {
+                    index: bits.read(4)? as u8,
+                    total: bits.read(4)? as u8 + 1,
+                    parity: bits.read(8)? as u8,
+                });
+            }
+            _ => return Err(DecodeError::Bitstream),
+        }
+    }
+
+    Ok((out, header))
+}
+
+/// Decodes `matrix` from the value of its modules, their types are ignored
+///
+/// # Errors
+/// - `DecodeError::Size` if the size does not match a version
+/// - `DecodeError::FormatInformation` / `DecodeError::VersionInformation`
+///   if both copies have more than 3 wrong bits
+/// - `DecodeError::ErrorCorrection` if a block has too many errors
+/// - `DecodeError::Bitstream` if data codewords are not valid segments
+pub fn decode<M: Matrix>(matrix: &M) -> Result<Decoded, DecodeError> {
+    let version = match matrix.size().checked_sub(17) {
+        Some(n) if n % 4 == 0 => Version::from_number(n / 4),
+        _ => None,
+    }
+    .ok_or(DecodeError::Size)?;
+
+    let ((ecl, mask), format_errors) = format_information(matrix)?;
+    let version_errors = version_information(matrix, version)?;
+
+    let codewords = read_codewords(matrix, version, mask);
+
+    let ecc_len = galois::ecc_codewords(version, ecl);
+    let mut data = Vec::with_capacity(hardcode::data_codewords(version, ecl));
+    let mut corrected_codewords = 0;
+    for mut block in blocks(&codewords, version, ecl) {
+        corrected_codewords += correct(&mut block, ecc_len)?;
+        data.extend_from_slice(&block[..block.len() - ecc_len]);
+    }
+
+    let (data, structured_append) = segments(&data, version)?;
+    Ok(Decoded {
+        data,
+        version,
+        ecl,
+        mask,
+        corrected_codewords,
+        information_errors: format_errors + version_errors,
+        structured_append,
+    })
+}
@@ -39,7 +39,7 @@ pub fn create_matrix(version: Version) -> QRCode {
     // Format information is not placed on the matrix yet
     // But we fill it anyway with garbage data to make it easier for placement
     {
-        if (version as usize) < (Version::V01 as usize) {
+        if version < Version::V01 {
             return qr;
         }
 
@@ -132,54 +132,41 @@ pub fn create_matrix_dark_module(qr: &mut QRCode) {
 
 /// Adds the smaller squares if needed
 pub fn create_matrix_alignments(qr: &mut QRCode, version: Version) {
-    if let Version::V01 = version {
-        return;
-    }
-
     // Alignments (smaller cubes)
-    let alignment_patterns = version.alignment_patterns_grid();
-    let max = alignment_patterns.len() - 1;
-
-    for (i, &alignment_y) in alignment_patterns.iter().enumerate() {
-        for (j, &alignment_x) in alignment_patterns.iter().enumerate() {
-            if i == 0 && (j == max || j == 0) || (i == max && j == 0) {
-                continue;
-            }
+    for (alignment_y, alignment_x) in hardcode::alignment_pattern_centers(version) {
+        let y = alignment_y - 2;
+        let x = alignment_x - 2;
 
-            let y = alignment_y - 2;
-            let x = alignment_x - 2;
+        for offset in 0..=4 {
+            qr[y][x + offset] = Module::alignment(Module::DARK);
+            qr[y + 4][x + offset] = Module::alignment(Module::DARK);
 
-            for offset in 0..=4 {
-                qr[y][x + offset] = Module::alignment(Module::DARK);
-                qr[y + 4][x + offset] = Module::alignment(Module::DARK);
-
-                qr[y + offset][x] = Module::alignment(Module::DARK);
-                qr[y + offset][x + 4] = Module::alignment(Module::DARK);
-            }
-
-            let y = alignment_y - 1;
-            let x = alignment_x - 1;
+            qr[y + offset][x] = Module::alignment(Module::DARK);
+            qr[y + offset][x + 4] = Module::alignment(Module::DARK);
+        }
 
-            for offset in 0..=2 {
-                qr[y][x + offset] = Module::alignment(Module::LIGHT);
-                qr[y + 2][x + offset] = Module::alignment(Module::LIGHT);
+        let y = alignment_y - 1;
+        let x = alignment_x - 1;
 
-                qr[y + offset][x] = Module::alignment(Module::LIGHT);
-                qr[y + offset][x + 2] = Module::alignment(Module::LIGHT);
-            }
+        for offset in 0..=2 {
+            qr[y][x + offset] = Module::alignment(Module::LIGHT);
+            qr[y + 2][x + offset] = Module::alignment(Module::LIGHT);
 
-            qr[alignment_y][alignment_x] = Module::alignment(Module::DARK);
+            qr[y + offset][x] = Module::alignment(Module::LIGHT);
+            qr[y + offset][x + 2] = Module::alignment(Module::LIGHT);
         }
+
+        qr[alignment_y][alignment_x] = Module::alignment(Module::DARK);
     }
 }
 
 /// Adds the version information if needed
 pub fn create_matrix_version_info(qr: &mut QRCode, version: Version) {
-    if (version as usize) < (Version::V07 as usize) {
+    if version < Version::V07 {
         return;
     }
 
-    let version_info = version.information();
+    let version_info = hardcode::VERSION_INFORMATION[version as usize];
 
     let n: usize = qr.size;
 
@@ -0,0 +1,111 @@
+//! Writes the intermediate steps of every encoding to a directory, enabled
+//! by the `dump` feature and the `FAST_QR_DUMP_DIR` environment variable.
+//!
+//! Each `QRCode` built overwrites:
+//! - `bitstream.txt`: data codewords in binary, padding included
+//! - `codewords.txt`: final codeword sequence with the block of each codeword
+//! - `matrix.txt`: matrix before masking and format information
+//! - `masks.txt`: penalty score of each mask, unless the mask is forced, and
+//!   the mask applied
+//!
+//! Dumping is best effort, write errors are ignored so that debugging never
+//! changes the outcome of an encoding.
+
+#![deny(unsafe_code)]
+
+use std::fmt::Write;
+use std::path::PathBuf;
+
+use crate::module::ModuleType;
+use crate::{polynomials, Mask, QRCode, Version, ECL};
+
+#[cfg(test)]
+std::thread_local! {
+    /// Replaces the environment variable for the current thread, tests run in parallel
+    pub(crate) static TEST_DIR: std::cell::RefCell<Option<PathBuf>> = const { std::cell::RefCell::new(None) };
+}
+
+/// Returns the dump directory, `None` when dumping is disabled
+fn dir() -> Option<PathBuf> {
+    #[cfg(test)]
+    return TEST_DIR.with(|dir| dir.borrow().clone());
+
+    #[cfg(not(test))]
+    std::env::var_os("FAST_QR_DUMP_DIR")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+}
+
+fn write(name: &str, content: &str) {
+    if let Some(dir) = dir() {
+        let _ = std::fs::create_dir_all(&dir);
+        let _ = std::fs::write(dir.join(name), content);
+    }
+}
+
+/// Dumps the data codewords and the interleaved codewords, `structure` being
+/// the output of [`polynomials::structure`]
+pub(crate) fn codewords(data: &[u8], structure: &[u8], ecl: ECL, version: Version) {
+    if dir().is_none() {
+        return;
+    }
+
+    let data_len = crate::hardcode::data_codewords(version, ecl);
+    let mut bitstream = format!("# {data_len} data codewords, version {version:?}, ECL {ecl}\n");
+    for line in data[..data_len].chunks(8) {
+        let bytes: Vec<_> = line.iter().map(|byte| format!("{byte:08b}")).collect();
+        let _ = writeln!(bitstream, "{}", bytes.join(" "));
+    }
+    write("bitstream.txt", &bitstream);
+
+    let blocks = polynomials::codeword_blocks(ecl, version);
+    let mut sequence = String::from("# index block kind value\n");
+    for (i, (&block, &value)) in blocks.iter().zip(structure).enumerate() {
+        let kind = if i < data_len { "data" } else { "ecc" };
+        let _ = writeln!(sequence, "{i:4} {block:2} {kind:4} {value:02X}");
+    }
+    write("codewords.txt", &sequence);
+}
+
+/// Dumps the matrix with its data placed but not yet masked
+pub(crate) fn unmasked(qr: &QRCode) {
+    if dir().is_none() {
+        return;
+    }
+
+    let mut matrix =
+        String::from("# function patterns: '#' dark, '.' light; data: '1' dark, '0' light\n");
+    for y in 0..qr.size {
+        for x in 0..qr.size {
+            let module = qr[y][x];
+            matrix.push(match (module.module_type(), module.value()) {
+                (ModuleType::Data, true) => '1',
+                (ModuleType::Data, false) => '0',
+                (_, true) => '#',
+                (_, false) => '.',
+            });
+        }
+        matrix.push('\n');
+    }
+    write("matrix.txt", &matrix);
+}
+
+/// Dumps the penalty of every mask, indexed by pattern, and the mask applied.
+/// `scores` is `None` when masks were not scored
+pub(crate) fn masks(scores: Option<&[u32; 8]>, applied: Mask) {
+    if dir().is_none() {
+        return;
+    }
+
+    let mut report = String::from("# pattern score\n");
+    match scores {
+        Some(scores) => {
+            for (pattern, score) in scores.iter().enumerate() {
+                let _ = writeln!(report, "{pattern} {score}");
+            }
+        }
+        None => report.push_str("# not scored\n"),
+    }
+    let _ = writeln!(report, "applied {} ({applied:?})", applied as usize);
+    write("masks.txt", &report);
+}
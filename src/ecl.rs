@@ -4,12 +4,14 @@
 #![deny(unsafe_code)]
 #![warn(missing_docs)]
 
-use std::fmt::Write;
+use core::fmt::Write;
 
 /// Error Correction Coding has 4 levels
 #[derive(Copy, Clone, Debug)]
 #[allow(dead_code)]
 #[cfg_attr(feature = "wasm-bindgen", wasm_bindgen::prelude::wasm_bindgen)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum ECL {
     /// Low, 7%
     L,
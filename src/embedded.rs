@@ -0,0 +1,117 @@
+//! Draws a code on [embedded-graphics](https://docs.rs/embedded-graphics)
+//! displays (SSD1306, ST7789, ...), without rendering an image first.
+//!
+//! ```rust,ignore
+//! use embedded_graphics::pixelcolor::BinaryColor;
+//! use embedded_graphics::prelude::*;
+//! use fast_qr::embedded::QRDrawable;
+//! use fast_qr::QRBuilder;
+//!
+//! let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+//!
+//! QRDrawable::new(&qrcode, BinaryColor::On, BinaryColor::Off)
+//!     .top_left(Point::new(32, 0))
+//!     .scale(2)
+//!     .draw(&mut display)?;
+//! ```
+
+#![deny(unsafe_code)]
+#![warn(missing_docs)]
+
+use embedded_graphics_core::draw_target::DrawTarget;
+use embedded_graphics_core::geometry::{Point, Size};
+use embedded_graphics_core::pixelcolor::PixelColor;
+use embedded_graphics_core::primitives::Rectangle;
+use embedded_graphics_core::Drawable;
+
+use crate::matrix::Matrix;
+use crate::Module;
+
+/// Draws a [`Matrix`] with one color for dark modules and one for light
+/// modules, quiet zone included
+#[derive(Debug, Clone, Copy)]
+pub struct QRDrawable<'a, M: Matrix, C: PixelColor> {
+    matrix: &'a M,
+    dark: C,
+    light: C,
+    /// Default is `Point::zero()`
+    top_left: Point,
+    /// Default is 1
+    scale: u32,
+    /// Default is 4
+    margin: u32,
+}
+
+impl<'a, M: Matrix, C: PixelColor> QRDrawable<'a, M, C> {
+    /// Creates a drawable for `matrix`, at the top left of the display
+    #[must_use]
+    pub fn new(matrix: &'a M, dark: C, light: C) -> Self {
+        QRDrawable {
+            matrix,
+            dark,
+            light,
+            top_left: Point::zero(),
+            scale: 1,
+            margin: 4,
+        }
+    }
+
+    /// Updates the top left corner of the quiet zone, in pixels (default: `Point::zero()`)
+    pub fn top_left(&mut self, top_left: Point) -> &mut Self {
+        self.top_left = top_left;
+        self
+    }
+
+    /// Updates the width of a module, in pixels (default: 1)
+    pub fn scale(&mut self, scale: u32) -> &mut Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Updates the quiet zone around the code, in modules (default: 4)
+    pub fn margin(&mut self, margin: u32) -> &mut Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Returns the area covered on the display, quiet zone included
+    #[must_use]
+    pub fn bounding_box(&self) -> Rectangle {
+        let side = (self.matrix.size() as u32 + self.margin * 2) * self.scale;
+        Rectangle::new(self.top_left, Size::new(side, side))
+    }
+}
+
+impl<M: Matrix, C: PixelColor> Drawable for QRDrawable<'_, M, C> {
+    type Color = C;
+    type Output = ();
+
+    fn draw<D>(&self, target: &mut D) -> Result<Self::Output, D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let area = self.bounding_box();
+        let side = area.size.width;
+        let size = self.matrix.size();
+        let margin = self.margin as usize;
+        let scale = self.scale.max(1) as usize;
+
+        // Pixels are sent row by row, displays stream them in a single transfer
+        let colors = (0..side as usize * side as usize).map(|i| {
+            let (y, x) = (i / side as usize / scale, i % side as usize / scale);
+            let dark = y >= margin
+                && x >= margin
+                && y - margin < size
+                && x - margin < size
+                && self.matrix.module(y - margin, x - margin).value() == Module::DARK;
+
+            if dark {
+                self.dark
+            } else {
+                self.light
+            }
+        });
+
+        target.fill_contiguous(&area, colors)
+    }
+}
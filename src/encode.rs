@@ -3,13 +3,18 @@
 #![deny(unsafe_code)]
 #![warn(missing_docs)]
 
+use core::fmt::{Display, Formatter};
+
 use crate::compact::CompactQR;
+use crate::compat::PadCodewords;
 use crate::ecl::ECL;
 use crate::hardcode;
 use crate::version::Version;
 
-/// Enum for the 3 encoding mode
+/// Enum for the 4 encoding mode
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum Mode {
     /// Numeric mode (0-9 only)
     Numeric,
@@ -17,30 +22,159 @@ pub enum Mode {
     Alphanumeric,
     /// Byte mode (any)
     Byte,
+    /// Kanji mode (double-byte Shift JIS characters), lengths are counted
+    /// in bytes, two per character
+    ///
+    /// With the `kanji` feature, UTF-8 text is converted to Shift JIS when
+    /// every character has a double-byte equivalent
+    Kanji,
+}
+
+impl Mode {
+    /// Returns the largest number of characters a version 40 `QRCode` holds in
+    /// this mode at `ecl`, the limits of the spec at `ECL::L`
+    ///
+    /// ```rust
+    /// use fast_qr::{Mode, ECL};
+    ///
+    /// assert_eq!(Mode::Numeric.max_len(ECL::L), 7089);
+    /// assert_eq!(Mode::Alphanumeric.max_len(ECL::L), 4296);
+    /// assert_eq!(Mode::Byte.max_len(ECL::L), 2953);
+    /// assert_eq!(Mode::Byte.max_len(ECL::H), 1273);
+    /// ```
+    #[must_use]
+    pub const fn max_len(self, ecl: ECL) -> usize {
+        crate::qr::capacity(self, hardcode::data_bits(Version::V40, ecl))
+    }
+
+    /// Returns whether every character of `input` can be encoded in this mode
+    ///
+    /// ```rust
+    /// use fast_qr::Mode;
+    ///
+    /// assert!(Mode::Numeric.can_encode(b"0123"));
+    /// assert!(!Mode::Numeric.can_encode(b"ABC"));
+    /// assert!(Mode::Alphanumeric.can_encode(b"ABC"));
+    /// assert!(Mode::Byte.can_encode("🚀".as_bytes()));
+    /// assert!(Mode::Kanji.can_encode(&[0x93, 0x5F]));
+    /// ```
+    #[must_use]
+    pub fn can_encode(self, input: &[u8]) -> bool {
+        no_panic!({
+            match self {
+                Mode::Numeric => input.iter().all(u8::is_ascii_digit),
+                Mode::Alphanumeric => input.iter().all(|&c| is_qr_alphanumeric(c)),
+                Mode::Byte => true,
+                Mode::Kanji => is_kanji(input),
+            }
+        })
+    }
+
+    /// Checks every character of `input` can be encoded in this mode, like
+    /// [`can_encode`](Mode::can_encode) but reporting the first one which
+    /// cannot
+    ///
+    /// ```rust
+    /// use fast_qr::Mode;
+    ///
+    /// let error = Mode::Numeric.validate(b"12a45").unwrap_err();
+    /// assert_eq!((error.index, error.byte), (2, b'a'));
+    /// ```
+    ///
+    /// # Errors
+    /// - `InvalidCharacter` with the position of the first character this
+    ///   mode cannot encode
+    pub fn validate(self, input: &[u8]) -> Result<(), InvalidCharacter> {
+        let index = match self {
+            Mode::Numeric => input.iter().position(|c| !c.is_ascii_digit()),
+            Mode::Alphanumeric => input.iter().position(|&c| !is_qr_alphanumeric(c)),
+            Mode::Byte => None,
+            Mode::Kanji => input
+                .chunks(2)
+                .position(|pair| {
+                    pair.len() != 2 || kanji_value(u16::from_be_bytes([pair[0], pair[1]])).is_none()
+                })
+                .map(|pair| pair * 2),
+        };
+
+        match index {
+            Some(index) => Err(InvalidCharacter {
+                mode: self,
+                index,
+                byte: input[index],
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+/// A character of the input that its mode cannot encode
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct InvalidCharacter {
+    /// Mode the input was encoded in
+    pub mode: Mode,
+    /// Position of the character in the input, in bytes
+    pub index: usize,
+    /// The byte at `index`, the first of the pair in Kanji mode
+    pub byte: u8,
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvalidCharacter {}
+
+impl Display for InvalidCharacter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "Byte {:#04x} at index {} cannot be encoded in {:?} mode",
+            self.byte, self.index, self.mode
+        )
+    }
 }
 
-/// Encodes the string according the mode and version
-pub fn encode(input: &[u8], ecl: ECL, mode: Mode, version: Version) -> CompactQR {
+/// Encodes the string according the mode and version, filling the remaining
+/// capacity with `padding`
+///
+/// # Errors
+/// - `InvalidCharacter` if `mode` cannot encode `input`
+pub fn encode(
+    input: &[u8],
+    ecl: ECL,
+    mode: Mode,
+    version: Version,
+    padding: PadCodewords,
+) -> Result<CompactQR, InvalidCharacter> {
     let cci_bits = hardcode::cci_bits(version, mode);
 
     let mut compact = CompactQR::from_version(version);
 
     match mode {
-        Mode::Numeric => encode_numeric(&mut compact, input, cci_bits),
-        Mode::Alphanumeric => encode_alphanumeric(&mut compact, input, cci_bits),
+        Mode::Numeric => encode_numeric(&mut compact, input, cci_bits)?,
+        Mode::Alphanumeric => encode_alphanumeric(&mut compact, input, cci_bits)?,
         Mode::Byte => encode_byte(&mut compact, input, cci_bits),
+        Mode::Kanji => encode_kanji(&mut compact, input, cci_bits)?,
     };
 
     let data_bits = hardcode::data_bits(version, ecl);
+    debug_assert!(
+        compact.len() <= data_bits,
+        "{} bits encoded for a capacity of {data_bits}",
+        compact.len()
+    );
 
     add_terminator(&mut compact, data_bits);
     pad_to_8(&mut compact);
-    compact.fill();
+    compact.fill(padding);
 
-    compact
+    Ok(compact)
 }
 
-/// Find the best encoding (Numeric -> Alnum -> Byte)
+/// Find the best encoding (Numeric -> Alnum -> Kanji -> Byte)
+///
+/// Kanji is picked for Shift JIS input, or UTF-8 text convertible to it with
+/// the `kanji` feature. Other text falls back to Byte, without ECI header:
+/// announce UTF-8 with [`Segment::eci`](crate::segment::Segment::eci) if
+/// needed.
 pub fn best_encoding(input: &[u8]) -> Mode {
     fn try_encode_numeric(input: &[u8], i: usize) -> Mode {
         for &c in input.iter().skip(i) {
@@ -54,17 +188,75 @@ pub fn best_encoding(input: &[u8]) -> Mode {
     fn try_encode_alphanumeric(input: &[u8], i: usize) -> Mode {
         for &c in input.iter().skip(i) {
             if !is_qr_alphanumeric(c) {
-                return Mode::Byte;
+                return try_encode_kanji(input);
             }
         }
         Mode::Alphanumeric
     }
 
+    fn try_encode_kanji(input: &[u8]) -> Mode {
+        if is_shift_jis(input) || kanji_from_utf8(input).is_some() {
+            Mode::Kanji
+        } else {
+            Mode::Byte
+        }
+    }
+
     try_encode_numeric(input, 0)
 }
 
+/// Returns the 13-bit value of a double-byte Shift JIS character, `None`
+/// outside of `0x8140..=0x9FFC` and `0xE040..=0xEBBF`, referring to 8.4.5 of
+/// the spec.
+pub(crate) const fn kanji_value(c: u16) -> Option<u16> {
+    let c = match c {
+        0x8140..=0x9FFC => c - 0x8140,
+        0xE040..=0xEBBF => c - 0xC140,
+        _ => return None,
+    };
+    Some((c >> 8) * 0xC0 + (c & 0xFF))
+}
+
+/// Checks if `input` only holds double-byte Shift JIS characters
+pub(crate) fn is_kanji(input: &[u8]) -> bool {
+    input.len() % 2 == 0
+        && input
+            .chunks_exact(2)
+            .all(|pair| kanji_value(u16::from_be_bytes([pair[0], pair[1]])).is_some())
+}
+
+/// Checks if `input` is Kanji encoded as Shift JIS and not UTF-8 text, whose
+/// multi-byte characters can also read as double-byte pairs
+fn is_shift_jis(input: &[u8]) -> bool {
+    !input.is_empty() && is_kanji(input) && core::str::from_utf8(input).is_err()
+}
+
+/// Converts UTF-8 text to Shift JIS, `None` if a character has no
+/// double-byte equivalent
+#[cfg(feature = "kanji")]
+pub(crate) fn kanji_from_utf8(input: &[u8]) -> Option<alloc::vec::Vec<u8>> {
+    let text = core::str::from_utf8(input).ok()?;
+    let (sjis, _, had_errors) = encoding_rs::SHIFT_JIS.encode(text);
+    if had_errors || text.is_empty() || !is_kanji(&sjis) {
+        return None;
+    }
+    Some(sjis.into_owned())
+}
+
+/// Without the `kanji` feature, UTF-8 text is never converted
+#[cfg(not(feature = "kanji"))]
+pub(crate) fn kanji_from_utf8(_input: &[u8]) -> Option<alloc::vec::Vec<u8>> {
+    None
+}
+
 /// Encodes numeric strings (i.e. "123456789"), referring to 8.4.2 of the spec.
-pub(crate) fn encode_numeric(compact: &mut CompactQR, input: &[u8], cci_bits: usize) {
+///
+/// Nothing is pushed if `input` has other characters than digits
+pub(crate) fn encode_numeric(
+    compact: &mut CompactQR,
+    input: &[u8],
+    cci_bits: usize,
+) -> Result<(), InvalidCharacter> {
     #[derive(Clone, Copy)]
     enum NumericEncoding {
         Single,
@@ -80,56 +272,58 @@ pub(crate) fn encode_numeric(compact: &mut CompactQR, input: &[u8], cci_bits: us
         }
     }
 
+    Mode::Numeric.validate(input)?;
+
     compact.push_bits(0b0001, 4);
     compact.push_bits(input.len(), cci_bits);
 
-    let mut i = 0;
-    let len = input.len() - input.len() % 3;
-
-    while i < len {
-        let number = ascii_to_digit(input[i]) * 100
-            + ascii_to_digit(input[i + 1]) * 10
-            + ascii_to_digit(input[i + 2]);
-
+    let chunks = input.chunks_exact(3);
+    let remainder = chunks.remainder();
+    for chunk in chunks {
+        let number = chunk
+            .iter()
+            .fold(0, |number, &c| number * 10 + ascii_to_digit(c));
         encode_number(compact, number, NumericEncoding::Triple);
-        i += 3;
-    }
-
-    // If the length is a multiple of 3, we are done
-    if len == input.len() {
-        return;
     }
 
-    let mut number = 0;
-    while i < input.len() {
-        number *= 10;
-        number += ascii_to_digit(input[i]);
-        i += 1;
-    }
-
-    let encoding = match i % 3 {
+    // The remaining 1 or 2 digits, if the length is not a multiple of 3
+    let encoding = match remainder.len() {
+        0 => return Ok(()),
         1 => NumericEncoding::Single,
-        2 => NumericEncoding::Double,
-        _ => unreachable!("i % 3 can only be 1 or 2"),
+        _ => NumericEncoding::Double,
     };
+    let number = remainder
+        .iter()
+        .fold(0, |number, &c| number * 10 + ascii_to_digit(c));
 
     encode_number(compact, number, encoding);
+    Ok(())
 }
 
 /// Encodes alphanumeric strings (i.e. "FAST-QR123"), referring to 8.4.3 of the spec.
-pub(crate) fn encode_alphanumeric(compact: &mut CompactQR, input: &[u8], cci_bits: usize) {
+///
+/// Nothing is pushed if `input` has characters out of the alphanumeric set
+pub(crate) fn encode_alphanumeric(
+    compact: &mut CompactQR,
+    input: &[u8],
+    cci_bits: usize,
+) -> Result<(), InvalidCharacter> {
+    Mode::Alphanumeric.validate(input)?;
+
     compact.push_bits(0b0010, 4);
     compact.push_bits(input.len(), cci_bits);
 
-    let even_size = input.len() - input.len() % 2;
-    for chunk in input.chunks_exact(2) {
+    let chunks = input.chunks_exact(2);
+    let remainder = chunks.remainder();
+    for chunk in chunks {
         let a = ascii_to_alphanumeric(chunk[0]);
         let b = ascii_to_alphanumeric(chunk[1]);
         compact.push_bits(a * 45 + b, 11);
     }
-    if even_size != input.len() {
-        compact.push_bits(ascii_to_alphanumeric(*input.last().unwrap()), 6);
+    if let Some(&last) = remainder.first() {
+        compact.push_bits(ascii_to_alphanumeric(last), 6);
     }
+    Ok(())
 }
 
 /// Encodes any string (i.e. "<https://fast-qr.com/🚀>"), referring to 8.4.4 of the spec.
@@ -139,34 +333,60 @@ pub(crate) fn encode_byte(compact: &mut CompactQR, input: &[u8], cci_bits: usize
     compact.push_u8_slice(input);
 }
 
+/// Encodes double-byte Shift JIS characters, referring to 8.4.5 of the spec.
+///
+/// Nothing is pushed if `input` has other bytes than double-byte pairs
+pub(crate) fn encode_kanji(
+    compact: &mut CompactQR,
+    input: &[u8],
+    cci_bits: usize,
+) -> Result<(), InvalidCharacter> {
+    Mode::Kanji.validate(input)?;
+
+    compact.push_bits(0b1000, 4);
+    compact.push_bits(input.len() / 2, cci_bits);
+
+    for pair in input.chunks_exact(2) {
+        let value = kanji_value(u16::from_be_bytes([pair[0], pair[1]])).unwrap_or(0);
+        compact.push_bits(usize::from(value), 13);
+    }
+    Ok(())
+}
+
 /// Adds needed terminator padding, terminating the data `BitString`, referring to 8.4.8 of the spec.
-fn add_terminator(compact: &mut CompactQR, data_bits: usize) {
-    let len = data_bits - compact.len();
+pub(crate) fn add_terminator(compact: &mut CompactQR, data_bits: usize) {
+    // Data filling the whole capacity has no terminator
+    let len = data_bits.saturating_sub(compact.len());
     let len = core::cmp::min(len, 4);
 
     compact.push_bits(0, len);
 }
 
 /// Adds the padding to make the length of the `BitString` a multiple of 8, referring to 8.4.9 of the spec.
-fn pad_to_8(compact: &mut CompactQR) {
+pub(crate) fn pad_to_8(compact: &mut CompactQR) {
     let len = (8 - compact.len() % 8) % 8;
     compact.push_bits(0, len);
 }
 
 /// Converts ascii number to it's value in usize \
 /// "5" -> 5
+///
+/// Inputs are checked with [`Mode::validate`] beforehand, other characters
+/// give an unspecified value instead of panicking
 fn ascii_to_digit(c: u8) -> usize {
-    assert!(
+    debug_assert!(
         c.is_ascii_digit(),
         "Unexpected character '{}' in Numeric mode",
         c as char
     );
-    (c - b'0') as usize
+    (c.wrapping_sub(b'0') % 10) as usize
 }
 
 /// Converts ascii alnum to it's numeric value, characters included in `AlphaNumeric` are: \
 /// 0-9, A-Z, $%*./:+-?.= [space] \
 /// referring to 7.1 of the spec.
+///
+/// Like [`ascii_to_digit`], other characters give an unspecified value
 pub(crate) fn ascii_to_alphanumeric(c: u8) -> usize {
     match c {
         b'0'..=b'9' => (c - b'0') as usize,
@@ -180,13 +400,20 @@ pub(crate) fn ascii_to_alphanumeric(c: u8) -> usize {
         b'.' => 42,
         b'/' => 43,
         b':' => 44,
-        _ => panic!("Unexpected character '{}' in Alphanumeric mode", c as char),
+        _ => {
+            debug_assert!(
+                false,
+                "Unexpected character '{}' in Alphanumeric mode",
+                c as char
+            );
+            0
+        }
     }
 }
 
 /// Checks if character c is alphanumeric: 0-9, A-Z, $%*./:+-?.= [space] \
 /// referring to 7.1 of the spec.
-const fn is_qr_alphanumeric(c: u8) -> bool {
+pub(crate) const fn is_qr_alphanumeric(c: u8) -> bool {
     matches!(c,
         b'A'..=b'Z'
         | b'0'..=b'9'
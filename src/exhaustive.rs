@@ -0,0 +1,48 @@
+//! Builds a code in every version, error correction level and mask, to test
+//! renderers and decoders against each arrangement of the matrix: the 40
+//! sizes, the alignment patterns, the version information from version 7 and
+//! the 32 format information words.
+//!
+//! ```rust
+//! use fast_qr::exhaustive::{self, CONFIGURATIONS};
+//!
+//! let mut count = 0;
+//! exhaustive::for_each_configuration(|qrcode| {
+//!     assert_eq!(qrcode.check_invariants(), Ok(()));
+//!     count += 1;
+//! });
+//! assert_eq!(count, CONFIGURATIONS);
+//! ```
+
+#![deny(unsafe_code)]
+#![warn(missing_docs)]
+
+use crate::{Mask, QRBuilder, QRCode, Version, ECL};
+
+/// Number of codes built by [`for_each_configuration`]: 40 versions, 4
+/// levels and 8 masks
+pub const CONFIGURATIONS: usize = 40 * 4 * 8;
+
+/// Data of every code, which fits version 1 at `ECL::H`
+pub const PAYLOAD: &str = "fast_qr";
+
+const ECLS: [ECL; 4] = [ECL::L, ECL::M, ECL::Q, ECL::H];
+
+/// Calls `callback` with a code holding [`PAYLOAD`] for each version, then
+/// each level from `ECL::L` to `ECL::H`, then each mask by pattern reference.
+/// The configuration is in the `version`, `ecl` and `mask` fields of the code.
+pub fn for_each_configuration<F: FnMut(&QRCode)>(mut callback: F) {
+    for version in Version::iter() {
+        for ecl in ECLS {
+            for mask in Mask::ALL {
+                let qrcode = QRBuilder::new(PAYLOAD)
+                    .version(version)
+                    .ecl(ecl)
+                    .mask(mask)
+                    .build()
+                    .expect("the payload fits version 1 at ECL::H");
+                callback(&qrcode);
+            }
+        }
+    }
+}
@@ -0,0 +1,18 @@
+//! Exports many codes at once
+//!
+//! - [`write_packed`] stores packed matrices in a single memory-mapped file,
+//!   so other machines can render them without encoding again (`mmap` feature)
+//! - [`zip_batch`] renders codes into a ZIP archive, one file per code, for
+//!   "download all" buttons (`zip` feature)
+
+#[cfg(feature = "mmap")]
+mod packed;
+#[cfg(feature = "zip")]
+mod zip;
+
+#[cfg(feature = "mmap")]
+#[cfg_attr(docsrs, doc(cfg(feature = "mmap")))]
+pub use packed::{write_packed, PackedFile};
+#[cfg(feature = "zip")]
+#[cfg_attr(docsrs, doc(cfg(feature = "zip")))]
+pub use zip::{zip_batch, ZipFormat};
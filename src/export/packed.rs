@@ -0,0 +1,195 @@
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::path::Path;
+
+use memmap2::{Mmap, MmapMut};
+
+use crate::matrix::Matrix;
+use crate::QRMatrix;
+
+const MAGIC: &[u8; 4] = b"FQRM";
+const FORMAT_VERSION: u32 = 1;
+const HEADER_LEN: usize = 16;
+const ENTRY_LEN: usize = 16;
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Writes every matrix to `path` through a memory map, in iteration order,
+/// so other machines can render them without encoding again.
+///
+/// The file grows while writing, matrices are never all held in memory.
+///
+/// The file starts with a header, followed by an index and the packed
+/// modules of each code ([`QRMatrix::as_bytes`]), all integers little-endian:
+///
+/// | Bytes            | Content                                        |
+/// |------------------|------------------------------------------------|
+/// | 4                | magic `FQRM`                                   |
+/// | 4                | format version, `1`                            |
+/// | 8                | number of codes `n`                            |
+/// | 16 per code      | offset of its modules (8), its size (4), 0 (4) |
+/// | rest             | packed modules                                 |
+///
+/// ```rust,no_run
+/// use fast_qr::export::{write_packed, PackedFile};
+/// use fast_qr::matrix::Matrix;
+/// use fast_qr::{QRBuilder, QRMatrix};
+///
+/// let matrices = (0..1000).map(|i| {
+///     let qrcode = QRBuilder::new(format!("https://example.com/{i}")).build().unwrap();
+///     QRMatrix::from(&qrcode)
+/// });
+/// write_packed("codes.fqrm", matrices).unwrap();
+///
+/// let file = PackedFile::open("codes.fqrm").unwrap();
+/// assert_eq!(file.len(), 1000);
+/// let first = file.get(0).unwrap();
+/// assert_eq!(first.size(), 25);
+/// ```
+///
+/// # Errors
+/// - Any IO error creating, resizing or mapping the file
+pub fn write_packed<P, I>(path: P, matrices: I) -> io::Result<()>
+where
+    P: AsRef<Path>,
+    I: IntoIterator<Item = QRMatrix>,
+    I::IntoIter: ExactSizeIterator,
+{
+    let matrices = matrices.into_iter();
+    let count = matrices.len();
+    let data_start = HEADER_LEN + count * ENTRY_LEN;
+
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)?;
+
+    // Enough for a thousand version 1 codes, doubled whenever needed
+    let mut capacity = data_start + 256 * 1024;
+    file.set_len(capacity as u64)?;
+    let mut mmap = map_mut(&file)?;
+
+    mmap[..4].copy_from_slice(MAGIC);
+    mmap[4..8].copy_from_slice(&FORMAT_VERSION.to_le_bytes());
+    mmap[8..16].copy_from_slice(&(count as u64).to_le_bytes());
+
+    let mut offset = data_start;
+    for (i, matrix) in matrices.enumerate() {
+        let bytes = matrix.as_bytes();
+
+        if offset + bytes.len() > capacity {
+            mmap.flush()?;
+            capacity = (capacity * 2).max(offset + bytes.len());
+            file.set_len(capacity as u64)?;
+            mmap = map_mut(&file)?;
+        }
+
+        let entry = HEADER_LEN + i * ENTRY_LEN;
+        mmap[entry..entry + 8].copy_from_slice(&(offset as u64).to_le_bytes());
+        mmap[entry + 8..entry + 12].copy_from_slice(&(matrix.size() as u32).to_le_bytes());
+
+        mmap[offset..offset + bytes.len()].copy_from_slice(bytes);
+        offset += bytes.len();
+    }
+
+    mmap.flush()?;
+    drop(mmap);
+    file.set_len(offset as u64)
+}
+
+#[allow(unsafe_code)]
+fn map_mut(file: &File) -> io::Result<MmapMut> {
+    // SAFETY: the file was just created by us, nothing else is expected to
+    // resize it while it is mapped
+    unsafe { MmapMut::map_mut(file) }
+}
+
+/// Read-only view of a file written by [`write_packed`]
+pub struct PackedFile {
+    mmap: Mmap,
+    count: usize,
+}
+
+impl PackedFile {
+    /// Maps the file at `path` and checks its header
+    ///
+    /// # Errors
+    /// - Any IO error opening or mapping the file
+    /// - `io::ErrorKind::InvalidData` if the header or index is invalid
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = File::open(path)?;
+
+        #[allow(unsafe_code)]
+        // SAFETY: the mapping is read-only, the file must not be truncated
+        // while it is open, as with any memory-mapped file
+        let mmap = unsafe { Mmap::map(&file)? };
+
+        if mmap.len() < HEADER_LEN || &mmap[..4] != MAGIC {
+            return Err(invalid_data("Not a packed QRCode file"));
+        }
+        if read_u32(&mmap, 4) != FORMAT_VERSION {
+            return Err(invalid_data("Unsupported packed QRCode file version"));
+        }
+
+        let count = read_u64(&mmap, 8) as usize;
+        let index_end = count
+            .checked_mul(ENTRY_LEN)
+            .and_then(|len| len.checked_add(HEADER_LEN));
+        if index_end.map_or(true, |end| end > mmap.len()) {
+            return Err(invalid_data("Truncated packed QRCode index"));
+        }
+
+        Ok(PackedFile { mmap, count })
+    }
+
+    /// Returns the number of codes in the file
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    /// Returns true if the file holds no code
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Returns the packed modules and size of the code at `index`, without copying
+    #[must_use]
+    pub fn get_bytes(&self, index: usize) -> Option<(usize, &[u8])> {
+        if index >= self.count {
+            return None;
+        }
+
+        let entry = HEADER_LEN + index * ENTRY_LEN;
+        let offset = read_u64(&self.mmap, entry) as usize;
+        let size = read_u32(&self.mmap, entry + 8) as usize;
+        let len = (size * size + 1) / 2;
+
+        let bytes = self.mmap.get(offset..offset.checked_add(len)?)?;
+        Some((size, bytes))
+    }
+
+    /// Returns the code at `index`, `None` if out of bounds or truncated
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<QRMatrix> {
+        let (size, bytes) = self.get_bytes(index)?;
+        QRMatrix::from_packed(size, bytes.to_vec())
+    }
+}
+
+fn read_u32(bytes: &[u8], at: usize) -> u32 {
+    let mut buffer = [0; 4];
+    buffer.copy_from_slice(&bytes[at..at + 4]);
+    u32::from_le_bytes(buffer)
+}
+
+fn read_u64(bytes: &[u8], at: usize) -> u64 {
+    let mut buffer = [0; 8];
+    buffer.copy_from_slice(&bytes[at..at + 8]);
+    u64::from_le_bytes(buffer)
+}
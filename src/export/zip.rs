@@ -0,0 +1,281 @@
+use std::io::{self, Write};
+
+use flate2::write::DeflateEncoder;
+use flate2::{Compression, Crc};
+
+use crate::convert::bitmap::{BitmapBuilder, BitmapFormat};
+#[cfg(feature = "dxf")]
+use crate::convert::dxf::DxfBuilder;
+#[cfg(feature = "eps")]
+use crate::convert::eps::EpsBuilder;
+use crate::convert::html::HtmlBuilder;
+#[cfg(feature = "pdf")]
+use crate::convert::pdf::PdfBuilder;
+#[cfg(feature = "skia")]
+use crate::convert::skia::SkiaBuilder;
+#[cfg(feature = "svg")]
+use crate::convert::svg::SvgBuilder;
+#[cfg(feature = "zpl")]
+use crate::convert::zpl::ZplBuilder;
+use crate::matrix::Matrix;
+
+const LOCAL_HEADER: u32 = 0x0403_4b50;
+const CENTRAL_HEADER: u32 = 0x0201_4b50;
+const END_OF_CENTRAL_DIRECTORY: u32 = 0x0605_4b50;
+/// ZIP 2.0, the first version with deflate
+const VERSION: u16 = 20;
+/// Bit 11, names are UTF-8
+const UTF8_NAMES: u16 = 1 << 11;
+const STORED: u16 = 0;
+const DEFLATED: u16 = 8;
+/// 1980-01-01 in MS-DOS format, the earliest date ZIP can hold
+const DOS_DATE: u16 = (1 << 5) | 1;
+
+/// Output of each code in a [`zip_batch`] archive, with the builder rendering it
+#[derive(Clone, Copy)]
+pub enum ZipFormat<'a> {
+    /// `.svg` files
+    #[cfg(feature = "svg")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "svg")))]
+    Svg(&'a SvgBuilder),
+    /// `.png` files, rendered with tiny-skia
+    #[cfg(feature = "skia")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "skia")))]
+    Png(&'a SkiaBuilder),
+    /// `.pdf` files
+    #[cfg(feature = "pdf")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "pdf")))]
+    Pdf(&'a PdfBuilder),
+    /// `.eps` files
+    #[cfg(feature = "eps")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "eps")))]
+    Eps(&'a EpsBuilder),
+    /// `.dxf` files
+    #[cfg(feature = "dxf")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "dxf")))]
+    Dxf(&'a DxfBuilder),
+    /// `.zpl` labels, drawing the code as a graphic field
+    #[cfg(feature = "zpl")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "zpl")))]
+    Zpl(&'a ZplBuilder),
+    /// `.pbm`, `.pgm` or `.xbm` files
+    Bitmap(&'a BitmapBuilder, BitmapFormat),
+    /// `.html` fragments
+    Html(&'a HtmlBuilder),
+}
+
+impl ZipFormat<'_> {
+    /// File extension of the entries, without the dot
+    #[must_use]
+    pub fn extension(&self) -> &'static str {
+        match self {
+            #[cfg(feature = "svg")]
+            ZipFormat::Svg(_) => "svg",
+            #[cfg(feature = "skia")]
+            ZipFormat::Png(_) => "png",
+            #[cfg(feature = "pdf")]
+            ZipFormat::Pdf(_) => "pdf",
+            #[cfg(feature = "eps")]
+            ZipFormat::Eps(_) => "eps",
+            #[cfg(feature = "dxf")]
+            ZipFormat::Dxf(_) => "dxf",
+            #[cfg(feature = "zpl")]
+            ZipFormat::Zpl(_) => "zpl",
+            ZipFormat::Bitmap(_, BitmapFormat::PbmAscii | BitmapFormat::Pbm) => "pbm",
+            ZipFormat::Bitmap(_, BitmapFormat::Pgm) => "pgm",
+            ZipFormat::Bitmap(_, BitmapFormat::Xbm) => "xbm",
+            ZipFormat::Html(_) => "html",
+        }
+    }
+
+    fn render<M: Matrix>(&self, qr: &M) -> io::Result<Vec<u8>> {
+        Ok(match self {
+            #[cfg(feature = "svg")]
+            ZipFormat::Svg(builder) => builder.to_str(qr).into_bytes(),
+            #[cfg(feature = "skia")]
+            ZipFormat::Png(builder) => builder
+                .to_bytes(qr)
+                .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?,
+            #[cfg(feature = "pdf")]
+            ZipFormat::Pdf(builder) => builder.to_bytes(qr),
+            #[cfg(feature = "eps")]
+            ZipFormat::Eps(builder) => builder.to_str(qr).into_bytes(),
+            #[cfg(feature = "dxf")]
+            ZipFormat::Dxf(builder) => builder.to_str(qr).into_bytes(),
+            #[cfg(feature = "zpl")]
+            ZipFormat::Zpl(builder) => builder.to_str(qr).into_bytes(),
+            ZipFormat::Bitmap(builder, format) => builder.to_bytes(qr, *format),
+            ZipFormat::Html(builder) => builder.to_str(qr).into_bytes(),
+        })
+    }
+}
+
+/// An archived file, as listed in the central directory
+struct Entry {
+    name: String,
+    method: u16,
+    crc: u32,
+    compressed_size: u32,
+    size: u32,
+    offset: u32,
+}
+
+impl Entry {
+    /// Fields shared by the local and central headers, from the version
+    /// needed to extract to the name length
+    fn write_common(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&VERSION.to_le_bytes());
+        out.extend_from_slice(&UTF8_NAMES.to_le_bytes());
+        out.extend_from_slice(&self.method.to_le_bytes());
+        out.extend_from_slice(&0u16.to_le_bytes());
+        out.extend_from_slice(&DOS_DATE.to_le_bytes());
+        out.extend_from_slice(&self.crc.to_le_bytes());
+        out.extend_from_slice(&self.compressed_size.to_le_bytes());
+        out.extend_from_slice(&self.size.to_le_bytes());
+        out.extend_from_slice(&(self.name.len() as u16).to_le_bytes());
+    }
+
+    fn write_local(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&LOCAL_HEADER.to_le_bytes());
+        self.write_common(out);
+        // Extra field length
+        out.extend_from_slice(&0u16.to_le_bytes());
+        out.extend_from_slice(self.name.as_bytes());
+    }
+
+    fn write_central(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&CENTRAL_HEADER.to_le_bytes());
+        out.extend_from_slice(&VERSION.to_le_bytes());
+        self.write_common(out);
+        // Extra field and comment lengths, disk number, internal and
+        // external attributes
+        out.extend_from_slice(&[0; 12]);
+        out.extend_from_slice(&self.offset.to_le_bytes());
+        out.extend_from_slice(self.name.as_bytes());
+    }
+}
+
+fn too_large(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message)
+}
+
+fn to_u32(value: usize) -> io::Result<u32> {
+    u32::try_from(value).map_err(|_| too_large("Archive larger than 4 GiB"))
+}
+
+/// Renders every code in `format` and writes them to `writer` as a ZIP
+/// archive, in iteration order. Each entry is named
+/// `naming(index, &code)` followed by the format extension, `/` separating
+/// folders.
+///
+/// Codes are written as soon as they are rendered: only the name and
+/// position of each entry are held until the end of the archive. Entries are
+/// deflated, or stored when deflate does not shrink them, and all dated
+/// 1980-01-01 so the same codes always give the same archive.
+///
+/// Returns `writer` once the archive is complete.
+///
+/// ```rust
+/// use fast_qr::convert::bitmap::{BitmapBuilder, BitmapFormat};
+/// use fast_qr::export::{zip_batch, ZipFormat};
+/// use fast_qr::QRBuilder;
+///
+/// let codes = (1..=3).map(|i| {
+///     QRBuilder::new(format!("https://example.com/ticket/{i}"))
+///         .build()
+///         .unwrap()
+/// });
+///
+/// let bitmap = BitmapBuilder::default();
+/// let archive = zip_batch(
+///     Vec::new(),
+///     codes,
+///     |i, _| format!("tickets/{}", i + 1),
+///     ZipFormat::Bitmap(&bitmap, BitmapFormat::Pbm),
+/// )
+/// .unwrap();
+/// assert!(archive.starts_with(b"PK\x03\x04"));
+/// ```
+///
+/// # Errors
+/// - Any IO error from `writer`, or from rendering a PNG
+/// - `io::ErrorKind::InvalidInput` if the archive would need ZIP64: more than
+///   65535 entries, a name longer than 65535 bytes or more than 4 GiB
+pub fn zip_batch<W, I, M, F>(
+    mut writer: W,
+    codes: I,
+    mut naming: F,
+    format: ZipFormat<'_>,
+) -> io::Result<W>
+where
+    W: Write,
+    I: IntoIterator<Item = M>,
+    M: Matrix,
+    F: FnMut(usize, &M) -> String,
+{
+    let mut entries = Vec::new();
+    let mut offset = 0;
+    let mut header = Vec::new();
+
+    for (index, qr) in codes.into_iter().enumerate() {
+        if index == usize::from(u16::MAX) {
+            return Err(too_large("More than 65535 entries"));
+        }
+        let name = format!("{}.{}", naming(index, &qr), format.extension());
+        if name.len() > usize::from(u16::MAX) {
+            return Err(too_large("Entry name longer than 65535 bytes"));
+        }
+
+        let data = format.render(&qr)?;
+        let size = to_u32(data.len())?;
+        let mut crc = Crc::new();
+        crc.update(&data);
+
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&data)?;
+        let deflated = encoder.finish()?;
+        let (method, stored) = if deflated.len() < data.len() {
+            (DEFLATED, deflated)
+        } else {
+            (STORED, data)
+        };
+
+        let entry = Entry {
+            name,
+            method,
+            crc: crc.sum(),
+            compressed_size: to_u32(stored.len())?,
+            size,
+            offset: to_u32(offset)?,
+        };
+
+        header.clear();
+        entry.write_local(&mut header);
+        writer.write_all(&header)?;
+        writer.write_all(&stored)?;
+        offset += header.len() + stored.len();
+        entries.push(entry);
+    }
+
+    let mut central = Vec::new();
+    for entry in &entries {
+        entry.write_central(&mut central);
+    }
+    let central_offset = to_u32(offset)?;
+    let central_size = to_u32(central.len())?;
+
+    let count = entries.len() as u16;
+    central.extend_from_slice(&END_OF_CENTRAL_DIRECTORY.to_le_bytes());
+    // Number of this disk, and of the disk holding the central directory
+    central.extend_from_slice(&[0; 4]);
+    central.extend_from_slice(&count.to_le_bytes());
+    central.extend_from_slice(&count.to_le_bytes());
+    central.extend_from_slice(&central_size.to_le_bytes());
+    central.extend_from_slice(&central_offset.to_le_bytes());
+    // Comment length
+    central.extend_from_slice(&0u16.to_le_bytes());
+
+    writer.write_all(&central)?;
+    writer.flush()?;
+    Ok(writer)
+}
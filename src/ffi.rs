@@ -0,0 +1,238 @@
+//! C bindings for the encoder, to build `fast_qr` as a shared or static library.
+//!
+//! Every function only takes plain C types. The header is shipped in
+//! `include/fast_qr.h`, `cbindgen` regenerates it from this module (see
+//! `cbindgen.toml`). Cargo features cannot change the crate type, the shared
+//! library comes from `cargo build` and the static one from `cargo rustc`:
+//!
+//! ```sh
+//! cargo build --release --features ffi                             # libfast_qr.so
+//! cargo rustc --release --features ffi --lib --crate-type staticlib  # libfast_qr.a
+//! cbindgen --config cbindgen.toml --output include/fast_qr.h
+//! ```
+//!
+//! # ABI stability
+//!
+//! Functions are prefixed by the version of the ABI, `fastqr_v1_`. Their
+//! signatures and the values of the constants never change within a
+//! version: a breaking change adds `fastqr_v2_` functions next to them, so a
+//! library can serve old and new consumers. [`fastqr_v1_abi_version`] lets
+//! consumers loading the library at runtime check the highest version it
+//! implements.
+//!
+//! ```c
+//! uint8_t modules[FAST_QR_MAX_MODULES];
+//! size_t size = sizeof(modules);
+//! if (fastqr_v1_generate(content, strlen(content), FAST_QR_ECL_Q, modules, &size) == FAST_QR_OK) {
+//!     // `size * size` modules, row by row, 1 for dark
+//! }
+//! ```
+
+#![allow(unsafe_code)]
+#![warn(missing_docs)]
+
+use core::slice;
+
+use crate::qr::QRCodeError;
+use crate::{QRBuilder, QRCode, ECL};
+
+/// Highest ABI version implemented, see [`fastqr_v1_abi_version`]
+pub const FAST_QR_ABI_VERSION: u32 = 1;
+
+/// Success
+pub const FAST_QR_OK: i32 = 0;
+/// A pointer argument is null
+pub const FAST_QR_NULL_POINTER: i32 = -1;
+/// `ecl` is not one of the `FAST_QR_ECL_*` values
+pub const FAST_QR_INVALID_ECL: i32 = -2;
+/// The content does not fit in a version 40 code
+pub const FAST_QR_DATA_TOO_BIG: i32 = -3;
+/// `out_buf` is too small, `*out_size` holds the required length
+pub const FAST_QR_BUFFER_TOO_SMALL: i32 = -4;
+/// The encoder refused the content for another reason than its size
+pub const FAST_QR_INVALID_INPUT: i32 = -5;
+
+/// Low error correction, 7%
+pub const FAST_QR_ECL_L: u8 = 0;
+/// Medium error correction, 15%
+pub const FAST_QR_ECL_M: u8 = 1;
+/// Quartile error correction, 25%
+pub const FAST_QR_ECL_Q: u8 = 2;
+/// High error correction, 30%
+pub const FAST_QR_ECL_H: u8 = 3;
+
+/// Bytes needed by the largest code, version 40 (177 x 177)
+pub const FAST_QR_MAX_MODULES: usize = 177 * 177;
+
+const fn ecl_from_u8(ecl: u8) -> Option<ECL> {
+    match ecl {
+        FAST_QR_ECL_L => Some(ECL::L),
+        FAST_QR_ECL_M => Some(ECL::M),
+        FAST_QR_ECL_Q => Some(ECL::Q),
+        FAST_QR_ECL_H => Some(ECL::H),
+        _ => None,
+    }
+}
+
+/// Returns [`FAST_QR_ABI_VERSION`], the highest `fastqr_vN_` prefix the
+/// library exports
+#[no_mangle]
+pub extern "C" fn fastqr_v1_abi_version() -> u32 {
+    FAST_QR_ABI_VERSION
+}
+
+/// # Safety
+/// `content` must be valid for reads of `len` bytes
+unsafe fn build(content: *const u8, len: usize, ecl: u8) -> Result<QRCode, i32> {
+    let ecl = ecl_from_u8(ecl).ok_or(FAST_QR_INVALID_ECL)?;
+    let content = if len == 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(content, len)
+    };
+
+    // Empty content gave an empty code before the builder refused it, the
+    // ABI keeps that behaviour
+    QRBuilder::new(content)
+        .ecl(ecl)
+        .allow_empty(true)
+        .build()
+        .map_err(|err| match err {
+            QRCodeError::EncodedData(_)
+            | QRCodeError::SpecifiedVersion
+            | QRCodeError::MaxVersion => FAST_QR_DATA_TOO_BIG,
+            QRCodeError::MaskPattern | QRCodeError::EmptyPayload | QRCodeError::InvalidMode(_) => {
+                FAST_QR_INVALID_INPUT
+            }
+        })
+}
+
+/// Encodes `len` bytes of `content` and writes its modules to `out_buf`, row
+/// by row, `1` for dark and `0` for light. `len` may be 0, giving a version 1
+/// code holding no data.
+///
+/// On input, `*out_size` is the length of `out_buf` in bytes. On success it
+/// is set to the number of modules per side, `out_buf` then holds
+/// `*out_size * *out_size` bytes. [`FAST_QR_MAX_MODULES`] bytes fit any code.
+///
+/// Returns [`FAST_QR_OK`] or a negative error code. With
+/// [`FAST_QR_BUFFER_TOO_SMALL`], `*out_size` is set to the required length
+/// in bytes and nothing is written.
+///
+/// # Safety
+/// - `content` must be valid for reads of `len` bytes, it may be null if `len` is 0
+/// - `out_size` must be valid for reads and writes
+/// - `out_buf` must be valid for writes of `*out_size` bytes
+#[no_mangle]
+pub unsafe extern "C" fn fastqr_v1_generate(
+    content: *const u8,
+    len: usize,
+    ecl: u8,
+    out_buf: *mut u8,
+    out_size: *mut usize,
+) -> i32 {
+    if (content.is_null() && len != 0) || out_buf.is_null() || out_size.is_null() {
+        return FAST_QR_NULL_POINTER;
+    }
+
+    let qrcode = match build(content, len, ecl) {
+        Ok(qrcode) => qrcode,
+        Err(code) => return code,
+    };
+
+    let modules = qrcode.size * qrcode.size;
+    if *out_size < modules {
+        *out_size = modules;
+        return FAST_QR_BUFFER_TOO_SMALL;
+    }
+
+    let out = slice::from_raw_parts_mut(out_buf, modules);
+    for (byte, module) in out.iter_mut().zip(&qrcode.data[..modules]) {
+        *byte = u8::from(module.value());
+    }
+
+    *out_size = qrcode.size;
+    FAST_QR_OK
+}
+
+/// Encodes `len` bytes of `content` and writes it as a NUL-terminated SVG
+/// document to `out_buf`, with the default [`SvgBuilder`](crate::convert::svg::SvgBuilder) options.
+/// `len` may be 0, giving a version 1 code holding no data.
+///
+/// On input, `*out_size` is the length of `out_buf` in bytes. On success it
+/// is set to the length of the document, NUL excluded.
+///
+/// Returns [`FAST_QR_OK`] or a negative error code. With
+/// [`FAST_QR_BUFFER_TOO_SMALL`], `*out_size` is set to the required length
+/// in bytes, NUL included, and nothing is written.
+///
+/// # Safety
+/// - `content` must be valid for reads of `len` bytes, it may be null if `len` is 0
+/// - `out_size` must be valid for reads and writes
+/// - `out_buf` must be valid for writes of `*out_size` bytes
+#[cfg(feature = "svg")]
+#[no_mangle]
+pub unsafe extern "C" fn fastqr_v1_generate_svg(
+    content: *const u8,
+    len: usize,
+    ecl: u8,
+    out_buf: *mut std::os::raw::c_char,
+    out_size: *mut usize,
+) -> i32 {
+    use crate::convert::svg::SvgBuilder;
+
+    if (content.is_null() && len != 0) || out_buf.is_null() || out_size.is_null() {
+        return FAST_QR_NULL_POINTER;
+    }
+
+    let qrcode = match build(content, len, ecl) {
+        Ok(qrcode) => qrcode,
+        Err(code) => return code,
+    };
+
+    let svg = SvgBuilder::default().to_str(&qrcode);
+    if *out_size < svg.len() + 1 {
+        *out_size = svg.len() + 1;
+        return FAST_QR_BUFFER_TOO_SMALL;
+    }
+
+    let out = slice::from_raw_parts_mut(out_buf.cast::<u8>(), svg.len() + 1);
+    out[..svg.len()].copy_from_slice(svg.as_bytes());
+    out[svg.len()] = 0;
+
+    *out_size = svg.len();
+    FAST_QR_OK
+}
+
+/// Unversioned name of [`fastqr_v1_generate`], from before the ABI was versioned
+///
+/// # Safety
+/// See [`fastqr_v1_generate`]
+#[deprecated(note = "use `fastqr_v1_generate`")]
+#[no_mangle]
+pub unsafe extern "C" fn fast_qr_generate(
+    content: *const u8,
+    len: usize,
+    ecl: u8,
+    out_buf: *mut u8,
+    out_size: *mut usize,
+) -> i32 {
+    fastqr_v1_generate(content, len, ecl, out_buf, out_size)
+}
+
+/// Unversioned name of [`fastqr_v1_generate_svg`], from before the ABI was versioned
+///
+/// # Safety
+/// See [`fastqr_v1_generate_svg`]
+#[cfg(feature = "svg")]
+#[deprecated(note = "use `fastqr_v1_generate_svg`")]
+#[no_mangle]
+pub unsafe extern "C" fn fast_qr_generate_svg(
+    content: *const u8,
+    len: usize,
+    ecl: u8,
+    out_buf: *mut std::os::raw::c_char,
+    out_size: *mut usize,
+) -> i32 {
+    fastqr_v1_generate_svg(content, len, ecl, out_buf, out_size)
+}
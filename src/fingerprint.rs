@@ -0,0 +1,89 @@
+//! Stable hashing of builder options, see `QRBuilder::options_fingerprint`.
+//!
+//! `core::hash::Hasher` implementations are free to change between Rust
+//! releases, a fingerprint stored next to generated assets must not: this is
+//! 64-bit FNV-1a, seeded with the crate version since encoder fixes can
+//! change the output for the same options.
+
+use core::fmt::Debug;
+
+use alloc::format;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+pub(crate) struct Fingerprint(u64);
+
+impl Fingerprint {
+    pub(crate) fn new(domain: &str) -> Self {
+        let mut fingerprint = Fingerprint(FNV_OFFSET_BASIS);
+        fingerprint.str(env!("CARGO_PKG_VERSION")).str(domain);
+        fingerprint
+    }
+
+    /// Length-prefixed, `"ab", "c"` and `"a", "bc"` hash differently
+    pub(crate) fn bytes(&mut self, bytes: &[u8]) -> &mut Self {
+        for &byte in (bytes.len() as u64).to_le_bytes().iter().chain(bytes) {
+            self.0 = (self.0 ^ u64::from(byte)).wrapping_mul(FNV_PRIME);
+        }
+        self
+    }
+
+    pub(crate) fn str(&mut self, value: &str) -> &mut Self {
+        self.bytes(value.as_bytes())
+    }
+
+    #[cfg(feature = "svg")]
+    pub(crate) fn u64(&mut self, value: u64) -> &mut Self {
+        self.bytes(&value.to_le_bytes())
+    }
+
+    /// For plain data types only, function pointers print their address
+    pub(crate) fn debug<T: Debug>(&mut self, value: &T) -> &mut Self {
+        self.str(&format!("{value:?}"))
+    }
+
+    pub(crate) const fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Fingerprint of a rendered output, from the options of the `QRBuilder`
+/// and of the renderer
+#[cfg(feature = "svg")]
+pub(crate) fn combine(qr_options: u64, render_options: u64) -> u64 {
+    Fingerprint::new("output")
+        .u64(qr_options)
+        .u64(render_options)
+        .finish()
+}
+
+/// Returns `png` with a `tEXt` chunk holding `keyword` and `text` (Latin-1),
+/// inserted right after the `IHDR` chunk
+#[cfg(any(all(test, feature = "svg"), feature = "image"))]
+pub(crate) fn png_with_text(png: &[u8], keyword: &str, text: &str) -> alloc::vec::Vec<u8> {
+    fn crc32(bytes: &[u8]) -> u32 {
+        let mut crc = !0_u32;
+        for &byte in bytes {
+            crc ^= u32::from(byte);
+            for _ in 0..8 {
+                crc = (crc >> 1) ^ (0xEDB8_8320 & (!(crc & 1)).wrapping_add(1));
+            }
+        }
+        !crc
+    }
+
+    // 8 bytes of signature, then IHDR: length, type, 13 bytes of data, CRC
+    const IHDR_END: usize = 8 + 4 + 4 + 13 + 4;
+
+    let mut chunk = alloc::vec::Vec::with_capacity(keyword.len() + text.len() + 13);
+    chunk.extend_from_slice(&((keyword.len() + 1 + text.len()) as u32).to_be_bytes());
+    chunk.extend_from_slice(b"tEXt");
+    chunk.extend_from_slice(keyword.as_bytes());
+    chunk.push(0);
+    chunk.extend_from_slice(text.as_bytes());
+    let crc = crc32(&chunk[4..]);
+    chunk.extend_from_slice(&crc.to_be_bytes());
+
+    [&png[..IHDR_END], &chunk, &png[IHDR_END..]].concat()
+}
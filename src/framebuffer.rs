@@ -0,0 +1,193 @@
+//! Rasterizes a code into a pixel buffer owned by the caller, without any
+//! allocation: for game engines, compositors and framebuffers which already
+//! hold their pixel memory.
+//!
+//! ```rust
+//! use fast_qr::framebuffer::{PixelFormat, RawRenderer};
+//! use fast_qr::QRBuilder;
+//!
+//! let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+//!
+//! // A 256 x 256 RGBA texture, the code 4 pixels per module at (8, 8)
+//! let stride = 256 * 4;
+//! let mut texture = vec![0; stride * 256];
+//! RawRenderer::default()
+//!     .render_into(&qrcode, &mut texture, stride, PixelFormat::Rgba8888, 4, (8, 8))
+//!     .unwrap();
+//! ```
+
+#![deny(unsafe_code)]
+#![warn(missing_docs)]
+
+use core::fmt::{Display, Formatter};
+
+use crate::matrix::Matrix;
+
+/// Layout of a pixel in the buffer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PixelFormat {
+    /// Red, green, blue and alpha, one byte each
+    Rgba8888,
+    /// Blue, green, red and alpha, one byte each, as most framebuffers
+    Bgra8888,
+    /// Red, green and blue, one byte each
+    Rgb888,
+    /// Luma, one byte, alpha is ignored
+    Gray8,
+}
+
+impl PixelFormat {
+    /// Number of bytes of a pixel
+    #[must_use]
+    pub const fn bytes_per_pixel(self) -> usize {
+        match self {
+            PixelFormat::Rgba8888 | PixelFormat::Bgra8888 => 4,
+            PixelFormat::Rgb888 => 3,
+            PixelFormat::Gray8 => 1,
+        }
+    }
+
+    /// Bytes of `rgba` in this format, the first
+    /// [`bytes_per_pixel`](PixelFormat::bytes_per_pixel) are used
+    fn encode(self, [r, g, b, a]: [u8; 4]) -> [u8; 4] {
+        match self {
+            PixelFormat::Rgba8888 => [r, g, b, a],
+            PixelFormat::Bgra8888 => [b, g, r, a],
+            PixelFormat::Rgb888 => [r, g, b, 0],
+            PixelFormat::Gray8 => {
+                // ITU-R BT.601 luma, in fixed point
+                let luma = (299 * u32::from(r) + 587 * u32::from(g) + 114 * u32::from(b)) / 1000;
+                [luma as u8, 0, 0, 0]
+            }
+        }
+    }
+}
+
+/// Contains the different errors when a code does not fit in the buffer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderError {
+    /// A row of the code, from the offset, is longer than the stride
+    StrideTooSmall,
+    /// The last rows of the code are past the end of the buffer
+    BufferTooSmall,
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RenderError {}
+
+impl Display for RenderError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            RenderError::StrideTooSmall => f.write_str("The code is wider than the stride"),
+            RenderError::BufferTooSmall => f.write_str("The code goes past the end of the buffer"),
+        }
+    }
+}
+
+/// Draws a [`Matrix`] with one color for dark modules and one for light
+/// modules, quiet zone included
+#[derive(Debug, Clone, Copy)]
+pub struct RawRenderer {
+    /// Default is opaque black
+    dark: [u8; 4],
+    /// Default is opaque white
+    light: [u8; 4],
+    /// Default is 4
+    margin: usize,
+}
+
+/// Renders black on white, with a quiet zone of 4 modules
+impl Default for RawRenderer {
+    fn default() -> Self {
+        RawRenderer::new([0, 0, 0, 255], [255; 4])
+    }
+}
+
+impl RawRenderer {
+    /// Creates a renderer drawing dark modules in `dark` and light ones in
+    /// `light`, both RGBA
+    #[must_use]
+    pub const fn new(dark: [u8; 4], light: [u8; 4]) -> Self {
+        RawRenderer {
+            dark,
+            light,
+            margin: 4,
+        }
+    }
+
+    /// Updates the quiet zone, in modules (default: 4)
+    pub fn margin(&mut self, margin: usize) -> &mut Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Width and height of the drawn area, quiet zone included, in pixels
+    #[must_use]
+    pub fn size<M: Matrix>(&self, qr: &M, scale: usize) -> usize {
+        (qr.size() + self.margin * 2) * scale.max(1)
+    }
+
+    /// Draws `qr` in `buffer`, rows being `stride` bytes apart, each module
+    /// `scale` pixels wide (at least 1). `offset` is the `(x, y)` pixel of
+    /// the top left corner of the quiet zone. Pixels around the drawn area
+    /// are left untouched.
+    ///
+    /// # Errors
+    /// - `RenderError::StrideTooSmall` if a row of the code does not fit in `stride`
+    /// - `RenderError::BufferTooSmall` if the last row goes past the end of `buffer`
+    pub fn render_into<M: Matrix>(
+        &self,
+        qr: &M,
+        buffer: &mut [u8],
+        stride: usize,
+        format: PixelFormat,
+        scale: usize,
+        offset: (usize, usize),
+    ) -> Result<(), RenderError> {
+        let scale = scale.max(1);
+        let size = self.size(qr, scale);
+        let bpp = format.bytes_per_pixel();
+        let (left, top) = offset;
+        if size == 0 {
+            return Ok(());
+        }
+
+        let row_end = left
+            .checked_add(size)
+            .and_then(|end| end.checked_mul(bpp))
+            .filter(|&end| end <= stride)
+            .ok_or(RenderError::StrideTooSmall)?;
+        let end = top
+            .checked_add(size - 1)
+            .and_then(|last| last.checked_mul(stride))
+            .and_then(|start| start.checked_add(row_end))
+            .filter(|&end| end <= buffer.len())
+            .ok_or(RenderError::BufferTooSmall)?;
+        let buffer = &mut buffer[..end];
+
+        let dark = format.encode(self.dark);
+        let light = format.encode(self.light);
+        let (dark, light) = (&dark[..bpp], &light[..bpp]);
+
+        let n = qr.size();
+        for py in 0..size {
+            let row_start = (top + py) * stride + left * bpp;
+            let row = &mut buffer[row_start..row_start + size * bpp];
+            // Module row of this pixel row, `None` in the quiet zone
+            let y = (py / scale).checked_sub(self.margin).filter(|&y| y < n);
+
+            for (mx, pixels) in row.chunks_exact_mut(scale * bpp).enumerate() {
+                let x = mx.checked_sub(self.margin).filter(|&x| x < n);
+                let color = match (y, x) {
+                    (Some(y), Some(x)) if qr.module(y, x).value() => dark,
+                    _ => light,
+                };
+                for pixel in pixels.chunks_exact_mut(bpp) {
+                    pixel.copy_from_slice(color);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -317,9 +317,103 @@ pub const fn cci_bits(version: Version, mode: Mode) -> usize {
             v if (v as usize) >= (V10 as usize) => 16,
             _ => 8,
         },
+        Mode::Kanji => match version {
+            v if (v as usize) >= (V27 as usize) => 12,
+            v if (v as usize) >= (V10 as usize) => 10,
+            _ => 8,
+        },
+    }
+}
+
+/// Row / column coordinates of the alignment pattern centers, for each version
+/// (Annex E of the spec). Every combination of two coordinates is a center,
+/// except the three overlapping finder patterns.
+pub const ALIGNMENT_PATTERNS_GRID: [&[usize]; 40] = [
+    &[],
+    &[6, 18],
+    &[6, 22],
+    &[6, 26],
+    &[6, 30],
+    &[6, 34],
+    &[6, 22, 38],
+    &[6, 24, 42],
+    &[6, 26, 46],
+    &[6, 28, 50],
+    &[6, 30, 54],
+    &[6, 32, 58],
+    &[6, 34, 62],
+    &[6, 26, 46, 66],
+    &[6, 26, 48, 70],
+    &[6, 26, 50, 74],
+    &[6, 30, 54, 78],
+    &[6, 30, 56, 82],
+    &[6, 30, 58, 86],
+    &[6, 34, 62, 90],
+    &[6, 28, 50, 72, 94],
+    &[6, 26, 50, 74, 98],
+    &[6, 30, 54, 78, 102],
+    &[6, 28, 54, 80, 106],
+    &[6, 32, 58, 84, 110],
+    &[6, 30, 58, 86, 114],
+    &[6, 34, 62, 90, 118],
+    &[6, 26, 50, 74, 98, 122],
+    &[6, 30, 54, 78, 102, 126],
+    &[6, 26, 52, 78, 104, 130],
+    &[6, 30, 56, 82, 108, 134],
+    &[6, 34, 60, 86, 112, 138],
+    &[6, 30, 58, 86, 114, 142],
+    &[6, 34, 62, 90, 118, 146],
+    &[6, 30, 54, 78, 102, 126, 150],
+    &[6, 24, 50, 76, 102, 128, 154],
+    &[6, 28, 54, 80, 106, 132, 158],
+    &[6, 32, 58, 84, 110, 136, 162],
+    &[6, 26, 54, 82, 110, 138, 166],
+    &[6, 30, 58, 86, 114, 142, 170],
+];
+
+/// Returns the `(y, x)` center of every alignment pattern of `version`,
+/// skipping the positions overlapping the finder patterns
+pub fn alignment_pattern_centers(version: Version) -> impl Iterator<Item = (usize, usize)> {
+    let grid = version.alignment_positions();
+    let last = grid.len().saturating_sub(1);
+
+    grid.iter().enumerate().flat_map(move |(i, &y)| {
+        grid.iter().enumerate().filter_map(move |(j, &x)| {
+            let on_finder = (i == 0 && (j == 0 || j == last)) || (i == last && j == 0);
+            (!on_finder).then(|| (y, x))
+        })
+    })
+}
+
+/// Returns the 18-bit version information of version `number` (from 7 to 40):
+/// the number on 6 bits followed by its BCH(18, 6) error correction (section 7.10)
+pub const fn version_information_bch(number: u32) -> u32 {
+    // x^12 + x^11 + x^10 + x^9 + x^8 + x^5 + x^2 + 1
+    const GENERATOR: u32 = 0b1_1111_0010_0101;
+
+    let mut remainder = number << 12;
+    let mut bit = 17;
+    while bit >= 12 {
+        if remainder & (1 << bit) != 0 {
+            remainder ^= GENERATOR << (bit - 12);
+        }
+        bit -= 1;
     }
+
+    (number << 12) | remainder
 }
 
+/// Version information for each version, `0` below version 7 which has none
+pub const VERSION_INFORMATION: [u32; 40] = {
+    let mut table = [0; 40];
+    let mut number = 7;
+    while number <= 40 {
+        table[number - 1] = version_information_bch(number as u32);
+        number += 1;
+    }
+    table
+};
+
 /// Returns required **dividing polynomial** according to `version` and `ecl`
 pub const fn get_polynomial(version: Version, ecl: ECL) -> &'static [u8] {
     use Version::{
@@ -2,8 +2,9 @@
 #![deny(unsafe_code)]
 #![warn(missing_docs)]
 
-use crate::module::Module;
 use crate::QRCode;
+use alloc::string::String;
+use alloc::{vec, vec::Vec};
 
 /// Used to print a ` ` (space)
 const EMPTY: char = ' ';
@@ -14,11 +15,12 @@ const TOP: char = '▀';
 /// Used to print a `▄`
 const BOTTOM: char = '▄';
 
-/// Helper to print two lines at the same time
-fn print_line(line1: &[Module], line2: &[Module], size: usize) -> String {
-    let mut line = String::with_capacity(size);
-    for i in 0..size {
-        match (line1[i].value(), line2[i].value()) {
+/// Helper to print two lines at the same time, `true` modules are printed
+/// as spaces (dark on a dark terminal)
+fn print_line(line1: &[bool], line2: &[bool]) -> String {
+    let mut line = String::with_capacity(line1.len());
+    for (&top, &bottom) in line1.iter().zip(line2) {
+        match (top, bottom) {
             (true, true) => line.push(EMPTY),
             (true, false) => line.push(BOTTOM),
             (false, true) => line.push(TOP),
@@ -28,34 +30,33 @@ fn print_line(line1: &[Module], line2: &[Module], size: usize) -> String {
     line
 }
 
-/// Prints a matrix with margins
-pub fn print_matrix_with_margin(qr: &QRCode) -> String {
-    let mut out = String::new();
+/// Prints a matrix surrounded by `margin` light modules
+pub fn print_matrix_with_margin(qr: &QRCode, margin: usize) -> String {
+    let width = qr.size + margin * 2;
 
-    let line = print_line(
-        &[Module::empty(true); 177],
-        &[Module::empty(false); 177],
-        qr.size,
-    );
-
-    out.push(BOTTOM);
-    out.push_str(&line);
-    out.push_str(&format!("{BOTTOM}\n"));
-
-    // Black background
-    for i in (0..qr.size - 1).step_by(2) {
-        let line = print_line(&qr[i], &qr[i + 1], qr.size);
-        out.push(BLOCK);
-        out.push_str(&line);
-        out.push_str(&format!("{BLOCK}\n"));
+    // Two modules rows are printed per line, the symbol having an odd size, an
+    // extra blank row (outside the margin) is added on top to pair them all
+    let mut rows: Vec<Vec<bool>> = Vec::with_capacity(width + 1);
+    rows.push(vec![true; width]);
+    for y in 0..width {
+        let row = (0..width)
+            .map(|x| {
+                y >= margin
+                    && x >= margin
+                    && y < margin + qr.size
+                    && x < margin + qr.size
+                    && qr[y - margin][x - margin].value()
+            })
+            .collect();
+        rows.push(row);
     }
 
-    let line = print_line(&qr[qr.size - 1], &[Module::empty(false); 177], qr.size);
-    out.push(BLOCK);
-    out.push_str(&line);
-    out.push(BLOCK);
+    let lines: Vec<String> = rows
+        .chunks_exact(2)
+        .map(|pair| print_line(&pair[0], &pair[1]))
+        .collect();
 
-    out
+    lines.join("\n")
 }
 
 #[cfg(test)]
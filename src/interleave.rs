@@ -0,0 +1,85 @@
+//! Splits data codewords into blocks and interleaves them with their error
+//! correction codewords, the final codeword sequence placed on the matrix
+//! (section 7.6 of the spec). [`QRCode::codewords`](crate::QRCode::codewords)
+//! and [`QRCode::blocks`](crate::QRCode::blocks) read both stages back from
+//! a built code.
+//!
+//! ```rust
+//! use fast_qr::interleave::{data_blocks, ecc_blocks, interleave};
+//! use fast_qr::{Version, ECL};
+//!
+//! let data = [0x40; 62];
+//! let blocks = data_blocks(&data, Version::V05, ECL::Q);
+//! assert_eq!(blocks.len(), 4);
+//!
+//! let ecc = ecc_blocks(&blocks, Version::V05, ECL::Q);
+//! let codewords = interleave(&blocks, &ecc, Version::V05);
+//! assert_eq!(codewords.len(), 134);
+//! ```
+
+#![deny(unsafe_code)]
+#![warn(missing_docs)]
+
+use crate::{galois, hardcode, Version, ECL};
+use alloc::vec::Vec;
+
+/// Splits `data` into the blocks of both groups, as defined by the spec
+/// tables for `version` and `ecl`
+///
+/// # Panics
+/// If `data` is shorter than the data capacity of `version` / `ecl`
+#[must_use]
+pub fn data_blocks(data: &[u8], version: Version, ecl: ECL) -> Vec<&[u8]> {
+    let [(g1_count, g1_size), (g2_count, g2_size)] = hardcode::ecc_to_groups(ecl, version);
+    let (group1, group2) = data.split_at(g1_count * g1_size);
+
+    group1
+        .chunks_exact(g1_size)
+        .chain(group2[..g2_count * g2_size].chunks_exact(g2_size.max(1)))
+        .collect()
+}
+
+/// Computes the error correction codewords of every block
+#[must_use]
+pub fn ecc_blocks(data_blocks: &[&[u8]], version: Version, ecl: ECL) -> Vec<Vec<u8>> {
+    let count = galois::ecc_codewords(version, ecl);
+
+    data_blocks
+        .iter()
+        .map(|block| galois::remainder(block, version, ecl)[..count].to_vec())
+        .collect()
+}
+
+/// Interleaves the blocks: the first codeword of each data block, then the
+/// second, ..., followed by the error correction codewords in the same way.
+/// Blocks of the second group are one codeword longer, their last codeword
+/// comes after every other data codeword.
+///
+/// The result holds every codeword of `version`, remainder bits excluded.
+#[must_use]
+pub fn interleave<D: AsRef<[u8]>, E: AsRef<[u8]>>(
+    data_blocks: &[D],
+    ecc_blocks: &[E],
+    version: Version,
+) -> Vec<u8> {
+    let mut out = Vec::with_capacity(version.max_bytes());
+    out.extend(columns(data_blocks).map(|(_, codeword)| codeword));
+    out.extend(columns(ecc_blocks).map(|(_, codeword)| codeword));
+
+    debug_assert!(out.len() <= version.max_bytes());
+    out
+}
+
+/// Yields the codewords of `blocks` in interleaved order with the index of
+/// their block: the first codeword of each block, then the second, ...
+pub(crate) fn columns<'a, B: AsRef<[u8]>>(
+    blocks: &'a [B],
+) -> impl Iterator<Item = (usize, u8)> + 'a {
+    let longest = blocks.iter().map(|b| b.as_ref().len()).max().unwrap_or(0);
+    (0..longest).flat_map(move |i| {
+        blocks
+            .iter()
+            .enumerate()
+            .filter_map(move |(block, codewords)| Some((block, *codewords.as_ref().get(i)?)))
+    })
+}
@@ -0,0 +1,188 @@
+//! Conversions with the types of the [`qrcode`](https://docs.rs/qrcode) and
+//! [`qrcodegen`](https://docs.rs/qrcodegen) crates, behind the features of
+//! the same name, to migrate a codebase one call site at a time.
+//!
+//! - `qrcode`: `QRCode` from a `qrcode::QrCode`, and the `Vec<qrcode::Color>`
+//!   its renderers take (`qrcode::render::Renderer::new`)
+//! - `qrcodegen`: `QRCode` from and to a `qrcodegen::QrCode`
+//!
+//! ```rust,ignore
+//! use std::convert::TryFrom;
+//!
+//! let theirs = qrcodegen::QrCode::encode_text("Hello", qrcodegen::QrCodeEcc::Medium).unwrap();
+//! let ours = fast_qr::QRCode::from(&theirs);
+//! let back = qrcodegen::QrCode::try_from(&ours).unwrap();
+//! ```
+
+#![deny(unsafe_code)]
+#![warn(missing_docs)]
+
+use core::fmt::{Display, Formatter};
+
+use crate::{default, Module, QRCode, Version, ECL};
+
+/// Contains the different errors when a conversion is not possible
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InteropError {
+    /// Micro QR codes have no equivalent in this crate
+    MicroQr,
+    /// The `QRCode` has no version, ECL or mask, i.e. it was not built by a [`QRBuilder`](crate::QRBuilder)
+    Incomplete,
+}
+
+impl std::error::Error for InteropError {}
+
+impl Display for InteropError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            InteropError::MicroQr => f.write_str("Micro QR codes are not supported"),
+            InteropError::Incomplete => f.write_str("QRCode has no version, ECL or mask"),
+        }
+    }
+}
+
+/// Builds a `QRCode` of `version` from the dark state of each module,
+/// module types coming from the function pattern layout
+fn from_modules(version: Version, ecl: ECL, is_dark: impl Fn(usize, usize) -> bool) -> QRCode {
+    let mut qr = default::create_matrix(version);
+    for y in 0..qr.size {
+        for x in 0..qr.size {
+            qr[y][x] = Module::new(is_dark(x, y), qr[y][x].module_type());
+        }
+    }
+
+    qr.version = Some(version);
+    qr.ecl = Some(ecl);
+    qr
+}
+
+#[cfg(feature = "qrcode")]
+mod qrcode_crate {
+    use core::convert::TryFrom;
+
+    use qrcode::{Color, EcLevel};
+
+    use super::{from_modules, InteropError};
+    use crate::{QRCode, Version, ECL};
+
+    impl TryFrom<&qrcode::QrCode> for QRCode {
+        type Error = InteropError;
+
+        /// Copies the modules, the mask is not exposed by `qrcode` and stays `None`
+        fn try_from(qrcode: &qrcode::QrCode) -> Result<Self, Self::Error> {
+            let version = match qrcode.version() {
+                qrcode::Version::Normal(number) => Version::from_number(number as usize),
+                qrcode::Version::Micro(_) => None,
+            }
+            .ok_or(InteropError::MicroQr)?;
+            let ecl = match qrcode.error_correction_level() {
+                EcLevel::L => ECL::L,
+                EcLevel::M => ECL::M,
+                EcLevel::Q => ECL::Q,
+                EcLevel::H => ECL::H,
+            };
+
+            let colors = qrcode.to_colors();
+            let width = qrcode.width();
+            Ok(from_modules(version, ecl, |x, y| {
+                colors[y * width + x] == Color::Dark
+            }))
+        }
+    }
+
+    impl From<&QRCode> for Vec<Color> {
+        fn from(qr: &QRCode) -> Self {
+            qr.data[..qr.size * qr.size]
+                .iter()
+                .map(|module| {
+                    if module.value() {
+                        Color::Dark
+                    } else {
+                        Color::Light
+                    }
+                })
+                .collect()
+        }
+    }
+}
+
+#[cfg(feature = "qrcodegen")]
+mod qrcodegen_crate {
+    use core::convert::TryFrom;
+
+    use qrcodegen::QrCodeEcc;
+
+    use super::{from_modules, InteropError};
+    use crate::{datamasking, hardcode, placement, polynomials, Mask, QRCode, Version, ECL};
+
+    impl From<&qrcodegen::QrCode> for QRCode {
+        fn from(qrcode: &qrcodegen::QrCode) -> Self {
+            let version = Version::from_number(usize::from(qrcode.version().value()))
+                .expect("qrcodegen versions range from 1 to 40");
+            let ecl = match qrcode.error_correction_level() {
+                QrCodeEcc::Low => ECL::L,
+                QrCodeEcc::Medium => ECL::M,
+                QrCodeEcc::Quartile => ECL::Q,
+                QrCodeEcc::High => ECL::H,
+            };
+
+            let mut qr = from_modules(version, ecl, |x, y| qrcode.get_module(x as i32, y as i32));
+            qr.mask = Mask::from_pattern(usize::from(qrcode.mask().value()));
+            qr
+        }
+    }
+
+    impl TryFrom<&QRCode> for qrcodegen::QrCode {
+        type Error = InteropError;
+
+        /// Reads the data codewords back from the matrix, `qrcodegen` then
+        /// recomputes the error correction and places them with the same mask
+        fn try_from(qr: &QRCode) -> Result<Self, Self::Error> {
+            let (version, ecl, mask) = match (qr.version, qr.ecl, qr.mask) {
+                (Some(version), Some(ecl), Some(mask)) => (version, ecl, mask),
+                _ => return Err(InteropError::Incomplete),
+            };
+
+            // Masking twice restores the placed bits
+            let mut unmasked = qr.clone();
+            datamasking::mask(&mut unmasked, mask);
+
+            let mut codewords = vec![0; version.max_bytes()];
+            for (i, (y, x)) in placement::data_positions(version)
+                .into_iter()
+                .take(codewords.len() * 8)
+                .enumerate()
+            {
+                codewords[i / 8] |= u8::from(unmasked[y][x].value()) << (7 - i % 8);
+            }
+
+            // Data codewords are interleaved block by block
+            let data_len = hardcode::data_codewords(version, ecl);
+            let blocks = polynomials::codeword_blocks(ecl, version);
+            let block_count = blocks[..data_len].iter().max().map_or(0, |b| b + 1);
+            let mut data = Vec::with_capacity(data_len);
+            for block in 0..block_count {
+                data.extend(
+                    codewords[..data_len]
+                        .iter()
+                        .zip(&blocks)
+                        .filter(|&(_, &b)| b == block)
+                        .map(|(&codeword, _)| codeword),
+                );
+            }
+
+            let ecl = match ecl {
+                ECL::L => QrCodeEcc::Low,
+                ECL::M => QrCodeEcc::Medium,
+                ECL::Q => QrCodeEcc::Quartile,
+                ECL::H => QrCodeEcc::High,
+            };
+            Ok(qrcodegen::QrCode::encode_codewords(
+                qrcodegen::Version::new(version as u8 + 1),
+                ecl,
+                &data,
+                Some(qrcodegen::Mask::new(mask.pattern() as u8)),
+            ))
+        }
+    }
+}
@@ -1,4 +1,5 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
+#![cfg_attr(not(feature = "std"), no_std)]
 #![warn(missing_docs)]
 //! # Easy to use fast QRCode generator
 //!
@@ -28,9 +29,11 @@
 //!
 //! ```rust
 //! # use fast_qr::convert::ConvertError;
+//! # #[cfg(feature = "svg")]
 //! use fast_qr::convert::{svg::SvgBuilder, Builder, Shape};
 //! use fast_qr::qr::QRBuilder;
 //!
+//! # #[cfg(feature = "svg")]
 //! # fn main() -> Result<(), ConvertError> {
 //! // QRBuilder::new can fail if content is too big for version,
 //! // please check before unwrapping.
@@ -45,15 +48,19 @@
 //!
 //! #     Ok(())
 //! # }
+//! # #[cfg(not(feature = "svg"))]
+//! # fn main() {}
 //! ```
 //!
 //! ## Converts [`QRCode`] to an image
 //!
 //! ```rust
 //! # use fast_qr::convert::ConvertError;
+//! # #[cfg(feature = "image")]
 //! use fast_qr::convert::{image::ImageBuilder, Builder, Shape};
 //! use fast_qr::qr::QRBuilder;
 //!
+//! # #[cfg(feature = "image")]
 //! # fn main() -> Result<(), ConvertError> {
 //! // QRBuilder::new can fail if content is too big for version,
 //! // please check before unwrapping.
@@ -70,35 +77,164 @@
 //!
 //! #     Ok(())
 //! # }
+//! # #[cfg(not(feature = "image"))]
+//! # fn main() {}
 //! ```
 
+//!
+//! ## `no_std`
+//!
+//! Disabling the default `std` feature and enabling `alloc` keeps the
+//! encoding pipeline (`QRBuilder`, `QRCode`, segments, matrices) for targets
+//! with an allocator but no operating system. Renderers, the scanner
+//! simulation and logo overlays need `std`.
+//!
+//! ```toml
+//! fast_qr = { version = "0.13", default-features = false, features = ["alloc"] }
+//! ```
+
+//!
+//! ## Thread safety
+//!
+//! Every public type is `Send + Sync`: codes, builders, render options,
+//! payloads and errors can be moved to or shared with other threads and
+//! async tasks. Custom hooks are plain `fn` pointers and
+//! [`TextShaper`](convert::caption::TextShaper) requires `Sync` for this
+//! reason. This is checked at compile time.
+
+//!
+//! ## Panics
+//!
+//! Building a [`QRCode`] reports every invalid input as a [`QRCodeError`](qr::QRCodeError),
+//! including characters a forced [`Mode`] cannot encode, and accessors which
+//! index (like [`QRCode::get`]) have fallible variants (like
+//! [`QRCode::try_get`]).
+//!
+//! For certified and FFI builds, the `fast_qr_no_panic` cfg checks at link
+//! time that [`Mode::can_encode`], [`QRCode::try_get`] and `ModuleType::from`
+//! contain no panic, even from bounds checks or arithmetic. Any binary
+//! calling them fails to link otherwise:
+//!
+//! ```sh
+//! RUSTFLAGS="--cfg fast_qr_no_panic" cargo run --profile no-panic --example no_panic
+//! ```
+//!
+//! Only these three functions are checked. Building a code and rendering it
+//! are covered by the fallible API above and by tests, not by the link-time
+//! check. The check needs optimizations and unwinding, which the `no-panic`
+//! profile sets.
+
+//!
+//! ## Debugging
+//!
+//! With the `dump` feature, setting `FAST_QR_DUMP_DIR` makes every encoding
+//! write its bitstream, codeword blocks, unmasked matrix and mask scores to
+//! that directory, to compare them step by step with another encoder when a
+//! code does not scan:
+//!
+//! ```sh
+//! FAST_QR_DUMP_DIR=/tmp/fast_qr cargo run --features cli,dump -- "https://example.com/"
+//! ```
+//!
+//! ## Fuzzing
+//!
+//! With the `arbitrary` feature, [`QRBuilder`] and its options implement
+//! `arbitrary::Arbitrary`, so `cargo fuzz` targets can generate any builder.
+//! Debug builds assert the bitstream fits the version and the codeword
+//! counts, and [`QRCode::check_invariants`] checks the built matrix:
+//!
+//! ```rust,ignore
+//! fuzz_target!(|builder: fast_qr::QRBuilder| {
+//!     if let Ok(qrcode) = builder.build() {
+//!         qrcode.check_invariants().unwrap();
+//!     }
+//! });
+//! ```
+
+#[cfg(not(feature = "alloc"))]
+compile_error!("fast_qr needs an allocator, enable the `std` or `alloc` feature");
+
+extern crate alloc;
+
+#[macro_use]
+mod no_panic;
+
+pub use crate::batch::QRBatchBuilder;
 pub use crate::datamasking::Mask;
 pub use crate::ecl::ECL;
-pub use crate::encode::Mode;
-pub use crate::module::{Module, ModuleType};
+pub use crate::encode::{InvalidCharacter, Mode};
+pub use crate::matrix::QRMatrix;
+pub use crate::module::{Module, ModuleCounts, ModuleType};
 pub use crate::qr::{QRBuilder, QRCode};
-pub use crate::version::Version;
+pub use crate::template::Template;
+#[cfg(feature = "std")]
+pub use crate::verify::VerifiedQRCode;
+pub use crate::version::{InvalidVersion, Version};
 
+pub mod baked;
+mod batch;
 mod compact;
+pub mod compat;
 #[doc(hidden)]
 pub mod datamasking;
+#[cfg(feature = "std")]
+pub mod decode;
 
+#[cfg(feature = "std")]
 pub mod convert;
 mod default;
+#[cfg(feature = "dump")]
+mod dump;
 mod ecl;
+#[cfg(feature = "embedded-graphics")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-graphics")))]
+pub mod embedded;
 mod encode;
+pub mod exhaustive;
+#[cfg(any(feature = "mmap", feature = "zip"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "mmap", feature = "zip"))))]
+pub mod export;
+#[cfg(feature = "ffi")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ffi")))]
+pub mod ffi;
+mod fingerprint;
+pub mod framebuffer;
+pub mod galois;
 mod hardcode;
 #[cfg(not(feature = "wasm-bindgen"))]
 mod helpers;
+pub mod interleave;
+#[cfg(any(feature = "qrcode", feature = "qrcodegen"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "qrcode", feature = "qrcodegen"))))]
+pub mod interop;
+pub mod matrix;
 mod module;
+#[cfg(feature = "std")]
+pub mod overlay;
+pub mod payload;
+pub mod physical;
 mod placement;
 mod polynomials;
 #[macro_use]
 pub mod qr;
+#[cfg(feature = "std")]
+pub mod scanner;
 mod score;
+pub mod segment;
+#[cfg(feature = "serde")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+mod serialize;
+#[cfg(feature = "simd")]
+#[cfg_attr(docsrs, doc(cfg(feature = "simd")))]
+pub mod simd;
+#[cfg(feature = "std")]
+pub mod structured_append;
+mod template;
+#[cfg(feature = "std")]
+pub mod verify;
 mod version;
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests;
 
 #[cfg(target_arch = "wasm32")]
@@ -106,3 +242,159 @@ mod wasm;
 
 #[cfg(target_arch = "wasm32")]
 pub use wasm::*;
+
+/// Fails to compile if a public type stops being `Send + Sync`
+#[allow(dead_code)]
+fn assert_thread_safe() {
+    fn send_sync<T: Send + Sync>() {}
+
+    send_sync::<QRCode>();
+    send_sync::<qr::QRCodeError>();
+    send_sync::<qr::Overflow>();
+    send_sync::<qr::InvariantError>();
+    send_sync::<qr::Block>();
+    send_sync::<qr::Conflict>();
+    send_sync::<qr::Negotiation>();
+    send_sync::<QRBuilder>();
+    send_sync::<QRBatchBuilder>();
+    send_sync::<Template>();
+    send_sync::<baked::BakedQR>();
+    send_sync::<QRMatrix>();
+    send_sync::<matrix::AsciiError>();
+    send_sync::<Module>();
+    send_sync::<ModuleType>();
+    send_sync::<Version>();
+    send_sync::<InvalidVersion>();
+    send_sync::<ECL>();
+    send_sync::<Mode>();
+    send_sync::<InvalidCharacter>();
+    send_sync::<Mask>();
+    send_sync::<datamasking::MaskScoring>();
+    send_sync::<datamasking::PenaltyWeights>();
+    send_sync::<datamasking::MaskReport>();
+    send_sync::<datamasking::MaskPenalty>();
+    send_sync::<compat::LegacyCompat>();
+    send_sync::<compat::MaskTieBreak>();
+    send_sync::<compat::PadCodewords>();
+    send_sync::<segment::Segment>();
+    send_sync::<segment::SegmentError>();
+    send_sync::<payload::UriPayload>();
+    send_sync::<payload::TextPayload>();
+    send_sync::<payload::PayloadError>();
+    send_sync::<payload::PayloadLenInfo>();
+    send_sync::<payload::ndef::NdefRecord>();
+    send_sync::<payload::ndef::NdefMessage>();
+    send_sync::<payload::wallet::WalletPass>();
+    send_sync::<payload::wallet::WalletProvider>();
+    send_sync::<payload::WiFi>();
+    send_sync::<payload::WiFiSecurity>();
+    send_sync::<payload::EpcPayment>();
+    send_sync::<payload::Totp>();
+    send_sync::<payload::OtpAlgorithm>();
+    send_sync::<ModuleCounts>();
+    send_sync::<physical::PrintSize>();
+    send_sync::<physical::PrintWarning>();
+    send_sync::<framebuffer::PixelFormat>();
+    send_sync::<framebuffer::RenderError>();
+    send_sync::<framebuffer::RawRenderer>();
+
+    #[cfg(feature = "std")]
+    {
+        send_sync::<convert::ConvertError>();
+        send_sync::<convert::Shape>();
+        send_sync::<convert::ImageBackgroundShape>();
+        send_sync::<convert::EyeShape>();
+        send_sync::<convert::Gradient>();
+        send_sync::<convert::Color>();
+        send_sync::<convert::bitmap::BitmapBuilder>();
+        send_sync::<convert::bitmap::BitmapFormat>();
+        send_sync::<convert::caption::CaptionBuilder<'static>>();
+        send_sync::<convert::caption::CaptionError>();
+        send_sync::<convert::caption::GrayImage>();
+        send_sync::<convert::html::HtmlBuilder>();
+        send_sync::<convert::html::HtmlLayout>();
+        send_sync::<convert::sixel::SixelBuilder>();
+        send_sync::<convert::sixel::SixelError>();
+        send_sync::<overlay::Overlay>();
+        send_sync::<overlay::OverlayPlanner>();
+        send_sync::<overlay::CorrectionMap>();
+        send_sync::<scanner::ScannerSimulation>();
+        send_sync::<scanner::MaskReport>();
+        send_sync::<VerifiedQRCode>();
+        send_sync::<verify::VerifyError>();
+        send_sync::<decode::Decoded>();
+        send_sync::<decode::DecodeError>();
+        send_sync::<structured_append::Header>();
+        send_sync::<structured_append::StructuredAppendError>();
+    }
+    #[cfg(all(feature = "std", not(feature = "wasm-bindgen")))]
+    send_sync::<convert::terminal::TerminalBuilder>();
+    #[cfg(feature = "svg")]
+    {
+        send_sync::<convert::svg::SvgBuilder>();
+        send_sync::<convert::svg::SvgGeometry>();
+        send_sync::<convert::svg::SvgError>();
+        send_sync::<convert::dark_mode::DarkModeBuilder>();
+        send_sync::<convert::dark_mode::DarkModeError>();
+        send_sync::<convert::ecc_map::EccMapBuilder>();
+        send_sync::<convert::test_sheet::TestSheetBuilder>();
+        send_sync::<convert::style::StyleValidator>();
+        send_sync::<convert::style::StyleIssue>();
+        send_sync::<convert::style::Severity>();
+    }
+    #[cfg(feature = "pdf")]
+    {
+        send_sync::<convert::pdf::PdfBuilder>();
+        send_sync::<convert::pdf::PdfColor>();
+    }
+    #[cfg(feature = "eps")]
+    {
+        send_sync::<convert::eps::EpsBuilder>();
+        send_sync::<convert::eps::EpsUnit>();
+        send_sync::<convert::eps::EpsColor>();
+    }
+    #[cfg(feature = "dxf")]
+    {
+        send_sync::<convert::dxf::DxfBuilder>();
+        send_sync::<convert::dxf::DxfUnit>();
+    }
+    #[cfg(feature = "zpl")]
+    send_sync::<convert::zpl::ZplBuilder>();
+    #[cfg(feature = "image")]
+    {
+        send_sync::<convert::image::ImageBuilder>();
+        send_sync::<convert::image::ImageError>();
+        send_sync::<convert::image::BackgroundWarning>();
+    }
+    #[cfg(feature = "animation")]
+    {
+        send_sync::<convert::animation::AnimationBuilder>();
+        send_sync::<convert::animation::AnimationFormat>();
+        send_sync::<convert::animation::AnimationError>();
+    }
+    #[cfg(feature = "skia")]
+    {
+        send_sync::<convert::skia::SkiaBuilder>();
+        send_sync::<convert::skia::SkiaError>();
+    }
+    #[cfg(feature = "font")]
+    send_sync::<convert::caption::FontShaper>();
+    #[cfg(feature = "embedded-graphics")]
+    {
+        use embedded_graphics_core::pixelcolor::BinaryColor;
+        send_sync::<embedded::QRDrawable<'static, QRMatrix, BinaryColor>>();
+    }
+    #[cfg(feature = "mmap")]
+    send_sync::<export::PackedFile>();
+    #[cfg(feature = "zip")]
+    send_sync::<export::ZipFormat<'static>>();
+    #[cfg(any(feature = "qrcode", feature = "qrcodegen"))]
+    send_sync::<interop::InteropError>();
+    #[cfg(feature = "simd")]
+    {
+        send_sync::<simd::Implementation>();
+        send_sync::<simd::SimdError>();
+    }
+    #[cfg(all(target_arch = "wasm32", feature = "svg"))]
+    send_sync::<SvgOptions>();
+}
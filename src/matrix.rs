@@ -0,0 +1,280 @@
+//! Heap-allocated, bit-packed alternative to the fixed-size [`QRCode`] matrix.
+//!
+//! A [`QRCode`] always holds room for the largest version (31 KB on the
+//! stack). [`QRMatrix`] only stores its own modules, two per byte, which is
+//! handy to keep many codes around. Renderers accept both through the
+//! [`Matrix`] trait.
+//!
+//! Its size is only known at runtime, so codes of different versions can be
+//! kept in the same collection.
+//!
+//! ```rust
+//! use fast_qr::matrix::{Matrix, QRMatrix};
+//! use fast_qr::QRBuilder;
+//!
+//! let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+//! let matrix = QRMatrix::from(&qrcode);
+//!
+//! assert_eq!(matrix.size(), qrcode.size);
+//! assert_eq!(matrix.module(0, 0), qrcode[0][0]);
+//! ```
+//!
+//! Matrices also round-trip through a plain text format, one character per
+//! module, to write expected matrices in test fixtures. Dark modules are
+//! uppercase, light ones lowercase, and the letter is the module type:
+//!
+//! | Module type    | Dark | Light |
+//! |----------------|------|-------|
+//! | Data           | `#`  | `.`   |
+//! | Finder pattern | `F`  | `f`   |
+//! | Alignment      | `A`  | `a`   |
+//! | Timing         | `T`  | `t`   |
+//! | Format         | `I`  | `i`   |
+//! | Version        | `V`  | `v`   |
+//! | Dark module    | `D`  | `d`   |
+//! | Separator      | `E`  | `e`   |
+//!
+//! ```rust
+//! use fast_qr::matrix::QRMatrix;
+//! use fast_qr::QRBuilder;
+//!
+//! let qrcode = QRBuilder::new("fixture").build().unwrap();
+//! let matrix = QRMatrix::from(&qrcode);
+//!
+//! let ascii = matrix.to_ascii();
+//! assert!(ascii.starts_with("FFFFFFFe"));
+//! assert_eq!(QRMatrix::parse_ascii(&ascii), Ok(matrix));
+//! ```
+
+#![deny(unsafe_code)]
+#![warn(missing_docs)]
+
+use core::fmt::{Display, Formatter};
+
+use crate::{Module, ModuleType, QRCode, ECL};
+use alloc::{string::String, vec, vec::Vec};
+
+/// Characters of the text format, light then dark, in [`ModuleType::ALL`] order
+const ASCII: [[char; 2]; 8] = [
+    ['.', '#'],
+    ['f', 'F'],
+    ['a', 'A'],
+    ['t', 'T'],
+    ['i', 'I'],
+    ['v', 'V'],
+    ['d', 'D'],
+    ['e', 'E'],
+];
+
+/// Contains the different errors when text cannot be parsed as a [`QRMatrix`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AsciiError {
+    /// The text has no rows
+    Empty,
+    /// A row does not have as many modules as the first one
+    RowLength {
+        /// Index of the row, blank lines excluded
+        row: usize,
+    },
+    /// The number of rows differs from the number of modules per row
+    RowCount,
+    /// A character is not part of the format
+    Character {
+        /// Index of the row, blank lines excluded
+        row: usize,
+        /// Index of the module in the row
+        column: usize,
+        /// The unexpected character
+        character: char,
+    },
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AsciiError {}
+
+impl Display for AsciiError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            AsciiError::Empty => f.write_str("Matrix has no rows"),
+            AsciiError::RowLength { row } => write!(f, "Row {row} differs in length from row 0"),
+            AsciiError::RowCount => f.write_str("Matrix is not square"),
+            AsciiError::Character {
+                row,
+                column,
+                character,
+            } => write!(
+                f,
+                "Unexpected character {character:?} in row {row}, column {column}"
+            ),
+        }
+    }
+}
+
+/// Read access to a square grid of modules
+pub trait Matrix {
+    /// Returns the number of modules per side
+    fn size(&self) -> usize;
+
+    /// Returns the module at row `y`, column `x`
+    fn module(&self, y: usize, x: usize) -> Module;
+
+    /// Returns the error correction level, `None` when unknown (default)
+    fn ecl(&self) -> Option<ECL> {
+        None
+    }
+}
+
+impl Matrix for QRCode {
+    fn size(&self) -> usize {
+        self.size
+    }
+
+    fn module(&self, y: usize, x: usize) -> Module {
+        self[y][x]
+    }
+
+    fn ecl(&self) -> Option<ECL> {
+        self.ecl
+    }
+}
+
+/// Row-major matrix storing each module on 4 bits: its value and its
+/// [`ModuleType`](crate::ModuleType)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QRMatrix {
+    size: usize,
+    /// Two modules per byte, the first one in the low nibble
+    data: Vec<u8>,
+}
+
+impl QRMatrix {
+    /// Creates a matrix of `size` x `size` light data modules
+    #[must_use]
+    pub fn new(size: usize) -> Self {
+        QRMatrix {
+            size,
+            data: vec![0; (size * size + 1) / 2],
+        }
+    }
+
+    /// Creates a matrix from modules packed by [`QRMatrix::as_bytes`],
+    /// `None` if `data` does not hold exactly `size` x `size` modules
+    #[must_use]
+    pub fn from_packed(size: usize, data: Vec<u8>) -> Option<Self> {
+        if data.len() != (size * size + 1) / 2 {
+            return None;
+        }
+
+        Some(QRMatrix { size, data })
+    }
+
+    /// Updates the module at row `y`, column `x`
+    pub fn set(&mut self, y: usize, x: usize, module: Module) {
+        let index = y * self.size + x;
+        let shift = (index % 2) * 4;
+
+        let byte = &mut self.data[index / 2];
+        *byte = (*byte & !(0x0F << shift)) | ((module.0 & 0x0F) << shift);
+    }
+
+    /// Returns an iterator over every module, row by row
+    pub fn iter(&self) -> impl Iterator<Item = Module> + '_ {
+        (0..self.size * self.size).map(move |i| self.module(i / self.size, i % self.size))
+    }
+
+    /// Returns the packed modules, two per byte, the first one in the low nibble
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns the matrix in the text format of the [module docs](self), one
+    /// line per row, each line ending with `\n`
+    #[must_use]
+    pub fn to_ascii(&self) -> String {
+        let mut out = String::with_capacity(self.size * (self.size + 1));
+        for y in 0..self.size {
+            for x in 0..self.size {
+                let module = self.module(y, x);
+                out.push(ASCII[module.module_type() as usize >> 1][module.value() as usize]);
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Parses the text format of the [module docs](self). Surrounding
+    /// whitespace and blank lines are ignored, so fixtures can be indented.
+    ///
+    /// # Errors
+    /// - `AsciiError::Empty` if there is no row
+    /// - `AsciiError::RowLength` if rows differ in length
+    /// - `AsciiError::RowCount` if there are not as many rows as modules per row
+    /// - `AsciiError::Character` if a character is not part of the format
+    pub fn parse_ascii(text: &str) -> Result<Self, AsciiError> {
+        let rows: Vec<&str> = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect();
+        let size = match rows.first() {
+            Some(row) => row.chars().count(),
+            None => return Err(AsciiError::Empty),
+        };
+        if rows.len() != size {
+            return Err(AsciiError::RowCount);
+        }
+
+        let mut matrix = QRMatrix::new(size);
+        for (y, row) in rows.iter().enumerate() {
+            if row.chars().count() != size {
+                return Err(AsciiError::RowLength { row: y });
+            }
+
+            for (x, character) in row.chars().enumerate() {
+                let module = ModuleType::ALL
+                    .iter()
+                    .zip(ASCII)
+                    .find_map(|(&module_type, chars)| {
+                        let value = chars.iter().position(|&c| c == character)?;
+                        Some(Module::new(value == 1, module_type))
+                    });
+                match module {
+                    Some(module) => matrix.set(y, x, module),
+                    None => {
+                        return Err(AsciiError::Character {
+                            row: y,
+                            column: x,
+                            character,
+                        })
+                    }
+                }
+            }
+        }
+
+        Ok(matrix)
+    }
+}
+
+impl Matrix for QRMatrix {
+    fn size(&self) -> usize {
+        self.size
+    }
+
+    fn module(&self, y: usize, x: usize) -> Module {
+        let index = y * self.size + x;
+        Module((self.data[index / 2] >> ((index % 2) * 4)) & 0x0F)
+    }
+}
+
+impl From<&QRCode> for QRMatrix {
+    fn from(qr: &QRCode) -> Self {
+        let mut matrix = QRMatrix::new(qr.size);
+        for y in 0..qr.size {
+            for (x, &module) in qr[y].iter().enumerate() {
+                matrix.set(y, x, module);
+            }
+        }
+        matrix
+    }
+}
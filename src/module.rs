@@ -20,25 +20,59 @@ pub enum ModuleType {
     Empty = 7 << 1,
 }
 
+impl ModuleType {
+    /// Every module type, in discriminant order
+    pub const ALL: [ModuleType; 8] = [
+        ModuleType::Data,
+        ModuleType::FinderPattern,
+        ModuleType::Alignment,
+        ModuleType::Timing,
+        ModuleType::Format,
+        ModuleType::Version,
+        ModuleType::DarkModule,
+        ModuleType::Empty,
+    ];
+
+    /// Position of the type in [`ModuleType::ALL`]
+    const fn index(self) -> usize {
+        self as usize >> 1
+    }
+}
+
+/// Number of modules of each [`ModuleType`] in a `QRCode`, see
+/// [`QRCode::count_by_type`](crate::QRCode::count_by_type)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ModuleCounts([usize; 8]);
+
+impl ModuleCounts {
+    /// Returns the number of modules of type `module_type`
+    #[must_use]
+    pub const fn get(&self, module_type: ModuleType) -> usize {
+        self.0[module_type.index()]
+    }
+
+    /// Returns the number of modules, `size * size`
+    #[must_use]
+    pub fn total(&self) -> usize {
+        self.0.iter().sum()
+    }
+
+    pub(crate) fn add(&mut self, module_type: ModuleType) {
+        self.0[module_type.index()] += 1;
+    }
+}
+
+/// Only the 3 low bits of `value` are read, from `0` (`Data`) to `7` (`Empty`)
 impl From<u8> for ModuleType {
     fn from(value: u8) -> Self {
-        match value {
-            0 => ModuleType::Data,
-            1 => ModuleType::FinderPattern,
-            2 => ModuleType::Alignment,
-            3 => ModuleType::Timing,
-            4 => ModuleType::Format,
-            5 => ModuleType::Version,
-            6 => ModuleType::DarkModule,
-            7 => ModuleType::Empty,
-            _ => unreachable!(),
-        }
+        no_panic!({ ModuleType::ALL[usize::from(value & 0b111)] })
     }
 }
 
 /// Module is a single pixel in the QR code.
 /// Module uses u8 to store value and type.
 #[derive(Copy, Clone, Debug)]
+#[repr(transparent)]
 pub struct Module(pub u8);
 
 impl Module {
@@ -125,6 +159,13 @@ impl Module {
     }
 }
 
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for Module {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
 impl From<bool> for Module {
     fn from(value: bool) -> Self {
         Module::empty(value)
@@ -0,0 +1,50 @@
+//! Link-time check that functions cannot panic, enabled by the
+//! `fast_qr_no_panic` cfg
+//!
+//! [`no_panic!`] wraps a function body with a guard which is only dropped if
+//! the body unwinds. Its `Drop` calls a symbol that does not exist: once the
+//! optimizer proves the body never panics, the call is removed, otherwise
+//! linking fails and names the problem. The check needs optimizations and
+//! unwinding, which is what the `no-panic` profile provides:
+//!
+//! ```sh
+//! RUSTFLAGS="--cfg fast_qr_no_panic" cargo build --profile no-panic
+//! ```
+//!
+//! It is a cfg rather than a feature: features are unified across the
+//! dependency graph, and every other profile would fail to link. Without the
+//! cfg, the macro expands to the body alone.
+
+/// Fails to link if `$body` may panic, when built with `--cfg fast_qr_no_panic`
+macro_rules! no_panic {
+    ($body:block) => {{
+        #[cfg(fast_qr_no_panic)]
+        let guard = {
+            struct Guard;
+
+            impl Drop for Guard {
+                fn drop(&mut self) {
+                    extern "C" {
+                        #[link_name = "\n\nERROR[fast_qr]: a function checked by `fast_qr_no_panic` may panic, see the `Panics` section of the crate docs\n\n"]
+                        fn fast_qr_may_panic() -> !;
+                    }
+                    #[allow(unsafe_code)]
+                    unsafe {
+                        fast_qr_may_panic()
+                    }
+                }
+            }
+
+            Guard
+        };
+
+        // In a closure so that `return` and `?` in the body do not skip the
+        // `forget`
+        #[allow(clippy::redundant_closure_call)]
+        let out = (|| $body)();
+
+        #[cfg(fast_qr_no_panic)]
+        core::mem::forget(guard);
+        out
+    }};
+}
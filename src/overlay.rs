@@ -0,0 +1,395 @@
+//! Computes how much of a [`QRCode`](crate::QRCode) can be hidden behind a logo
+//! while staying decodable.
+//!
+//! Covering modules damages codewords, and each Reed-Solomon block can only
+//! recover up to half of its error correction codewords. The helpers below
+//! use that budget to size a centered logo area.
+//!
+//! ```rust
+//! use fast_qr::overlay::{safe_overlay_area, OverlayPlanner};
+//! use fast_qr::{Version, ECL};
+//!
+//! let max_modules = safe_overlay_area(Version::V05, ECL::H);
+//!
+//! let overlay = OverlayPlanner::new(Version::V05, ECL::H)
+//!     .aspect_ratio(2.0)
+//!     .verify(true)
+//!     .plan()
+//!     .unwrap();
+//! assert!(overlay.width * overlay.height <= max_modules);
+//! ```
+//!
+//! On large versions the center often falls on an alignment pattern, use
+//! [`OverlayPlanner::avoid_function_patterns`] to move the logo off-center instead.
+
+#![deny(unsafe_code)]
+#![warn(missing_docs)]
+
+use crate::module::ModuleType;
+use crate::{default, hardcode, placement, polynomials, Version, ECL};
+
+/// Finder pattern, separator and format information, on each side
+const RESERVED_BORDER: usize = 9;
+
+/// Fraction of the correction capacity the planner allows itself to use,
+/// the rest is kept for print defects and scanning noise
+const MARGIN_NUMERATOR: usize = 3;
+const MARGIN_DENOMINATOR: usize = 4;
+
+/// Returns the number of codewords each block can recover
+pub(crate) fn correctable_per_block(version: Version, ecl: ECL) -> usize {
+    (hardcode::get_polynomial(version, ecl).len() - 1) / 2
+}
+
+/// Returns the number of blocks for a `version` / `ecl` pair
+fn block_count(version: Version, ecl: ECL) -> usize {
+    let [(g1_count, _), (g2_count, _)] = hardcode::ecc_to_groups(ecl, version);
+    g1_count + g2_count
+}
+
+/// Returns the maximum number of modules that can be covered, assuming every
+/// covered module lands in a distinct correctable codeword slot.
+///
+/// This is a theoretical upper bound, [`OverlayPlanner`] keeps a margin below it.
+#[must_use]
+pub fn safe_overlay_area(version: Version, ecl: ECL) -> usize {
+    block_count(version, ecl) * correctable_per_block(version, ecl) * 8
+}
+
+/// Rectangle covered by an overlay, in modules, relative to the top left of
+/// the `QRCode` (margin excluded)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overlay {
+    /// Left-most covered column
+    pub x: usize,
+    /// Top-most covered row
+    pub y: usize,
+    /// Number of covered columns
+    pub width: usize,
+    /// Number of covered rows
+    pub height: usize,
+}
+
+impl Overlay {
+    /// Returns true if the module at `(y, x)` is covered by the overlay
+    #[must_use]
+    pub const fn contains(&self, y: usize, x: usize) -> bool {
+        y >= self.y && y < self.y + self.height && x >= self.x && x < self.x + self.width
+    }
+}
+
+/// Finds the largest centered rectangle that can be blanked out for a logo.
+///
+/// # Example
+/// ```rust
+/// use fast_qr::overlay::OverlayPlanner;
+/// use fast_qr::{Version, ECL};
+///
+/// let overlay = OverlayPlanner::new(Version::V10, ECL::Q)
+///     .aspect_ratio(16.0 / 9.0)
+///     .plan();
+/// ```
+pub struct OverlayPlanner {
+    version: Version,
+    ecl: ECL,
+    aspect_ratio: f64,
+    verify: bool,
+    avoid_function_patterns: bool,
+}
+
+impl OverlayPlanner {
+    /// Creates an instance of `OverlayPlanner` for a square logo
+    #[must_use]
+    pub fn new(version: Version, ecl: ECL) -> Self {
+        OverlayPlanner {
+            version,
+            ecl,
+            aspect_ratio: 1.0,
+            verify: false,
+            avoid_function_patterns: false,
+        }
+    }
+
+    /// Updates the desired width / height ratio of the logo (default: 1.0)
+    pub fn aspect_ratio(&mut self, aspect_ratio: f64) -> &mut Self {
+        self.aspect_ratio = aspect_ratio;
+        self
+    }
+
+    /// Requires every block to still be correctable once the overlay is
+    /// blanked, instead of only comparing areas (default: false)
+    pub fn verify(&mut self, verify: bool) -> &mut Self {
+        self.verify = verify;
+        self
+    }
+
+    /// Only proposes overlays that leave alignment patterns, timing patterns,
+    /// format and version information uncovered, moving away from the center
+    /// when needed (default: false)
+    pub fn avoid_function_patterns(&mut self, avoid_function_patterns: bool) -> &mut Self {
+        self.avoid_function_patterns = avoid_function_patterns;
+        self
+    }
+
+    /// Returns true if every block keeps enough error correction once
+    /// `overlay` is blanked, planner margin included
+    #[must_use]
+    pub fn is_decodable(&self, overlay: &Overlay) -> bool {
+        let blocks = polynomials::codeword_blocks(self.ecl, self.version);
+        let positions = placement::data_positions(self.version);
+
+        self.is_decodable_with(overlay, &blocks, &positions)
+    }
+
+    /// Returns the number of damaged codewords of the block that suffers
+    /// the most once `overlay` is blanked
+    #[must_use]
+    pub fn worst_block_damage(&self, overlay: &Overlay) -> usize {
+        let blocks = polynomials::codeword_blocks(self.ecl, self.version);
+        let positions = placement::data_positions(self.version);
+
+        self.block_damage(overlay, &blocks, &positions)
+            .into_iter()
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn block_damage(
+        &self,
+        overlay: &Overlay,
+        blocks: &[usize],
+        positions: &[(usize, usize)],
+    ) -> Vec<usize> {
+        let mut damaged = vec![false; blocks.len()];
+        for (i, &(y, x)) in positions.iter().enumerate() {
+            // Remainder bits do not belong to any codeword
+            if i / 8 < damaged.len() && overlay.contains(y, x) {
+                damaged[i / 8] = true;
+            }
+        }
+
+        let mut errors = vec![0; block_count(self.version, self.ecl)];
+        for (codeword, &block) in blocks.iter().enumerate() {
+            if damaged[codeword] {
+                errors[block] += 1;
+            }
+        }
+        errors
+    }
+
+    fn is_decodable_with(
+        &self,
+        overlay: &Overlay,
+        blocks: &[usize],
+        positions: &[(usize, usize)],
+    ) -> bool {
+        let allowed =
+            correctable_per_block(self.version, self.ecl) * MARGIN_NUMERATOR / MARGIN_DENOMINATOR;
+        self.block_damage(overlay, blocks, positions)
+            .iter()
+            .all(|&count| count <= allowed)
+    }
+
+    /// Returns the `(width, height)` candidates, largest first
+    fn extents(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let max_extent = self.version.size().saturating_sub(RESERVED_BORDER * 2);
+        let budget =
+            safe_overlay_area(self.version, self.ecl) * MARGIN_NUMERATOR / MARGIN_DENOMINATOR;
+
+        // `size` is odd, odd extents keep the overlay exactly centered
+        (1..=max_extent)
+            .rev()
+            .step_by(2)
+            .map(move |height| {
+                let width = (height as f64 * self.aspect_ratio).round() as usize;
+                let width = if width % 2 == 0 {
+                    width.saturating_sub(1)
+                } else {
+                    width
+                };
+                (width, height)
+            })
+            .filter(move |&(width, height)| {
+                width != 0 && width <= max_extent && width * height <= budget
+            })
+    }
+
+    /// Returns the largest overlay, `None` if nothing fits.
+    ///
+    /// The overlay is centered, unless [`OverlayPlanner::avoid_function_patterns`]
+    /// is set, in which case the closest position to the center is used.
+    #[must_use]
+    pub fn plan(&self) -> Option<Overlay> {
+        let size = self.version.size();
+        let (blocks, positions) = if self.verify {
+            (
+                polynomials::codeword_blocks(self.ecl, self.version),
+                placement::data_positions(self.version),
+            )
+        } else {
+            (Vec::new(), Vec::new())
+        };
+        let accept = |overlay: &Overlay| {
+            !self.verify || self.is_decodable_with(overlay, &blocks, &positions)
+        };
+
+        if !self.avoid_function_patterns {
+            return self
+                .extents()
+                .map(|(width, height)| Overlay {
+                    x: (size - width) / 2,
+                    y: (size - height) / 2,
+                    width,
+                    height,
+                })
+                .find(|overlay| accept(overlay));
+        }
+
+        let functions = FunctionModules::new(self.version);
+        for (width, height) in self.extents() {
+            let mut candidates = Vec::new();
+            for y in RESERVED_BORDER..=size - RESERVED_BORDER - height {
+                for x in RESERVED_BORDER..=size - RESERVED_BORDER - width {
+                    if functions.count(y, x, width, height) == 0 {
+                        candidates.push(Overlay {
+                            x,
+                            y,
+                            width,
+                            height,
+                        });
+                    }
+                }
+            }
+
+            // Twice the distance between centers, keeps everything in integers
+            let distance = |overlay: &Overlay| {
+                let dx = (overlay.x * 2 + width) as isize - size as isize;
+                let dy = (overlay.y * 2 + height) as isize - size as isize;
+                dx * dx + dy * dy
+            };
+            candidates.sort_by_key(distance);
+
+            if let Some(overlay) = candidates.into_iter().find(|overlay| accept(overlay)) {
+                return Some(overlay);
+            }
+        }
+
+        None
+    }
+}
+
+/// Error correction left in the block of each module once a square around
+/// it is damaged, see [`correction_map`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CorrectionMap {
+    /// Number of modules per side
+    pub size: usize,
+    /// Number of codewords each block can recover when intact
+    pub capacity: usize,
+    remaining: Vec<Option<usize>>,
+}
+
+impl CorrectionMap {
+    /// Returns the number of codewords the block of the module at `(y, x)`
+    /// could still recover, `None` for function modules and remainder bits
+    #[must_use]
+    pub fn get(&self, y: usize, x: usize) -> Option<usize> {
+        self.remaining[y * self.size + x]
+    }
+}
+
+/// For each module, blanks the `damage` x `damage` square centered on it and
+/// returns how many codewords its block could still recover.
+///
+/// Areas with the most correction left are the safest to cover with a
+/// sticker or a label.
+///
+/// ```rust
+/// use fast_qr::overlay::correction_map;
+/// use fast_qr::{Version, ECL};
+///
+/// let map = correction_map(Version::V03, ECL::M, 3);
+/// // Finder patterns are not data modules
+/// assert_eq!(map.get(0, 0), None);
+/// assert!(map.get(28, 28).unwrap() < map.capacity);
+/// ```
+#[must_use]
+pub fn correction_map(version: Version, ecl: ECL, damage: usize) -> CorrectionMap {
+    let size = version.size();
+    let capacity = correctable_per_block(version, ecl);
+    let blocks = polynomials::codeword_blocks(ecl, version);
+
+    // Codeword held by each module, remainder bits excluded
+    let mut codewords = vec![None; size * size];
+    for (i, &(y, x)) in placement::data_positions(version).iter().enumerate() {
+        if i / 8 < blocks.len() {
+            codewords[y * size + x] = Some(i / 8);
+        }
+    }
+
+    let before = damage / 2;
+    let after = damage - before;
+    let mut damaged = Vec::new();
+
+    let remaining = (0..size * size)
+        .map(|i| {
+            let codeword = codewords[i]?;
+            let (y, x) = (i / size, i % size);
+
+            damaged.clear();
+            for dy in y.saturating_sub(before)..core::cmp::min(size, y + after) {
+                for dx in x.saturating_sub(before)..core::cmp::min(size, x + after) {
+                    if let Some(other) = codewords[dy * size + dx] {
+                        if blocks[other] == blocks[codeword] && !damaged.contains(&other) {
+                            damaged.push(other);
+                        }
+                    }
+                }
+            }
+
+            Some(capacity.saturating_sub(damaged.len()))
+        })
+        .collect();
+
+    CorrectionMap {
+        size,
+        capacity,
+        remaining,
+    }
+}
+
+/// Summed-area table of the non-data modules of a version, allows to count
+/// the function modules under any rectangle in constant time
+struct FunctionModules {
+    size: usize,
+    sums: Vec<usize>,
+}
+
+impl FunctionModules {
+    fn new(version: Version) -> Self {
+        let qr = default::create_matrix(version);
+        let size = qr.size;
+        let stride = size + 1;
+
+        let mut sums = vec![0; stride * stride];
+        for y in 0..size {
+            for x in 0..size {
+                let function = usize::from(qr[y][x].module_type() != ModuleType::Data);
+                sums[(y + 1) * stride + x + 1] =
+                    function + sums[y * stride + x + 1] + sums[(y + 1) * stride + x]
+                        - sums[y * stride + x];
+            }
+        }
+
+        FunctionModules { size, sums }
+    }
+
+    fn count(&self, y: usize, x: usize, width: usize, height: usize) -> usize {
+        let stride = self.size + 1;
+        let (bottom, right) = (y + height, x + width);
+
+        self.sums[bottom * stride + right] + self.sums[y * stride + x]
+            - self.sums[y * stride + right]
+            - self.sums[bottom * stride + x]
+    }
+}
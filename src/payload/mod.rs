@@ -0,0 +1,290 @@
+//! Validated payloads, usable for `QRCode` generation as well as other
+//! carriers such as NFC tags (see [`ndef`]).
+//!
+//! ```rust
+//! use fast_qr::payload::UriPayload;
+//! use fast_qr::QRBuilder;
+//!
+//! let uri = UriPayload::new("https://example.com/").unwrap();
+//! let qrcode = QRBuilder::new(uri).build().unwrap();
+//! ```
+
+#![deny(unsafe_code)]
+#![warn(missing_docs)]
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+
+use crate::{encode, Mode, Version, ECL};
+
+mod epc;
+pub mod ndef;
+mod otp;
+pub mod wallet;
+mod wifi;
+
+pub use epc::EpcPayment;
+pub use otp::{OtpAlgorithm, Totp};
+pub use wifi::{WiFi, WiFiSecurity};
+
+/// Contains the different errors when a payload could not be created
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PayloadError {
+    /// The payload is empty
+    Empty,
+    /// The URI has no scheme (i.e. `https:`) or contains invalid characters
+    InvalidUri,
+    /// The language code is not a valid ASCII tag of at most 63 bytes
+    InvalidLanguage,
+    /// The URI must use `https`
+    InsecureUri,
+    /// The payload is longer than `max` bytes
+    TooLong {
+        /// Maximum accepted length, in bytes
+        max: usize,
+    },
+    /// A field is malformed or spans several lines, i.e. `"IBAN"`
+    InvalidField(&'static str),
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PayloadError {}
+
+impl Display for PayloadError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            PayloadError::Empty => f.write_str("Payload is empty"),
+            PayloadError::InvalidUri => f.write_str("Invalid URI"),
+            PayloadError::InvalidLanguage => f.write_str("Invalid language code"),
+            PayloadError::InsecureUri => f.write_str("URI must use https"),
+            PayloadError::TooLong { max } => write!(f, "Payload longer than {max} bytes"),
+            PayloadError::InvalidField(field) => write!(f, "Invalid {field}"),
+        }
+    }
+}
+
+/// A URI (web link, `tel:`, `mailto:`, ...) checked to have a scheme and no
+/// whitespace or control characters
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UriPayload(String);
+
+impl UriPayload {
+    /// Validates `uri` and creates a `UriPayload`
+    ///
+    /// # Errors
+    /// - `PayloadError::Empty` if `uri` is empty
+    /// - `PayloadError::InvalidUri` if `uri` has no scheme or contains whitespace
+    pub fn new<S: Into<String>>(uri: S) -> Result<Self, PayloadError> {
+        let uri = uri.into();
+        if uri.is_empty() {
+            return Err(PayloadError::Empty);
+        }
+
+        // Scheme: ALPHA *( ALPHA / DIGIT / "+" / "-" / "." ) ":" (RFC 3986, 3.1)
+        let scheme = uri.split(':').next().unwrap_or_default();
+        let valid_scheme = scheme.len() < uri.len()
+            && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+
+        if !valid_scheme || uri.chars().any(|c| c.is_whitespace() || c.is_control()) {
+            return Err(PayloadError::InvalidUri);
+        }
+
+        Ok(UriPayload(uri))
+    }
+
+    /// Returns the URI
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the scheme, lowercased (i.e. `https`)
+    #[must_use]
+    pub fn scheme(&self) -> String {
+        self.0[..self.0.find(':').unwrap_or_default()].to_ascii_lowercase()
+    }
+}
+
+impl From<UriPayload> for Vec<u8> {
+    fn from(mut uri: UriPayload) -> Self {
+        core::mem::take(&mut uri.0).into_bytes()
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for UriPayload {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for UriPayload {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
+/// A plain text with its language, `en` by default
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextPayload {
+    text: String,
+    language: String,
+}
+
+impl TextPayload {
+    /// Creates a `TextPayload` in english
+    ///
+    /// # Errors
+    /// - `PayloadError::Empty` if `text` is empty
+    pub fn new<S: Into<String>>(text: S) -> Result<Self, PayloadError> {
+        let text = text.into();
+        if text.is_empty() {
+            return Err(PayloadError::Empty);
+        }
+
+        Ok(TextPayload {
+            text,
+            language: String::from("en"),
+        })
+    }
+
+    /// Updates the language code (IANA tag, i.e. `fr` or `en-US`)
+    ///
+    /// # Errors
+    /// - `PayloadError::InvalidLanguage` if `language` is empty, not ASCII or longer than 63 bytes
+    pub fn language<S: Into<String>>(mut self, language: S) -> Result<Self, PayloadError> {
+        let language = language.into();
+        if language.is_empty()
+            || language.len() > 63
+            || !language
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-')
+        {
+            return Err(PayloadError::InvalidLanguage);
+        }
+
+        self.language = language;
+        Ok(self)
+    }
+
+    /// Returns the text
+    #[must_use]
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the language code
+    #[must_use]
+    pub fn language_code(&self) -> &str {
+        &self.language
+    }
+}
+
+impl From<TextPayload> for Vec<u8> {
+    fn from(mut text: TextPayload) -> Self {
+        core::mem::take(&mut text.text).into_bytes()
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for TextPayload {
+    fn zeroize(&mut self) {
+        self.text.zeroize();
+        self.language.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for TextPayload {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
+/// Length of a text as seen by users and by each encoding mode, see [`payload_len_info`]
+///
+/// Data bits exclude the 4-bit mode indicator and the character count,
+/// which depends on the version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PayloadLenInfo {
+    /// Number of characters (Unicode scalar values), what users usually count
+    pub chars: usize,
+    /// Number of UTF-8 bytes, what byte mode stores and capacities count
+    pub bytes: usize,
+    /// Number of characters taking more than one byte (accents, emoji, ...)
+    pub multibyte_chars: usize,
+    /// Data bits in numeric mode, `None` if the text is not only digits
+    pub numeric_bits: Option<usize>,
+    /// Data bits in alphanumeric mode, `None` if the text has other
+    /// characters than `0-9`, `A-Z`, space and `$%*+-./:`
+    pub alphanumeric_bits: Option<usize>,
+    /// Data bits in byte mode, 8 per byte
+    pub byte_bits: usize,
+    /// Data bits in Kanji mode, 13 per character, `None` if the text is not
+    /// only Kanji convertible to Shift JIS (always without the `kanji` feature)
+    pub kanji_bits: Option<usize>,
+    /// Mode picked by [`QRBuilder`](crate::QRBuilder), the smallest one able to encode the text
+    pub mode: Mode,
+}
+
+impl PayloadLenInfo {
+    /// Returns the smallest version holding the text at `ecl`, `None` if it
+    /// does not fit in a version 40 `QRCode`
+    #[must_use]
+    pub const fn version(&self, ecl: ECL) -> Option<Version> {
+        // Kanji capacities count Shift JIS bytes, two per character
+        let len = match (self.mode, self.kanji_bits) {
+            (Mode::Kanji, Some(bits)) => bits / 13 * 2,
+            _ => self.bytes,
+        };
+        Version::get(self.mode, ecl, len)
+    }
+}
+
+/// Reports how long `text` is for a `QRCode`, which often differs from its
+/// number of characters
+///
+/// ```rust
+/// use fast_qr::payload::payload_len_info;
+/// use fast_qr::{Mode, ECL};
+///
+/// let info = payload_len_info("Café ☕");
+/// assert_eq!(info.chars, 6);
+/// assert_eq!(info.bytes, 9);
+/// assert_eq!(info.multibyte_chars, 2);
+/// assert_eq!(info.mode, Mode::Byte);
+/// assert_eq!(info.byte_bits, 72);
+/// assert!(info.version(ECL::H).is_some());
+/// ```
+#[must_use]
+pub fn payload_len_info(text: &str) -> PayloadLenInfo {
+    let bytes = text.len();
+    let chars = text.chars().count();
+    let input = text.as_bytes();
+
+    let numeric_bits = input
+        .iter()
+        .all(u8::is_ascii_digit)
+        .then(|| bytes / 3 * 10 + [0, 4, 7][bytes % 3]);
+    let alphanumeric_bits = input
+        .iter()
+        .all(|&c| encode::is_qr_alphanumeric(c))
+        .then(|| bytes / 2 * 11 + bytes % 2 * 6);
+    let kanji_bits = encode::kanji_from_utf8(input).map(|sjis| sjis.len() / 2 * 13);
+
+    PayloadLenInfo {
+        chars,
+        bytes,
+        multibyte_chars: text.chars().filter(|c| c.len_utf8() > 1).count(),
+        numeric_bits,
+        alphanumeric_bits,
+        byte_bits: bytes * 8,
+        kanji_bits,
+        mode: encode::best_encoding(input),
+    }
+}
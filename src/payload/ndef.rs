@@ -0,0 +1,234 @@
+//! Builds NDEF (NFC Data Exchange Format) messages from the same payloads
+//! used for `QRCode`s, for hybrid QR / NFC deployments.
+//!
+//! ```rust
+//! use fast_qr::payload::ndef::{NdefMessage, NdefRecord};
+//! use fast_qr::payload::UriPayload;
+//!
+//! let uri = UriPayload::new("https://www.example.com/").unwrap();
+//! let bytes = NdefMessage::new().record(NdefRecord::uri(&uri)).to_bytes();
+//!
+//! // Short well-known record "U", prefix 0x02 stands for "https://www."
+//! assert_eq!(&bytes[..5], &[0xD1, 0x01, 0x0D, b'U', 0x02]);
+//! ```
+
+#![deny(unsafe_code)]
+#![warn(missing_docs)]
+
+use super::{TextPayload, UriPayload};
+use alloc::{vec, vec::Vec};
+
+/// Message Begin flag
+const MB: u8 = 0x80;
+/// Message End flag
+const ME: u8 = 0x40;
+/// Chunk Flag
+const CF: u8 = 0x20;
+/// Short Record flag, payload length fits in one byte
+const SR: u8 = 0x10;
+
+/// Type Name Format: NFC Forum well-known type
+const TNF_WELL_KNOWN: u8 = 0x01;
+/// Type Name Format: middle and last chunks of a chunked record
+const TNF_UNCHANGED: u8 = 0x06;
+
+/// URI identifier codes, referring to table 3 of the NFC Forum URI RTD.
+/// The index is the code written before the shortened URI.
+const URI_PREFIXES: [&str; 36] = [
+    "",
+    "http://www.",
+    "https://www.",
+    "http://",
+    "https://",
+    "tel:",
+    "mailto:",
+    "ftp://anonymous:anonymous@",
+    "ftp://ftp.",
+    "ftps://",
+    "sftp://",
+    "smb://",
+    "nfs://",
+    "ftp://",
+    "dav://",
+    "news:",
+    "telnet://",
+    "imap:",
+    "rtsp://",
+    "urn:",
+    "pop:",
+    "sip:",
+    "sips:",
+    "tftp:",
+    "btspp://",
+    "btl2cap://",
+    "btgoep://",
+    "tcpobex://",
+    "irdaobex://",
+    "file://",
+    "urn:epc:id:",
+    "urn:epc:tag:",
+    "urn:epc:pat:",
+    "urn:epc:raw:",
+    "urn:epc:",
+    "urn:nfc:",
+];
+
+/// Returns the URI identifier code of the longest matching prefix, and the
+/// remainder of the URI
+fn shorten_uri(uri: &str) -> (u8, &str) {
+    let mut best = 0;
+    for (code, prefix) in URI_PREFIXES.iter().enumerate() {
+        if uri.starts_with(prefix) && prefix.len() > URI_PREFIXES[best].len() {
+            best = code;
+        }
+    }
+
+    (best as u8, &uri[URI_PREFIXES[best].len()..])
+}
+
+/// A single NDEF record
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NdefRecord {
+    tnf: u8,
+    record_type: Vec<u8>,
+    payload: Vec<u8>,
+}
+
+impl NdefRecord {
+    /// Creates a well-known URI record (`U`), the URI prefix is abbreviated
+    #[must_use]
+    pub fn uri(uri: &UriPayload) -> Self {
+        let (code, rest) = shorten_uri(uri.as_str());
+
+        let mut payload = Vec::with_capacity(rest.len() + 1);
+        payload.push(code);
+        payload.extend_from_slice(rest.as_bytes());
+
+        NdefRecord {
+            tnf: TNF_WELL_KNOWN,
+            record_type: vec![b'U'],
+            payload,
+        }
+    }
+
+    /// Creates a well-known UTF-8 text record (`T`)
+    #[must_use]
+    pub fn text(text: &TextPayload) -> Self {
+        let language = text.language_code().as_bytes();
+
+        let mut payload = Vec::with_capacity(1 + language.len() + text.text().len());
+        // Bit 7 cleared for UTF-8, bits 5..0 hold the language code length
+        payload.push(language.len() as u8);
+        payload.extend_from_slice(language);
+        payload.extend_from_slice(text.text().as_bytes());
+
+        NdefRecord {
+            tnf: TNF_WELL_KNOWN,
+            record_type: vec![b'T'],
+            payload,
+        }
+    }
+
+    /// Returns the record payload
+    #[must_use]
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+
+    /// Writes one record (or chunk) header followed by its type and payload
+    fn write(out: &mut Vec<u8>, flags: u8, tnf: u8, record_type: &[u8], payload: &[u8]) {
+        let short = payload.len() <= u8::MAX as usize;
+
+        out.push(flags | if short { SR } else { 0 } | tnf);
+        out.push(record_type.len() as u8);
+        if short {
+            out.push(payload.len() as u8);
+        } else {
+            out.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+        }
+        out.extend_from_slice(record_type);
+        out.extend_from_slice(payload);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for NdefRecord {
+    fn zeroize(&mut self) {
+        self.record_type.zeroize();
+        self.payload.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for NdefRecord {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
+/// An NDEF message, a list of records ready to be written on a tag
+#[derive(Debug, Clone, Default)]
+pub struct NdefMessage {
+    records: Vec<NdefRecord>,
+    chunk_size: Option<usize>,
+}
+
+impl NdefMessage {
+    /// Creates an empty `NdefMessage`
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a record to the message
+    pub fn record(&mut self, record: NdefRecord) -> &mut Self {
+        self.records.push(record);
+        self
+    }
+
+    /// Splits record payloads larger than `chunk_size` bytes into chunked
+    /// records (default: no chunking)
+    pub fn chunk_size(&mut self, chunk_size: usize) -> &mut Self {
+        self.chunk_size = Some(chunk_size.max(1));
+        self
+    }
+
+    /// Returns the encoded message
+    #[must_use]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        let count = self.records.len();
+
+        for (i, record) in self.records.iter().enumerate() {
+            let chunk_size = self.chunk_size.unwrap_or(usize::MAX);
+            let chunks: Vec<&[u8]> = if record.payload.len() > chunk_size {
+                record.payload.chunks(chunk_size).collect()
+            } else {
+                vec![&record.payload]
+            };
+
+            let last_chunk = chunks.len() - 1;
+            for (j, chunk) in chunks.into_iter().enumerate() {
+                let mut flags = 0;
+                if i == 0 && j == 0 {
+                    flags |= MB;
+                }
+                if i == count - 1 && j == last_chunk {
+                    flags |= ME;
+                }
+                if j != last_chunk {
+                    flags |= CF;
+                }
+
+                // Only the first chunk carries the type
+                if j == 0 {
+                    NdefRecord::write(&mut out, flags, record.tnf, &record.record_type, chunk);
+                } else {
+                    NdefRecord::write(&mut out, flags, TNF_UNCHANGED, &[], chunk);
+                }
+            }
+        }
+
+        out
+    }
+}
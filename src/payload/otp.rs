@@ -0,0 +1,206 @@
+//! `otpauth://` provisioning URIs, scanned by authenticator apps to enroll a
+//! one-time password secret ([Key URI format](https://github.com/google/google-authenticator/wiki/Key-Uri-Format)).
+//!
+//! ```rust
+//! use fast_qr::payload::Totp;
+//!
+//! let totp = Totp::new("ACME Co", "john@example.com", b"12345678901234567890").unwrap();
+//! assert_eq!(
+//!     totp.to_string(),
+//!     "otpauth://totp/ACME%20Co:john%40example.com?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&issuer=ACME%20Co&algorithm=SHA1&digits=6&period=30"
+//! );
+//! ```
+
+#![deny(unsafe_code)]
+#![warn(missing_docs)]
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter, Write};
+
+use super::PayloadError;
+
+/// HMAC hash function, most apps only support `Sha1`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OtpAlgorithm {
+    /// HMAC-SHA1, the default
+    Sha1,
+    /// HMAC-SHA256
+    Sha256,
+    /// HMAC-SHA512
+    Sha512,
+}
+
+/// A time-based (or counter-based, see [`Totp::counter`]) one-time password
+/// secret, with the issuer and account shown by the authenticator app
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Totp {
+    issuer: String,
+    account: String,
+    secret: Vec<u8>,
+    digits: u8,
+    period: u32,
+    algorithm: OtpAlgorithm,
+    counter: Option<u64>,
+}
+
+/// RFC 4648 base 32, without padding as authenticator apps expect
+fn write_base32(f: &mut Formatter<'_>, bytes: &[u8]) -> core::fmt::Result {
+    const ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+    let mut buffer = 0_u16;
+    let mut bits = 0;
+    for &byte in bytes {
+        buffer = buffer << 8 | u16::from(byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            f.write_char(ALPHABET[usize::from(buffer >> bits & 31)] as char)?;
+        }
+    }
+    if bits > 0 {
+        f.write_char(ALPHABET[usize::from(buffer << (5 - bits) & 31)] as char)?;
+    }
+    Ok(())
+}
+
+/// Percent-encodes every byte but the RFC 3986 unreserved characters
+fn write_percent_encoded(f: &mut Formatter<'_>, value: &str) -> core::fmt::Result {
+    for &byte in value.as_bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            f.write_char(byte as char)?;
+        } else {
+            write!(f, "%{byte:02X}")?;
+        }
+    }
+    Ok(())
+}
+
+impl Totp {
+    /// Creates a 6 digits, 30 seconds, SHA1 secret, the most widely supported
+    ///
+    /// # Errors
+    /// - `PayloadError::Empty` if `account` or `secret` is empty
+    /// - `PayloadError::InvalidField("issuer")` / `PayloadError::InvalidField("account")`
+    ///   if `issuer` or `account` contains a `:`, the label separator
+    pub fn new<I: Into<String>, A: Into<String>>(
+        issuer: I,
+        account: A,
+        secret: &[u8],
+    ) -> Result<Self, PayloadError> {
+        let (issuer, account) = (issuer.into(), account.into());
+        if account.is_empty() || secret.is_empty() {
+            return Err(PayloadError::Empty);
+        }
+        if issuer.contains(':') {
+            return Err(PayloadError::InvalidField("issuer"));
+        }
+        if account.contains(':') {
+            return Err(PayloadError::InvalidField("account"));
+        }
+
+        Ok(Totp {
+            issuer,
+            account,
+            secret: secret.to_vec(),
+            digits: 6,
+            period: 30,
+            algorithm: OtpAlgorithm::Sha1,
+            counter: None,
+        })
+    }
+
+    /// Updates the number of digits of each password (default: 6)
+    ///
+    /// # Errors
+    /// - `PayloadError::InvalidField("digits")` if `digits` is not 6 or 8
+    pub fn digits(mut self, digits: u8) -> Result<Self, PayloadError> {
+        if digits != 6 && digits != 8 {
+            return Err(PayloadError::InvalidField("digits"));
+        }
+
+        self.digits = digits;
+        Ok(self)
+    }
+
+    /// Updates how long each password is valid, in seconds (default: 30)
+    ///
+    /// # Errors
+    /// - `PayloadError::InvalidField("period")` if `period` is 0
+    pub fn period(mut self, period: u32) -> Result<Self, PayloadError> {
+        if period == 0 {
+            return Err(PayloadError::InvalidField("period"));
+        }
+
+        self.period = period;
+        Ok(self)
+    }
+
+    /// Updates the hash function (default: `Sha1`)
+    #[must_use]
+    pub const fn algorithm(mut self, algorithm: OtpAlgorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// Switches to a counter-based HOTP secret starting at `counter`, the period is then ignored
+    #[must_use]
+    pub const fn counter(mut self, counter: u64) -> Self {
+        self.counter = Some(counter);
+        self
+    }
+}
+
+impl Display for Totp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let kind = if self.counter.is_some() {
+            "hotp"
+        } else {
+            "totp"
+        };
+        write!(f, "otpauth://{kind}/")?;
+        if !self.issuer.is_empty() {
+            write_percent_encoded(f, &self.issuer)?;
+            f.write_char(':')?;
+        }
+        write_percent_encoded(f, &self.account)?;
+
+        f.write_str("?secret=")?;
+        write_base32(f, &self.secret)?;
+        if !self.issuer.is_empty() {
+            f.write_str("&issuer=")?;
+            write_percent_encoded(f, &self.issuer)?;
+        }
+
+        let algorithm = match self.algorithm {
+            OtpAlgorithm::Sha1 => "SHA1",
+            OtpAlgorithm::Sha256 => "SHA256",
+            OtpAlgorithm::Sha512 => "SHA512",
+        };
+        write!(f, "&algorithm={algorithm}&digits={}", self.digits)?;
+        match self.counter {
+            Some(counter) => write!(f, "&counter={counter}"),
+            None => write!(f, "&period={}", self.period),
+        }
+    }
+}
+
+impl From<Totp> for Vec<u8> {
+    fn from(totp: Totp) -> Self {
+        totp.to_string().into_bytes()
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for Totp {
+    fn zeroize(&mut self) {
+        self.secret.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for Totp {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
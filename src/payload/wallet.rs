@@ -0,0 +1,114 @@
+//! Validates Apple Wallet and Google Wallet pass distribution URLs.
+//!
+//! Passes are mostly scanned from another screen, which is less forgiving
+//! than paper, so the returned [`QRBuilder`] uses [`WalletPass::RECOMMENDED_ECL`].
+//!
+//! ```rust
+//! use fast_qr::payload::wallet::WalletPass;
+//!
+//! let pass = WalletPass::apple("https://example.com/passes/ticket.pkpass").unwrap();
+//! let qrcode = pass.qr_builder().build().unwrap();
+//! ```
+
+#![deny(unsafe_code)]
+#![warn(missing_docs)]
+
+use super::{PayloadError, UriPayload};
+use crate::encode::Mode;
+use crate::{QRBuilder, Version, ECL};
+use alloc::string::String;
+
+/// Prefix of every "Save to Google Wallet" link
+const GOOGLE_SAVE_PREFIX: &str = "https://pay.google.com/gp/v/save/";
+
+/// Largest byte payload a version 40 `QRCode` holds at [`WalletPass::RECOMMENDED_ECL`].
+/// Also below the 1800 characters Google allows for save links
+const QR_MAX_LEN: usize = 1663;
+
+/// Wallet application the pass is meant for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WalletProvider {
+    /// Apple Wallet, the URL serves a `.pkpass` file
+    Apple,
+    /// Google Wallet, the URL is a "Save to Google Wallet" link
+    Google,
+}
+
+/// A validated pass distribution URL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WalletPass {
+    uri: UriPayload,
+    provider: WalletProvider,
+}
+
+impl WalletPass {
+    /// Recommended ECL for on-screen scanning
+    pub const RECOMMENDED_ECL: ECL = ECL::Q;
+
+    /// Validates an Apple Wallet pass URL
+    ///
+    /// # Errors
+    /// - `PayloadError::Empty` / `PayloadError::InvalidUri` if `url` is not a valid URI
+    /// - `PayloadError::InsecureUri` if `url` does not use `https` or has no host
+    /// - `PayloadError::TooLong` if `url` does not fit a `QRCode` at ECL Q
+    pub fn apple<S: Into<String>>(url: S) -> Result<Self, PayloadError> {
+        Self::new(url.into(), WalletProvider::Apple)
+    }
+
+    /// Validates a Google Wallet "Save to Google Wallet" URL
+    ///
+    /// # Errors
+    /// - `PayloadError::Empty` / `PayloadError::InvalidUri` if `url` is not a valid URI,
+    ///   or not a `https://pay.google.com/gp/v/save/` link
+    /// - `PayloadError::InsecureUri` if `url` does not use `https`
+    /// - `PayloadError::TooLong` if `url` does not fit a `QRCode` at ECL Q
+    pub fn google<S: Into<String>>(url: S) -> Result<Self, PayloadError> {
+        Self::new(url.into(), WalletProvider::Google)
+    }
+
+    fn new(url: String, provider: WalletProvider) -> Result<Self, PayloadError> {
+        let uri = UriPayload::new(url)?;
+
+        let host = uri.as_str()[uri.scheme().len()..]
+            .strip_prefix("://")
+            .and_then(|rest| rest.split('/').next())
+            .unwrap_or_default();
+        if uri.scheme() != "https" || host.is_empty() {
+            return Err(PayloadError::InsecureUri);
+        }
+
+        if provider == WalletProvider::Google {
+            let is_save_link = uri.as_str().starts_with(GOOGLE_SAVE_PREFIX)
+                && uri.as_str().len() > GOOGLE_SAVE_PREFIX.len();
+            if !is_save_link {
+                return Err(PayloadError::InvalidUri);
+            }
+        }
+
+        if Version::get(Mode::Byte, Self::RECOMMENDED_ECL, uri.as_str().len()).is_none() {
+            return Err(PayloadError::TooLong { max: QR_MAX_LEN });
+        }
+
+        Ok(WalletPass { uri, provider })
+    }
+
+    /// Returns the URL
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        self.uri.as_str()
+    }
+
+    /// Returns the wallet application the pass is meant for
+    #[must_use]
+    pub const fn provider(&self) -> WalletProvider {
+        self.provider
+    }
+
+    /// Returns a [`QRBuilder`] for the URL, set to [`WalletPass::RECOMMENDED_ECL`]
+    #[must_use]
+    pub fn qr_builder(&self) -> QRBuilder {
+        let mut builder = QRBuilder::new(self.as_str());
+        builder.ecl(Self::RECOMMENDED_ECL);
+        builder
+    }
+}
@@ -0,0 +1,151 @@
+//! Wi-Fi network credentials, joined by scanning the code with a phone camera.
+//!
+//! ```rust
+//! use fast_qr::payload::WiFi;
+//! use fast_qr::QRBuilder;
+//!
+//! let wifi = WiFi::new("Home; 5GHz").unwrap().password("s3cr:t");
+//! assert_eq!(wifi.to_string(), r"WIFI:T:WPA;S:Home\; 5GHz;P:s3cr\:t;;");
+//!
+//! let qrcode = QRBuilder::new(wifi).build().unwrap();
+//! ```
+
+#![deny(unsafe_code)]
+#![warn(missing_docs)]
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter, Write};
+
+use super::PayloadError;
+
+/// Authentication of the network
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WiFiSecurity {
+    /// WPA, WPA2 or WPA3 personal
+    Wpa,
+    /// WEP, deprecated but still found on old routers
+    Wep,
+    /// Open network, the password is ignored
+    NoPass,
+}
+
+impl WiFiSecurity {
+    const fn as_str(self) -> &'static str {
+        match self {
+            WiFiSecurity::Wpa => "WPA",
+            WiFiSecurity::Wep => "WEP",
+            WiFiSecurity::NoPass => "nopass",
+        }
+    }
+}
+
+/// A network to join, formatted as `WIFI:T:WPA;S:<ssid>;P:<password>;;`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WiFi {
+    ssid: String,
+    password: String,
+    security: WiFiSecurity,
+    hidden: bool,
+}
+
+impl WiFi {
+    /// Creates an open, visible network named `ssid`
+    ///
+    /// # Errors
+    /// - `PayloadError::Empty` if `ssid` is empty
+    pub fn new<S: Into<String>>(ssid: S) -> Result<Self, PayloadError> {
+        let ssid = ssid.into();
+        if ssid.is_empty() {
+            return Err(PayloadError::Empty);
+        }
+
+        Ok(WiFi {
+            ssid,
+            password: String::new(),
+            security: WiFiSecurity::NoPass,
+            hidden: false,
+        })
+    }
+
+    /// Updates the password, an open network switches to [`WiFiSecurity::Wpa`]
+    #[must_use]
+    pub fn password<S: Into<String>>(mut self, password: S) -> Self {
+        self.password = password.into();
+        if self.security == WiFiSecurity::NoPass {
+            self.security = WiFiSecurity::Wpa;
+        }
+        self
+    }
+
+    /// Updates the authentication (default: `NoPass`, `Wpa` once a password is set)
+    #[must_use]
+    pub const fn security(mut self, security: WiFiSecurity) -> Self {
+        self.security = security;
+        self
+    }
+
+    /// Marks the network as not broadcasting its SSID (default: false)
+    #[must_use]
+    pub const fn hidden(mut self, hidden: bool) -> Self {
+        self.hidden = hidden;
+        self
+    }
+
+    /// Returns the network name
+    #[must_use]
+    pub fn ssid(&self) -> &str {
+        &self.ssid
+    }
+}
+
+/// Backslash-escapes the characters delimiting fields
+fn write_escaped(f: &mut Formatter<'_>, value: &str) -> core::fmt::Result {
+    for c in value.chars() {
+        if matches!(c, '\\' | ';' | ',' | ':') {
+            f.write_char('\\')?;
+        }
+        f.write_char(c)?;
+    }
+    Ok(())
+}
+
+impl Display for WiFi {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "WIFI:T:{};S:", self.security.as_str())?;
+        write_escaped(f, &self.ssid)?;
+        f.write_char(';')?;
+
+        if self.security != WiFiSecurity::NoPass {
+            f.write_str("P:")?;
+            write_escaped(f, &self.password)?;
+            f.write_char(';')?;
+        }
+        if self.hidden {
+            f.write_str("H:true;")?;
+        }
+
+        f.write_char(';')
+    }
+}
+
+impl From<WiFi> for Vec<u8> {
+    fn from(wifi: WiFi) -> Self {
+        wifi.to_string().into_bytes()
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for WiFi {
+    fn zeroize(&mut self) {
+        self.ssid.zeroize();
+        self.password.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for WiFi {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
@@ -0,0 +1,163 @@
+//! Checks a [`QRCode`](crate::QRCode) against the size it will be printed at.
+//!
+//! Scanners struggle once a module gets smaller than about a third of a
+//! millimeter. [`PrintSize`] warns about it before printing, and gives the
+//! largest version that stays readable, to pass to
+//! [`QRBuilder::max_version`](crate::QRBuilder::max_version). For raster
+//! output, [`PrintSize::pixels_per_module`] turns the width into a module
+//! size at a given DPI.
+//!
+//! ```rust
+//! use fast_qr::physical::{PrintSize, PrintWarning};
+//! use fast_qr::QRBuilder;
+//!
+//! let print = PrintSize::new(15.0);
+//!
+//! let qrcode = QRBuilder::new("https://example.com/")
+//!     .max_version(print.max_version().unwrap())
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(print.check(&qrcode), None);
+//!
+//! let large = QRBuilder::new("a".repeat(500)).build().unwrap();
+//! assert!(matches!(
+//!     print.check(&large),
+//!     Some(PrintWarning::ModuleTooSmall { .. })
+//! ));
+//! ```
+
+#![deny(unsafe_code)]
+#![warn(missing_docs)]
+
+use core::fmt::{Display, Formatter};
+
+use crate::matrix::Matrix;
+use crate::Version;
+
+/// Smallest module size commonly read by phone cameras, in millimeters
+pub const DEFAULT_MIN_MODULE_SIZE: f64 = 0.33;
+
+const MM_PER_INCH: f64 = 25.4;
+
+/// Issue found when checking a code against its print size
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PrintWarning {
+    /// Modules are printed smaller than the minimum
+    ModuleTooSmall {
+        /// Printed module width, in millimeters
+        module_size: f64,
+        /// Configured minimum, in millimeters
+        min_module_size: f64,
+    },
+}
+
+impl Display for PrintWarning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            PrintWarning::ModuleTooSmall {
+                module_size,
+                min_module_size,
+            } => write!(
+                f,
+                "Modules are {module_size:.3}mm wide, below the {min_module_size}mm minimum"
+            ),
+        }
+    }
+}
+
+/// Printed width of a code, quiet zone included, and the smallest module it allows
+#[derive(Debug, Clone, Copy)]
+pub struct PrintSize {
+    width: f64,
+    /// Default is [`DEFAULT_MIN_MODULE_SIZE`]
+    min_module_size: f64,
+    /// Default is 4
+    margin: usize,
+}
+
+impl PrintSize {
+    /// Creates an instance of `PrintSize` for a code printed `width` millimeters wide
+    #[must_use]
+    pub const fn new(width: f64) -> Self {
+        PrintSize {
+            width,
+            min_module_size: DEFAULT_MIN_MODULE_SIZE,
+            margin: 4,
+        }
+    }
+
+    /// Updates the smallest acceptable module width in millimeters (default: 0.33)
+    pub fn min_module_size(&mut self, min_module_size: f64) -> &mut Self {
+        self.min_module_size = min_module_size;
+        self
+    }
+
+    /// Updates the quiet zone in modules, it is part of the printed width (default: 4)
+    pub fn margin(&mut self, margin: usize) -> &mut Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Returns the printed module width, in millimeters, of a code `size` modules wide
+    #[must_use]
+    pub fn module_size(&self, size: usize) -> f64 {
+        self.width / (size + self.margin * 2) as f64
+    }
+
+    /// Returns the largest version whose modules are not smaller than the
+    /// minimum, `None` if even `Version::V01` is too dense
+    #[must_use]
+    pub fn max_version(&self) -> Option<Version> {
+        (1..=40)
+            .rev()
+            .map(|n| Version::from_n(n * 4 + 17))
+            .find(|version| self.module_size(version.size()) >= self.min_module_size)
+    }
+
+    /// Returns a warning if the modules of `qr` would be printed too small
+    #[must_use]
+    pub fn check<M: Matrix>(&self, qr: &M) -> Option<PrintWarning> {
+        self.warning(self.module_size(qr.size())).err()
+    }
+
+    /// Returns the number of pixels per module to render `qr` at `dpi`, to
+    /// pass as the module size of a raster renderer. It is rounded down, the
+    /// code is at most `width` millimeters wide.
+    ///
+    /// ```rust
+    /// use fast_qr::convert::bitmap::{BitmapBuilder, BitmapFormat};
+    /// use fast_qr::physical::PrintSize;
+    /// use fast_qr::QRBuilder;
+    ///
+    /// let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    ///
+    /// // 25mm at 300 dpi: 33 modules of 0.758mm, 8 pixels each
+    /// let pixels = PrintSize::new(25.0).pixels_per_module(&qrcode, 300.0).unwrap();
+    /// assert_eq!(pixels, 8);
+    ///
+    /// let pbm = BitmapBuilder::default()
+    ///     .module_size(pixels)
+    ///     .to_bytes(&qrcode, BitmapFormat::Pbm);
+    /// ```
+    ///
+    /// # Errors
+    /// - `PrintWarning::ModuleTooSmall` if modules, once rounded to whole
+    ///   pixels, are smaller than the minimum
+    pub fn pixels_per_module<M: Matrix>(&self, qr: &M, dpi: f64) -> Result<usize, PrintWarning> {
+        // Truncation rounds down, the epsilon keeps exact sizes from losing a pixel
+        let pixels = (self.module_size(qr.size()) * dpi / MM_PER_INCH + 1e-9) as usize;
+        self.warning(pixels as f64 * MM_PER_INCH / dpi)?;
+        Ok(pixels)
+    }
+
+    fn warning(&self, module_size: f64) -> Result<(), PrintWarning> {
+        if module_size >= self.min_module_size {
+            return Ok(());
+        }
+
+        Err(PrintWarning::ModuleTooSmall {
+            module_size,
+            min_module_size: self.min_module_size,
+        })
+    }
+}
@@ -3,12 +3,14 @@
 #![warn(missing_docs)]
 
 use crate::compact::CompactQR;
-use crate::datamasking::Mask;
-use crate::encode::Mode;
+use crate::compat::{LegacyCompat, MaskTieBreak};
+use crate::datamasking::{Mask, MaskPenalty, MaskReport, MaskScoring, PenaltyWeights};
+use crate::encode::{InvalidCharacter, Mode};
 
-use crate::module::ModuleType;
+use crate::module::{Module, ModuleType};
 use crate::{datamasking, default, encode, polynomials, score, QRCode};
 use crate::{Version, ECL};
+use alloc::vec::Vec;
 use core::iter::Rev;
 use core::ops::Range;
 
@@ -70,72 +72,236 @@ pub fn place_on_matrix_data(qr: &mut QRCode, structure_as_binarystring: &Compact
     }
 }
 
-const MASKS: [Mask; 8] = [
-    Mask::Checkerboard,
-    Mask::HorizontalLines,
-    Mask::VerticalLines,
-    Mask::DiagonalLines,
-    Mask::LargeCheckerboard,
-    Mask::Fields,
-    Mask::Diamonds,
-    Mask::Meadow,
-];
-
-/// Main function to place everything in the `QRCode`, returns a valid matrix
+/// Returns the `(y, x)` position of every data module, in the order bits are
+/// placed on the matrix. The `n`-th position holds bit `n % 8` of codeword `n / 8`.
+pub fn data_positions(version: Version) -> Vec<(usize, usize)> {
+    let qr = default::create_matrix(version);
+    let mut positions = Vec::with_capacity(version.max_bytes() * 8 + version.missing_bits());
+
+    let mut rev = true;
+    for x in (0..6).chain(7..qr.size).rev().step_by(2) {
+        let y_range = if rev {
+            BiRange::Backwards((0..qr.size).rev())
+        } else {
+            BiRange::Forward(0..qr.size)
+        };
+
+        for y in y_range {
+            if qr[y][x].module_type() == ModuleType::Data {
+                positions.push((y, x));
+            }
+            if qr[y][x - 1].module_type() == ModuleType::Data {
+                positions.push((y, x - 1));
+            }
+        }
+
+        rev = !rev;
+    }
+
+    positions
+}
+
+/// Main function to place everything in the `QRCode`, returns a valid matrix.
+/// Masks are only scored when `mask` is `None`.
 pub fn place_on_matrix(
     structure_as_binarystring: &CompactQR,
     quality: ECL,
     version: Version,
     mask: &mut Option<Mask>,
+    scoring: MaskScoring,
+    tie_break: MaskTieBreak,
 ) -> QRCode {
-    let mut best_score = u32::MAX;
-    let mut best_mask = MASKS[0];
-
     let mut qr = default::create_matrix(version);
     place_on_matrix_data(&mut qr, structure_as_binarystring);
 
-    let transpose = default::transpose(&qr);
+    #[cfg(feature = "dump")]
+    crate::dump::unmasked(&qr);
+
+    let best_mask = match *mask {
+        Some(mask) => {
+            #[cfg(feature = "dump")]
+            crate::dump::masks(None, mask);
+            mask
+        }
+        None => best_mask(&qr, scoring, tie_break),
+    };
+    *mask = Some(best_mask);
+
+    default::create_matrix_format_info(&mut qr, quality, best_mask);
+    datamasking::mask(&mut qr, best_mask);
+
+    qr.mask = *mask;
+    qr
+}
+
+/// Returns the mask of `qr` with the lowest score
+fn best_mask(qr: &QRCode, scoring: MaskScoring, tie_break: MaskTieBreak) -> Mask {
+    let mut best_score = u32::MAX;
+    let mut best_mask = Mask::ALL[0];
+
+    #[cfg(feature = "dump")]
+    let mut scores = [0; 8];
 
-    for mask in MASKS {
-        let mut copy = qr.clone();
-        let copy_transpose = transpose.clone();
+    #[cfg_attr(not(feature = "zeroize"), allow(unused_mut))]
+    let mut transpose = default::transpose(qr);
+    let mut copy = qr.clone();
+
+    for mask in Mask::ALL {
+        copy.data[..qr.size * qr.size].copy_from_slice(&qr.data[..qr.size * qr.size]);
 
         datamasking::mask(&mut copy, mask);
-        let matrix_score = score::score(&copy, &copy_transpose);
-        if matrix_score < best_score {
+        let matrix_score = score::score_with(&copy, &transpose, scoring);
+        #[cfg(feature = "dump")]
+        {
+            scores[mask as usize] = matrix_score;
+        }
+        let better = match tie_break {
+            MaskTieBreak::First => matrix_score < best_score,
+            MaskTieBreak::Last => matrix_score <= best_score,
+        };
+        if better {
             best_score = matrix_score;
             best_mask = mask;
         }
     }
 
-    best_mask = mask.unwrap_or(best_mask);
-    *mask = Some(best_mask);
+    // Both copies hold the data modules
+    #[cfg(feature = "zeroize")]
+    {
+        zeroize::Zeroize::zeroize(&mut copy.data);
+        zeroize::Zeroize::zeroize(&mut transpose.data);
+    }
 
-    default::create_matrix_format_info(&mut qr, quality, best_mask);
-    datamasking::mask(&mut qr, best_mask);
+    #[cfg(feature = "dump")]
+    crate::dump::masks(Some(&scores), best_mask);
 
-    qr.mask = *mask;
-    qr
+    best_mask
+}
+
+/// Scores every mask of a built `qr` again, `None` if it has no mask or version
+pub fn mask_report(qr: &QRCode) -> Option<MaskReport> {
+    let applied = qr.mask?;
+    qr.version?;
+
+    // Back to the matrix compared during the mask selection: unmasked, and
+    // format information not placed yet
+    let mut unmasked = qr.clone();
+    let n = unmasked.size;
+    for module in &mut unmasked.data[..n * n] {
+        if module.module_type() == ModuleType::Format {
+            *module = Module::format(Module::LIGHT);
+        }
+    }
+    datamasking::mask(&mut unmasked, applied);
+    let transpose = default::transpose(&unmasked);
+
+    let candidates = Mask::ALL.map(|mask| {
+        let mut copy = unmasked.clone();
+        datamasking::mask(&mut copy, mask);
+        let [runs, squares, finder_patterns, balance] =
+            score::penalties(&copy, &transpose, &PenaltyWeights::SPEC);
+        MaskPenalty {
+            mask,
+            runs,
+            squares,
+            finder_patterns,
+            balance,
+        }
+    });
+
+    Some(MaskReport {
+        candidates,
+        applied,
+    })
+}
+
+/// Encoding settings picked on the [`QRBuilder`](crate::QRBuilder)
+#[derive(Clone, Copy, Default)]
+pub struct MatrixOptions {
+    /// How masks are compared when none is forced
+    pub scoring: MaskScoring,
+    /// Quirks of older encoders to reproduce
+    pub compat: LegacyCompat,
+    /// Avoids data-dependent branches and lookups, see `QRBuilder::constant_time`
+    pub constant_time: bool,
+    /// Largest version allowed, see `QRBuilder::max_version`
+    pub max_version: Option<Version>,
 }
 
 /// Generate the whole matrix
+///
+/// # Errors
+/// - `InvalidCharacter` if `mode` cannot encode `input`
 pub fn create_matrix(
     input: &[u8],
     ecl: ECL,
     mode: Mode,
     version: Version,
     mask: &mut Option<Mask>,
+    options: MatrixOptions,
+) -> Result<QRCode, InvalidCharacter> {
+    let data_codewords = encode::encode(input, ecl, mode, version, options.compat.padding)?;
+
+    Ok(QRCode {
+        mode: Some(mode),
+        ..create_matrix_from_codewords(&data_codewords, ecl, version, mask, options)
+    })
+}
+
+/// Generate the whole matrix from already encoded and padded data codewords,
+/// the mode is left unset
+pub fn create_matrix_from_codewords(
+    data_codewords: &CompactQR,
+    ecl: ECL,
+    version: Version,
+    mask: &mut Option<Mask>,
+    options: MatrixOptions,
 ) -> QRCode {
-    let data_codewords = encode::encode(input, ecl, mode, version);
-    let structure = polynomials::structure(data_codewords.get_data(), ecl, version);
+    #[cfg_attr(not(feature = "zeroize"), allow(unused_mut))]
+    let mut structure = if options.constant_time {
+        polynomials::structure_with(
+            data_codewords.get_data(),
+            ecl,
+            version,
+            polynomials::division_constant_time,
+        )
+    } else {
+        polynomials::structure(data_codewords.get_data(), ecl, version)
+    };
+
+    #[cfg(feature = "dump")]
+    crate::dump::codewords(
+        data_codewords.get_data(),
+        &structure[..version.max_bytes()],
+        ecl,
+        version,
+    );
 
     let max = version.max_bytes() * 8;
     let structure_binstring = CompactQR::from_array(&structure, max + version.missing_bits());
 
-    QRCode {
-        mode: Some(mode),
+    // Scoring masks branches on the data, a fixed mask is used instead
+    if options.constant_time && mask.is_none() {
+        *mask = Some(Mask::Checkerboard);
+    }
+
+    let qr = QRCode {
+        mode: None,
         ecl: Some(ecl),
         version: Some(version),
-        ..place_on_matrix(&structure_binstring, ecl, version, mask)
-    }
+        ..place_on_matrix(
+            &structure_binstring,
+            ecl,
+            version,
+            mask,
+            options.scoring,
+            options.compat.mask_tie_break,
+        )
+    };
+
+    // `CompactQR` wipes itself on drop
+    #[cfg(feature = "zeroize")]
+    zeroize::Zeroize::zeroize(&mut structure[..]);
+
+    qr
 }
@@ -3,43 +3,11 @@
 #![deny(unsafe_code)]
 #![warn(missing_docs)]
 
-use crate::hardcode;
+use crate::galois::{self, ANTILOG};
 use crate::polynomials;
+use crate::{hardcode, interleave};
 use crate::{Version, ECL};
-
-/// Used in the ring, convert a^x using `LOG[x % 255]` to it's decimal Galois-Field value
-const LOG: [u8; 256] = [
-    1, 2, 4, 8, 16, 32, 64, 128, 29, 58, 116, 232, 205, 135, 19, 38, 76, 152, 45, 90, 180, 117,
-    234, 201, 143, 3, 6, 12, 24, 48, 96, 192, 157, 39, 78, 156, 37, 74, 148, 53, 106, 212, 181,
-    119, 238, 193, 159, 35, 70, 140, 5, 10, 20, 40, 80, 160, 93, 186, 105, 210, 185, 111, 222, 161,
-    95, 190, 97, 194, 153, 47, 94, 188, 101, 202, 137, 15, 30, 60, 120, 240, 253, 231, 211, 187,
-    107, 214, 177, 127, 254, 225, 223, 163, 91, 182, 113, 226, 217, 175, 67, 134, 17, 34, 68, 136,
-    13, 26, 52, 104, 208, 189, 103, 206, 129, 31, 62, 124, 248, 237, 199, 147, 59, 118, 236, 197,
-    151, 51, 102, 204, 133, 23, 46, 92, 184, 109, 218, 169, 79, 158, 33, 66, 132, 21, 42, 84, 168,
-    77, 154, 41, 82, 164, 85, 170, 73, 146, 57, 114, 228, 213, 183, 115, 230, 209, 191, 99, 198,
-    145, 63, 126, 252, 229, 215, 179, 123, 246, 241, 255, 227, 219, 171, 75, 150, 49, 98, 196, 149,
-    55, 110, 220, 165, 87, 174, 65, 130, 25, 50, 100, 200, 141, 7, 14, 28, 56, 112, 224, 221, 167,
-    83, 166, 81, 162, 89, 178, 121, 242, 249, 239, 195, 155, 43, 86, 172, 69, 138, 9, 18, 36, 72,
-    144, 61, 122, 244, 245, 247, 243, 251, 235, 203, 139, 11, 22, 44, 88, 176, 125, 250, 233, 207,
-    131, 27, 54, 108, 216, 173, 71, 142, 1,
-];
-
-/// Reverses a ring value, converts decimal value x using `ANTILOG[x % 255]` to it's alpha power value
-const ANTILOG: [u8; 256] = [
-    175, 0, 1, 25, 2, 50, 26, 198, 3, 223, 51, 238, 27, 104, 199, 75, 4, 100, 224, 14, 52, 141,
-    239, 129, 28, 193, 105, 248, 200, 8, 76, 113, 5, 138, 101, 47, 225, 36, 15, 33, 53, 147, 142,
-    218, 240, 18, 130, 69, 29, 181, 194, 125, 106, 39, 249, 185, 201, 154, 9, 120, 77, 228, 114,
-    166, 6, 191, 139, 98, 102, 221, 48, 253, 226, 152, 37, 179, 16, 145, 34, 136, 54, 208, 148,
-    206, 143, 150, 219, 189, 241, 210, 19, 92, 131, 56, 70, 64, 30, 66, 182, 163, 195, 72, 126,
-    110, 107, 58, 40, 84, 250, 133, 186, 61, 202, 94, 155, 159, 10, 21, 121, 43, 78, 212, 229, 172,
-    115, 243, 167, 87, 7, 112, 192, 247, 140, 128, 99, 13, 103, 74, 222, 237, 49, 197, 254, 24,
-    227, 165, 153, 119, 38, 184, 180, 124, 17, 68, 146, 217, 35, 32, 137, 46, 55, 63, 209, 91, 149,
-    188, 207, 205, 144, 135, 151, 178, 220, 252, 190, 97, 242, 86, 211, 171, 20, 42, 93, 158, 132,
-    60, 57, 83, 71, 109, 65, 162, 31, 45, 67, 216, 183, 123, 164, 118, 196, 23, 73, 236, 127, 12,
-    111, 246, 108, 161, 59, 82, 41, 157, 85, 170, 251, 96, 134, 177, 187, 204, 62, 90, 203, 89, 95,
-    176, 156, 169, 160, 81, 11, 245, 22, 235, 122, 117, 44, 215, 79, 174, 213, 233, 230, 231, 173,
-    232, 116, 214, 244, 234, 168, 80, 88, 175,
-];
+use alloc::vec::Vec;
 
 /// Return a string of human readable polynomial
 ///
@@ -78,23 +46,49 @@ pub fn generated_to_string(poly: &[u8]) -> String {
 ///         17, 236,  17, 236,  17,   0, ..eight..,   0] (integer)
 /// ```
 ///
-/// Then the actual division takes place
-/// We convert `from` from INTEGER to ALPHA
+/// Then the actual division takes place in [`galois::divide`], the remainder
+/// is in the last `by.len() - 1` values
 pub fn division(from: &[u8], by: &[u8]) -> [u8; 255] {
+    let remainder = galois::divide(from, 0, from.len(), by);
+    let degree = by.len() - 1;
+
+    let mut from_mut = [0; 255];
+    from_mut[255 - degree..].copy_from_slice(&remainder[..degree]);
+    from_mut
+}
+
+/// Multiplies two Galois-Field (256) integers without table lookups nor
+/// data-dependent branches
+const fn multiply_constant_time(mut a: u8, b: u8) -> u8 {
+    let mut product = 0;
+    let mut i = 0;
+    while i < 8 {
+        product ^= a & 0u8.wrapping_sub((b >> i) & 1);
+        // Reduces by x^8 + x^4 + x^3 + x^2 + 1
+        a = (a << 1) ^ (0x1D & 0u8.wrapping_sub(a >> 7));
+        i += 1;
+    }
+    product
+}
+
+/// Same as [`division`], but the time taken only depends on the lengths of
+/// `from` and `by`, not on the data
+pub fn division_constant_time(from: &[u8], by: &[u8]) -> [u8; 255] {
     let mut from_mut = [0; 255];
     let start = 256 - from.len() - by.len();
 
     from_mut[start..(256 - by.len())].copy_from_slice(&from[..((256 - by.len()) - start)]);
 
-    for i in start..start + from.len() {
-        if from_mut[i] == 0 {
-            continue;
-        }
+    // The generator polynomial is public, converting it with the table is fine
+    let mut generator = [0; 31];
+    for (coefficient, &alpha) in generator.iter_mut().zip(by) {
+        *coefficient = ANTILOG[alpha as usize];
+    }
 
-        let alpha = ANTILOG[from_mut[i] as usize];
+    for i in start..start + from.len() {
+        let factor = from_mut[i];
         for j in 0..by.len() {
-            let tmp = by[j] as usize + alpha as usize;
-            from_mut[i + j] ^= LOG[tmp % 255];
+            from_mut[i + j] ^= multiply_constant_time(factor, generator[j]);
         }
     }
 
@@ -104,61 +98,102 @@ pub fn division(from: &[u8], by: &[u8]) -> [u8; 255] {
 /// Uses the data and error(generator polynomial) to compute the divisions
 /// for each block.
 pub fn structure(data: &[u8], quality: ECL, version: Version) -> [u8; 5430] {
+    structure_with(data, quality, version, polynomials::division)
+}
+
+/// Same as [`structure`], dividing each block with `divide`
+pub fn structure_with(
+    data: &[u8],
+    quality: ECL,
+    version: Version,
+    divide: fn(&[u8], &[u8]) -> [u8; 255],
+) -> [u8; 5430] {
     const MAX_ERROR: usize = 30;
     const MAX_GROUP_COUNT: usize = 81;
     const MAX_DATABITS: usize = 3000;
 
-    // Need to find a more accurate way to do this.
-    // let mut interleaved_data = vec![0; 0];
-
     let error = hardcode::get_polynomial(version, quality);
-
-    let [(g1_count, g1_size), (g2_count, g2_size)] = hardcode::ecc_to_groups(quality, version);
-    let groups_count_total = g1_count + g2_count;
-
-    let mut interleaved_data = [0; MAX_DATABITS + MAX_ERROR * MAX_GROUP_COUNT];
+    let error_len = error.len() - 1;
 
     let start_error_idx = hardcode::data_codewords(version, quality);
-
-    for i in 0..g1_count {
-        let start_idx = i * g1_size;
-        let division = polynomials::division(&data[start_idx..start_idx + g1_size], error);
-
-        for j in 0..error.len() - 1 {
-            interleaved_data[start_error_idx + j * groups_count_total + i] =
-                division[256 - error.len() + j];
+    let groups_count_total = {
+        let [(g1_count, g1_size), (g2_count, g2_size)] = hardcode::ecc_to_groups(quality, version);
+        debug_assert_eq!(
+            g1_count * g1_size + g2_count * g2_size,
+            start_error_idx,
+            "Blocks of {version:?}-{quality:?} do not hold its data codewords"
+        );
+        g1_count + g2_count
+    };
+    debug_assert_eq!(
+        start_error_idx + groups_count_total * error_len,
+        version.max_bytes(),
+        "Codewords of {version:?}-{quality:?} do not fill the matrix"
+    );
+    debug_assert!(
+        data.len() >= start_error_idx,
+        "{} data codewords for {start_error_idx} expected",
+        data.len()
+    );
+
+    let blocks = interleave::data_blocks(data, version, quality);
+
+    // From V30 on, there are 15 to 81 blocks, dividing them dominates the
+    // encoding time and each block is independent
+    #[cfg(feature = "rayon")]
+    let parallel = version >= Version::V30;
+    #[cfg(not(feature = "rayon"))]
+    let parallel = false;
+
+    #[cfg_attr(not(feature = "zeroize"), allow(unused_mut))]
+    let mut divisions: Vec<[u8; 255]> = if parallel {
+        #[cfg(feature = "rayon")]
+        {
+            use rayon::prelude::*;
+            blocks
+                .par_iter()
+                .map(|block| divide(block, error))
+                .collect()
         }
-    }
-
-    for i in 0..g2_count {
-        let start_idx = g1_size * g1_count + i * g2_size;
-        let division = polynomials::division(&data[start_idx..start_idx + g2_size], error);
+        #[cfg(not(feature = "rayon"))]
+        unreachable!()
+    } else {
+        blocks.iter().map(|block| divide(block, error)).collect()
+    };
+
+    // The remainder is at the end of each division
+    let ecc: Vec<&[u8]> = divisions
+        .iter()
+        .map(|division| &division[255 - error_len..])
+        .collect();
 
-        for j in 0..error.len() - 1 {
-            interleaved_data[start_error_idx + j * groups_count_total + i + g1_count] =
-                division[256 - error.len() + j];
-        }
+    let mut interleaved_data = [0; MAX_DATABITS + MAX_ERROR * MAX_GROUP_COUNT];
+    let codewords = interleave::columns(&blocks).chain(interleave::columns(&ecc));
+    for (slot, (_, codeword)) in interleaved_data.iter_mut().zip(codewords) {
+        *slot = codeword;
     }
+    drop(ecc);
 
-    let mut push_idx = 0;
-    let max = core::cmp::max(g1_size, g2_size);
-
-    for i in 0..max {
-        if i < g1_size {
-            for j in 0..g1_count {
-                let idx = j * g1_size + i;
-                interleaved_data[push_idx] = data[idx];
-                push_idx += 1;
-            }
-        }
-        if i < g2_size {
-            for j in 0..g2_count {
-                let idx = j * g2_size + i + g1_size * g1_count;
-                interleaved_data[push_idx] = data[idx];
-                push_idx += 1;
-            }
-        }
+    #[cfg(feature = "zeroize")]
+    for division in &mut divisions {
+        zeroize::Zeroize::zeroize(&mut division[..]);
     }
 
     interleaved_data
 }
+
+/// Returns, for each codeword in its final (interleaved) order, the index of
+/// the block it belongs to. Data codewords come first, then ECC codewords,
+/// following the same interleaving as [`structure`].
+#[cfg(feature = "std")]
+pub fn codeword_blocks(quality: ECL, version: Version) -> Vec<usize> {
+    let data = alloc::vec![0; hardcode::data_codewords(version, quality)];
+    let blocks = interleave::data_blocks(&data, version, quality);
+    let zeros = [0; galois::MAX_ECC_CODEWORDS];
+    let ecc = alloc::vec![&zeros[..galois::ecc_codewords(version, quality)]; blocks.len()];
+
+    interleave::columns(&blocks)
+        .chain(interleave::columns(&ecc))
+        .map(|(block, _)| block)
+        .collect()
+}
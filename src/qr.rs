@@ -1,14 +1,21 @@
 //! Module `qr` is the entrypoint to start making `QRCodes`
 
-use crate::module::Module;
+use crate::module::{Module, ModuleCounts, ModuleType};
+use alloc::string::String;
+use alloc::vec::Vec;
 use core::fmt::{Debug, Formatter};
 use core::ops::{Index, IndexMut};
 
-use crate::datamasking::Mask;
-use crate::encode::Mode;
+use crate::compat::{LegacyCompat, PadCodewords};
+use crate::datamasking::{Mask, MaskReport, MaskScoring};
+use crate::encode::{InvalidCharacter, Mode};
+use crate::fingerprint::Fingerprint;
 #[cfg(not(feature = "wasm-bindgen"))]
 use crate::helpers;
-use crate::{encode, Version, ECL};
+use crate::placement::MatrixOptions;
+#[cfg(feature = "std")]
+use crate::verify::{VerifiedQRCode, VerifyError};
+use crate::{default, encode, hardcode, Version, ECL};
 
 const QR_MAX_WIDTH: usize = 177;
 const QR_MAX_MODULES: usize = QR_MAX_WIDTH * QR_MAX_WIDTH;
@@ -51,14 +58,11 @@ pub struct QRCode {
 
     /// Changes the final pattern used.
     ///
-    /// None will find the best suited mask.
+    /// None will find the best suited mask. Once built, holds the mask that was applied.
     pub mask: Option<Mask>,
-    /// Mode defines which data is being parsed, between Numeric, AlphaNumeric & Byte.
+    /// Mode defines which data is being parsed, between Numeric, AlphaNumeric, Byte & Kanji.
     ///
     /// `None` will optimize Mode according to user input.
-    ///
-    /// ## Note
-    /// Kanji mode is not supported (yet).
     pub mode: Option<Mode>,
 }
 
@@ -87,6 +91,284 @@ impl QRCode {
             mode: None,
         }
     }
+
+    /// Returns the number of modules per side, same as the `size` field
+    #[must_use]
+    pub const fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the module at column `x`, row `y`
+    ///
+    /// # Panics
+    /// If `x` or `y` is not lower than `size`
+    #[must_use]
+    pub fn get(&self, x: usize, y: usize) -> Module {
+        assert!(
+            x < self.size && y < self.size,
+            "({x}, {y}) is out of the QRCode"
+        );
+        self.data[y * self.size + x]
+    }
+
+    /// Returns the module at column `x`, row `y`, `None` if it is out of the
+    /// `QRCode`. Never panics, unlike [`get`](QRCode::get) and indexing, which
+    /// `fast_qr_no_panic` checks
+    #[must_use]
+    pub fn try_get(&self, x: usize, y: usize) -> Option<Module> {
+        no_panic!({
+            if x < self.size && y < self.size {
+                self.data.get(y * self.size + x).copied()
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Returns an iterator over the rows, from top to bottom
+    pub fn iter_rows(&self) -> impl Iterator<Item = &[Module]> + '_ {
+        self.data[..self.size * self.size].chunks_exact(self.size.max(1))
+    }
+
+    /// Returns the `(x, y)` position of every dark module, row by row
+    pub fn iter_dark_modules(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.iter_rows().enumerate().flat_map(|(y, row)| {
+            row.iter()
+                .enumerate()
+                .filter(|(_, module)| module.value())
+                .map(move |(x, _)| (x, y))
+        })
+    }
+
+    /// Returns the `(x, y)` position of every module of type `module_type`, row by row
+    ///
+    /// ```rust
+    /// use fast_qr::{ModuleType, QRBuilder};
+    ///
+    /// let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    /// // Horizontal timing pattern, between the top finder patterns
+    /// let timing: Vec<_> = qrcode
+    ///     .region_of(ModuleType::Timing)
+    ///     .filter(|&(_, y)| y == 6)
+    ///     .collect();
+    /// assert_eq!(timing.len(), qrcode.size - 16);
+    /// ```
+    pub fn region_of(&self, module_type: ModuleType) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.iter_rows().enumerate().flat_map(move |(y, row)| {
+            row.iter()
+                .enumerate()
+                .filter(move |(_, module)| module.module_type() == module_type)
+                .map(move |(x, _)| (x, y))
+        })
+    }
+
+    /// Returns the number of modules of each type, in a single pass
+    #[must_use]
+    pub fn count_by_type(&self) -> ModuleCounts {
+        let mut counts = ModuleCounts::default();
+        for module in &self.data[..self.size * self.size] {
+            counts.add(module.module_type());
+        }
+        counts
+    }
+
+    /// Returns the code as a 1-bit bitmap, row by row, dark modules set and
+    /// no quiet zone. Each row starts on a new byte, its leftmost module in
+    /// the most significant bit: the raster layout of most printer commands
+    /// (i.e. ESC/POS `GS v 0`).
+    ///
+    /// ```rust
+    /// use fast_qr::QRBuilder;
+    ///
+    /// let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    /// let bits = qrcode.to_bits_row_major();
+    ///
+    /// // 25 modules per row take 4 bytes, the first row starts with a finder pattern
+    /// assert_eq!(bits.len(), 4 * 25);
+    /// assert_eq!(bits[0], 0b1111_1110);
+    /// ```
+    #[must_use]
+    pub fn to_bits_row_major(&self) -> Vec<u8> {
+        let stride = (self.size + 7) / 8;
+        let mut bits = alloc::vec![0; stride * self.size];
+        for (x, y) in self.iter_dark_modules() {
+            bits[y * stride + x / 8] |= 0x80 >> (x % 8);
+        }
+        bits
+    }
+
+    /// Returns the code as a 1-bit bitmap, column by column, for printers
+    /// whose head draws vertical strips. Each column starts on a new byte,
+    /// its topmost module in the most significant bit. Same bytes as
+    /// [`to_bits_row_major`](QRCode::to_bits_row_major) of the
+    /// [`transpose`](QRCode::transpose).
+    #[must_use]
+    pub fn to_bits_column_major(&self) -> Vec<u8> {
+        let stride = (self.size + 7) / 8;
+        let mut bits = alloc::vec![0; stride * self.size];
+        for (x, y) in self.iter_dark_modules() {
+            bits[x * stride + y / 8] |= 0x80 >> (y % 8);
+        }
+        bits
+    }
+
+    /// Returns the code mirrored along its top left to bottom right
+    /// diagonal, rows becoming columns. This is a mirror image of the code,
+    /// which not every scanner reads.
+    #[must_use]
+    pub fn transpose(&self) -> QRCode {
+        default::transpose(self)
+    }
+
+    /// Scores the 8 masks again and returns each penalty rule, to understand
+    /// why a mask was selected. `None` if the `QRCode` has no mask or version,
+    /// i.e. it was not built by a [`QRBuilder`]
+    ///
+    /// Scores use the weights of the spec, whatever the
+    /// [`mask_scoring`](QRBuilder::mask_scoring) used to build the `QRCode`.
+    ///
+    /// ```rust
+    /// use fast_qr::QRBuilder;
+    ///
+    /// let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    /// let report = qrcode.mask_report().unwrap();
+    ///
+    /// assert_eq!(report.best() as usize, report.applied as usize);
+    /// for candidate in &report.candidates {
+    ///     println!("{:?}: {} ({candidate:?})", candidate.mask, candidate.total());
+    /// }
+    /// ```
+    #[must_use]
+    pub fn mask_report(&self) -> Option<MaskReport> {
+        crate::placement::mask_report(self)
+    }
+
+    /// Checks the structure of a built `QRCode`: its version, ECL and mask
+    /// are set, its size matches the version, and every module has the type
+    /// it has at this position in every code of this version. Function
+    /// patterns, format and version information must also have their exact
+    /// colors, only data modules are free.
+    ///
+    /// Meant for fuzzing and test harnesses, [`build`](QRBuilder::build)
+    /// always returns codes passing it.
+    ///
+    /// ```rust
+    /// use fast_qr::QRBuilder;
+    ///
+    /// let mut qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    /// assert!(qrcode.check_invariants().is_ok());
+    ///
+    /// // Top left module of the top left finder pattern
+    /// qrcode[0][0].toggle();
+    /// assert!(qrcode.check_invariants().is_err());
+    /// ```
+    ///
+    /// # Errors
+    /// - `InvariantError::Unset` if the version, ECL or mask is `None`
+    /// - `InvariantError::Size` if the size is not the one of the version
+    /// - `InvariantError::Module` with the position of the first wrong module
+    pub fn check_invariants(&self) -> Result<(), InvariantError> {
+        let (version, ecl, mask) = match (self.version, self.ecl, self.mask) {
+            (Some(version), Some(ecl), Some(mask)) => (version, ecl, mask),
+            _ => return Err(InvariantError::Unset),
+        };
+        if self.size != version.size() {
+            return Err(InvariantError::Size);
+        }
+
+        let mut expected = default::create_matrix(version);
+        default::create_matrix_format_info(&mut expected, ecl, mask);
+
+        for y in 0..self.size {
+            for x in 0..self.size {
+                let (module, expected) = (self[y][x], expected[y][x]);
+                let module_type = module.module_type();
+                if module_type != expected.module_type()
+                    || (module_type != ModuleType::Data && module.value() != expected.value())
+                {
+                    return Err(InvariantError::Module { y, x });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads the codewords back from the matrix, in the interleaved order
+    /// they are placed in (section 7.6): data codewords of every block, then
+    /// their error correction codewords. `None` if the `QRCode` has no
+    /// version or mask, i.e. it was not built by a [`QRBuilder`]
+    ///
+    /// ```rust
+    /// use fast_qr::{QRBuilder, ECL};
+    ///
+    /// let qrcode = QRBuilder::new("HELLO WORLD")
+    ///     .ecl(ECL::M)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// // Alphanumeric mode, 11 characters, then "HE": 0010 000001011 01100001011
+    /// let codewords = qrcode.codewords().unwrap();
+    /// assert_eq!(codewords.len(), 26);
+    /// assert_eq!(codewords[..3], [0b0010_0000, 0b0101_1011, 0b0000_1011]);
+    /// ```
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn codewords(&self) -> Option<Vec<u8>> {
+        match (self.version, self.mask) {
+            (Some(version), Some(mask)) if self.size == version.size() => {
+                Some(crate::decode::read_codewords(self, version, mask))
+            }
+            _ => None,
+        }
+    }
+
+    /// Reads the blocks back from the matrix, before interleaving: each
+    /// holds a run of data codewords and the error correction codewords
+    /// computed from it. `None` if the `QRCode` has no version, ECL or mask
+    ///
+    /// ```rust
+    /// use fast_qr::{QRBuilder, Version, ECL};
+    ///
+    /// let qrcode = QRBuilder::new("https://example.com/")
+    ///     .version(Version::V05)
+    ///     .ecl(ECL::Q)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// // Two blocks of 15 data codewords, then two of 16
+    /// let blocks = qrcode.blocks().unwrap();
+    /// let sizes: Vec<_> = blocks.iter().map(|block| block.data.len()).collect();
+    /// assert_eq!(sizes, [15, 15, 16, 16]);
+    /// assert!(blocks.iter().all(|block| block.ecc.len() == 18));
+    /// ```
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn blocks(&self) -> Option<Vec<Block>> {
+        let ecl = self.ecl?;
+        let codewords = self.codewords()?;
+        let version = self.version?;
+        let ecc_len = crate::galois::ecc_codewords(version, ecl);
+
+        let blocks = crate::decode::blocks(&codewords, version, ecl)
+            .into_iter()
+            .map(|mut data| {
+                let ecc = data.split_off(data.len() - ecc_len);
+                Block { data, ecc }
+            })
+            .collect();
+        Some(blocks)
+    }
+}
+
+/// Block of codewords, see [`QRCode::blocks`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Block {
+    /// Data codewords
+    pub data: Vec<u8>,
+    /// Error correction codewords, the remainder of the division of `data`
+    /// by the generator polynomial
+    pub ecc: Vec<u8>,
 }
 
 impl Index<usize> for QRCode {
@@ -104,23 +386,184 @@ impl IndexMut<usize> for QRCode {
 }
 
 /// Contains different error when [`QRCode`] could not be created
+///
+/// New variants may be added in minor releases, matches need a wildcard arm.
+#[non_exhaustive]
 pub enum QRCodeError {
-    /// If data if too large to be encoded (refer to Table 7-11 of the spec or [an online table](https://fast-qr.com/blog/tables/ecl))
-    EncodedData,
+    /// If data if too large to be encoded (refer to Table 7-11 of the spec or [an online table](https://fast-qr.com/blog/tables/ecl)),
+    /// with the alternatives computed in [`Overflow`]
+    EncodedData(Overflow),
     /// Specified version too small to contain data
     SpecifiedVersion,
+    /// Specified mask pattern is not between 0 and 7
+    MaskPattern,
+    /// Data needs a version larger than the specified maximum, even once shortened
+    MaxVersion,
+    /// Input is empty, which is refused unless explicitly allowed (see [`QRBuilder::allow_empty`])
+    EmptyPayload,
+    /// Input has characters the forced mode cannot encode (see [`Mode::validate`]),
+    /// with the first of them
+    InvalidMode(InvalidCharacter),
+}
+
+/// Broken invariant of a `QRCode`, see [`QRCode::check_invariants`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvariantError {
+    /// The version, ECL or mask is not set
+    Unset,
+    /// The size is not the one of the version
+    Size,
+    /// A module does not have the type or color it must have
+    Module {
+        /// Row of the module
+        y: usize,
+        /// Column of the module
+        x: usize,
+    },
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvariantError {}
+
+impl core::fmt::Display for InvariantError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            InvariantError::Unset => f.write_str("Version, ECL or mask is not set"),
+            InvariantError::Size => f.write_str("Size does not match the version"),
+            InvariantError::Module { y, x } => write!(f, "Wrong module at row {y}, column {x}"),
+        }
+    }
+}
+
+/// Bits of the structured append header repeated in every part: mode,
+/// part index, part count and parity (section 8)
+const STRUCTURED_APPEND_HEADER_BITS: usize = 4 + 4 + 4 + 8;
+
+/// Data too large for a version 40 `QRCode`, and how far off it is
+///
+/// Lengths count characters of `mode`, which are bytes in byte mode. Inputs
+/// are checked against [`Mode::max_len`] before being read: when no mode is
+/// forced and the input is longer than even numeric mode holds, its mode is
+/// not detected and `mode` is `Mode::Numeric`.
+///
+/// ```rust
+/// use fast_qr::qr::QRCodeError;
+/// use fast_qr::{QRBuilder, ECL};
+///
+/// let input = "a".repeat(2000);
+/// match QRBuilder::new(input).ecl(ECL::H).build() {
+///     Err(QRCodeError::EncodedData(overflow)) => {
+///         assert_eq!(overflow.capacity, 1273);
+///         assert_eq!(overflow.excess(), 727);
+///         assert!(matches!(overflow.lower_ecl(), Some(ECL::M)));
+///         assert_eq!(overflow.structured_append_parts(ECL::H), 2);
+///     }
+///     _ => unreachable!(),
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Overflow {
+    /// Mode the data is encoded with
+    pub mode: Mode,
+    /// Requested error correction level
+    pub ecl: ECL,
+    /// Length of the data
+    pub len: usize,
+    /// Largest length a version 40 `QRCode` holds at `ecl`
+    pub capacity: usize,
+}
+
+impl Overflow {
+    pub(crate) const fn new(mode: Mode, ecl: ECL, len: usize) -> Self {
+        Overflow {
+            mode,
+            ecl,
+            len,
+            capacity: capacity(mode, hardcode::data_bits(Version::V40, ecl)),
+        }
+    }
+
+    /// Returns the number of characters above the capacity
+    #[must_use]
+    pub const fn excess(&self) -> usize {
+        self.len.saturating_sub(self.capacity)
+    }
+
+    /// Returns the largest length a version 40 `QRCode` holds at `ecl`
+    #[must_use]
+    pub const fn capacity_at(&self, ecl: ECL) -> usize {
+        capacity(self.mode, hardcode::data_bits(Version::V40, ecl))
+    }
+
+    /// Returns the strongest error correction level below the requested one
+    /// that fits the data, `None` if even `ECL::L` is too small
+    #[must_use]
+    pub fn lower_ecl(&self) -> Option<ECL> {
+        [ECL::H, ECL::Q, ECL::M, ECL::L]
+            .into_iter()
+            .filter(|&ecl| (ecl as usize) < (self.ecl as usize))
+            .find(|&ecl| self.capacity_at(ecl) >= self.len)
+    }
+
+    /// Returns the number of version 40 `QRCode`s at `ecl` needed to split
+    /// the data with structured append. The spec allows up to 16 parts, a
+    /// larger result means the data cannot be split at this level.
+    #[must_use]
+    pub const fn structured_append_parts(&self, ecl: ECL) -> usize {
+        let bits = hardcode::data_bits(Version::V40, ecl) - STRUCTURED_APPEND_HEADER_BITS;
+        let per_part = capacity(self.mode, bits);
+        (self.len + per_part - 1) / per_part
+    }
+}
+
+/// Rejects `len` characters if they cannot fit a version 40 `QRCode` in
+/// `mode`, or in any mode if it is not known yet, without reading the input
+pub(crate) const fn check_len(len: usize, mode: Option<Mode>, ecl: ECL) -> Result<(), QRCodeError> {
+    // Numeric is the densest mode
+    let mode = match mode {
+        Some(mode) => mode,
+        None => Mode::Numeric,
+    };
+
+    if len > mode.max_len(ecl) {
+        return Err(QRCodeError::EncodedData(Overflow::new(mode, ecl, len)));
+    }
+    Ok(())
+}
+
+/// Returns the number of characters of `mode` fitting in `bits`, after the
+/// mode indicator and version 40 character count
+pub(crate) const fn capacity(mode: Mode, bits: usize) -> usize {
+    let bits = bits - 4 - hardcode::cci_bits(Version::V40, mode);
+    match mode {
+        Mode::Numeric => bits / 10 * 3 + [0, 0, 0, 0, 1, 1, 1, 2, 2, 2][bits % 10],
+        Mode::Alphanumeric => bits / 11 * 2 + (bits % 11 >= 6) as usize,
+        Mode::Byte => bits / 8,
+        // In bytes, two per character
+        Mode::Kanji => bits / 13 * 2,
+    }
 }
 
 // We don't want to use `std::error::Error` on wasm32
+#[cfg(feature = "std")]
 impl std::error::Error for QRCodeError {}
 
-impl std::fmt::Display for QRCodeError {
+impl core::fmt::Display for QRCodeError {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         match self {
-            QRCodeError::EncodedData => f.write_str("Data too big to be encoded"),
+            QRCodeError::EncodedData(overflow) => write!(
+                f,
+                "Data too big to be encoded: {} characters over the capacity of {}",
+                overflow.excess(),
+                overflow.capacity
+            ),
             QRCodeError::SpecifiedVersion => {
                 f.write_str("Specified version too low to contain data")
             }
+            QRCodeError::MaskPattern => f.write_str("Mask pattern must be between 0 and 7"),
+            QRCodeError::MaxVersion => f.write_str("Data too big for the maximum version"),
+            QRCodeError::EmptyPayload => f.write_str("Input is empty"),
+            QRCodeError::InvalidMode(error) => write!(f, "{error}"),
         }
     }
 }
@@ -128,10 +571,19 @@ impl std::fmt::Display for QRCodeError {
 impl Debug for QRCodeError {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         match self {
-            QRCodeError::EncodedData => f.write_str("Data too big to be encoded"),
+            QRCodeError::EncodedData(overflow) => write!(
+                f,
+                "Data too big to be encoded: {} characters over the capacity of {}",
+                overflow.excess(),
+                overflow.capacity
+            ),
             QRCodeError::SpecifiedVersion => {
                 f.write_str("Specified version too low to contain data")
             }
+            QRCodeError::MaskPattern => f.write_str("Mask pattern must be between 0 and 7"),
+            QRCodeError::MaxVersion => f.write_str("Data too big for the maximum version"),
+            QRCodeError::EmptyPayload => f.write_str("Input is empty"),
+            QRCodeError::InvalidMode(error) => write!(f, "{error}"),
         }
     }
 }
@@ -143,45 +595,176 @@ impl QRCode {
     /// - `QRCodeError::EncodedData` if `input` is too large to be encoded
     /// - `QRCodeError::SpecifiedVersion` if specified `version` is too small to contain data
     pub(crate) fn new(
+        input: &[u8],
+        ecl: Option<ECL>,
+        v: Option<Version>,
+        mode: Option<Mode>,
+        mask: Option<Mask>,
+    ) -> Result<Self, QRCodeError> {
+        QRCode::with_options(input, ecl, v, mode, mask, MatrixOptions::default())
+    }
+
+    /// Creates a new `QRCode`, with the pipeline settings of `options`
+    ///
+    /// # Errors
+    /// - `QRCodeError::EncodedData` if `input` is too large to be encoded
+    /// - `QRCodeError::SpecifiedVersion` if specified `version` is too small to contain data
+    /// - `QRCodeError::MaxVersion` if the version would exceed `options.max_version`
+    pub(crate) fn with_options(
         input: &[u8],
         ecl: Option<ECL>,
         v: Option<Version>,
         mode: Option<Mode>,
         mut mask: Option<Mask>,
+        options: MatrixOptions,
     ) -> Result<Self, QRCodeError> {
         use crate::placement::create_matrix;
 
-        let mode = mode.unwrap_or_else(|| encode::best_encoding(input));
         let level = ecl.unwrap_or(ECL::Q);
+        let mode = match mode {
+            Some(mode) => Some(mode),
+            // Picking the mode reads every byte, byte mode fits any content
+            None if options.constant_time => Some(Mode::Byte),
+            None => None,
+        };
+        // Rejected before reading the input. UTF-8 text is never shorter than
+        // its Shift JIS form, and Kanji in UTF-8 (3 bytes each) stays under the
+        // numeric limit at every level
+        check_len(input.len(), mode.filter(|&mode| mode != Mode::Kanji), level)?;
+        // Text is converted to Shift JIS when encoded in Kanji mode
+        let sjis = match mode {
+            Some(Mode::Kanji) | None => encode::kanji_from_utf8(input),
+            Some(_) => None,
+        };
+        let input = sjis.as_deref().unwrap_or(input);
+        check_len(input.len(), mode, level)?;
+        if let Some(mode) = mode {
+            mode.validate(input).map_err(QRCodeError::InvalidMode)?;
+        }
+
+        let mode = match mode {
+            Some(mode) => mode,
+            None => encode::best_encoding(input),
+        };
 
         let version = match Version::get(mode, level, input.len()) {
             Some(version) => version,
-            None => return Err(QRCodeError::EncodedData),
+            None => {
+                return Err(QRCodeError::EncodedData(Overflow::new(
+                    mode,
+                    level,
+                    input.len(),
+                )))
+            }
         };
         let version = match v {
-            Some(user_version) if user_version as usize >= version as usize => user_version,
+            Some(user_version) if user_version >= version => user_version,
             None => version,
             Some(_) => return Err(QRCodeError::SpecifiedVersion),
         };
 
-        let out = create_matrix(input, level, mode, version, &mut mask);
-        Ok(out)
+        if let Some(max_version) = options.max_version {
+            if version > max_version {
+                return Err(QRCodeError::MaxVersion);
+            }
+        }
+
+        create_matrix(input, level, mode, version, &mut mask, options)
+            .map_err(QRCodeError::InvalidMode)
     }
 
     /// Prints the `QRCode` to the terminal
     #[must_use]
     #[cfg(not(feature = "wasm-bindgen"))]
     pub fn to_str(&self) -> String {
-        helpers::print_matrix_with_margin(self)
+        helpers::print_matrix_with_margin(self, 1)
     }
 
-    /// Prints the `QRCode` to the terminal
+    /// Prints the `QRCode` to the terminal, surrounded by `margin` modules
+    /// (`0` when the surrounding layout already provides whitespace)
+    #[must_use]
     #[cfg(not(feature = "wasm-bindgen"))]
+    pub fn to_str_with_margin(&self, margin: usize) -> String {
+        helpers::print_matrix_with_margin(self, margin)
+    }
+
+    /// Prints the `QRCode` to the terminal
+    #[cfg(all(feature = "std", not(feature = "wasm-bindgen")))]
     pub fn print(&self) {
-        println!("{}", helpers::print_matrix_with_margin(self));
+        println!("{}", helpers::print_matrix_with_margin(self, 1));
     }
 }
 
+/// Shortens or compresses data too large for the maximum version, see [`QRBuilder::shortener`]
+pub type Shortener = fn(&[u8]) -> Vec<u8>;
+
+/// Picks how to encode data which does not fit the requested constraints,
+/// see [`QRBuilder::negotiator`]
+pub type Negotiator = fn(&Conflict) -> Negotiation;
+
+/// Requested constraints the data does not fit, with the alternatives which
+/// would, given to a [`Negotiator`]
+#[derive(Debug, Clone, Copy)]
+pub struct Conflict {
+    /// Mode the data is encoded with
+    pub mode: Mode,
+    /// Length of the data, in characters of `mode`
+    pub len: usize,
+    /// Requested error correction level (`ECL::Q` if none was set)
+    pub ecl: ECL,
+    /// Requested version, if any
+    pub version: Option<Version>,
+    /// Requested maximum version, if any
+    pub max_version: Option<Version>,
+    /// Smallest version holding the data at `ecl`, `None` if even a version
+    /// 40 `QRCode` is too small
+    pub min_version: Option<Version>,
+    /// Strongest error correction level below `ecl` holding the data in
+    /// the requested version (or under the maximum), `None` if none does
+    pub lower_ecl: Option<ECL>,
+    /// Number of version 40 `QRCode`s at `ecl` needed to split the data with
+    /// structured append, see [`Overflow::structured_append_parts`]
+    pub split_parts: usize,
+}
+
+impl Conflict {
+    fn new(
+        len: usize,
+        mode: Mode,
+        ecl: ECL,
+        version: Option<Version>,
+        max_version: Option<Version>,
+    ) -> Self {
+        let limit = version.or(max_version).unwrap_or(Version::V40);
+        let lower_ecl = [ECL::H, ECL::Q, ECL::M, ECL::L]
+            .into_iter()
+            .filter(|&lower| (lower as usize) < (ecl as usize))
+            .find(|&lower| Version::get(mode, lower, len).map_or(false, |needed| needed <= limit));
+
+        Conflict {
+            mode,
+            len,
+            ecl,
+            version,
+            max_version,
+            min_version: Version::get(mode, ecl, len),
+            lower_ecl,
+            split_parts: Overflow::new(mode, ecl, len).structured_append_parts(ecl),
+        }
+    }
+}
+
+/// Alternative chosen by a [`Negotiator`]
+#[derive(Debug, Clone, Copy)]
+pub enum Negotiation {
+    /// Encodes at this error correction level, keeping the version constraints
+    Ecl(ECL),
+    /// Encodes in this version, lifting the maximum version
+    Version(Version),
+    /// Keeps the error, i.e. to split the data with structured append
+    Fail,
+}
+
 /// Builder struct, makes it easier to create a [`QRCode`].
 ///
 /// # Example
@@ -197,12 +780,40 @@ impl QRCode {
 ///     // .mask(Mask::Checkerboard)
 ///     .build();
 /// ```
+///
+/// With the `serde` feature, the builder is serialized with its input and
+/// options, the [`Shortener`] and [`Negotiator`] hooks are skipped. With the
+/// `arbitrary` feature, fuzzers generate builders the same way, without hooks
+/// nor custom mask scoring or padding.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct QRBuilder {
     input: Vec<u8>,
     ecl: Option<ECL>,
     mode: Option<Mode>,
     version: Option<Version>,
     mask: Option<Mask>,
+    mask_pattern: Option<usize>,
+    mask_scoring: MaskScoring,
+    legacy_compat: LegacyCompat,
+    constant_time: bool,
+    max_version: Option<Version>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    #[cfg_attr(feature = "arbitrary", arbitrary(default))]
+    shortener: Option<Shortener>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    #[cfg_attr(feature = "arbitrary", arbitrary(default))]
+    negotiator: Option<Negotiator>,
+    allow_empty: bool,
+    image_embedding: bool,
+}
+
+/// The input is wiped once the builder is dropped
+#[cfg(feature = "zeroize")]
+impl Drop for QRBuilder {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(&mut self.input);
+    }
 }
 
 impl QRBuilder {
@@ -212,6 +823,15 @@ impl QRBuilder {
         QRBuilder {
             input: input.into(),
             mask: None,
+            mask_pattern: None,
+            mask_scoring: MaskScoring::Spec,
+            legacy_compat: LegacyCompat::default(),
+            constant_time: false,
+            max_version: None,
+            shortener: None,
+            negotiator: None,
+            allow_empty: false,
+            image_embedding: false,
             mode: None,
             version: None,
             ecl: None,
@@ -236,18 +856,374 @@ impl QRBuilder {
         self
     }
 
+    /// Keeps enough redundancy to embed an image in the center of the [`QRCode`]:
+    /// the ECL is raised to at least `ECL::Q` (`ECL::H` is kept), whatever
+    /// the order of the calls to [`ecl`](QRBuilder::ecl)
+    pub fn image_embedding(&mut self) -> &mut Self {
+        self.image_embedding = true;
+        self
+    }
+
+    /// Forced ECL, raised by [`image_embedding`](QRBuilder::image_embedding)
+    fn embedding_ecl(&self) -> Option<ECL> {
+        match (self.ecl, self.image_embedding) {
+            (Some(ECL::H), true) => Some(ECL::H),
+            (_, true) => Some(ECL::Q),
+            (ecl, false) => ecl,
+        }
+    }
+
     /// Forces the mask, should very rarely be used
     pub fn mask(&mut self, mask: Mask) -> &mut Self {
         self.mask = Some(mask);
+        self.mask_pattern = None;
+        self
+    }
+
+    /// Forces the mask from its pattern reference, from 0 to 7 (see [`Mask`])
+    #[deprecated(
+        since = "0.13.0",
+        note = "use `mask` with `Mask::from_pattern`, which rejects invalid patterns right away"
+    )]
+    pub fn mask_pattern(&mut self, pattern: usize) -> &mut Self {
+        self.mask = None;
+        self.mask_pattern = Some(pattern);
+        self
+    }
+
+    /// Changes how masks are compared when none is forced (default: `MaskScoring::Spec`)
+    ///
+    /// # Example
+    /// ```rust
+    /// use fast_qr::datamasking::MaskScoring;
+    /// use fast_qr::{QRBuilder, QRCode};
+    ///
+    /// // Favors masks leaving the top left data modules light
+    /// fn penalty(qr: &QRCode) -> u32 {
+    ///     (9..15).filter(|&i| qr[i][i].value()).count() as u32
+    /// }
+    ///
+    /// let qrcode = QRBuilder::new("https://example.com/")
+    ///     .mask_scoring(MaskScoring::Custom(penalty))
+    ///     .build();
+    /// ```
+    pub fn mask_scoring(&mut self, scoring: MaskScoring) -> &mut Self {
+        self.mask_scoring = scoring;
+        self
+    }
+
+    /// Reproduces quirks of older encoders, to re-print archived codes
+    /// bit for bit (default: no quirk, see [`LegacyCompat`])
+    pub fn legacy_compat(&mut self, compat: LegacyCompat) -> &mut Self {
+        self.legacy_compat = compat;
+        self
+    }
+
+    /// Updates the codewords filling the capacity left after the data,
+    /// same as [`LegacyCompat::padding`] (default: `PadCodewords::Alternating`)
+    ///
+    /// ```rust
+    /// use fast_qr::compat::PadCodewords;
+    /// use fast_qr::QRBuilder;
+    ///
+    /// let qrcode = QRBuilder::new("https://example.com/")
+    ///     .padding(PadCodewords::Custom(|i| if i % 3 == 0 { 0xFF } else { 0x00 }))
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn padding(&mut self, padding: PadCodewords) -> &mut Self {
+        self.legacy_compat.padding = padding;
         self
     }
 
+    /// Encodes secret payloads (OTP seeds, keys) without data-dependent
+    /// branches nor lookups where feasible (default: false):
+    /// - byte mode is used unless a mode is forced
+    /// - error correction is computed without log / antilog tables
+    /// - masks are not scored, `Mask::Checkerboard` is used unless one is forced
+    ///
+    /// The `zeroize` feature wipes intermediate buffers whether this is set or not.
+    pub fn constant_time(&mut self, constant_time: bool) -> &mut Self {
+        self.constant_time = constant_time;
+        self
+    }
+
+    /// Caps the version, for labels too small to carry larger codes
+    /// (default: no cap, up to `Version::V40`)
+    ///
+    /// Building fails with `QRCodeError::MaxVersion` when the data does not fit,
+    /// unless a [`shortener`](QRBuilder::shortener) makes it fit.
+    pub fn max_version(&mut self, max_version: Version) -> &mut Self {
+        self.max_version = Some(max_version);
+        self
+    }
+
+    /// Called once with the input when it exceeds the [`max_version`](QRBuilder::max_version)
+    /// or does not fit a version 40, to shorten or compress it (i.e. swap a long URL for a short link).
+    /// The returned data is encoded instead.
+    ///
+    /// # Example
+    /// ```rust
+    /// use fast_qr::{QRBuilder, Version};
+    ///
+    /// fn shorten(_: &[u8]) -> Vec<u8> {
+    ///     b"https://ex.co/a1".to_vec()
+    /// }
+    ///
+    /// let long_url = format!("https://example.com/?q={}", "a".repeat(200));
+    /// let qrcode = QRBuilder::new(long_url)
+    ///     .max_version(Version::V02)
+    ///     .shortener(shorten)
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(qrcode.size, 25);
+    /// ```
+    pub fn shortener(&mut self, shortener: Shortener) -> &mut Self {
+        self.shortener = Some(shortener);
+        self
+    }
+
+    /// Called once when the data does not fit the requested error correction
+    /// level, version or maximum version, with the alternatives that would
+    /// fit. The chosen one is encoded right away, without a retry loop
+    /// computing them again. Runs before the [`shortener`](QRBuilder::shortener).
+    ///
+    /// # Example
+    /// ```rust
+    /// use fast_qr::qr::{Conflict, Negotiation};
+    /// use fast_qr::{QRBuilder, Version, ECL};
+    ///
+    /// /// Lowers the error correction rather than growing the code
+    /// fn negotiate(conflict: &Conflict) -> Negotiation {
+    ///     match (conflict.lower_ecl, conflict.min_version) {
+    ///         (Some(ecl), _) => Negotiation::Ecl(ecl),
+    ///         (None, Some(version)) => Negotiation::Version(version),
+    ///         (None, None) => Negotiation::Fail,
+    ///     }
+    /// }
+    ///
+    /// let qrcode = QRBuilder::new("https://example.com/")
+    ///     .ecl(ECL::H)
+    ///     .version(Version::V02)
+    ///     .negotiator(negotiate)
+    ///     .build()
+    ///     .unwrap();
+    /// assert!(matches!(qrcode.ecl, Some(ECL::Q)));
+    /// assert_eq!(qrcode.size, 25);
+    /// ```
+    pub fn negotiator(&mut self, negotiator: Negotiator) -> &mut Self {
+        self.negotiator = Some(negotiator);
+        self
+    }
+
+    /// Encodes an empty input as a zero-length byte segment (default: false,
+    /// building fails with `QRCodeError::EmptyPayload`).
+    ///
+    /// Empty input is refused by default as it usually means a missing value
+    /// (i.e. a blank column in a mail merge), printing a code that scans to
+    /// nothing would hide it. The empty symbol is valid but some scanners
+    /// ignore it.
+    ///
+    /// # Example
+    /// ```rust
+    /// use fast_qr::qr::QRCodeError;
+    /// use fast_qr::{Mode, QRBuilder};
+    ///
+    /// assert!(matches!(QRBuilder::new("").build(), Err(QRCodeError::EmptyPayload)));
+    ///
+    /// let qrcode = QRBuilder::new("").allow_empty(true).build().unwrap();
+    /// assert_eq!(qrcode.mode, Some(Mode::Byte));
+    /// assert_eq!(qrcode.size, 21);
+    /// ```
+    pub fn allow_empty(&mut self, allow_empty: bool) -> &mut Self {
+        self.allow_empty = allow_empty;
+        self
+    }
+
+    /// Hashes every option changing the modules of the built `QRCode`, the
+    /// input excluded. Two builders with the same fingerprint encode the same
+    /// input identically, with this version of the crate: store it next to
+    /// generated assets to know which ones to regenerate after an upgrade or
+    /// a settings change.
+    ///
+    /// `MaskScoring::Custom` penalties, shorteners and negotiators are `fn`
+    /// pointers, only their presence is hashed.
+    ///
+    /// # Example
+    /// ```rust
+    /// use fast_qr::{QRBuilder, ECL};
+    ///
+    /// let a = QRBuilder::new("https://example.com/").ecl(ECL::H).options_fingerprint();
+    /// let b = QRBuilder::new("https://example.org/").ecl(ECL::H).options_fingerprint();
+    /// let c = QRBuilder::new("https://example.com/").ecl(ECL::L).options_fingerprint();
+    /// assert_eq!(a, b);
+    /// assert_ne!(a, c);
+    /// ```
+    #[must_use]
+    pub fn options_fingerprint(&self) -> u64 {
+        // `mask(Mask::Checkerboard)` and `mask_pattern(0)` build the same code
+        let mask = self.mask_pattern.or_else(|| self.mask.map(Mask::pattern));
+
+        let mut fingerprint = Fingerprint::new("QRBuilder");
+        fingerprint
+            .debug(&self.embedding_ecl())
+            .debug(&self.mode)
+            .debug(&self.version)
+            .debug(&mask);
+        match self.legacy_compat.padding {
+            PadCodewords::Custom(_) => fingerprint
+                .debug(&self.legacy_compat.mask_tie_break)
+                .str("Custom"),
+            _ => fingerprint.debug(&self.legacy_compat),
+        };
+        fingerprint
+            .debug(&self.constant_time)
+            .debug(&self.max_version)
+            .debug(&self.shortener.is_some())
+            .debug(&self.allow_empty);
+        // Hashed only when set, fingerprints from before negotiators stay the same
+        if self.negotiator.is_some() {
+            fingerprint.str("negotiator");
+        }
+        match self.mask_scoring {
+            MaskScoring::Custom(_) => fingerprint.str("Custom"),
+            scoring => fingerprint.debug(&scoring),
+        };
+        fingerprint.finish()
+    }
+
     /// Computes a [`QRCode`] with given parameters
     ///
     /// # Errors
-    /// - `QRCodeError::EncodedData` if `input` is too large to be encoded. See [an online table](https://fast-qr.com/blog/tables/ecl) for more info.
+    /// - `QRCodeError::EncodedData` if `input` is too large to be encoded, even once shortened. See [an online table](https://fast-qr.com/blog/tables/ecl) for more info.
     /// - `QRCodeError::SpecifiedVersion` if specified `version` is too small to contain data
+    /// - `QRCodeError::MaskPattern` if specified mask pattern is greater than 7
+    /// - `QRCodeError::MaxVersion` if data does not fit the maximum version, even once shortened
+    /// - `QRCodeError::EmptyPayload` if `input`, or its shortened form, is empty and
+    ///   [`allow_empty`](QRBuilder::allow_empty) is not set
+    /// - `QRCodeError::InvalidMode` if the forced [`mode`](QRBuilder::mode) cannot encode `input`,
+    ///   with the index of the first invalid character
     pub fn build(&self) -> Result<QRCode, QRCodeError> {
-        QRCode::new(&self.input, self.ecl, self.version, self.mode, self.mask)
+        self.build_payload().map(|(qrcode, _)| qrcode)
+    }
+
+    /// Computes the [`QRCode`] like [`build`](QRBuilder::build), along with
+    /// the output of the [`shortener`](QRBuilder::shortener) when it was called
+    fn build_payload(&self) -> Result<(QRCode, Option<Vec<u8>>), QRCodeError> {
+        let mut mode = self.mode;
+        if self.input.is_empty() {
+            if !self.allow_empty {
+                return Err(QRCodeError::EmptyPayload);
+            }
+            // Every mode can hold zero characters, byte mode is the most widely supported
+            mode = mode.or(Some(Mode::Byte));
+        }
+
+        let mask = match self.mask_pattern {
+            Some(pattern) => Some(Mask::from_pattern(pattern).ok_or(QRCodeError::MaskPattern)?),
+            None => self.mask,
+        };
+        let ecl = self.embedding_ecl();
+
+        let options = MatrixOptions {
+            scoring: self.mask_scoring,
+            compat: self.legacy_compat,
+            constant_time: self.constant_time,
+            max_version: self.max_version,
+        };
+
+        let qrcode = QRCode::with_options(&self.input, ecl, self.version, mode, mask, options);
+        let qrcode = match (qrcode, self.negotiator) {
+            (
+                Err(
+                    err @ (QRCodeError::EncodedData(_)
+                    | QRCodeError::SpecifiedVersion
+                    | QRCodeError::MaxVersion),
+                ),
+                Some(negotiator),
+            ) => {
+                let conflict_mode = match mode {
+                    Some(mode) => mode,
+                    None if self.constant_time => Mode::Byte,
+                    None => encode::best_encoding(&self.input),
+                };
+                let sjis = match conflict_mode {
+                    Mode::Kanji => encode::kanji_from_utf8(&self.input),
+                    _ => None,
+                };
+                let conflict = Conflict::new(
+                    sjis.as_ref().map_or(self.input.len(), Vec::len),
+                    conflict_mode,
+                    ecl.unwrap_or(ECL::Q),
+                    self.version,
+                    self.max_version,
+                );
+
+                match negotiator(&conflict) {
+                    Negotiation::Ecl(ecl) => QRCode::with_options(
+                        &self.input,
+                        Some(ecl),
+                        self.version,
+                        mode,
+                        mask,
+                        options,
+                    ),
+                    Negotiation::Version(version) => QRCode::with_options(
+                        &self.input,
+                        ecl,
+                        Some(version),
+                        mode,
+                        mask,
+                        MatrixOptions {
+                            max_version: None,
+                            ..options
+                        },
+                    ),
+                    Negotiation::Fail => Err(err),
+                }
+            }
+            (qrcode, _) => qrcode,
+        };
+
+        match (qrcode, self.shortener) {
+            (Err(QRCodeError::MaxVersion | QRCodeError::EncodedData(_)), Some(shortener)) => {
+                let shortened = shortener(&self.input);
+                if shortened.is_empty() && !self.allow_empty {
+                    return Err(QRCodeError::EmptyPayload);
+                }
+                // A forced mode may not suit the shortened data
+                let mode = shortened.is_empty().then(|| Mode::Byte);
+                QRCode::with_options(&shortened, ecl, self.version, mode, mask, options)
+                    .map(|qrcode| (qrcode, Some(shortened)))
+            }
+            (qrcode, _) => qrcode.map(|qrcode| (qrcode, None)),
+        }
+    }
+
+    /// Computes a [`QRCode`] like [`build`](QRBuilder::build), then reads it
+    /// back from its modules and checks it holds the input (or the output of
+    /// the [`shortener`](QRBuilder::shortener)), see [`verify`](crate::verify)
+    ///
+    /// # Errors
+    /// - `VerifyError::Build` if the `QRCode` cannot be built
+    /// - `VerifyError::Mismatch` if it does not read back as its input
+    /// - `VerifyError::Corrected` / `VerifyError::Decode` if it cannot be read back as is
+    #[cfg(feature = "std")]
+    pub fn build_verified(&self) -> Result<VerifiedQRCode, VerifyError> {
+        // The shortener is not called again, its output may change
+        let (qrcode, shortened) = self.build_payload()?;
+        let decoded = crate::verify::read_back(&qrcode)?;
+
+        let payload = shortened.as_deref().unwrap_or(&self.input);
+        // Kanji reads back as Shift JIS, not as the UTF-8 payload
+        let sjis = match qrcode.mode {
+            Some(Mode::Kanji) => encode::kanji_from_utf8(payload),
+            _ => None,
+        };
+        if decoded != payload && Some(&decoded) != sjis.as_ref() {
+            return Err(VerifyError::Mismatch);
+        }
+
+        Ok(VerifiedQRCode(qrcode))
     }
 }
@@ -0,0 +1,234 @@
+//! **Experimental**: simulates a scanner reading a rendered `QRCode`, to compare
+//! masks by empirical robustness rather than by the spec penalty alone.
+//!
+//! The code is rasterized with some dot gain (ink spreading around dark
+//! modules), then read row by row by a run-length state machine, like a
+//! 1D barcode reader: each run of same-colored pixels is converted to a
+//! number of modules using the estimated module pitch. Sampling can be
+//! misaligned by a fraction of a module and the pitch can be misjudged.
+//!
+//! ```rust
+//! use fast_qr::scanner::ScannerSimulation;
+//! use fast_qr::ECL;
+//!
+//! let reports = ScannerSimulation::default()
+//!     .dot_gain(0.2)
+//!     .pitch_error(0.03)
+//!     .mask_report(b"https://example.com/", ECL::M)
+//!     .unwrap();
+//!
+//! // Most robust mask first
+//! println!("{:?}", reports[0]);
+//! ```
+
+#![deny(unsafe_code)]
+#![warn(missing_docs)]
+
+use crate::datamasking::Mask;
+use crate::module::ModuleType;
+use crate::qr::QRCodeError;
+use crate::{QRBuilder, QRCode, ECL};
+
+/// Bit error rate measured for one mask
+#[derive(Debug, Clone, Copy)]
+pub struct MaskReport {
+    /// Mask used for the `QRCode`
+    pub mask: Mask,
+    /// Ratio of misread data modules, from `0.0` to `1.0`
+    pub bit_error_rate: f64,
+}
+
+/// Parameters of the simulated scanning passes
+pub struct ScannerSimulation {
+    /// Pixels per module
+    scale: usize,
+    /// Ink spreading around dark modules, in modules
+    dot_gain: f64,
+    /// Relative error on the module pitch estimated by the scanner
+    pitch_error: f64,
+    /// Sampling offsets of each pass, in modules
+    offsets: Vec<(f64, f64)>,
+}
+
+/// Creates a simulation with 8 pixels per module, no dot gain, no pitch
+/// error and passes offset by -0.3, 0 and 0.3 module on both axes
+impl Default for ScannerSimulation {
+    fn default() -> Self {
+        let steps = [-0.3, 0.0, 0.3];
+        let offsets = steps
+            .iter()
+            .flat_map(|&dy| steps.iter().map(move |&dx| (dx, dy)))
+            .collect();
+
+        ScannerSimulation {
+            scale: 8,
+            dot_gain: 0.0,
+            pitch_error: 0.0,
+            offsets,
+        }
+    }
+}
+
+impl ScannerSimulation {
+    /// Updates the number of pixels per module (default: 8)
+    pub fn scale(&mut self, scale: usize) -> &mut Self {
+        self.scale = scale.max(1);
+        self
+    }
+
+    /// Updates how far ink spreads around dark modules, in modules (default: 0.0)
+    pub fn dot_gain(&mut self, dot_gain: f64) -> &mut Self {
+        self.dot_gain = dot_gain.max(0.0);
+        self
+    }
+
+    /// Updates the relative error on the module pitch, `0.05` reads modules
+    /// as 5% wider than they are (default: 0.0)
+    pub fn pitch_error(&mut self, pitch_error: f64) -> &mut Self {
+        self.pitch_error = pitch_error;
+        self
+    }
+
+    /// Replaces the sampling passes, each one offset by `(x, y)` modules
+    pub fn offsets(&mut self, offsets: Vec<(f64, f64)>) -> &mut Self {
+        self.offsets = offsets;
+        self
+    }
+
+    /// Rasterizes the `QRCode`, dark pixels are `true`
+    fn rasterize(&self, qr: &QRCode) -> Vec<bool> {
+        let width = qr.size * self.scale;
+
+        let mut bitmap = vec![false; width * width];
+        for (y, row) in bitmap.chunks_exact_mut(width).enumerate() {
+            for (x, pixel) in row.iter_mut().enumerate() {
+                *pixel = qr[y / self.scale][x / self.scale].value();
+            }
+        }
+
+        // Dot gain: separable dilation, horizontal then vertical
+        let gain = (self.dot_gain * self.scale as f64).round() as usize;
+        if gain == 0 {
+            return bitmap;
+        }
+
+        let mut dilated = vec![false; width * width];
+        for y in 0..width {
+            for x in 0..width {
+                let from = x.saturating_sub(gain);
+                let to = core::cmp::min(width, x + gain + 1);
+                dilated[y * width + x] =
+                    bitmap[y * width + from..y * width + to].iter().any(|&p| p);
+            }
+        }
+        for y in 0..width {
+            for x in 0..width {
+                let from = y.saturating_sub(gain);
+                let to = core::cmp::min(width, y + gain + 1);
+                bitmap[y * width + x] = (from..to).any(|y| dilated[y * width + x]);
+            }
+        }
+
+        bitmap
+    }
+
+    /// Reads one row of modules with a run-length state machine
+    fn scan_row(
+        &self,
+        bitmap: &[bool],
+        width: usize,
+        size: usize,
+        py: usize,
+        dx: f64,
+    ) -> Vec<bool> {
+        let pitch = self.scale as f64 * (1.0 + self.pitch_error);
+        let start = (dx * self.scale as f64).round() as isize;
+
+        let mut modules = Vec::with_capacity(size + 1);
+        let mut current = false;
+        let mut run = 0usize;
+
+        // Runs shorter than half a module are dropped as noise
+        let flush = |color: bool, run: usize, modules: &mut Vec<bool>| {
+            let count = (run as f64 / pitch).round() as usize;
+            modules.extend(core::iter::repeat(color).take(count));
+        };
+
+        // Read from the estimated left edge to the right edge of the symbol
+        for i in 0..(size as f64 * pitch).round() as isize {
+            let px = start + i;
+            let pixel = px >= 0 && (px as usize) < width && bitmap[py * width + px as usize];
+
+            if pixel != current {
+                flush(current, run, &mut modules);
+                current = pixel;
+                run = 0;
+            }
+            run += 1;
+        }
+        flush(current, run, &mut modules);
+
+        modules.resize(size, false);
+        modules
+    }
+
+    /// Returns the ratio of data modules misread over every sampling pass
+    #[must_use]
+    pub fn bit_error_rate(&self, qr: &QRCode) -> f64 {
+        let size = qr.size;
+        let width = size * self.scale;
+        let bitmap = self.rasterize(qr);
+        let pitch = self.scale as f64 * (1.0 + self.pitch_error);
+
+        let mut errors = 0usize;
+        let mut total = 0usize;
+
+        for &(dx, dy) in &self.offsets {
+            for y in 0..size {
+                let py = ((y as f64 + 0.5 + dy) * pitch).round();
+                let py = (py.max(0.0) as usize).min(width - 1);
+                let row = self.scan_row(&bitmap, width, size, py, dx);
+
+                for (x, &read) in row.iter().enumerate() {
+                    let module = qr[y][x];
+                    if module.module_type() != ModuleType::Data {
+                        continue;
+                    }
+                    total += 1;
+                    if read != module.value() {
+                        errors += 1;
+                    }
+                }
+            }
+        }
+
+        if total == 0 {
+            return 0.0;
+        }
+        errors as f64 / total as f64
+    }
+
+    /// Builds `input` once per mask and returns the bit error rate of each,
+    /// most robust first
+    ///
+    /// # Errors
+    /// - `QRCodeError::EncodedData` if `input` is too large to be encoded
+    pub fn mask_report(&self, input: &[u8], ecl: ECL) -> Result<Vec<MaskReport>, QRCodeError> {
+        let mut reports = Vec::with_capacity(Mask::ALL.len());
+
+        for mask in Mask::ALL {
+            let qr = QRBuilder::new(input).ecl(ecl).mask(mask).build()?;
+            reports.push(MaskReport {
+                mask,
+                bit_error_rate: self.bit_error_rate(&qr),
+            });
+        }
+
+        reports.sort_by(|a, b| {
+            a.bit_error_rate
+                .partial_cmp(&b.bit_error_rate)
+                .unwrap_or(core::cmp::Ordering::Equal)
+        });
+        Ok(reports)
+    }
+}
@@ -3,6 +3,7 @@
 
 #![warn(missing_docs)]
 
+use crate::datamasking::{MaskScoring, PenaltyWeights};
 #[cfg(test)]
 use crate::default::transpose;
 use crate::module::{Module, ModuleType};
@@ -12,155 +13,245 @@ use super::hardcode;
 
 #[cfg(test)]
 pub fn test_score_line(l: &[Module]) -> u32 {
-    line(l).1
+    line(&PackedLine::new(l), &PenaltyWeights::SPEC).1
 }
 
 #[cfg(test)]
 pub fn test_score_pattern(l: &[Module]) -> u32 {
-    line(l).0
+    line(&PackedLine::new(l), &PenaltyWeights::SPEC).0
 }
 
 #[cfg(test)]
 pub fn test_matrix_dark_modules(qr: &QRCode) -> u32 {
-    dark_module_score(qr)
+    dark_module_score(qr, &PenaltyWeights::SPEC)
 }
 
 #[cfg(test)]
 pub fn test_matrix_pattern_and_line(qr: &QRCode) -> (u32, u32, u32) {
     let transpose = transpose(qr);
-    matrix_pattern_and_line(qr, &transpose)
+    matrix_pattern_and_line(qr, &transpose, &PenaltyWeights::SPEC)
 }
 
 #[cfg(test)]
 pub fn test_matrix_score_squares(qr: &QRCode) -> u32 {
-    matrix_score_squares(qr)
+    matrix_score_squares(qr, &PenaltyWeights::SPEC)
 }
 
-/// Computes scores for squares, any 2x2 square (black or white)
-/// add 3 to the score
-///
-/// ### Opti:
-/// We don't want to access the 4 squares each time, so we score the left most
-/// ones and only fetch the next right ones
-fn matrix_score_squares(qr: &QRCode) -> u32 {
-    let mut square_score = 0;
-
-    for i in 0..qr.size - 1 {
-        let mut count_data = 2;
+/// Number of `u64` words holding one row of the largest version (177 modules)
+const WORDS: usize = 3;
 
-        let line1 = &qr[i];
-        let line2 = &qr[i + 1];
+/// One row of modules packed in bits, bit `j` of the row is bit `j % 64` of
+/// word `j / 64`
+#[derive(Clone, Copy, Default)]
+struct Bits([u64; WORDS]);
 
-        let mut buffer = 0u8;
-        buffer |= u8::from(line1[0].value()) << 2;
-        buffer |= u8::from(line2[0].value()) << 3;
+impl Bits {
+    const fn and(self, other: Bits) -> Bits {
+        let [a0, a1, a2] = self.0;
+        let [b0, b1, b2] = other.0;
+        Bits([a0 & b0, a1 & b1, a2 & b2])
+    }
 
-        for j in 0..qr.size - 1 {
-            buffer >>= 2;
-            buffer |= u8::from(line1[j + 1].value()) << 2;
-            buffer |= u8::from(line2[j + 1].value()) << 3;
+    /// Bits set where `self` and `other` are equal
+    const fn eq(self, other: Bits) -> Bits {
+        let [a0, a1, a2] = self.0;
+        let [b0, b1, b2] = other.0;
+        Bits([!(a0 ^ b0), !(a1 ^ b1), !(a2 ^ b2)])
+    }
 
-            if line1[j + 1].module_type() != ModuleType::Data
-                || line2[j + 1].module_type() != ModuleType::Data
-            {
-                count_data = 0;
-            }
+    const fn or(self, other: Bits) -> Bits {
+        let [a0, a1, a2] = self.0;
+        let [b0, b1, b2] = other.0;
+        Bits([a0 | b0, a1 | b1, a2 | b2])
+    }
 
-            if count_data >= 2 && (buffer == 0b1111 || buffer == 0b0000) {
-                square_score += 3;
-            }
+    const fn not(self) -> Bits {
+        let [a0, a1, a2] = self.0;
+        Bits([!a0, !a1, !a2])
+    }
 
-            count_data += 1;
+    /// Moves every bit `t` positions up, bit `j` holds the previous bit `j - t`
+    const fn prev(self, t: u32) -> Bits {
+        let [a0, a1, a2] = self.0;
+        if t == 0 {
+            return self;
         }
+        Bits([
+            a0 << t,
+            (a1 << t) | (a0 >> (64 - t)),
+            (a2 << t) | (a1 >> (64 - t)),
+        ])
     }
 
-    square_score
-}
-
-/// Computes scores for both patterns (`0b10111010000` or `0b00001011101`)
-///
-/// ### Opti:
-/// We convert the line to a u11 (supposedly) so comparing it to a pattern is
-/// a simple comparison.
-fn line(line: &[Module]) -> (u32, u32) {
-    const PATTERN_LEN: usize = 7;
-
-    let mut line_score = 0;
-    let mut patt_score = 0;
-
-    let mut count = 1;
-    let mut current = !line[0].value();
-
-    let mut buffer = 0;
-    let mut count_data = 0;
+    /// Moves every bit `t` positions down, bit `j` holds the next bit `j + t`
+    const fn next(self, t: u32) -> Bits {
+        let [a0, a1, a2] = self.0;
+        if t == 0 {
+            return self;
+        }
+        Bits([
+            (a0 >> t) | (a1 << (64 - t)),
+            (a1 >> t) | (a2 << (64 - t)),
+            a2 >> t,
+        ])
+    }
 
-    for &item in line {
-        buffer = ((buffer << 1) | u16::from(item.value())) & 0b111_1111;
-        count_data += 1;
+    const fn count(self) -> u32 {
+        let [a0, a1, a2] = self.0;
+        a0.count_ones() + a1.count_ones() + a2.count_ones()
+    }
 
-        if item.value() != current {
-            if count >= 5 {
-                line_score += count - 2;
+    /// Bits `0..n` set
+    fn first(n: usize) -> Bits {
+        let mut bits = Bits::default();
+        for (i, word) in bits.0.iter_mut().enumerate() {
+            let low = i * 64;
+            if n >= low + 64 {
+                *word = u64::MAX;
+            } else if n > low {
+                *word = (1 << (n - low)) - 1;
             }
-            count = 0;
-            current = item.value();
         }
+        bits
+    }
+}
 
-        if item.module_type() != ModuleType::Data {
-            if count >= 5 {
-                line_score += count - 2;
-            }
-
-            count_data = 0;
-            count = 0;
-            continue;
-        }
+/// A line of modules as packed values and packed data module flags
+#[derive(Clone, Copy, Default)]
+struct PackedLine {
+    values: Bits,
+    data: Bits,
+}
 
-        if count_data >= PATTERN_LEN && buffer == 0b101_1101 {
-            patt_score += 40;
+impl PackedLine {
+    fn new(line: &[Module]) -> Self {
+        let mut packed = PackedLine::default();
+        for (j, module) in line.iter().enumerate() {
+            let bit = 1 << (j % 64);
+            if module.value() {
+                packed.values.0[j / 64] |= bit;
+            }
+            if module.module_type() == ModuleType::Data {
+                packed.data.0[j / 64] |= bit;
+            }
         }
-
-        count += 1;
+        packed
     }
+}
 
-    if count >= 5 {
-        line_score += count - 2;
+/// Computes scores for both patterns (`0b1011101` surrounded by data modules)
+/// and runs of 5 or more data modules of the same color.
+///
+/// ### Opti:
+/// The line is packed in `u64` words, every position is tested at once with
+/// shifts and masks, then counted with `count_ones`.
+fn line(line: &PackedLine, weights: &PenaltyWeights) -> (u32, u32) {
+    const PATTERN: [bool; 7] = [true, false, true, true, true, false, true];
+
+    let PackedLine { values, data } = *line;
+
+    // Position `j` ends a `[TFTTTFT]` pattern made of data modules only
+    let mut pattern = data;
+    for (t, &dark) in PATTERN.iter().rev().enumerate() {
+        let expected = if dark { values } else { values.not() };
+        pattern = pattern
+            .and(expected.prev(t as u32))
+            .and(data.prev(t as u32));
     }
 
-    (patt_score, line_score)
+    // Position `j` is a data module with the same color as the previous one,
+    // a run of `n` modules holds `n - 1` of those
+    let same = data.and(data.prev(1)).and(values.eq(values.prev(1)));
+
+    // A run of `n >= 5` modules scores `runs + n - 5`: `n - 4` windows of 4
+    // `same` bits, plus `runs - 1` for each window starting the run
+    let windows = same.and(same.prev(1)).and(same.prev(2)).and(same.prev(3));
+    let starts = windows.and(windows.prev(1).not());
+
+    let runs = match weights.runs {
+        0 => 0,
+        n => windows
+            .count()
+            .saturating_add(starts.count().saturating_mul(n - 1)),
+    };
+
+    (
+        pattern.count().saturating_mul(weights.finder_patterns),
+        runs,
+    )
 }
 
-/// Converts the matrix to lines & columns and feed it to `score_line`
-fn matrix_pattern_and_line(qr: &QRCode, qr_transpose: &QRCode) -> (u32, u32, u32) {
+/// Converts the matrix to lines & columns and feed it to `line`
+fn matrix_pattern_and_line(
+    qr: &QRCode,
+    qr_transpose: &QRCode,
+    weights: &PenaltyWeights,
+) -> (u32, u32, u32) {
     let mut line_score = 0;
     let mut col_score = 0;
     let mut patt_score = 0;
 
+    for i in 0..qr.size {
+        let l = line(&PackedLine::new(&qr[i]), weights);
+        line_score = l.1.saturating_add(line_score);
+
+        let c = line(&PackedLine::new(&qr_transpose[i]), weights);
+        col_score = c.1.saturating_add(col_score);
+
+        patt_score = l.0.saturating_add(c.0).saturating_add(patt_score);
+    }
+
+    (line_score, col_score, patt_score)
+}
+
+/// Computes scores for squares, any 2x2 square (black or white) made of data
+/// modules add `weights.squares` (3 in the spec) to the score
+///
+/// ### Opti:
+/// Rows are packed in `u64` words, a whole row pair is tested at once
+fn matrix_score_squares(qr: &QRCode, weights: &PenaltyWeights) -> u32 {
     let n = qr.size;
+    let mut square_score = 0;
+
+    // The left-most square only needs its right column to be data
+    let first = Bits::first(1);
+    let columns = Bits::first(n - 1);
 
-    for i in 0..n {
-        let l = line(&qr[i]);
-        line_score += l.1;
+    let mut line2 = PackedLine::new(&qr[0]);
+    for i in 0..n - 1 {
+        let line1 = line2;
+        line2 = PackedLine::new(&qr[i + 1]);
 
-        let c = line(&qr_transpose[i]);
-        col_score += c.1;
+        let uniform = line1.values.eq(line2.values);
+        let uniform = uniform.and(uniform.next(1));
+        let uniform = uniform.and(line1.values.eq(line1.values.next(1)));
 
-        patt_score += l.0 + c.0;
+        let data = line1.data.and(line2.data);
+        let data = data.or(first).and(data.next(1));
+
+        let squares = uniform.and(data).and(columns).count();
+        square_score = squares
+            .saturating_mul(weights.squares)
+            .saturating_add(square_score);
     }
 
-    (line_score, col_score, patt_score)
+    square_score
 }
 
 /// Computes the number of `ModuleType::Dark` modules
-fn dark_module_score(qr: &QRCode) -> u32 {
+fn dark_module_score(qr: &QRCode, weights: &PenaltyWeights) -> u32 {
     let n = qr.size;
+    #[cfg(feature = "simd")]
+    let dark_modules = crate::simd::count_dark(&qr.data[..n * n]);
+    #[cfg(not(feature = "simd"))]
     let dark_modules = qr.data[..n * n]
         .iter()
         .filter(|m| m.value() == Module::DARK)
         .count();
 
     let percent = (dark_modules * 100) / (n * n);
-    u32::from(hardcode::PERCENT_SCORE[percent])
+    // The table holds 10 points per step, the spec weight
+    (u32::from(hardcode::PERCENT_SCORE[percent]) / 10).saturating_mul(weights.balance)
 }
 
 /// Computes the score for the matrix
@@ -170,9 +261,37 @@ fn dark_module_score(qr: &QRCode) -> u32 {
 /// - `matrix_score_squares`: 3 points for each 2x2 square (black or white)
 /// - `dark_module_score`: 10 points for each 5% of dark modules away from 50%
 pub fn score(qr: &QRCode, qr_transpose: &QRCode) -> u32 {
-    let dark_score = dark_module_score(qr);
-    let square_score = matrix_score_squares(qr);
-    let (line_score, col_score, patt_score) = matrix_pattern_and_line(qr, qr_transpose);
+    score_weighted(qr, qr_transpose, &PenaltyWeights::SPEC)
+}
+
+/// Same as [`score`], each rule scaled by `weights`, saturating at `u32::MAX`
+pub fn score_weighted(qr: &QRCode, qr_transpose: &QRCode, weights: &PenaltyWeights) -> u32 {
+    penalties(qr, qr_transpose, weights)
+        .iter()
+        .fold(0, |score: u32, &penalty| score.saturating_add(penalty))
+}
 
-    line_score + patt_score + col_score + dark_score + square_score
+/// Points of each rule summed by [`score_weighted`]: runs, squares, finder
+/// patterns and dark / light balance
+pub fn penalties(qr: &QRCode, qr_transpose: &QRCode, weights: &PenaltyWeights) -> [u32; 4] {
+    let dark_score = dark_module_score(qr, weights);
+    let square_score = matrix_score_squares(qr, weights);
+    let (line_score, col_score, patt_score) = matrix_pattern_and_line(qr, qr_transpose, weights);
+
+    [
+        line_score.saturating_add(col_score),
+        square_score,
+        patt_score,
+        dark_score,
+    ]
+}
+
+/// Computes the score for the matrix using `scoring`
+pub fn score_with(qr: &QRCode, qr_transpose: &QRCode, scoring: MaskScoring) -> u32 {
+    match scoring {
+        MaskScoring::Spec => score(qr, qr_transpose),
+        MaskScoring::DarkRatio => dark_module_score(qr, &PenaltyWeights::SPEC),
+        MaskScoring::Weighted(weights) => score_weighted(qr, qr_transpose, &weights),
+        MaskScoring::Custom(penalty) => penalty(qr),
+    }
 }
@@ -0,0 +1,368 @@
+//! Builds a [`QRCode`] from manually chosen segments, bypassing mode detection.
+//!
+//! Each segment is encoded with its own mode, which keeps digits compact in a
+//! mostly textual payload, or announces a character set with an ECI header.
+//!
+//! ```rust
+//! use fast_qr::segment::{encode_segments, Segment};
+//! use fast_qr::ECL;
+//!
+//! let segments = [
+//!     Segment::alphanumeric("INVOICE ").unwrap(),
+//!     Segment::numeric("0123456789012345").unwrap(),
+//!     Segment::eci(26).unwrap(), // UTF-8
+//!     Segment::bytes("Ünïcödé".as_bytes()),
+//! ];
+//!
+//! let qrcode = encode_segments(&segments, ECL::M).unwrap();
+//! ```
+
+#![deny(unsafe_code)]
+#![warn(missing_docs)]
+
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+
+use crate::compact::CompactQR;
+use crate::compat::PadCodewords;
+use crate::encode::{self, InvalidCharacter, Mode};
+use crate::placement::{self, MatrixOptions};
+use crate::qr::{Overflow, QRCodeError};
+use crate::{hardcode, QRCode, Version, ECL};
+
+/// Largest ECI assignment number, encoded on 24 bits (section 7.4.2.2)
+const MAX_ECI: u32 = 999_999;
+
+/// Largest number of codes in a structured append sequence (section 8)
+const MAX_PARTS: u8 = 16;
+
+/// Contains the different errors when a segment could not be created
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SegmentError {
+    /// Numeric segments only hold `0-9`
+    InvalidNumeric,
+    /// Alphanumeric segments only hold `0-9`, `A-Z`, space and `$%*+-./:`
+    InvalidAlphanumeric,
+    /// Kanji segments only hold double-byte Shift JIS characters
+    InvalidKanji,
+    /// ECI assignment numbers go from 0 to 999999
+    InvalidEci,
+    /// Structured append sequences hold 1 to 16 codes, indexed from 0
+    InvalidStructuredAppend,
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SegmentError {}
+
+impl Display for SegmentError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SegmentError::InvalidNumeric => f.write_str("Numeric segments only hold digits"),
+            SegmentError::InvalidAlphanumeric => {
+                f.write_str("Character not supported in alphanumeric segments")
+            }
+            SegmentError::InvalidKanji => {
+                f.write_str("Kanji segments only hold double-byte Shift JIS characters")
+            }
+            SegmentError::InvalidEci => f.write_str("ECI assignment number above 999999"),
+            SegmentError::InvalidStructuredAppend => {
+                f.write_str("Structured append index out of a sequence of 1 to 16 codes")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    /// Kanji data is kept as Shift JIS
+    Data(Mode),
+    Eci(u32),
+    StructuredAppend {
+        index: u8,
+        total: u8,
+        parity: u8,
+    },
+}
+
+/// A run of data encoded with a single mode, or an ECI header
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Segment {
+    kind: Kind,
+    data: Vec<u8>,
+}
+
+impl Segment {
+    /// Creates a numeric segment, 10 bits every 3 digits
+    ///
+    /// # Errors
+    /// - `SegmentError::InvalidNumeric` if `digits` holds anything but `0-9`
+    pub fn numeric(digits: &str) -> Result<Self, SegmentError> {
+        if !digits.bytes().all(|c| c.is_ascii_digit()) {
+            return Err(SegmentError::InvalidNumeric);
+        }
+
+        Ok(Segment {
+            kind: Kind::Data(Mode::Numeric),
+            data: digits.as_bytes().to_vec(),
+        })
+    }
+
+    /// Creates an alphanumeric segment, 11 bits every 2 characters
+    ///
+    /// # Errors
+    /// - `SegmentError::InvalidAlphanumeric` if `text` holds a character outside
+    ///   `0-9`, `A-Z`, space and `$%*+-./:`
+    pub fn alphanumeric(text: &str) -> Result<Self, SegmentError> {
+        if !text.bytes().all(encode::is_qr_alphanumeric) {
+            return Err(SegmentError::InvalidAlphanumeric);
+        }
+
+        Ok(Segment {
+            kind: Kind::Data(Mode::Alphanumeric),
+            data: text.as_bytes().to_vec(),
+        })
+    }
+
+    /// Creates a byte segment, 8 bits per byte
+    #[must_use]
+    pub fn bytes(bytes: &[u8]) -> Self {
+        Segment {
+            kind: Kind::Data(Mode::Byte),
+            data: bytes.to_vec(),
+        }
+    }
+
+    /// Creates a Kanji segment from Shift JIS bytes, 13 bits per character
+    ///
+    /// # Errors
+    /// - `SegmentError::InvalidKanji` if `sjis` is not a sequence of
+    ///   double-byte characters in `0x8140..=0x9FFC` or `0xE040..=0xEBBF`
+    pub fn kanji_sjis(sjis: &[u8]) -> Result<Self, SegmentError> {
+        if !encode::is_kanji(sjis) {
+            return Err(SegmentError::InvalidKanji);
+        }
+
+        Ok(Segment {
+            kind: Kind::Data(Mode::Kanji),
+            data: sjis.to_vec(),
+        })
+    }
+
+    /// Creates a Kanji segment, converting `text` to Shift JIS
+    ///
+    /// # Errors
+    /// - `SegmentError::InvalidKanji` if a character has no double-byte
+    ///   Shift JIS equivalent
+    #[cfg(feature = "kanji")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "kanji")))]
+    pub fn kanji(text: &str) -> Result<Self, SegmentError> {
+        let (sjis, _, had_errors) = encoding_rs::SHIFT_JIS.encode(text);
+        if had_errors {
+            return Err(SegmentError::InvalidKanji);
+        }
+
+        Segment::kanji_sjis(&sjis)
+    }
+
+    /// Creates an ECI header, changing how the following byte segments are
+    /// interpreted (i.e. `26` for UTF-8, `20` for Shift JIS)
+    ///
+    /// # Errors
+    /// - `SegmentError::InvalidEci` if `assignment` is above 999999
+    pub fn eci(assignment: u32) -> Result<Self, SegmentError> {
+        if assignment > MAX_ECI {
+            return Err(SegmentError::InvalidEci);
+        }
+
+        Ok(Segment {
+            kind: Kind::Eci(assignment),
+            data: Vec::new(),
+        })
+    }
+
+    /// Creates a structured append header, which must be the first segment:
+    /// the code is part `index` (from 0) of `total` codes whose data
+    /// together has `parity`, see [`structured_append::parity`](crate::structured_append::parity)
+    ///
+    /// # Errors
+    /// - `SegmentError::InvalidStructuredAppend` if `total` is not 1 to 16 or
+    ///   `index` is not below it
+    pub fn structured_append(index: u8, total: u8, parity: u8) -> Result<Self, SegmentError> {
+        if total == 0 || total > MAX_PARTS || index >= total {
+            return Err(SegmentError::InvalidStructuredAppend);
+        }
+
+        Ok(Segment {
+            kind: Kind::StructuredAppend {
+                index,
+                total,
+                parity,
+            },
+            data: Vec::new(),
+        })
+    }
+
+    /// Returns the number of characters, as written in the character count indicator
+    fn char_count(&self) -> usize {
+        match self.kind {
+            Kind::Data(Mode::Kanji) => self.data.len() / 2,
+            Kind::Data(_) => self.data.len(),
+            Kind::Eci(_) | Kind::StructuredAppend { .. } => 0,
+        }
+    }
+
+    /// Returns the character count indicator length, `None` for headers
+    fn cci_bits(&self, version: Version) -> Option<usize> {
+        match self.kind {
+            Kind::Data(mode) => Some(hardcode::cci_bits(version, mode)),
+            Kind::Eci(_) | Kind::StructuredAppend { .. } => None,
+        }
+    }
+
+    /// Returns the number of bits of the segment in `version`, even if its
+    /// character count does not fit the indicator
+    fn encoded_bits(&self, version: Version) -> usize {
+        let n = self.char_count();
+
+        let data_bits = match self.kind {
+            Kind::Data(Mode::Numeric) => n / 3 * 10 + [0, 4, 7][n % 3],
+            Kind::Data(Mode::Alphanumeric) => n / 2 * 11 + n % 2 * 6,
+            Kind::Data(Mode::Byte) => n * 8,
+            Kind::Data(Mode::Kanji) => n * 13,
+            Kind::Eci(assignment) => match assignment {
+                0..=127 => 8,
+                128..=16_383 => 16,
+                _ => 24,
+            },
+            Kind::StructuredAppend { .. } => 4 + 4 + 8,
+        };
+
+        4 + self.cci_bits(version).unwrap_or(0) + data_bits
+    }
+
+    /// Returns the number of bits of the segment in `version`, `None` if its
+    /// character count does not fit the indicator
+    fn bit_len(&self, version: Version) -> Option<usize> {
+        match self.cci_bits(version) {
+            Some(cci_bits) if self.char_count() >> cci_bits != 0 => None,
+            _ => Some(self.encoded_bits(version)),
+        }
+    }
+
+    fn push(&self, compact: &mut CompactQR, version: Version) -> Result<(), InvalidCharacter> {
+        match self.kind {
+            Kind::Data(mode) => {
+                let cci_bits = hardcode::cci_bits(version, mode);
+                match mode {
+                    Mode::Numeric => encode::encode_numeric(compact, &self.data, cci_bits)?,
+                    Mode::Alphanumeric => {
+                        encode::encode_alphanumeric(compact, &self.data, cci_bits)?;
+                    }
+                    Mode::Byte => encode::encode_byte(compact, &self.data, cci_bits),
+                    Mode::Kanji => encode::encode_kanji(compact, &self.data, cci_bits)?,
+                }
+            }
+            Kind::Eci(assignment) => {
+                compact.push_bits(0b0111, 4);
+                let assignment = assignment as usize;
+                match assignment {
+                    0..=127 => compact.push_bits(assignment, 8),
+                    128..=16_383 => compact.push_bits(0b10 << 14 | assignment, 16),
+                    _ => compact.push_bits(0b110 << 21 | assignment, 24),
+                }
+            }
+            Kind::StructuredAppend {
+                index,
+                total,
+                parity,
+            } => {
+                compact.push_bits(0b0011, 4);
+                compact.push_bits(usize::from(index), 4);
+                compact.push_bits(usize::from(total - 1), 4);
+                compact.push_bits(usize::from(parity), 8);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the number of data bits `segments` take in `version`, headers
+/// included but without the terminator and padding. Only the segment
+/// lengths are read, so it is cheap enough to run on every keystroke.
+///
+/// The segments fit when the result is at most
+/// [`Version::data_bits`], and no segment holds more characters than its
+/// character count indicator allows in `version` (i.e. 1023 digits in
+/// version 1 to 9).
+///
+/// ```rust
+/// use fast_qr::segment::{estimate_bits, Segment};
+/// use fast_qr::{Version, ECL};
+///
+/// let segments = [
+///     Segment::alphanumeric("INVOICE ").unwrap(),
+///     Segment::numeric("0123456789012345").unwrap(),
+/// ];
+///
+/// // 4 + 9 + 44 bits, then 4 + 10 + 54 bits
+/// assert_eq!(estimate_bits(&segments, Version::V01), 125);
+/// assert!(estimate_bits(&segments, Version::V01) <= Version::V01.data_bits(ECL::M));
+/// ```
+#[must_use]
+pub fn estimate_bits(segments: &[Segment], version: Version) -> usize {
+    segments
+        .iter()
+        .map(|segment| segment.encoded_bits(version))
+        .sum()
+}
+
+/// Encodes `segments` in order, in the smallest version that fits them
+///
+/// # Errors
+/// - `QRCodeError::EncodedData` if the segments do not fit in a version 40
+///   `QRCode`, the overflow is measured as the byte mode length taking as
+///   many bits as every segment together
+/// - `QRCodeError::InvalidMode` if a segment holds characters its mode cannot
+///   encode, which the constructors of [`Segment`] rule out
+pub fn encode_segments(segments: &[Segment], ecl: ECL) -> Result<QRCode, QRCodeError> {
+    let version = Version::iter()
+        .find(|&version| {
+            let bits = segments
+                .iter()
+                .map(|segment| segment.bit_len(version))
+                .sum::<Option<usize>>();
+            bits.map_or(false, |bits| bits <= hardcode::data_bits(version, ecl))
+        })
+        .ok_or_else(|| {
+            let bits = estimate_bits(segments, Version::V40);
+            let header = 4 + hardcode::cci_bits(Version::V40, Mode::Byte);
+            let len = (bits.saturating_sub(header) + 7) / 8;
+            QRCodeError::EncodedData(Overflow::new(Mode::Byte, ecl, len))
+        })?;
+
+    let mut compact = CompactQR::from_version(version);
+    for segment in segments {
+        segment
+            .push(&mut compact, version)
+            .map_err(QRCodeError::InvalidMode)?;
+    }
+
+    let data_bits = hardcode::data_bits(version, ecl);
+    debug_assert!(
+        compact.len() <= data_bits,
+        "{} bits encoded for a capacity of {data_bits}",
+        compact.len()
+    );
+    encode::add_terminator(&mut compact, data_bits);
+    encode::pad_to_8(&mut compact);
+    compact.fill(PadCodewords::Alternating);
+
+    let mut mask = None;
+    Ok(placement::create_matrix_from_codewords(
+        &compact,
+        ecl,
+        version,
+        &mut mask,
+        MatrixOptions::default(),
+    ))
+}
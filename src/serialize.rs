@@ -0,0 +1,107 @@
+//! `serde` support for [`QRCode`] and [`QRMatrix`], behind the `serde` feature
+//!
+//! Modules are stored packed as in [`QRMatrix::as_bytes`], two per byte with
+//! their [`ModuleType`](crate::ModuleType), so that a rehydrated code renders
+//! exactly like the original one. [`ECL`], [`Version`], [`Mask`], [`Mode`] and
+//! the [`QRBuilder`](crate::QRBuilder) options derive their implementations.
+//!
+//! ```rust
+//! use fast_qr::{QRBuilder, QRCode};
+//!
+//! let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+//!
+//! let json = serde_json::to_string(&qrcode).unwrap();
+//! let cached: QRCode = serde_json::from_str(&json).unwrap();
+//! assert_eq!(cached.to_str(), qrcode.to_str());
+//! ```
+
+#![deny(unsafe_code)]
+#![warn(missing_docs)]
+
+use alloc::vec::Vec;
+
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{Mask, Mode, QRCode, QRMatrix, Version, ECL};
+
+/// Largest side of a `QRCode`, version 40
+const MAX_SIZE: usize = 177;
+
+/// Serialized form of a [`QRCode`]
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "QRCode")]
+struct PackedCode {
+    size: usize,
+    version: Option<Version>,
+    ecl: Option<ECL>,
+    mask: Option<Mask>,
+    mode: Option<Mode>,
+    modules: Vec<u8>,
+}
+
+/// Serialized form of a [`QRMatrix`]
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "QRMatrix")]
+struct PackedMatrix {
+    size: usize,
+    modules: Vec<u8>,
+}
+
+/// Rebuilds the matrix, rejecting sizes no `QRCode` can have
+fn unpack<E: Error>(size: usize, modules: Vec<u8>) -> Result<QRMatrix, E> {
+    if size > MAX_SIZE {
+        return Err(E::custom(format_args!("size {size} is over {MAX_SIZE}")));
+    }
+
+    QRMatrix::from_packed(size, modules)
+        .ok_or_else(|| E::custom(format_args!("modules do not fill a {size}x{size} matrix")))
+}
+
+impl Serialize for QRCode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        PackedCode {
+            size: self.size,
+            version: self.version,
+            ecl: self.ecl,
+            mask: self.mask,
+            mode: self.mode,
+            modules: QRMatrix::from(self).as_bytes().to_vec(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for QRCode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let packed = PackedCode::deserialize(deserializer)?;
+        let matrix = unpack::<D::Error>(packed.size, packed.modules)?;
+
+        let mut qr = QRCode::default(packed.size);
+        for (i, module) in matrix.iter().enumerate() {
+            qr[i / packed.size][i % packed.size] = module;
+        }
+        qr.version = packed.version;
+        qr.ecl = packed.ecl;
+        qr.mask = packed.mask;
+        qr.mode = packed.mode;
+        Ok(qr)
+    }
+}
+
+impl Serialize for QRMatrix {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        PackedMatrix {
+            size: crate::matrix::Matrix::size(self),
+            modules: self.as_bytes().to_vec(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for QRMatrix {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let packed = PackedMatrix::deserialize(deserializer)?;
+        unpack(packed.size, packed.modules)
+    }
+}
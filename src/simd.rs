@@ -0,0 +1,209 @@
+//! Runtime selection of the vectorized code paths.
+//!
+//! **Unstable**: this module is behind the opt-in `simd` feature, the set of
+//! accelerated routines and this API may change in any minor release.
+//!
+//! The best implementation supported by the running CPU is detected on first
+//! use, a single binary can then be shipped to machines with and without
+//! AVX2. [`force`] overrides the choice, i.e. to benchmark or to rule out the
+//! vectorized paths while debugging.
+//!
+//! ```rust
+//! use fast_qr::simd::{self, Implementation};
+//!
+//! println!("Using {:?}", simd::active());
+//!
+//! simd::force(Implementation::Scalar).unwrap();
+//! assert_eq!(simd::active(), Implementation::Scalar);
+//! simd::reset();
+//! ```
+
+#![warn(missing_docs)]
+
+use core::fmt::{Display, Formatter};
+use core::sync::atomic::{AtomicU8, Ordering};
+
+use crate::Module;
+
+/// Code path used by the accelerated routines
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Implementation {
+    /// Portable code, always available
+    Scalar,
+    /// x86 / x86_64 with AVX2
+    Avx2,
+    /// aarch64 with NEON
+    Neon,
+}
+
+/// Error returned by [`force`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimdError {
+    /// The running CPU does not support the requested implementation
+    Unsupported(Implementation),
+}
+
+impl std::error::Error for SimdError {}
+
+impl Display for SimdError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SimdError::Unsupported(implementation) => {
+                write!(f, "{implementation:?} is not supported by this CPU")
+            }
+        }
+    }
+}
+
+/// 0 until the first detection, then `Implementation as u8 + 1`
+static ACTIVE: AtomicU8 = AtomicU8::new(0);
+
+impl Implementation {
+    const fn from_u8(value: u8) -> Self {
+        match value {
+            2 => Implementation::Avx2,
+            3 => Implementation::Neon,
+            _ => Implementation::Scalar,
+        }
+    }
+
+    /// Returns true if the running CPU can execute this implementation
+    #[must_use]
+    pub fn is_supported(self) -> bool {
+        match self {
+            Implementation::Scalar => true,
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Implementation::Avx2 => std::is_x86_feature_detected!("avx2"),
+            // NEON is part of the aarch64 baseline
+            #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+            Implementation::Neon => true,
+            #[allow(unreachable_patterns)]
+            _ => false,
+        }
+    }
+}
+
+/// Returns the fastest implementation supported by the running CPU
+#[must_use]
+pub fn detected() -> Implementation {
+    [Implementation::Avx2, Implementation::Neon]
+        .into_iter()
+        .find(|implementation| implementation.is_supported())
+        .unwrap_or(Implementation::Scalar)
+}
+
+/// Returns the implementation currently in use, detecting it on first call
+#[must_use]
+pub fn active() -> Implementation {
+    match ACTIVE.load(Ordering::Relaxed) {
+        0 => {
+            let implementation = detected();
+            ACTIVE.store(implementation as u8 + 1, Ordering::Relaxed);
+            implementation
+        }
+        value => Implementation::from_u8(value),
+    }
+}
+
+/// Uses `implementation` for every following call, process-wide
+///
+/// # Errors
+/// - `SimdError::Unsupported` if the running CPU lacks the required instructions
+pub fn force(implementation: Implementation) -> Result<(), SimdError> {
+    if !implementation.is_supported() {
+        return Err(SimdError::Unsupported(implementation));
+    }
+
+    ACTIVE.store(implementation as u8 + 1, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Drops any forced implementation, the next call detects it again
+pub fn reset() {
+    ACTIVE.store(0, Ordering::Relaxed);
+}
+
+/// Returns the number of dark modules in `modules`
+pub(crate) fn count_dark(modules: &[Module]) -> usize {
+    match active() {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        #[allow(unsafe_code)]
+        // SAFETY: `force` and `detected` only select AVX2 when it is available
+        Implementation::Avx2 => unsafe { avx2::count_dark(modules) },
+        #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+        #[allow(unsafe_code)]
+        // SAFETY: NEON is enabled at compile time
+        Implementation::Neon => unsafe { neon::count_dark(modules) },
+        _ => count_dark_scalar(modules),
+    }
+}
+
+fn count_dark_scalar(modules: &[Module]) -> usize {
+    modules.iter().filter(|m| m.value() == Module::DARK).count()
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[allow(unsafe_code)]
+mod avx2 {
+    #[cfg(target_arch = "x86")]
+    use core::arch::x86::{
+        __m256i, _mm256_add_epi64, _mm256_and_si256, _mm256_loadu_si256, _mm256_sad_epu8,
+        _mm256_set1_epi8, _mm256_setzero_si256, _mm256_storeu_si256,
+    };
+    #[cfg(target_arch = "x86_64")]
+    use core::arch::x86_64::{
+        __m256i, _mm256_add_epi64, _mm256_and_si256, _mm256_loadu_si256, _mm256_sad_epu8,
+        _mm256_set1_epi8, _mm256_setzero_si256, _mm256_storeu_si256,
+    };
+
+    use crate::Module;
+
+    /// Sums the value bit of 32 modules at a time
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn count_dark(modules: &[Module]) -> usize {
+        // `Module` is a transparent `u8`
+        let bytes = core::slice::from_raw_parts(modules.as_ptr().cast::<u8>(), modules.len());
+        let chunks = bytes.chunks_exact(32);
+        let remainder = chunks.remainder();
+
+        let ones = _mm256_set1_epi8(1);
+        let zero = _mm256_setzero_si256();
+        let mut sums = zero;
+        for chunk in chunks {
+            let values = _mm256_loadu_si256(chunk.as_ptr().cast::<__m256i>());
+            let values = _mm256_and_si256(values, ones);
+            sums = _mm256_add_epi64(sums, _mm256_sad_epu8(values, zero));
+        }
+
+        let mut lanes = [0_u64; 4];
+        _mm256_storeu_si256(lanes.as_mut_ptr().cast::<__m256i>(), sums);
+        let tail = remainder.iter().filter(|&&b| b & 1 == 1).count();
+
+        lanes.iter().sum::<u64>() as usize + tail
+    }
+}
+
+#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+#[allow(unsafe_code)]
+mod neon {
+    use core::arch::aarch64::{vaddlvq_u8, vandq_u8, vdupq_n_u8, vld1q_u8};
+
+    use crate::Module;
+
+    /// Sums the value bit of 16 modules at a time
+    pub(super) unsafe fn count_dark(modules: &[Module]) -> usize {
+        // `Module` is a transparent `u8`
+        let bytes = core::slice::from_raw_parts(modules.as_ptr().cast::<u8>(), modules.len());
+        let chunks = bytes.chunks_exact(16);
+        let remainder = chunks.remainder();
+
+        let ones = vdupq_n_u8(1);
+        let mut count = 0;
+        for chunk in chunks {
+            let values = vandq_u8(vld1q_u8(chunk.as_ptr()), ones);
+            count += usize::from(vaddlvq_u8(values));
+        }
+
+        count + remainder.iter().filter(|&&b| b & 1 == 1).count()
+    }
+}
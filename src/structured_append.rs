@@ -0,0 +1,171 @@
+//! Checks the parts of a structured append sequence (section 8)
+//!
+//! A payload too long for one code can be split over up to 16 codes, each
+//! starting with a [`Segment::structured_append`](crate::segment::Segment::structured_append)
+//! header: its index, the number of codes and the parity of the whole payload.
+//! Readers only reassemble parts whose headers agree, [`validate`] checks it
+//! the same way so multi-part pipelines can be unit tested.
+//!
+//! ```rust
+//! use fast_qr::segment::{encode_segments, Segment};
+//! use fast_qr::structured_append::{self, parity};
+//! use fast_qr::ECL;
+//!
+//! let data = b"a payload split over two codes";
+//! let (first, second) = data.split_at(10);
+//! let parts = [first, second]
+//!     .iter()
+//!     .enumerate()
+//!     .map(|(i, part)| {
+//!         let header = Segment::structured_append(i as u8, 2, parity(data)).unwrap();
+//!         encode_segments(&[header, Segment::bytes(part)], ECL::M).unwrap()
+//!     })
+//!     .collect::<Vec<_>>();
+//!
+//! assert_eq!(structured_append::validate(&parts).unwrap(), data);
+//! ```
+
+#![deny(unsafe_code)]
+#![warn(missing_docs)]
+
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+
+use crate::decode::{self, DecodeError};
+use crate::matrix::Matrix;
+
+/// Structured append header of a code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Header {
+    /// Position of the code in the sequence, from 0
+    pub index: u8,
+    /// Number of codes in the sequence, from 1 to 16
+    pub total: u8,
+    /// Parity of the whole payload, see [`parity`]
+    pub parity: u8,
+}
+
+/// Returns the parity of `data`, every byte XORed together. Kanji is
+/// counted as its Shift JIS bytes.
+#[must_use]
+pub fn parity(data: &[u8]) -> u8 {
+    data.iter().fold(0, |parity, byte| parity ^ byte)
+}
+
+/// Contains the different errors when parts do not form a sequence
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StructuredAppendError {
+    /// No part to check
+    Empty,
+    /// A part cannot be decoded
+    Decode(usize, DecodeError),
+    /// A part has no structured append header
+    MissingHeader(usize),
+    /// A part announces another number of codes than the first part, or an
+    /// index past it
+    Total(usize),
+    /// A part announces another parity than the first part
+    Parity(usize),
+    /// Two parts have the same index
+    DuplicateIndex(u8),
+    /// No part has this index
+    MissingIndex(u8),
+    /// The parity of the reassembled payload is not the announced one
+    Checksum {
+        /// Parity in the headers
+        expected: u8,
+        /// Parity of the decoded data
+        found: u8,
+    },
+}
+
+impl std::error::Error for StructuredAppendError {}
+
+impl Display for StructuredAppendError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            StructuredAppendError::Empty => f.write_str("No part to check"),
+            StructuredAppendError::Decode(part, error) => write!(f, "Part {part}: {error}"),
+            StructuredAppendError::MissingHeader(part) => {
+                write!(f, "Part {part} has no structured append header")
+            }
+            StructuredAppendError::Total(part) => {
+                write!(f, "Part {part} belongs to a sequence of another length")
+            }
+            StructuredAppendError::Parity(part) => {
+                write!(f, "Part {part} announces another parity")
+            }
+            StructuredAppendError::DuplicateIndex(index) => {
+                write!(f, "Index {index} appears more than once")
+            }
+            StructuredAppendError::MissingIndex(index) => write!(f, "Index {index} is missing"),
+            StructuredAppendError::Checksum { expected, found } => write!(
+                f,
+                "Payload parity is {found:#04x}, headers announce {expected:#04x}"
+            ),
+        }
+    }
+}
+
+/// Decodes `parts` and checks they form a whole sequence: every header
+/// announces the same number of codes and parity, each index appears exactly
+/// once, and the parity of the payload matches. Parts may come in any order.
+///
+/// Returns the payload, reassembled in index order.
+///
+/// # Errors
+/// - `StructuredAppendError::Empty` if `parts` is empty
+/// - `StructuredAppendError::Decode` / `StructuredAppendError::MissingHeader`
+///   if a part cannot be read as a member of a sequence, with its position in `parts`
+/// - `StructuredAppendError::Total` / `StructuredAppendError::Parity` if a
+///   header disagrees with the first one
+/// - `StructuredAppendError::DuplicateIndex` / `StructuredAppendError::MissingIndex`
+///   if the indices do not go from 0 to the number of codes
+/// - `StructuredAppendError::Checksum` if the payload parity is not the announced one
+pub fn validate<M: Matrix>(parts: &[M]) -> Result<Vec<u8>, StructuredAppendError> {
+    let mut decoded = Vec::with_capacity(parts.len());
+    for (part, matrix) in parts.iter().enumerate() {
+        let result =
+            decode::decode(matrix).map_err(|error| StructuredAppendError::Decode(part, error))?;
+        let header = result
+            .structured_append
+            .ok_or(StructuredAppendError::MissingHeader(part))?;
+        decoded.push((header, result.data));
+    }
+
+    let first = match decoded.first() {
+        Some((header, _)) => *header,
+        None => return Err(StructuredAppendError::Empty),
+    };
+
+    let mut ordered: Vec<Option<&[u8]>> = vec![None; usize::from(first.total)];
+    for (part, (header, data)) in decoded.iter().enumerate() {
+        if header.total != first.total || header.index >= first.total {
+            return Err(StructuredAppendError::Total(part));
+        }
+        if header.parity != first.parity {
+            return Err(StructuredAppendError::Parity(part));
+        }
+        let slot = &mut ordered[usize::from(header.index)];
+        if slot.is_some() {
+            return Err(StructuredAppendError::DuplicateIndex(header.index));
+        }
+        *slot = Some(data);
+    }
+
+    let mut payload = Vec::new();
+    for (index, data) in ordered.into_iter().enumerate() {
+        let data = data.ok_or(StructuredAppendError::MissingIndex(index as u8))?;
+        payload.extend_from_slice(data);
+    }
+
+    let found = parity(&payload);
+    if found != first.parity {
+        return Err(StructuredAppendError::Checksum {
+            expected: first.parity,
+            found,
+        });
+    }
+
+    Ok(payload)
+}
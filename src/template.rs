@@ -0,0 +1,177 @@
+//! Encodes many payloads sharing one configuration
+
+#![deny(unsafe_code)]
+#![warn(missing_docs)]
+
+use crate::compat::PadCodewords;
+use crate::datamasking::Mask;
+use crate::encode::{self, Mode};
+use crate::qr::{check_len, Overflow, QRCodeError};
+use crate::{datamasking, default, placement, polynomials, QRCode, Version, ECL};
+use alloc::vec::Vec;
+
+/// Precomputed matrix for a fixed mode, version, ECL and mask, to encode
+/// large runs of similar payloads (serial numbers, tickets, ...).
+///
+/// Function patterns, format and version information are placed once, along
+/// with the position and mask bit of every data module. Building a code then
+/// only encodes the data, computes the error correction and writes the data
+/// modules, skipping the mask evaluation of [`QRBuilder`](crate::QRBuilder).
+///
+/// Codes are bitwise identical to the ones of a `QRBuilder` given the same
+/// mode, version, ECL and mask.
+///
+/// # Example
+/// ```rust
+/// use fast_qr::{Mask, Mode, QRBuilder, Template, Version, ECL};
+///
+/// let template = Template::new(Mode::Numeric, Version::V02, ECL::M, Mask::Checkerboard);
+///
+/// for id in 10_000_000_000_000_000_000u128..10_000_000_000_000_000_010 {
+///     let id = id.to_string();
+///     let qrcode = template.build(&id).unwrap();
+///
+///     let expected = QRBuilder::new(id.as_str())
+///         .mode(Mode::Numeric)
+///         .version(Version::V02)
+///         .ecl(ECL::M)
+///         .mask(Mask::Checkerboard)
+///         .build()
+///         .unwrap();
+///     assert_eq!(qrcode.data, expected.data);
+/// }
+/// ```
+#[derive(Clone)]
+pub struct Template {
+    mode: Mode,
+    version: Version,
+    ecl: ECL,
+    mask: Mask,
+    /// Function patterns and format information, data modules light
+    base: QRCode,
+    /// Index in `data` and mask bit of each data module, in placement order
+    modules: Vec<(usize, bool)>,
+}
+
+impl Template {
+    /// Precomputes the matrix of codes in `mode`, `version`, `ecl` and `mask`
+    #[must_use]
+    pub fn new(mode: Mode, version: Version, ecl: ECL, mask: Mask) -> Template {
+        let mut base = default::create_matrix(version);
+        default::create_matrix_format_info(&mut base, ecl, mask);
+        base.version = Some(version);
+        base.ecl = Some(ecl);
+        base.mask = Some(mask);
+        base.mode = Some(mode);
+
+        // Data modules are all light, masking them leaves the mask pattern
+        let mut pattern = base.clone();
+        datamasking::mask(&mut pattern, mask);
+
+        let size = base.size;
+        let modules = placement::data_positions(version)
+            .into_iter()
+            .map(|(y, x)| (y * size + x, pattern[y][x].value()))
+            .collect();
+
+        Template {
+            mode,
+            version,
+            ecl,
+            mask,
+            base,
+            modules,
+        }
+    }
+
+    /// Returns the mode of the codes
+    #[must_use]
+    pub const fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// Returns the version of the codes
+    #[must_use]
+    pub const fn version(&self) -> Version {
+        self.version
+    }
+
+    /// Returns the error correction level of the codes
+    #[must_use]
+    pub const fn ecl(&self) -> ECL {
+        self.ecl
+    }
+
+    /// Returns the mask of the codes
+    #[must_use]
+    pub const fn mask(&self) -> Mask {
+        self.mask
+    }
+
+    /// Computes the [`QRCode`] of `input`, padded to the version of the
+    /// template. Text is converted to Shift JIS in Kanji mode, like with
+    /// [`QRBuilder`](crate::QRBuilder).
+    ///
+    /// # Errors
+    /// - `QRCodeError::EmptyPayload` if `input` is empty
+    /// - `QRCodeError::InvalidMode` if the mode cannot encode `input`
+    /// - `QRCodeError::EncodedData` if `input` is too large to be encoded
+    /// - `QRCodeError::SpecifiedVersion` if the version is too small to contain `input`
+    pub fn build<I: AsRef<[u8]>>(&self, input: I) -> Result<QRCode, QRCodeError> {
+        let input = input.as_ref();
+        if input.is_empty() {
+            return Err(QRCodeError::EmptyPayload);
+        }
+
+        // UTF-8 text is never shorter than its Shift JIS form, so oversized
+        // input is rejected before being converted
+        let mode = Some(self.mode).filter(|&mode| mode != Mode::Kanji);
+        check_len(input.len(), mode, self.ecl)?;
+        let sjis = match self.mode {
+            Mode::Kanji => encode::kanji_from_utf8(input),
+            _ => None,
+        };
+        let input = sjis.as_deref().unwrap_or(input);
+        check_len(input.len(), Some(self.mode), self.ecl)?;
+        self.mode
+            .validate(input)
+            .map_err(QRCodeError::InvalidMode)?;
+
+        match Version::get(self.mode, self.ecl, input.len()) {
+            Some(version) if version <= self.version => {}
+            Some(_) => return Err(QRCodeError::SpecifiedVersion),
+            None => {
+                return Err(QRCodeError::EncodedData(Overflow::new(
+                    self.mode,
+                    self.ecl,
+                    input.len(),
+                )))
+            }
+        }
+
+        let data_codewords = encode::encode(
+            input,
+            self.ecl,
+            self.mode,
+            self.version,
+            PadCodewords::Alternating,
+        )
+        .map_err(QRCodeError::InvalidMode)?;
+        #[cfg_attr(not(feature = "zeroize"), allow(unused_mut))]
+        let mut structure =
+            polynomials::structure(data_codewords.get_data(), self.ecl, self.version);
+
+        // Remainder bits are past the last codeword, where `structure` is 0
+        let mut qr = self.base.clone();
+        for (i, &(index, mask)) in self.modules.iter().enumerate() {
+            let bit = structure[i / 8] & (0x80 >> (i % 8)) != 0;
+            qr.data[index].set(bit != mask);
+        }
+
+        // `CompactQR` wipes itself on drop
+        #[cfg(feature = "zeroize")]
+        zeroize::Zeroize::zeroize(&mut structure[..]);
+
+        Ok(qr)
+    }
+}
@@ -0,0 +1,111 @@
+use crate::convert::animation::{AnimationBuilder, AnimationError, AnimationFormat};
+use crate::matrix::Matrix;
+use crate::{QRBuilder, QRCode, Version};
+
+fn gif_frames(gif: &[u8]) -> Vec<(u16, Vec<u8>)> {
+    let mut options = gif::DecodeOptions::new();
+    options.set_color_output(gif::ColorOutput::Indexed);
+    let mut decoder = options.read_info(gif).unwrap();
+
+    let mut frames = Vec::new();
+    while let Some(frame) = decoder.read_next_frame().unwrap() {
+        frames.push((frame.delay, frame.buffer.to_vec()));
+    }
+    frames
+}
+
+/// Palette index of the center of every module, margin excluded
+fn modules(pixels: &[u8], n: usize, margin: usize, module_size: usize) -> Vec<bool> {
+    let width = (n + margin * 2) * module_size;
+    let center = |i: usize| (i + margin) * module_size + module_size / 2;
+    (0..n * n)
+        .map(|i| pixels[center(i / n) * width + center(i % n)] == 1)
+        .collect()
+}
+
+fn values<M: Matrix>(qr: &M) -> Vec<bool> {
+    let n = qr.size();
+    (0..n * n)
+        .map(|i| qr.module(i / n, i % n).value())
+        .collect()
+}
+
+#[test]
+fn animation_cycles_codes() {
+    let small = QRBuilder::new("1").version(Version::V01).build().unwrap();
+    let large = QRBuilder::new("2").version(Version::V02).build().unwrap();
+
+    let gif = AnimationBuilder::default()
+        .module_size(2)
+        .frame_delay(1500)
+        .cycle(&[large.clone(), small.clone()], AnimationFormat::Gif)
+        .unwrap();
+    assert!(gif.starts_with(b"GIF89a"));
+
+    let frames = gif_frames(&gif);
+    assert_eq!(frames.len(), 2);
+    assert_eq!(frames[0].0, 150);
+    assert_eq!(modules(&frames[0].1, 25, 4, 2), values(&large));
+    // The smaller code is centered, 2 more modules of margin on each side
+    assert_eq!(modules(&frames[1].1, 21, 6, 2), values(&small));
+}
+
+#[test]
+fn animation_fades_in() {
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    let n = qrcode.size;
+
+    let gif = AnimationBuilder::default()
+        .module_size(1)
+        .margin(0)
+        .fade_in(&qrcode, 4, AnimationFormat::Gif)
+        .unwrap();
+    let frames = gif_frames(&gif);
+    assert_eq!(frames.len(), 4);
+
+    let dark = |pixels: &[u8]| pixels.iter().filter(|&&pixel| pixel == 1).count();
+    for pair in frames.windows(2) {
+        assert!(dark(&pair[0].1) < dark(&pair[1].1));
+    }
+    assert_eq!(modules(&frames[3].1, n, 0, 1), values(&qrcode));
+}
+
+#[test]
+fn animation_apng() {
+    let parts: Vec<QRCode> = ["first", "second", "third"]
+        .iter()
+        .map(|part| QRBuilder::new(*part).build().unwrap())
+        .collect();
+
+    let apng = AnimationBuilder::default()
+        .loops(2)
+        .cycle(&parts, AnimationFormat::Apng)
+        .unwrap();
+
+    let mut reader = png::Decoder::new(&apng[..]).read_info().unwrap();
+    let control = reader.info().animation_control().unwrap();
+    assert_eq!((control.num_frames, control.num_plays), (3, 2));
+
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    for part in &parts {
+        reader.next_frame(&mut buffer).unwrap();
+        assert_eq!(modules(&buffer, part.size, 4, 8), values(part));
+    }
+}
+
+#[test]
+fn animation_errors() {
+    let none: [QRCode; 0] = [];
+    assert_eq!(
+        AnimationBuilder::default().cycle(&none, AnimationFormat::Gif),
+        Err(AnimationError::NoFrames)
+    );
+
+    let qrcode = QRBuilder::new("Test").build().unwrap();
+    assert_eq!(
+        AnimationBuilder::default()
+            .module_size(5000)
+            .fade_in(&qrcode, 2, AnimationFormat::Gif),
+        Err(AnimationError::TooLarge)
+    );
+}
@@ -0,0 +1,101 @@
+use crate::baked::{pack, to_rust, BakedQR};
+use crate::matrix::Matrix;
+use crate::QRBuilder;
+
+#[test]
+fn baked_matches_qrcode() {
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    let bits: &'static [u8] = Box::leak(pack(&qrcode).into_boxed_slice());
+
+    let baked = BakedQR::new(qrcode.size, bits);
+    for y in 0..qrcode.size {
+        for x in 0..qrcode.size {
+            assert_eq!(baked.module(y, x).value(), qrcode[y][x].value());
+        }
+    }
+    assert!(!baked.get(qrcode.size, 0));
+}
+
+#[test]
+fn baked_const() {
+    const TINY: BakedQR = BakedQR::new(3, &[0b1010_1010, 0b1000_0000]);
+    const DARK: usize = TINY.get(0, 0) as usize + TINY.get(2, 2) as usize;
+
+    assert_eq!(DARK, 2);
+    assert_eq!(TINY.size(), 3);
+}
+
+#[test]
+#[should_panic(expected = "size x size")]
+fn baked_wrong_length() {
+    let _ = BakedQR::new(21, &[0; 10]);
+}
+
+#[test]
+fn baked_to_rust() {
+    let qrcode = QRBuilder::new("Hello").build().unwrap();
+    let source = to_rust(&qrcode, "HELLO");
+
+    assert!(source.contains("pub const HELLO: fast_qr::baked::BakedQR"));
+    assert!(source.contains("BakedQR::new(21, &["));
+    // 441 modules on 56 bytes
+    assert_eq!(source.matches("0x").count(), 56);
+    assert!(source.ends_with("]);\n"));
+}
+
+#[test]
+fn generate_const_matches_builder() {
+    use crate::baked::{generate_const, packed_len};
+    use crate::{Mask, Mode, Version, ECL};
+
+    macro_rules! check {
+        ($($version:ident),*) => {$(
+            for (input, mode) in [
+                (&b"0123456789"[..], Mode::Numeric),
+                (b"HELLO WORLD $%*+-./:", Mode::Alphanumeric),
+                (b"https://ex.co", Mode::Byte),
+                (b"\x93\x5f\xe4\xaa", Mode::Kanji),
+            ] {
+                for ecl in [ECL::L, ECL::M, ECL::Q, ECL::H] {
+                    for pattern in 0..8 {
+                        let mask = Mask::from_pattern(pattern).unwrap();
+                        let bits: [u8; packed_len(Version::$version)] =
+                            generate_const(input, mode, Version::$version, ecl, mask);
+
+                        let qrcode = QRBuilder::new(input)
+                            .mode(mode)
+                            .version(Version::$version)
+                            .ecl(ecl)
+                            .mask(mask)
+                            .build()
+                            .unwrap();
+                        assert_eq!(bits[..], pack(&qrcode)[..], "{:?} {:?} {:?}", Version::$version, ecl, pattern);
+                    }
+                }
+            }
+        )*};
+    }
+
+    check!(V02, V07, V10, V14, V27, V32, V40);
+}
+
+#[test]
+fn qr_const() {
+    use crate::{Mask, Mode, Version, ECL};
+
+    const FULL: BakedQR = crate::qr_const!(
+        [b'9'; 7089].as_slice(),
+        Mode::Numeric,
+        Version::V40,
+        ECL::L,
+        Mask::Diamonds,
+    );
+
+    let qrcode = QRBuilder::new([b'9'; 7089])
+        .version(Version::V40)
+        .ecl(ECL::L)
+        .mask(Mask::Diamonds)
+        .build()
+        .unwrap();
+    assert_eq!(FULL.bits(), &pack(&qrcode)[..]);
+}
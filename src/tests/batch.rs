@@ -0,0 +1,71 @@
+use crate::qr::QRCodeError;
+use crate::{QRBatchBuilder, Version, ECL};
+
+#[test]
+fn batch_shares_version_and_mask() {
+    let qrcodes = QRBatchBuilder::new(["1234", "https://example.com/a/much/longer/payload"])
+        .ecl(ECL::H)
+        .build()
+        .unwrap();
+
+    assert_eq!(qrcodes.len(), 2);
+    assert_eq!(qrcodes[0].size, qrcodes[1].size);
+    assert_eq!(qrcodes[0].version, qrcodes[1].version);
+    assert_eq!(
+        qrcodes[0].mask.unwrap() as usize,
+        qrcodes[1].mask.unwrap() as usize
+    );
+    assert!(qrcodes[0].version.unwrap() > Version::V01);
+}
+
+#[test]
+fn batch_rejects_small_version() {
+    let qrcodes = QRBatchBuilder::new(["a", "https://example.com/a/much/longer/payload"])
+        .version(Version::V01)
+        .build();
+
+    assert!(matches!(qrcodes, Err(QRCodeError::SpecifiedVersion)));
+}
+
+#[test]
+fn batch_rejects_empty_input() {
+    let qrcodes = QRBatchBuilder::new(["a", "", "c"]).build();
+    assert!(matches!(qrcodes, Err(QRCodeError::EmptyPayload)));
+}
+
+#[test]
+fn batch_keeps_input_order() {
+    let inputs: Vec<String> = (0..20)
+        .map(|i| format!("https://example.com/{i}"))
+        .collect();
+    let qrcodes = QRBatchBuilder::new(inputs.clone()).build().unwrap();
+
+    for (input, qrcode) in inputs.iter().zip(&qrcodes) {
+        let alone = crate::QRBuilder::new(input.as_str())
+            .ecl(ECL::Q)
+            .version(qrcode.version.unwrap())
+            .mask(qrcode.mask.unwrap())
+            .build()
+            .unwrap();
+        assert_eq!(alone.data[..], qrcode.data[..]);
+    }
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn par_build_matches_build() {
+    let inputs: Vec<String> = (0..200).map(|i| format!("item-{}", i * 7919)).collect();
+    let batch = QRBatchBuilder::new(inputs);
+
+    let sequential = batch.build().unwrap();
+    let parallel = batch.par_build().unwrap();
+
+    assert_eq!(sequential.len(), parallel.len());
+    for (a, b) in sequential.iter().zip(&parallel) {
+        assert_eq!(a.data[..], b.data[..]);
+        assert_eq!(a.mask.map(|m| m as usize), b.mask.map(|m| m as usize));
+    }
+
+    let empty: [&str; 0] = [];
+    assert!(QRBatchBuilder::new(empty).par_build().unwrap().is_empty());
+}
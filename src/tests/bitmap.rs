@@ -0,0 +1,138 @@
+use crate::convert::bitmap::{BitmapBuilder, BitmapFormat};
+use crate::QRBuilder;
+
+#[test]
+fn pbm_and_pgm_pixels() {
+    let qrcode = QRBuilder::new("bitmap").build().unwrap();
+    let mut builder = BitmapBuilder::default();
+    builder.margin(1).module_size(3);
+    // 21 modules, a quiet zone of 1 and 3 pixels per module
+    assert_eq!(builder.width(&qrcode), 69);
+
+    let pgm = builder.to_bytes(&qrcode, BitmapFormat::Pgm);
+    let header = b"P5\n69 69\n255\n";
+    assert!(pgm.starts_with(header));
+    let pixels = &pgm[header.len()..];
+    assert_eq!(pixels.len(), 69 * 69);
+
+    let pbm = builder.to_bytes(&qrcode, BitmapFormat::Pbm);
+    let header = b"P4\n69 69\n";
+    assert!(pbm.starts_with(header));
+    // Rows are padded to 9 bytes
+    let bits = &pbm[header.len()..];
+    assert_eq!(bits.len(), 69 * 9);
+
+    let plain = builder.to_bytes(&qrcode, BitmapFormat::PbmAscii);
+    let plain = String::from_utf8(plain).unwrap();
+    assert!(plain.starts_with("P1\n69 69\n"));
+    assert!(plain.lines().all(|line| line.len() <= 70));
+    let digits: Vec<char> = plain.lines().skip(2).flat_map(str::chars).collect();
+    assert_eq!(digits.len(), 69 * 69);
+
+    for y in 0..69usize {
+        for x in 0..69usize {
+            let dark = match ((y / 3).checked_sub(1), (x / 3).checked_sub(1)) {
+                (Some(y), Some(x)) if y < 21 && x < 21 => qrcode[y][x].value(),
+                _ => false,
+            };
+            assert_eq!(pixels[y * 69 + x] == 0, dark);
+            assert_eq!(bits[y * 9 + x / 8] >> (7 - x % 8) & 1 == 1, dark);
+            assert_eq!(digits[y * 69 + x] == '1', dark);
+        }
+    }
+}
+
+#[test]
+fn xbm_source() {
+    let qrcode = QRBuilder::new("bitmap").build().unwrap();
+    let xbm = BitmapBuilder::default()
+        .margin(0)
+        .xbm_name("code")
+        .to_bytes(&qrcode, BitmapFormat::Xbm);
+    let xbm = String::from_utf8(xbm).unwrap();
+
+    assert!(xbm.starts_with(
+        "#define code_width 21\n#define code_height 21\nstatic unsigned char code_bits[] = {\n"
+    ));
+    assert!(xbm.ends_with(",\n};\n"));
+
+    let bytes: Vec<u8> = xbm
+        .split("0x")
+        .skip(1)
+        .map(|byte| u8::from_str_radix(&byte[..2], 16).unwrap())
+        .collect();
+    // 3 bytes per row, the first pixel in the least significant bit
+    assert_eq!(bytes.len(), 21 * 3);
+    // Top row: 7 dark finder modules, then the light separator
+    assert_eq!(bytes[0], 0x7F);
+    for y in 0..21 {
+        for x in 0..21 {
+            assert_eq!(
+                bytes[y * 3 + x / 8] >> (x % 8) & 1 == 1,
+                qrcode[y][x].value()
+            );
+        }
+    }
+}
+
+#[test]
+fn bitmap_bleed() {
+    let qrcode = QRBuilder::new("bitmap").build().unwrap();
+    let dark_pixels = |bleed: f64| {
+        let pgm = BitmapBuilder::default()
+            .module_size(10)
+            .bleed(bleed)
+            .to_bytes(&qrcode, BitmapFormat::Pgm);
+        pgm.iter()
+            .skip(b"P5\n290 290\n255\n".len())
+            .filter(|&&pixel| pixel == 0)
+            .count()
+    };
+
+    let dark_modules = (0..21)
+        .flat_map(|y| (0..21).map(move |x| (y, x)))
+        .filter(|&(y, x)| qrcode[y][x].value())
+        .count();
+    assert_eq!(dark_pixels(0.0), dark_modules * 100);
+    assert!(dark_pixels(0.2) > dark_pixels(0.0));
+    assert!(dark_pixels(-0.2) < dark_pixels(0.0));
+
+    // A lone module shrinks on its 4 sides, 10 pixels minus 2 on each
+    let pgm = BitmapBuilder::default()
+        .margin(0)
+        .module_size(10)
+        .bleed(-0.2)
+        .to_bytes(&qrcode, BitmapFormat::Pgm);
+    let pixels = &pgm[b"P5\n210 210\n255\n".len()..];
+    // Top left finder pattern: the first row is dark from pixel 2 to 67
+    let row = &pixels[5 * 210..6 * 210];
+    assert_eq!(row.iter().position(|&pixel| pixel == 0), Some(2));
+    assert_eq!(row[..70].iter().rposition(|&pixel| pixel == 0), Some(67));
+}
+
+#[test]
+fn packed_row_and_column_major() {
+    for input in ["bitmap", "https://example.com/"] {
+        let qrcode = QRBuilder::new(input).build().unwrap();
+        let n = qrcode.size;
+        let stride = (n + 7) / 8;
+
+        let rows = qrcode.to_bits_row_major();
+        let columns = qrcode.to_bits_column_major();
+        assert_eq!(rows.len(), stride * n);
+        assert_eq!(columns.len(), stride * n);
+
+        for y in 0..n {
+            for x in 0..n {
+                let dark = qrcode[y][x].value();
+                assert_eq!(rows[y * stride + x / 8] & (0x80 >> (x % 8)) != 0, dark);
+                assert_eq!(columns[x * stride + y / 8] & (0x80 >> (y % 8)) != 0, dark);
+            }
+            // Padding bits at the end of each row stay clear
+            assert_eq!(rows[y * stride + stride - 1] & (0xFF >> (n % 8)), 0);
+        }
+
+        assert_eq!(qrcode.transpose().to_bits_row_major(), columns);
+        assert_eq!(qrcode.transpose().transpose().data[..], qrcode.data[..]);
+    }
+}
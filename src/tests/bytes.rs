@@ -27,3 +27,86 @@ fn it_can_output_to_bytes_from_image() {
     // Verify
     assert_eq!(data_uri, expected_data_uri);
 }
+
+#[cfg(feature = "image")]
+#[test]
+fn it_can_output_a_png_data_uri() {
+    use base64::engine::general_purpose;
+    use base64::Engine;
+
+    use crate::convert::image::ImageBuilder;
+    use crate::QRBuilder;
+
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    let builder = ImageBuilder::default();
+
+    let png_base64 = general_purpose::STANDARD.encode(builder.to_bytes(&qrcode).unwrap());
+    assert_eq!(
+        builder.to_png_data_uri(&qrcode).unwrap(),
+        format!("data:image/png;base64,{png_base64}")
+    );
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn it_can_output_an_svg_data_uri() {
+    use base64::engine::general_purpose;
+    use base64::Engine;
+
+    use crate::convert::svg::SvgBuilder;
+    use crate::QRBuilder;
+
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    let builder = SvgBuilder::default();
+
+    let data_uri = builder.to_data_uri(&qrcode);
+    let svg_base64 = data_uri.strip_prefix("data:image/svg+xml;base64,").unwrap();
+    let svg = general_purpose::STANDARD.decode(svg_base64).unwrap();
+    assert_eq!(svg, builder.to_str(&qrcode).as_bytes());
+
+    // Every padding length
+    for len in 0..7 {
+        let bytes: Vec<u8> = (0..len).map(|i| 0xF0 ^ i as u8).collect();
+        assert_eq!(
+            crate::convert::data_uri("a/b", &bytes),
+            format!(
+                "data:a/b;base64,{}",
+                general_purpose::STANDARD.encode(&bytes)
+            )
+        );
+    }
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn background_image_contrast_warning() {
+    use crate::convert::image::{BackgroundWarning, ImageBuilder};
+    use crate::QRBuilder;
+
+    // 1x1 pixel images, #404040 and #FFFFFF
+    let gray = "data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAIAAACQd1PeAAAADElEQVR4nGNwcHAAAAGEAMGDX2mUAAAAAElFTkSuQmCC";
+    let white = "data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAIAAACQd1PeAAAADElEQVR4nGP4//8/AAX+Av4N70a4AAAAAElFTkSuQmCC";
+
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+
+    assert_eq!(ImageBuilder::default().background_warning(&qrcode), None);
+    assert_eq!(
+        ImageBuilder::default()
+            .background_image(String::from(white))
+            .background_warning(&qrcode),
+        None
+    );
+
+    // Light modules show the dark background
+    let warning = ImageBuilder::default()
+        .background_image(String::from(gray))
+        .background_warning(&qrcode);
+    assert!(matches!(warning, Some(BackgroundWarning::LowContrast(ratio)) if ratio < 3.0));
+
+    // Faded dark modules
+    let warning = ImageBuilder::default()
+        .background_image(String::from(white))
+        .dark_opacity(0.2)
+        .background_warning(&qrcode);
+    assert!(matches!(warning, Some(BackgroundWarning::LowContrast(_))));
+}
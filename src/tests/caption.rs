@@ -0,0 +1,72 @@
+use crate::convert::caption::{CaptionBuilder, TextShaper};
+use crate::{QRBuilder, Version, ECL};
+
+/// Fills the whole measured box
+struct Filled;
+
+impl TextShaper for Filled {
+    fn measure(&self, text: &str, size: f32) -> (u32, u32) {
+        (text.len() as u32 * size as u32 / 2, size as u32)
+    }
+
+    fn draw(&self, text: &str, size: f32, draw: &mut dyn FnMut(u32, u32, f32)) {
+        let (width, height) = self.measure(text, size);
+        for y in 0..height {
+            for x in 0..width {
+                draw(x, y, 1.0);
+            }
+        }
+    }
+}
+
+#[test]
+fn caption_without_text_is_square() {
+    let qrcode = QRBuilder::new("Test")
+        .ecl(ECL::M)
+        .version(Version::V01)
+        .build()
+        .unwrap();
+
+    let image = CaptionBuilder::new(&Filled).module_size(2).to_gray(&qrcode);
+
+    assert_eq!(image.width, (21 + 8) * 2);
+    assert_eq!(image.height, image.width);
+    // Quiet zone then top left finder pattern
+    assert_eq!(image.get(7, 7), 255);
+    assert_eq!(image.get(8, 8), 0);
+}
+
+#[test]
+fn caption_is_centered_below() {
+    let qrcode = QRBuilder::new("Test")
+        .ecl(ECL::M)
+        .version(Version::V01)
+        .build()
+        .unwrap();
+
+    let image = CaptionBuilder::new(&Filled)
+        .text("abcd")
+        .module_size(2)
+        .font_size(10.0)
+        .to_gray(&qrcode);
+
+    // 10px of text, then 4 modules of padding
+    assert_eq!(image.height, image.width + 10 + 8);
+
+    // Text box is 20px wide, centered in 58px
+    let top = image.width;
+    assert_eq!(image.get(18, top), 255);
+    assert_eq!(image.get(19, top), 0);
+    assert_eq!(image.get(38, top + 9), 0);
+    assert_eq!(image.get(39, top + 9), 255);
+    assert_eq!(image.get(19, top + 10), 255);
+}
+
+#[test]
+#[cfg(feature = "font")]
+fn caption_invalid_font() {
+    use crate::convert::caption::{CaptionError, FontShaper};
+
+    let font = FontShaper::from_ttf(vec![0; 16]);
+    assert!(matches!(font, Err(CaptionError::InvalidFont)));
+}
@@ -198,3 +198,17 @@ fn push_random() {
     expected[1] = 0b0100_1000;
     assert_eq!(res.get_data()[..8], expected[..8]);
 }
+
+#[cfg(feature = "zeroize")]
+#[test]
+fn zeroize_clears_bits() {
+    use zeroize::Zeroize;
+
+    let mut res = CompactQR::with_len(16);
+    res.push_u8(0b1010_1010);
+    res.push_u8(0xFF);
+
+    res.zeroize();
+    assert_eq!(res.len(), 0);
+    assert!(res.get_data().iter().all(|&byte| byte == 0));
+}
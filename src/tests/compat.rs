@@ -0,0 +1,103 @@
+use crate::compat::{LegacyCompat, MaskTieBreak, PadCodewords};
+use crate::datamasking::MaskScoring;
+use crate::{Mask, QRBuilder, Version};
+
+fn constant(_: &crate::QRCode) -> u32 {
+    0
+}
+
+#[test]
+fn default_compat_is_spec() {
+    let spec = QRBuilder::new("https://example.com/").build().unwrap();
+    let compat = QRBuilder::new("https://example.com/")
+        .legacy_compat(LegacyCompat::default())
+        .build()
+        .unwrap();
+
+    assert_eq!(spec.data[..], compat.data[..]);
+}
+
+#[test]
+fn mask_tie_break() {
+    let build = |mask_tie_break| {
+        QRBuilder::new("tie")
+            .mask_scoring(MaskScoring::Custom(constant))
+            .legacy_compat(LegacyCompat {
+                mask_tie_break,
+                ..LegacyCompat::default()
+            })
+            .build()
+            .unwrap()
+            .mask
+    };
+
+    assert_eq!(build(MaskTieBreak::First).map(Mask::pattern), Some(0));
+    assert_eq!(build(MaskTieBreak::Last).map(Mask::pattern), Some(7));
+}
+
+#[test]
+fn pad_codewords() {
+    let build = |padding| {
+        QRBuilder::new("1")
+            .version(Version::V02)
+            .mask(Mask::Checkerboard)
+            .legacy_compat(LegacyCompat {
+                padding,
+                ..LegacyCompat::default()
+            })
+            .build()
+            .unwrap()
+            .data
+    };
+
+    let spec = build(PadCodewords::Alternating);
+    let swapped = build(PadCodewords::Swapped);
+    let zeros = build(PadCodewords::Zeros);
+    assert_ne!(spec[..], swapped[..]);
+    assert_ne!(spec[..], zeros[..]);
+    assert_ne!(swapped[..], zeros[..]);
+}
+
+#[test]
+fn custom_padding() {
+    let build = |padding| {
+        QRBuilder::new("1")
+            .version(Version::V02)
+            .mask(Mask::Checkerboard)
+            .padding(padding)
+            .build()
+            .unwrap()
+    };
+
+    // The spec bytes through a function give the same code
+    let spec = build(PadCodewords::Alternating);
+    let same = build(PadCodewords::Custom(|i| [0xEC, 0x11][i % 2]));
+    assert_eq!(spec.data[..], same.data[..]);
+
+    let dark = build(PadCodewords::Custom(|_| 0xFF));
+    assert_ne!(spec.data[..], dark.data[..]);
+    assert_eq!(crate::decode::decode(&dark).unwrap().data, b"1");
+}
+
+#[test]
+fn custom_padding_fingerprint() {
+    let fingerprint = |padding| QRBuilder::new("1").padding(padding).options_fingerprint();
+
+    assert_eq!(
+        fingerprint(PadCodewords::Custom(|_| 0)),
+        fingerprint(PadCodewords::Custom(|_| 0xFF))
+    );
+    assert_ne!(
+        fingerprint(PadCodewords::Custom(|_| 0)),
+        fingerprint(PadCodewords::Zeros)
+    );
+    assert_eq!(
+        fingerprint(PadCodewords::Zeros),
+        QRBuilder::new("1")
+            .legacy_compat(LegacyCompat {
+                padding: PadCodewords::Zeros,
+                ..LegacyCompat::default()
+            })
+            .options_fingerprint()
+    );
+}
@@ -0,0 +1,92 @@
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::datamasking::MaskScoring;
+use crate::polynomials::{division, division_constant_time, structure, structure_with};
+use crate::{hardcode, Mask, Mode, QRBuilder, QRCode, Version, ECL};
+
+#[test]
+fn constant_time_division_matches_tables() {
+    let data: Vec<u8> = (0..=255).step_by(7).map(|n| n as u8).collect();
+
+    for ecl in [ECL::L, ECL::M, ECL::Q, ECL::H] {
+        for version in [Version::V01, Version::V10, Version::V40] {
+            let generator = hardcode::get_polynomial(version, ecl);
+            assert_eq!(
+                division(&data[..16], generator),
+                division_constant_time(&data[..16], generator)
+            );
+        }
+    }
+
+    let data_codewords = [0u8; 55];
+    assert_eq!(
+        structure(&data_codewords, ECL::L, Version::V03),
+        structure_with(
+            &data_codewords,
+            ECL::L,
+            Version::V03,
+            division_constant_time
+        )
+    );
+}
+
+#[test]
+fn constant_time_uses_byte_mode_and_fixed_mask() {
+    let qrcode = QRBuilder::new("123456")
+        .constant_time(true)
+        .build()
+        .unwrap();
+
+    assert_eq!(qrcode.mode, Some(Mode::Byte));
+    assert_eq!(qrcode.mask.map(Mask::pattern), Some(0));
+}
+
+#[test]
+fn constant_time_matches_forced_settings() {
+    let secret = "otpauth://totp/Example?secret=JBSWY3DPEHPK3PXP";
+
+    let regular = QRBuilder::new(secret)
+        .mode(Mode::Byte)
+        .mask(Mask::Diamonds)
+        .build()
+        .unwrap();
+    let constant_time = QRBuilder::new(secret)
+        .mask(Mask::Diamonds)
+        .constant_time(true)
+        .build()
+        .unwrap();
+
+    assert_eq!(regular.data[..], constant_time.data[..]);
+}
+
+static SCORED: AtomicUsize = AtomicUsize::new(0);
+
+/// Counts the masks scored, every strategy goes through `score::score_with`
+fn counting_penalty(_: &QRCode) -> u32 {
+    SCORED.fetch_add(1, Ordering::SeqCst);
+    0
+}
+
+#[test]
+fn masks_are_not_scored_when_forced_or_constant_time() {
+    let secret = "otpauth://totp/Example?secret=JBSWY3DPEHPK3PXP";
+    let scoring = MaskScoring::Custom(counting_penalty);
+
+    QRBuilder::new(secret)
+        .mask(Mask::Diamonds)
+        .mask_scoring(scoring)
+        .build()
+        .unwrap();
+    QRBuilder::new(secret)
+        .constant_time(true)
+        .mask_scoring(scoring)
+        .build()
+        .unwrap();
+    assert_eq!(SCORED.load(Ordering::SeqCst), 0);
+
+    QRBuilder::new(secret)
+        .mask_scoring(scoring)
+        .build()
+        .unwrap();
+    assert_eq!(SCORED.load(Ordering::SeqCst), Mask::ALL.len());
+}
@@ -0,0 +1,57 @@
+use crate::convert::dark_mode::{DarkModeBuilder, DarkModeError};
+use crate::QRBuilder;
+
+#[test]
+fn dark_mode_ring_around_code() {
+    let qrcode = QRBuilder::new("dark mode").build().unwrap();
+    let svg = DarkModeBuilder::default()
+        .ring(2)
+        .padding(3)
+        .to_str(&qrcode)
+        .unwrap();
+
+    // 21 modules, a ring of 2 and a padding of 3 on every side
+    assert!(svg.starts_with(r#"<svg viewBox="0 0 31 31""#));
+    assert!(svg.contains(r##"<rect width="31px" height="31px" fill="#121212"/>"##));
+    assert!(svg.contains(r##"<rect x="3" y="3" width="25" height="25" rx="1" fill="#ffffff"/>"##));
+    // The top left module of the finder pattern
+    assert!(svg.contains(r#"<path d="M5,5h1v1h-1"#));
+
+    // The ring cannot disappear, and its corners never reach the code
+    let svg = DarkModeBuilder::default()
+        .ring(0)
+        .corner_radius(5.0)
+        .to_str(&qrcode)
+        .unwrap();
+    assert!(svg.contains(r#"width="23" height="23" rx="1""#));
+}
+
+#[test]
+fn dark_mode_contrast() {
+    let mut builder = DarkModeBuilder::default();
+    assert!((builder.contrast().unwrap() - 21.0).abs() < 1e-9);
+
+    let qrcode = QRBuilder::new("dark mode").build().unwrap();
+    builder.module_color("#777");
+    match builder.to_str(&qrcode) {
+        Err(DarkModeError::Contrast { ratio, min }) => {
+            assert!((4.4..4.5).contains(&ratio));
+            assert!((min - 7.0).abs() < f64::EPSILON);
+        }
+        other => panic!("Unexpected {other:?}"),
+    }
+    assert!(builder.min_contrast(4.0).to_str(&qrcode).is_ok());
+
+    // A transparent ring shows the dark page behind the modules
+    builder.module_color("#000000").ring_color("#ffffff00");
+    assert!(builder.contrast().unwrap() < 1.2);
+
+    builder.module_color("#fff").ring_color("#000000");
+    assert_eq!(builder.contrast(), Err(DarkModeError::Inverted));
+
+    builder.ring_color("white");
+    assert_eq!(
+        builder.contrast(),
+        Err(DarkModeError::Color(String::from("white")))
+    );
+}
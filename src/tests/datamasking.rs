@@ -236,3 +236,130 @@ fn mask_meadow_test() {
         ]
     );
 }
+
+#[test]
+fn mask_from_pattern_roundtrip() {
+    for pattern in 0..8 {
+        assert_eq!(Mask::from_pattern(pattern).unwrap().pattern(), pattern);
+    }
+    assert!(Mask::from_pattern(8).is_none());
+
+    for (pattern, mask) in Mask::ALL.iter().enumerate() {
+        assert_eq!(mask.pattern(), pattern);
+    }
+}
+
+#[test]
+#[allow(deprecated)]
+fn builder_mask_pattern() {
+    use crate::qr::{QRBuilder, QRCodeError};
+
+    let forced = QRBuilder::new("Hello World!")
+        .mask_pattern(5)
+        .build()
+        .unwrap();
+    assert_eq!(forced.mask.unwrap().pattern(), 5);
+
+    // The chosen mask is exposed, building again with it gives the same matrix
+    let chosen = QRBuilder::new("Hello World!").build().unwrap();
+    let pattern = chosen.mask.unwrap().pattern();
+    let again = QRBuilder::new("Hello World!")
+        .mask_pattern(pattern)
+        .build()
+        .unwrap();
+    assert!(chosen.data[..] == again.data[..]);
+
+    let invalid = QRBuilder::new("Hello World!").mask_pattern(8).build();
+    assert!(matches!(invalid, Err(QRCodeError::MaskPattern)));
+}
+
+#[test]
+fn builder_mask_scoring_custom() {
+    use crate::datamasking::MaskScoring;
+    use crate::qr::QRBuilder;
+
+    fn dark_data(qr: &QRCode) -> u32 {
+        let n = qr.size;
+        qr.data[..n * n]
+            .iter()
+            .filter(|m| m.module_type() == crate::ModuleType::Data && m.value())
+            .count() as u32
+    }
+
+    let qrcode = QRBuilder::new("Hello World!")
+        .mask_scoring(MaskScoring::Custom(dark_data))
+        .build()
+        .unwrap();
+    let best = Mask::ALL
+        .iter()
+        .map(|&mask| {
+            let forced = QRBuilder::new("Hello World!").mask(mask).build().unwrap();
+            dark_data(&forced)
+        })
+        .min()
+        .unwrap();
+    assert_eq!(dark_data(&qrcode), best);
+
+    fn constant(_: &QRCode) -> u32 {
+        0
+    }
+
+    // Ties keep the first mask
+    let qrcode = QRBuilder::new("Hello World!")
+        .mask_scoring(MaskScoring::Custom(constant))
+        .build()
+        .unwrap();
+    assert_eq!(qrcode.mask.unwrap().pattern(), 0);
+
+    let spec = QRBuilder::new("Hello World!").build().unwrap();
+    let default = QRBuilder::new("Hello World!")
+        .mask_scoring(MaskScoring::Spec)
+        .build()
+        .unwrap();
+    assert_eq!(
+        spec.mask.unwrap().pattern(),
+        default.mask.unwrap().pattern()
+    );
+
+    let dark_ratio = QRBuilder::new("Hello World!")
+        .mask_scoring(MaskScoring::DarkRatio)
+        .build();
+    assert!(dark_ratio.is_ok());
+}
+
+#[test]
+fn mask_report_matches_selection() {
+    use crate::{QRBuilder, ECL};
+
+    for input in ["Hello", "https://example.com/", "0123456789", "FAST QR"] {
+        for ecl in [ECL::L, ECL::H] {
+            let qrcode = QRBuilder::new(input).ecl(ecl).build().unwrap();
+            let report = qrcode.mask_report().unwrap();
+
+            assert_eq!(report.best() as usize, qrcode.mask.unwrap() as usize);
+            for (pattern, candidate) in report.candidates.iter().enumerate() {
+                assert_eq!(candidate.mask as usize, pattern);
+            }
+        }
+    }
+}
+
+#[test]
+fn mask_report_forced_mask() {
+    use crate::QRBuilder;
+
+    let selected = QRBuilder::new("Hello").build().unwrap();
+    let forced = QRBuilder::new("Hello").mask(Mask::Meadow).build().unwrap();
+
+    let selected = selected.mask_report().unwrap();
+    let forced = forced.mask_report().unwrap();
+    assert!(matches!(forced.applied, Mask::Meadow));
+    // The same data gives the same penalties, whatever mask was applied
+    for (a, b) in selected.candidates.iter().zip(&forced.candidates) {
+        assert_eq!(a.total(), b.total());
+        assert_eq!(a.balance, b.balance);
+    }
+    assert_eq!(forced.best() as usize, selected.applied as usize);
+
+    assert!(crate::QRCode::default(21).mask_report().is_none());
+}
@@ -0,0 +1,132 @@
+use crate::decode::{self, DecodeError};
+use crate::matrix::QRMatrix;
+use crate::{galois, hardcode, placement, QRBuilder, QRCode, Version, ECL};
+
+/// Deterministic xorshift, tests do not need a good generator
+struct Rng(u32);
+
+impl Rng {
+    fn next(&mut self) -> u32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        self.0
+    }
+}
+
+/// XORs the `index`-th codeword (interleaved order) with `error`
+fn corrupt_codeword(qr: &mut QRCode, positions: &[(usize, usize)], index: usize, error: u8) {
+    for bit in 0..8 {
+        if error >> (7 - bit) & 1 == 1 {
+            let (y, x) = positions[index * 8 + bit];
+            qr[y][x].toggle();
+        }
+    }
+}
+
+/// Index of the `i`-th codeword of `block`, data codewords first
+fn interleaved_index(version: Version, ecl: ECL, block: usize, i: usize) -> usize {
+    let [(g1_count, g1_size), (g2_count, _)] = hardcode::ecc_to_groups(ecl, version);
+    let blocks = g1_count + g2_count;
+    // Every block has at least `g1_size` data codewords
+    if i < g1_size {
+        i * blocks + block
+    } else {
+        hardcode::data_codewords(version, ecl) + (i - g1_size) * blocks + block
+    }
+}
+
+#[test]
+fn decode_corrects_up_to_capacity() {
+    let mut rng = Rng(0x2545_F491);
+    let input = b"fast_qr";
+
+    for number in 1..=40 {
+        let version = Version::from_number(number).unwrap();
+        let positions = placement::data_positions(version);
+        for ecl in [ECL::L, ECL::M, ECL::Q, ECL::H] {
+            let mut qrcode = QRBuilder::new(&input[..])
+                .version(version)
+                .ecl(ecl)
+                .build()
+                .unwrap();
+
+            let [(g1_count, _), (g2_count, _)] = hardcode::ecc_to_groups(ecl, version);
+            let capacity = galois::ecc_codewords(version, ecl) / 2;
+            for block in 0..g1_count + g2_count {
+                for i in 0..capacity {
+                    let error = (rng.next() % 255 + 1) as u8;
+                    let index = interleaved_index(version, ecl, block, i * 2);
+                    corrupt_codeword(&mut qrcode, &positions, index, error);
+                }
+            }
+
+            let decoded = decode::decode(&qrcode).unwrap();
+            assert_eq!(decoded.data, input, "version {number}, ecl {ecl}");
+            assert_eq!(decoded.version, version);
+            assert_eq!(decoded.ecl as usize, ecl as usize);
+            assert_eq!(
+                decoded.corrected_codewords,
+                capacity * (g1_count + g2_count)
+            );
+        }
+    }
+}
+
+#[test]
+fn decode_fails_over_capacity() {
+    let input = b"https://example.com/";
+    let mut qrcode = QRBuilder::new(&input[..]).ecl(ECL::L).build().unwrap();
+    let version = qrcode.version.unwrap();
+
+    // 10 error correction codewords in the single block of a 2-L code
+    assert_eq!(version, Version::V02);
+    let positions = placement::data_positions(version);
+    for i in 0..6 {
+        corrupt_codeword(&mut qrcode, &positions, i * 3, 0x5A);
+    }
+    let decoded = decode::decode(&qrcode);
+    assert!(!matches!(decoded, Ok(decoded) if decoded.data == input));
+}
+
+#[test]
+fn decode_information_errors() {
+    let qrcode = QRBuilder::new("https://example.com/")
+        .version(Version::V07)
+        .build()
+        .unwrap();
+
+    let decoded = decode::decode(&QRMatrix::from(&qrcode)).unwrap();
+    assert_eq!(decoded.information_errors, 0);
+    assert_eq!(decoded.mask.pattern(), qrcode.mask.unwrap().pattern());
+
+    // Two wrong bits in each copy of the format and version information
+    let mut corrupted = qrcode.clone();
+    let size = corrupted.size;
+    for (y, x) in [(0, 8), (8, 2), (8, size - 1), (size - 2, 8)] {
+        corrupted[y][x].toggle();
+    }
+    for (y, x) in [(0, size - 11), (5, size - 9)] {
+        corrupted[y][x].toggle();
+        corrupted[x][y].toggle();
+    }
+    let decoded = decode::decode(&corrupted).unwrap();
+    assert_eq!(decoded.data, b"https://example.com/");
+    assert_eq!(decoded.information_errors, 8);
+
+    // Too many wrong bits in both copies of the format information
+    let mut corrupted = qrcode.clone();
+    for i in 0..4 {
+        corrupted[i][8].toggle();
+        corrupted[8][size - 1 - i].toggle();
+    }
+    assert!(matches!(
+        decode::decode(&corrupted),
+        Err(DecodeError::FormatInformation)
+    ));
+
+    assert!(matches!(
+        decode::decode(&QRMatrix::new(30)),
+        Err(DecodeError::Size)
+    ));
+}
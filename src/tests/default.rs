@@ -279,3 +279,48 @@ fn transpose() {
         }
     }
 }
+
+#[test]
+fn image_embedding_raises_ecl() {
+    use crate::{QRBuilder, ECL};
+
+    let qrcode = QRBuilder::new("Test").ecl(ECL::L).image_embedding().build();
+    assert!(matches!(qrcode.unwrap().ecl, Some(ECL::Q)));
+
+    let qrcode = QRBuilder::new("Test").ecl(ECL::H).image_embedding().build();
+    assert!(matches!(qrcode.unwrap().ecl, Some(ECL::H)));
+
+    // A later lower ECL does not undo it
+    let qrcode = QRBuilder::new("Test").image_embedding().ecl(ECL::L).build();
+    assert!(matches!(qrcode.unwrap().ecl, Some(ECL::Q)));
+}
+
+#[test]
+fn alignment_pattern_centers() {
+    use crate::hardcode::alignment_pattern_centers;
+    use crate::Version;
+
+    let centers = |version| alignment_pattern_centers(version).collect::<Vec<_>>();
+
+    assert!(centers(Version::V01).is_empty());
+    assert_eq!(centers(Version::V02), [(18, 18)]);
+    assert_eq!(
+        centers(Version::V07),
+        [(6, 22), (22, 6), (22, 22), (22, 38), (38, 22), (38, 38)]
+    );
+
+    for version in Version::iter().skip(1) {
+        let grid = version.alignment_positions();
+        let centers = centers(version);
+        let size = version.size();
+
+        assert_eq!(centers.len(), grid.len() * grid.len() - 3);
+        for &(y, x) in &centers {
+            // The 5x5 pattern fits and stays clear of the 8x8 finder corners
+            assert!(y + 2 < size && x + 2 < size);
+            let on_finder =
+                |fy: usize, fx: usize| y < fy + 10 && fy < y + 10 && x < fx + 10 && fx < x + 10;
+            assert!(!on_finder(0, 0) && !on_finder(0, size - 10) && !on_finder(size - 10, 0));
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use std::fs;
+
+use crate::dump::TEST_DIR;
+use crate::{Mask, QRBuilder, Version, ECL};
+
+#[test]
+fn dump_writes_every_step() {
+    let dir = std::env::temp_dir().join(format!("fast_qr_dump_{}", std::process::id()));
+    TEST_DIR.with(|test_dir| *test_dir.borrow_mut() = Some(dir.clone()));
+
+    let qrcode = QRBuilder::new("HELLO WORLD")
+        .ecl(ECL::M)
+        .version(Version::V01)
+        .build()
+        .unwrap();
+    TEST_DIR.with(|test_dir| *test_dir.borrow_mut() = None);
+
+    // Mode, count and the first characters, 16 data codewords at 1-M
+    let bitstream = fs::read_to_string(dir.join("bitstream.txt")).unwrap();
+    assert!(bitstream.contains("00100000 01011011 00001011 01111000"));
+    assert_eq!(bitstream.lines().count(), 1 + 2);
+
+    let codewords = fs::read_to_string(dir.join("codewords.txt")).unwrap();
+    assert_eq!(codewords.lines().count(), 1 + 26);
+    assert!(codewords.lines().nth(17).unwrap().contains(" ecc "));
+
+    let matrix = fs::read_to_string(dir.join("matrix.txt")).unwrap();
+    let rows: Vec<_> = matrix.lines().skip(1).collect();
+    assert_eq!(rows.len(), 21);
+    assert!(rows[0].starts_with("#######."));
+
+    let masks = fs::read_to_string(dir.join("masks.txt")).unwrap();
+    let applied = qrcode.mask.map(|mask| mask as usize).unwrap();
+    assert!(masks.contains(&format!("applied {applied}")));
+
+    // A forced mask is reported as applied
+    TEST_DIR.with(|test_dir| *test_dir.borrow_mut() = Some(dir.clone()));
+    QRBuilder::new("HELLO WORLD")
+        .mask(Mask::Meadow)
+        .build()
+        .unwrap();
+    TEST_DIR.with(|test_dir| *test_dir.borrow_mut() = None);
+    let masks = fs::read_to_string(dir.join("masks.txt")).unwrap();
+    assert!(masks.contains("applied 7 (Meadow)"));
+
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,115 @@
+use crate::convert::dxf::{DxfBuilder, DxfUnit};
+use crate::matrix::QRMatrix;
+use crate::QRBuilder;
+
+/// Corners of every polyline, as `(x, y)` strings
+fn polylines(dxf: &str) -> Vec<Vec<(String, String)>> {
+    dxf.split("0\nPOLYLINE\n")
+        .skip(1)
+        .map(|polyline| {
+            polyline
+                .split("0\nVERTEX\n")
+                .skip(1)
+                .map(|vertex| {
+                    let lines: Vec<&str> = vertex.lines().collect();
+                    assert_eq!((lines[2], lines[4]), ("10", "20"));
+                    (String::from(lines[3]), String::from(lines[5]))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn dxf_header() {
+    let qrcode = QRBuilder::new("dxf").build().unwrap();
+    let dxf = DxfBuilder::default().to_str(&qrcode);
+
+    assert!(dxf.starts_with("0\nSECTION\n2\nHEADER\n9\n$ACADVER\n1\nAC1009\n"));
+    assert!(dxf.contains("\n9\n$INSUNITS\n70\n4\n"));
+    assert!(dxf.contains("\n9\n$EXTMAX\n10\n30\n20\n30\n"));
+    assert!(dxf.ends_with("0\nENDSEC\n0\nEOF\n"));
+    assert_eq!(
+        dxf.matches("0\nPOLYLINE\n").count(),
+        dxf.matches("0\nSEQEND\n").count()
+    );
+
+    let dxf = DxfBuilder::default()
+        .unit(DxfUnit::Inch)
+        .width(1.5)
+        .to_str(&qrcode);
+    assert!(dxf.contains("\n9\n$INSUNITS\n70\n1\n"));
+    assert!(dxf.contains("\n9\n$EXTMAX\n10\n1.5\n20\n1.5\n"));
+}
+
+#[test]
+fn dxf_outlines_dark_areas() {
+    // A ring with a hole, a module touching it by a corner, and a bar
+    let matrix = QRMatrix::parse_ascii(
+        "
+        ###..
+        #.#..
+        ###..
+        ...#.
+        .###.
+        ",
+    )
+    .unwrap();
+    let dxf = DxfBuilder::default()
+        .margin(0)
+        .width(5.0)
+        .layer("CUT")
+        .to_str(&matrix);
+
+    assert!(dxf.contains("0\nPOLYLINE\n8\nCUT\n66\n1\n70\n1\n"));
+    let corners = |points: &[(usize, usize)]| -> Vec<(String, String)> {
+        points
+            .iter()
+            .map(|&(x, y)| (x.to_string(), y.to_string()))
+            .collect()
+    };
+    // y goes up from the bottom of the code
+    assert_eq!(
+        polylines(&dxf),
+        [
+            corners(&[(3, 5), (3, 2), (0, 2), (0, 5)]),
+            corners(&[(1, 4), (1, 3), (2, 3), (2, 4)]),
+            corners(&[(4, 2), (4, 0), (1, 0), (1, 1), (3, 1), (3, 2)]),
+        ]
+    );
+}
+
+#[test]
+fn dxf_covers_every_dark_module() {
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    let n = qrcode.size;
+    let dxf = DxfBuilder::default()
+        .margin(2)
+        .width((n + 4) as f64)
+        .to_str(&qrcode);
+
+    // The signed areas of the polylines add up to the dark modules, outer
+    // borders are clockwise (negative) and holes counterclockwise
+    let area: f64 = polylines(&dxf)
+        .iter()
+        .map(|corners| {
+            let points: Vec<(f64, f64)> = corners
+                .iter()
+                .map(|(x, y)| (x.parse().unwrap(), y.parse().unwrap()))
+                .collect();
+            (0..points.len())
+                .map(|i| {
+                    let (x0, y0) = points[i];
+                    let (x1, y1) = points[(i + 1) % points.len()];
+                    x0 * y1 - x1 * y0
+                })
+                .sum::<f64>()
+                / 2.0
+        })
+        .sum();
+    let dark = (0..n)
+        .flat_map(|y| (0..n).map(move |x| (y, x)))
+        .filter(|&(y, x)| qrcode[y][x].value())
+        .count();
+    assert_eq!(-area, dark as f64);
+}
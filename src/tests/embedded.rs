@@ -0,0 +1,87 @@
+use core::convert::Infallible;
+
+use embedded_graphics_core::draw_target::DrawTarget;
+use embedded_graphics_core::geometry::{Dimensions, OriginDimensions, Point, Size};
+use embedded_graphics_core::pixelcolor::BinaryColor;
+use embedded_graphics_core::{Drawable, Pixel};
+
+use crate::embedded::QRDrawable;
+use crate::{Module, QRBuilder};
+
+/// 128x64, as an SSD1306
+struct Display {
+    pixels: [[Option<BinaryColor>; 128]; 64],
+}
+
+impl Display {
+    fn new() -> Self {
+        Display {
+            pixels: [[None; 128]; 64],
+        }
+    }
+}
+
+impl OriginDimensions for Display {
+    fn size(&self) -> Size {
+        Size::new(128, 64)
+    }
+}
+
+impl DrawTarget for Display {
+    type Color = BinaryColor;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            if self.bounding_box().contains(point) {
+                self.pixels[point.y as usize][point.x as usize] = Some(color);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn draws_modules_with_offset_and_scale() {
+    let qrcode = QRBuilder::new("Hello").build().unwrap();
+    let mut display = Display::new();
+
+    QRDrawable::new(&qrcode, BinaryColor::On, BinaryColor::Off)
+        .top_left(Point::new(10, 2))
+        .scale(2)
+        .margin(1)
+        .draw(&mut display)
+        .unwrap();
+
+    // 21 modules + 2 of quiet zone, 2 pixels each
+    let side = (21 + 2) * 2;
+    for (y, row) in display.pixels.iter().enumerate() {
+        for (x, &pixel) in row.iter().enumerate() {
+            let inside = (2..2 + side).contains(&y) && (10..10 + side).contains(&x);
+            if !inside {
+                assert_eq!(pixel, None);
+                continue;
+            }
+
+            let (my, mx) = ((y - 2) / 2, (x - 10) / 2);
+            let dark = (1..22).contains(&my)
+                && (1..22).contains(&mx)
+                && qrcode[my - 1][mx - 1].value() == Module::DARK;
+            assert_eq!(pixel, Some(BinaryColor::from(dark)));
+        }
+    }
+}
+
+#[test]
+fn bounding_box() {
+    let qrcode = QRBuilder::new("Hello").build().unwrap();
+    let mut drawable = QRDrawable::new(&qrcode, BinaryColor::On, BinaryColor::Off);
+
+    assert_eq!(drawable.bounding_box().size, Size::new(29, 29));
+    drawable.scale(3).top_left(Point::new(5, 5));
+    assert_eq!(drawable.bounding_box().top_left, Point::new(5, 5));
+    assert_eq!(drawable.bounding_box().size, Size::new(87, 87));
+}
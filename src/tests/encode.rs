@@ -1,3 +1,5 @@
+#![allow(clippy::identity_op)]
+
 use crate::compact::{CompactQR, KEEP_LAST};
 use crate::encode;
 use crate::encode::Mode;
@@ -99,7 +101,7 @@ fn encode_byte_1() {
 fn encode_alphanumeric_1() {
     let mut compact = CompactQR::new();
     const INPUT: &[u8] = b"HELLO WORLD";
-    encode::encode_alphanumeric(&mut compact, INPUT, 9);
+    encode::encode_alphanumeric(&mut compact, INPUT, 9).unwrap();
 
     test_encode_header(&compact, INPUT, Mode::Alphanumeric);
 
@@ -136,7 +138,7 @@ fn encode_alphanumeric_1() {
 fn encode_numeric_1() {
     let mut compact = CompactQR::new();
     const INPUT: &[u8] = b"5894";
-    encode::encode_numeric(&mut compact, INPUT, 10);
+    encode::encode_numeric(&mut compact, INPUT, 10).unwrap();
 
     test_encode_header(&compact, INPUT, Mode::Numeric);
 
@@ -159,7 +161,7 @@ fn encode_numeric_1() {
 fn encode_numeric_2() {
     let mut compact = CompactQR::new();
     const INPUT: &[u8] = b"58949";
-    encode::encode_numeric(&mut compact, INPUT, 10);
+    encode::encode_numeric(&mut compact, INPUT, 10).unwrap();
 
     test_encode_header(&compact, INPUT, Mode::Numeric);
 
@@ -182,7 +184,7 @@ fn encode_numeric_2() {
 fn encode_numeric_3() {
     let mut compact = CompactQR::new();
     const INPUT: &[u8] = b"589491";
-    encode::encode_numeric(&mut compact, INPUT, 10);
+    encode::encode_numeric(&mut compact, INPUT, 10).unwrap();
 
     test_encode_header(&compact, INPUT, Mode::Numeric);
 
@@ -206,7 +208,7 @@ fn encode_numeric_3() {
 fn encode_numeric_4() {
     let mut compact = CompactQR::new();
     const INPUT: &[u8] = b"200505150001";
-    encode::encode_numeric(&mut compact, INPUT, 10);
+    encode::encode_numeric(&mut compact, INPUT, 10).unwrap();
 
     test_encode_header(&compact, INPUT, Mode::Numeric);
 
@@ -231,3 +233,252 @@ fn encode_numeric_4() {
     assert_eq!(res[5] & 0b0000_1111, (1) >> 6);
     assert_eq!(res[6] & 0b1111_1100, (1) << 2 & keep_last[8]);
 }
+
+#[test]
+fn empty_payload() {
+    use crate::qr::QRCodeError;
+    use crate::{decode, QRBuilder, ECL};
+
+    assert!(matches!(
+        QRBuilder::new("").build(),
+        Err(QRCodeError::EmptyPayload)
+    ));
+    assert!(matches!(
+        QRBuilder::new("").mode(Mode::Numeric).build(),
+        Err(QRCodeError::EmptyPayload)
+    ));
+
+    for ecl in [ECL::L, ECL::M, ECL::Q, ECL::H] {
+        let qrcode = QRBuilder::new("")
+            .allow_empty(true)
+            .ecl(ecl)
+            .build_verified()
+            .unwrap();
+        assert_eq!(qrcode.mode, Some(Mode::Byte));
+        assert_eq!(qrcode.size, 21);
+        assert!(decode::decode(&qrcode).unwrap().data.is_empty());
+    }
+
+    // A forced mode is kept
+    let qrcode = QRBuilder::new("")
+        .allow_empty(true)
+        .mode(Mode::Numeric)
+        .build()
+        .unwrap();
+    assert_eq!(qrcode.mode, Some(Mode::Numeric));
+}
+
+#[test]
+fn oversized_payload_limits() {
+    use crate::qr::QRCodeError;
+    use crate::{QRBatchBuilder, QRBuilder, ECL};
+
+    for (mode, max) in [
+        (Mode::Numeric, 7089),
+        (Mode::Alphanumeric, 4296),
+        (Mode::Byte, 2953),
+    ] {
+        assert_eq!(mode.max_len(ECL::L), max);
+
+        let fits = QRBuilder::new("1".repeat(max))
+            .mode(mode)
+            .ecl(ECL::L)
+            .build();
+        assert!(fits.is_ok());
+
+        match QRBuilder::new("1".repeat(max + 1))
+            .mode(mode)
+            .ecl(ECL::L)
+            .build()
+        {
+            Err(QRCodeError::EncodedData(overflow)) => {
+                assert_eq!(overflow.mode, mode);
+                assert_eq!(overflow.capacity, max);
+                assert_eq!(overflow.excess(), 1);
+            }
+            _ => panic!("{mode:?} accepts {} characters", max + 1),
+        }
+    }
+
+    // Too long for any mode, rejected before the mode is detected
+    let input = vec![0xFF; 1 << 20];
+    match QRBuilder::new(input.clone()).ecl(ECL::L).build() {
+        Err(QRCodeError::EncodedData(overflow)) => {
+            assert_eq!(overflow.mode, Mode::Numeric);
+            assert_eq!(overflow.capacity, 7089);
+            assert_eq!(overflow.len, 1 << 20);
+        }
+        _ => unreachable!(),
+    }
+    match QRBatchBuilder::new([input]).ecl(ECL::L).build() {
+        Err(QRCodeError::EncodedData(overflow)) => assert_eq!(overflow.capacity, 7089),
+        _ => unreachable!(),
+    }
+
+    // Constant time encoding uses byte mode
+    match QRBuilder::new("1".repeat(2954))
+        .ecl(ECL::L)
+        .constant_time(true)
+        .build()
+    {
+        Err(QRCodeError::EncodedData(overflow)) => assert_eq!(overflow.mode, Mode::Byte),
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn forced_mode_rejects_characters() {
+    use crate::qr::QRCodeError;
+    use crate::QRBuilder;
+
+    for (mode, input, index) in [
+        (Mode::Numeric, "12a45", 2),
+        (Mode::Alphanumeric, "https://example.com/", 0),
+    ] {
+        assert!(!mode.can_encode(input.as_bytes()));
+        match QRBuilder::new(input).mode(mode).build() {
+            Err(QRCodeError::InvalidMode(error)) => {
+                assert_eq!(error.mode, mode);
+                assert_eq!(error.index, index);
+                assert_eq!(error.byte, input.as_bytes()[index]);
+            }
+            _ => panic!("{input} encoded in {mode:?} mode"),
+        }
+    }
+
+    // Remaining 1 or 2 digits or characters after the groups
+    for input in ["1", "12", "1234", "12345"] {
+        let qrcode = QRBuilder::new(input).mode(Mode::Numeric).build_verified();
+        assert!(qrcode.is_ok(), "{input}");
+    }
+    for input in ["A", "AB", "ABC"] {
+        let qrcode = QRBuilder::new(input)
+            .mode(Mode::Alphanumeric)
+            .build_verified();
+        assert!(qrcode.is_ok(), "{input}");
+    }
+}
+
+#[test]
+fn encoders_reject_invalid_characters() {
+    let mut compact = CompactQR::from_version(crate::Version::V01);
+
+    let error = encode::encode_numeric(&mut compact, b"0123456789/", 10).unwrap_err();
+    assert_eq!(
+        (error.mode, error.index, error.byte),
+        (Mode::Numeric, 10, b'/')
+    );
+    let error = encode::encode_alphanumeric(&mut compact, b"FAST-qr", 9).unwrap_err();
+    assert_eq!((error.index, error.byte), (5, b'q'));
+    // Odd length, the last byte has no pair
+    let error = encode::encode_kanji(&mut compact, &[0x93, 0x5F, 0x93], 8).unwrap_err();
+    assert_eq!((error.index, error.byte), (2, 0x93));
+
+    // Nothing is pushed before the error
+    assert_eq!(compact.len(), 0);
+
+    assert_eq!(
+        error.to_string(),
+        "Byte 0x93 at index 2 cannot be encoded in Kanji mode"
+    );
+}
+
+#[test]
+fn terminator_on_full_capacity() {
+    let mut compact = CompactQR::from_version(crate::Version::V01);
+    compact.push_bits(0, 20);
+
+    // Data already past the capacity gets no terminator instead of underflowing
+    encode::add_terminator(&mut compact, 16);
+    assert_eq!(compact.len(), 20);
+    encode::add_terminator(&mut compact, 22);
+    assert_eq!(compact.len(), 22);
+}
+
+#[test]
+fn panic_free_accessors() {
+    use crate::{ModuleType, QRBuilder};
+
+    let qrcode = QRBuilder::new("panic").build().unwrap();
+    assert_eq!(qrcode.try_get(3, 20), Some(qrcode.get(3, 20)));
+    assert_eq!(qrcode.try_get(21, 0), None);
+    assert_eq!(qrcode.try_get(0, usize::MAX), None);
+
+    // Only the 3 low bits are read
+    assert_eq!(ModuleType::from(1), ModuleType::FinderPattern);
+    assert_eq!(ModuleType::from(0b1111_1001), ModuleType::FinderPattern);
+}
+
+/// "点茗" in Shift JIS, the example of section 7.4.6 of the spec
+const SJIS: [u8; 4] = [0x93, 0x5F, 0xE4, 0xAA];
+
+#[test]
+fn best_encoding_kanji() {
+    assert_eq!(encode::best_encoding(&SJIS), Mode::Kanji);
+    // Odd length or outside of the Kanji ranges
+    assert_eq!(encode::best_encoding(&SJIS[..3]), Mode::Byte);
+    assert_eq!(encode::best_encoding(&[0x93, 0x5F, 0x20, 0x20]), Mode::Byte);
+    // "こん" in UTF-8 also reads as three valid Shift JIS pairs
+    let utf8 = "こん".as_bytes();
+    assert!(Mode::Kanji.can_encode(utf8));
+    let expected = if cfg!(feature = "kanji") {
+        Mode::Kanji
+    } else {
+        Mode::Byte
+    };
+    assert_eq!(encode::best_encoding(utf8), expected);
+}
+
+#[test]
+fn kanji_capacity() {
+    use crate::{Version, ECL};
+
+    // Lengths in bytes, two per character
+    assert!(matches!(
+        Version::get(Mode::Kanji, ECL::L, 20),
+        Some(Version::V01)
+    ));
+    assert!(matches!(
+        Version::get(Mode::Kanji, ECL::L, 22),
+        Some(Version::V02)
+    ));
+    assert!(matches!(
+        Version::get(Mode::Kanji, ECL::H, 8),
+        Some(Version::V01)
+    ));
+    assert!(matches!(
+        Version::get(Mode::Kanji, ECL::H, 10),
+        Some(Version::V02)
+    ));
+    assert!(Version::get(Mode::Kanji, ECL::L, 3636).is_none());
+    assert_eq!(Mode::Kanji.max_len(ECL::L), 1817 * 2);
+    assert_eq!(cci_bits(Version::V27, Mode::Kanji), 12);
+}
+
+#[test]
+fn kanji_round_trip() {
+    use crate::decode::decode;
+    use crate::QRBuilder;
+
+    let qrcode = QRBuilder::new(SJIS).build().unwrap();
+    assert_eq!(qrcode.mode, Some(Mode::Kanji));
+    assert_eq!(decode(&qrcode).unwrap().data, SJIS);
+
+    let err = QRBuilder::new("ab").mode(Mode::Kanji).build().unwrap_err();
+    assert!(matches!(err, crate::qr::QRCodeError::InvalidMode(_)));
+}
+
+#[cfg(feature = "kanji")]
+#[test]
+fn kanji_from_utf8() {
+    use crate::decode::decode;
+    use crate::QRBuilder;
+
+    let qrcode = QRBuilder::new("点茗").build_verified().unwrap();
+    assert_eq!(qrcode.mode, Some(Mode::Kanji));
+    assert_eq!(decode(&*qrcode).unwrap().data, SJIS);
+
+    // A single-byte character keeps the whole text in byte mode
+    let qrcode = QRBuilder::new("点茗 A").build().unwrap();
+    assert_eq!(qrcode.mode, Some(Mode::Byte));
+}
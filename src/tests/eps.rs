@@ -0,0 +1,68 @@
+use crate::convert::eps::{EpsBuilder, EpsColor, EpsUnit};
+use crate::QRBuilder;
+
+#[test]
+fn eps_header() {
+    let qrcode = QRBuilder::new("eps").build().unwrap();
+    let eps = EpsBuilder::default().to_str(&qrcode);
+
+    assert!(eps.starts_with("%!PS-Adobe-3.0 EPSF-3.0\n"));
+    assert!(eps.ends_with("showpage\n%%EOF\n"));
+    // 30mm are 85.0394pt, rounded up to contain the drawing
+    assert!(eps.contains("\n%%BoundingBox: 0 0 86 86\n"));
+    assert!(eps.contains("\n%%HiResBoundingBox: 0 0 85.0394 85.0394\n"));
+    assert_eq!(
+        eps.matches("gsave").count(),
+        eps.matches("grestore").count()
+    );
+}
+
+#[test]
+fn eps_units() {
+    let qrcode = QRBuilder::new("eps").build().unwrap();
+
+    assert_eq!(EpsUnit::Inch.to_points(1.0), 72.0);
+    assert_eq!(EpsUnit::Millimeter.to_points(25.4), 72.0);
+    assert_eq!(EpsUnit::Point.to_points(72.0), 72.0);
+
+    // 29 modules of 3pt, centered in a 2 x 1.5 inches box
+    let eps = EpsBuilder::default()
+        .unit(EpsUnit::Point)
+        .width(87.0)
+        .to_str(&qrcode);
+    assert!(eps.contains("\n%%BoundingBox: 0 0 87 87\n"));
+    assert!(eps.contains("\n0 87 translate\n3 -3 scale\n"));
+
+    let eps = EpsBuilder::default()
+        .unit(EpsUnit::Inch)
+        .width(1.0)
+        .bounding_box(2.0, 1.5)
+        .to_str(&qrcode);
+    assert!(eps.contains("\n%%BoundingBox: 0 0 144 108\n"));
+    assert!(eps.contains("\n36 90 translate\n"));
+}
+
+#[test]
+fn eps_modules() {
+    let qrcode = QRBuilder::new("eps").build().unwrap();
+    let eps = EpsBuilder::default()
+        .margin(0)
+        .module_color(EpsColor::Cmyk([0, 0, 0, 100]))
+        .background_color(EpsColor::Gray(255))
+        .to_str(&qrcode);
+
+    assert!(
+        eps.contains("\n1 setgray\n0 0 21 21 rectfill\n0 0 0 1 setcmykcolor\n0 0 7 1 rectfill\n")
+    );
+
+    let dark: usize = eps
+        .lines()
+        .filter(|line| line.ends_with(" 1 rectfill"))
+        .map(|line| line.split(' ').nth(2).unwrap().parse::<usize>().unwrap())
+        .sum();
+    let expected = (0..qrcode.size)
+        .flat_map(|y| (0..qrcode.size).map(move |x| (y, x)))
+        .filter(|&(y, x)| qrcode[y][x].value())
+        .count();
+    assert_eq!(dark, expected);
+}
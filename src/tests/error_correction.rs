@@ -129,3 +129,35 @@ fn division_small_1() {
     let a = polynomials::division(&[32, 9], &[0, 0]);
     assert_eq!(&a[254..], &[41])
 }
+
+#[test]
+fn structure_large_versions_blocks() {
+    // Covers the blocks divided in parallel with the `rayon` feature
+    for number in 30..=40 {
+        let version = Version::from_number(number).unwrap();
+        for quality in [ECL::L, ECL::M, ECL::Q, ECL::H] {
+            let data_len = hardcode::data_codewords(version, quality);
+            let data: Vec<u8> = (0..data_len).map(|i| (i * 31 + 7) as u8).collect();
+            let structure = polynomials::structure(&data, quality, version);
+
+            let error = hardcode::get_polynomial(version, quality);
+            let [(g1_count, g1_size), (g2_count, g2_size)] =
+                hardcode::ecc_to_groups(quality, version);
+            let blocks = g1_count + g2_count;
+
+            let mut start = 0;
+            for i in 0..blocks {
+                let size = if i < g1_count { g1_size } else { g2_size };
+                let div = polynomials::division(&data[start..start + size], error);
+                start += size;
+
+                for j in 0..error.len() - 1 {
+                    assert_eq!(
+                        structure[data_len + j * blocks + i],
+                        div[256 - error.len() + j]
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use crate::decode::decode;
+use crate::exhaustive::{for_each_configuration, CONFIGURATIONS, PAYLOAD};
+
+#[test]
+fn every_configuration_once_and_reads_back() {
+    let mut seen = Vec::with_capacity(CONFIGURATIONS);
+    for_each_configuration(|qrcode| {
+        let version = qrcode.version.unwrap().number() - 1;
+        let ecl = qrcode.ecl.unwrap() as usize;
+        let mask = qrcode.mask.unwrap().pattern();
+        seen.push((version * 4 + ecl) * 8 + mask);
+
+        let label = format!("{:?} {:?} {:?}", qrcode.version, qrcode.ecl, qrcode.mask);
+        assert_eq!(qrcode.size, version * 4 + 21, "{label}");
+        assert_eq!(qrcode.check_invariants(), Ok(()), "{label}");
+
+        let decoded = decode(qrcode).unwrap();
+        assert_eq!(decoded.data, PAYLOAD.as_bytes(), "{label}");
+        assert_eq!(decoded.corrected_codewords, 0, "{label}");
+        assert_eq!(decoded.ecl as usize, ecl, "{label}");
+        assert_eq!(decoded.mask.pattern(), mask, "{label}");
+    });
+
+    // In order: version, then level, then mask
+    assert_eq!(seen, (0..CONFIGURATIONS).collect::<Vec<_>>());
+}
@@ -0,0 +1,58 @@
+use crate::export::{write_packed, PackedFile};
+use crate::matrix::Matrix;
+use crate::{QRBuilder, QRMatrix, Version};
+
+fn temp_file(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("fast_qr_{}_{name}", std::process::id()))
+}
+
+#[test]
+fn packed_file_round_trip() {
+    let versions = [Version::V01, Version::V10, Version::V40, Version::V02];
+    let matrices: Vec<QRMatrix> = (0..400)
+        .map(|i| {
+            let qrcode = QRBuilder::new(format!("code {i}"))
+                .version(versions[i % versions.len()])
+                .build()
+                .unwrap();
+            QRMatrix::from(&qrcode)
+        })
+        .collect();
+
+    // Version 40 codes make the file grow past its first mapping
+    let path = temp_file("round_trip.fqrm");
+    write_packed(&path, matrices.clone()).unwrap();
+
+    let file = PackedFile::open(&path).unwrap();
+    assert_eq!(file.len(), matrices.len());
+    for (i, matrix) in matrices.iter().enumerate() {
+        assert_eq!(file.get(i).as_ref(), Some(matrix));
+    }
+    assert!(file.get(matrices.len()).is_none());
+
+    let (size, bytes) = file.get_bytes(2).unwrap();
+    assert_eq!(size, matrices[2].size());
+    assert_eq!(bytes, matrices[2].as_bytes());
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn packed_file_rejects_invalid() {
+    let path = temp_file("invalid.fqrm");
+
+    std::fs::write(&path, b"not a packed file").unwrap();
+    assert!(PackedFile::open(&path).is_err());
+
+    // Claims a million codes without their index
+    let mut header = b"FQRM".to_vec();
+    header.extend_from_slice(&1u32.to_le_bytes());
+    header.extend_from_slice(&1_000_000u64.to_le_bytes());
+    std::fs::write(&path, header).unwrap();
+    assert!(PackedFile::open(&path).is_err());
+
+    write_packed(&path, Vec::new()).unwrap();
+    assert!(PackedFile::open(&path).unwrap().is_empty());
+
+    std::fs::remove_file(path).unwrap();
+}
@@ -0,0 +1,145 @@
+use std::io::Read;
+
+use flate2::read::DeflateDecoder;
+use flate2::Crc;
+
+use crate::convert::bitmap::{BitmapBuilder, BitmapFormat};
+use crate::convert::html::HtmlBuilder;
+use crate::export::{zip_batch, ZipFormat};
+use crate::{QRBuilder, QRCode};
+
+fn u16_at(bytes: &[u8], at: usize) -> usize {
+    usize::from(u16::from_le_bytes([bytes[at], bytes[at + 1]]))
+}
+
+fn u32_at(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]])
+}
+
+/// Reads back every entry through the central directory, as unzip does
+fn unzip(archive: &[u8]) -> Vec<(String, Vec<u8>)> {
+    let end = archive.len() - 22;
+    assert_eq!(&archive[end..end + 4], b"PK\x05\x06");
+    let count = u16_at(archive, end + 10);
+    let mut at = u32_at(archive, end + 16) as usize;
+
+    let mut entries = Vec::new();
+    for _ in 0..count {
+        assert_eq!(&archive[at..at + 4], b"PK\x01\x02");
+        let method = u16_at(archive, at + 10);
+        let crc = u32_at(archive, at + 16);
+        let compressed_size = u32_at(archive, at + 20) as usize;
+        let size = u32_at(archive, at + 24) as usize;
+        let name_len = u16_at(archive, at + 28);
+        let offset = u32_at(archive, at + 42) as usize;
+        let name = String::from_utf8(archive[at + 46..at + 46 + name_len].to_vec()).unwrap();
+        at += 46 + name_len;
+
+        assert_eq!(&archive[offset..offset + 4], b"PK\x03\x04");
+        assert_eq!(u32_at(archive, offset + 14), crc);
+        let start = offset + 30 + u16_at(archive, offset + 26);
+        let stored = &archive[start..start + compressed_size];
+        let data = match method {
+            0 => stored.to_vec(),
+            8 => {
+                let mut data = Vec::new();
+                DeflateDecoder::new(stored).read_to_end(&mut data).unwrap();
+                data
+            }
+            _ => panic!("Unknown method {method}"),
+        };
+
+        assert_eq!(data.len(), size);
+        let mut check = Crc::new();
+        check.update(&data);
+        assert_eq!(check.sum(), crc);
+        entries.push((name, data));
+    }
+    assert_eq!(at, end);
+
+    entries
+}
+
+fn codes() -> Vec<QRCode> {
+    (0..5)
+        .map(|i| {
+            QRBuilder::new(format!("https://example.com/{i}"))
+                .build()
+                .unwrap()
+        })
+        .collect()
+}
+
+#[test]
+fn zip_batch_round_trip() {
+    let codes = codes();
+    let bitmap = BitmapBuilder::default();
+    let format = ZipFormat::Bitmap(&bitmap, BitmapFormat::Pgm);
+
+    let archive = zip_batch(
+        Vec::new(),
+        codes.iter().cloned(),
+        |i, _| format!("codes/{i}"),
+        format,
+    )
+    .unwrap();
+
+    let entries = unzip(&archive);
+    assert_eq!(entries.len(), codes.len());
+    for (i, ((name, data), qrcode)) in entries.iter().zip(&codes).enumerate() {
+        assert_eq!(name, &format!("codes/{i}.pgm"));
+        assert_eq!(data, &bitmap.to_bytes(qrcode, BitmapFormat::Pgm));
+    }
+}
+
+#[test]
+fn zip_batch_is_reproducible() {
+    let html = HtmlBuilder::default();
+    let archive = |codes: Vec<QRCode>| {
+        zip_batch(
+            Vec::new(),
+            codes,
+            |i, _| i.to_string(),
+            ZipFormat::Html(&html),
+        )
+        .unwrap()
+    };
+
+    assert_eq!(archive(codes()), archive(codes()));
+}
+
+#[test]
+fn zip_batch_empty() {
+    let bitmap = BitmapBuilder::default();
+    let archive = zip_batch(
+        Vec::new(),
+        Vec::<QRCode>::new(),
+        |i, _| i.to_string(),
+        ZipFormat::Bitmap(&bitmap, BitmapFormat::Pbm),
+    )
+    .unwrap();
+
+    assert_eq!(archive.len(), 22);
+    assert!(unzip(&archive).is_empty());
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn zip_batch_svg_names() {
+    use crate::convert::svg::SvgBuilder;
+
+    let svg = SvgBuilder::default();
+    let archive = zip_batch(
+        Vec::new(),
+        codes(),
+        |_, qrcode| format!("{}", qrcode.size),
+        ZipFormat::Svg(&svg),
+    )
+    .unwrap();
+
+    let entries = unzip(&archive);
+    let name = format!("{}.svg", codes()[0].size);
+    assert!(entries.iter().all(|(entry, _)| entry == &name));
+    // SVG is text, deflate shrinks it
+    assert_eq!(u16_at(&archive, 8), 8);
+}
@@ -0,0 +1,200 @@
+use crate::ffi::{
+    fastqr_v1_generate, FAST_QR_BUFFER_TOO_SMALL, FAST_QR_DATA_TOO_BIG, FAST_QR_ECL_M,
+    FAST_QR_INVALID_ECL, FAST_QR_INVALID_INPUT, FAST_QR_MAX_MODULES, FAST_QR_NULL_POINTER,
+    FAST_QR_OK,
+};
+use crate::{QRBuilder, ECL};
+
+#[test]
+fn generate_matches_builder() {
+    let content = b"https://example.com/";
+    let mut out = vec![0; FAST_QR_MAX_MODULES];
+    let mut size = out.len();
+
+    let code = unsafe {
+        fastqr_v1_generate(
+            content.as_ptr(),
+            content.len(),
+            FAST_QR_ECL_M,
+            out.as_mut_ptr(),
+            &mut size,
+        )
+    };
+    assert_eq!(code, FAST_QR_OK);
+
+    let qrcode = QRBuilder::new(&content[..]).ecl(ECL::M).build().unwrap();
+    assert_eq!(size, qrcode.size);
+    for (byte, module) in out.iter().zip(&qrcode.data[..size * size]) {
+        assert_eq!(*byte, u8::from(module.value()));
+    }
+}
+
+#[test]
+fn generate_errors() {
+    let content = b"Hello";
+    let mut out = [0; 16];
+    let mut size = out.len();
+    let generate = |ecl, out: &mut [u8], size: &mut usize| unsafe {
+        fastqr_v1_generate(content.as_ptr(), content.len(), ecl, out.as_mut_ptr(), size)
+    };
+
+    assert_eq!(generate(4, &mut out, &mut size), FAST_QR_INVALID_ECL);
+    assert_eq!(
+        generate(FAST_QR_ECL_M, &mut out, &mut size),
+        FAST_QR_BUFFER_TOO_SMALL
+    );
+    assert_eq!(size, 21 * 21);
+
+    let null = unsafe {
+        fastqr_v1_generate(
+            core::ptr::null(),
+            5,
+            FAST_QR_ECL_M,
+            out.as_mut_ptr(),
+            &mut size,
+        )
+    };
+    assert_eq!(null, FAST_QR_NULL_POINTER);
+
+    let big = vec![0; 4000];
+    let mut out = vec![0; FAST_QR_MAX_MODULES];
+    let mut size = out.len();
+    let too_big = unsafe {
+        fastqr_v1_generate(
+            big.as_ptr(),
+            big.len(),
+            FAST_QR_ECL_M,
+            out.as_mut_ptr(),
+            &mut size,
+        )
+    };
+    assert_eq!(too_big, FAST_QR_DATA_TOO_BIG);
+}
+
+#[test]
+fn generate_empty_content() {
+    let mut out = vec![0; FAST_QR_MAX_MODULES];
+    let mut size = out.len();
+
+    let code = unsafe {
+        fastqr_v1_generate(
+            core::ptr::null(),
+            0,
+            FAST_QR_ECL_M,
+            out.as_mut_ptr(),
+            &mut size,
+        )
+    };
+    assert_eq!(code, FAST_QR_OK);
+
+    let qrcode = QRBuilder::new("")
+        .ecl(ECL::M)
+        .allow_empty(true)
+        .build()
+        .unwrap();
+    assert_eq!(size, 21);
+    for (byte, module) in out.iter().zip(&qrcode.data[..size * size]) {
+        assert_eq!(*byte, u8::from(module.value()));
+    }
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn generate_svg() {
+    use crate::convert::svg::SvgBuilder;
+    use crate::ffi::fastqr_v1_generate_svg;
+
+    let content = b"Hello";
+    let generate = |out: &mut [u8], size: &mut usize| unsafe {
+        fastqr_v1_generate_svg(
+            content.as_ptr(),
+            content.len(),
+            FAST_QR_ECL_M,
+            out.as_mut_ptr().cast(),
+            size,
+        )
+    };
+
+    let mut size = 0;
+    assert_eq!(generate(&mut [], &mut size), FAST_QR_BUFFER_TOO_SMALL);
+
+    let mut out = vec![0; size];
+    assert_eq!(generate(&mut out, &mut size), FAST_QR_OK);
+
+    let qrcode = QRBuilder::new("Hello").ecl(ECL::M).build().unwrap();
+    let svg = SvgBuilder::default().to_str(&qrcode);
+    assert_eq!(&out[..size], svg.as_bytes());
+    assert_eq!(out[size], 0);
+}
+
+#[test]
+fn header_matches_abi() {
+    use crate::ffi::{
+        fastqr_v1_abi_version, FAST_QR_ABI_VERSION, FAST_QR_ECL_H, FAST_QR_ECL_L, FAST_QR_ECL_Q,
+    };
+
+    assert_eq!(fastqr_v1_abi_version(), FAST_QR_ABI_VERSION);
+
+    let header = include_str!("../../include/fast_qr.h");
+    let defines = [
+        ("FAST_QR_ABI_VERSION", i64::from(FAST_QR_ABI_VERSION)),
+        ("FAST_QR_OK", i64::from(FAST_QR_OK)),
+        ("FAST_QR_NULL_POINTER", i64::from(FAST_QR_NULL_POINTER)),
+        ("FAST_QR_INVALID_ECL", i64::from(FAST_QR_INVALID_ECL)),
+        ("FAST_QR_DATA_TOO_BIG", i64::from(FAST_QR_DATA_TOO_BIG)),
+        (
+            "FAST_QR_BUFFER_TOO_SMALL",
+            i64::from(FAST_QR_BUFFER_TOO_SMALL),
+        ),
+        ("FAST_QR_INVALID_INPUT", i64::from(FAST_QR_INVALID_INPUT)),
+        ("FAST_QR_ECL_L", i64::from(FAST_QR_ECL_L)),
+        ("FAST_QR_ECL_M", i64::from(FAST_QR_ECL_M)),
+        ("FAST_QR_ECL_Q", i64::from(FAST_QR_ECL_Q)),
+        ("FAST_QR_ECL_H", i64::from(FAST_QR_ECL_H)),
+    ];
+    for (name, value) in defines {
+        assert!(
+            header.contains(&format!("#define {name} {value}\n")),
+            "{name} is not {value} in the header"
+        );
+    }
+    assert_eq!(FAST_QR_MAX_MODULES, 177 * 177);
+    assert!(header.contains("#define FAST_QR_MAX_MODULES (177 * 177)\n"));
+
+    for function in [
+        "uint32_t fastqr_v1_abi_version(void);",
+        "int32_t fastqr_v1_generate(const uint8_t *content,",
+        "int32_t fastqr_v1_generate_svg(const uint8_t *content,",
+        "int32_t fast_qr_generate(const uint8_t *content,",
+        "int32_t fast_qr_generate_svg(const uint8_t *content,",
+    ] {
+        assert!(header.contains(function), "{function} is not in the header");
+    }
+}
+
+#[test]
+#[allow(deprecated)]
+fn unversioned_alias() {
+    use crate::ffi::fast_qr_generate;
+
+    let content = b"alias";
+    let (mut versioned, mut alias) = (vec![0; 441], vec![0; 441]);
+    let (mut versioned_size, mut alias_size) = (441, 441);
+    unsafe {
+        fastqr_v1_generate(
+            content.as_ptr(),
+            content.len(),
+            FAST_QR_ECL_M,
+            versioned.as_mut_ptr(),
+            &mut versioned_size,
+        );
+        fast_qr_generate(
+            content.as_ptr(),
+            content.len(),
+            FAST_QR_ECL_M,
+            alias.as_mut_ptr(),
+            &mut alias_size,
+        );
+    }
+    assert_eq!((versioned_size, &versioned), (alias_size, &alias));
+}
@@ -0,0 +1,108 @@
+use crate::compat::{LegacyCompat, MaskTieBreak, PadCodewords};
+use crate::datamasking::MaskScoring;
+use crate::{Mask, QRBuilder, Version, ECL};
+
+#[test]
+fn options_fingerprint_ignores_input() {
+    let a = QRBuilder::new("https://example.com/").options_fingerprint();
+    let b = QRBuilder::new("https://example.org/").options_fingerprint();
+    assert_eq!(a, b);
+
+    let a = QRBuilder::new("a")
+        .mask(Mask::Checkerboard)
+        .options_fingerprint();
+    #[allow(deprecated)]
+    let b = QRBuilder::new("b").mask_pattern(0).options_fingerprint();
+    assert_eq!(a, b);
+}
+
+#[test]
+fn options_fingerprint_changes_with_options() {
+    let default = QRBuilder::new("fast_qr").options_fingerprint();
+    let changed = [
+        QRBuilder::new("fast_qr").ecl(ECL::H).options_fingerprint(),
+        QRBuilder::new("fast_qr")
+            .version(Version::V05)
+            .options_fingerprint(),
+        QRBuilder::new("fast_qr")
+            .mask(Mask::LargeCheckerboard)
+            .options_fingerprint(),
+        QRBuilder::new("fast_qr")
+            .mask_scoring(MaskScoring::DarkRatio)
+            .options_fingerprint(),
+        QRBuilder::new("fast_qr")
+            .legacy_compat(LegacyCompat {
+                mask_tie_break: MaskTieBreak::Last,
+                padding: PadCodewords::Alternating,
+            })
+            .options_fingerprint(),
+        QRBuilder::new("fast_qr")
+            .constant_time(true)
+            .options_fingerprint(),
+        QRBuilder::new("fast_qr")
+            .max_version(Version::V10)
+            .options_fingerprint(),
+    ];
+
+    for (i, fingerprint) in changed.iter().enumerate() {
+        assert_ne!(default, *fingerprint, "option {i}");
+        assert!(changed[..i].iter().all(|other| other != fingerprint));
+    }
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn svg_fingerprint_attribute() {
+    use crate::convert::{svg::SvgBuilder, Builder, Shape};
+
+    let builder = QRBuilder::new("https://example.com/");
+    let qrcode = builder.build().unwrap();
+    let qr_options = builder.options_fingerprint();
+
+    let svg = SvgBuilder::default().to_str(&qrcode);
+    assert!(!svg.contains("data-fingerprint"));
+
+    let mut svg_builder = SvgBuilder::default();
+    let svg = svg_builder.fingerprint(qr_options).to_str(&qrcode);
+    let expected = format!(
+        r#"data-fingerprint="{:016x}""#,
+        svg_builder.output_fingerprint(qr_options)
+    );
+    assert!(svg.starts_with("<svg "));
+    assert!(svg.contains(&expected));
+
+    let square = SvgBuilder::default().options_fingerprint();
+    let circle = SvgBuilder::default()
+        .shape(Shape::Circle)
+        .options_fingerprint();
+    let margin = SvgBuilder::default().margin(2).options_fingerprint();
+    assert_ne!(square, circle);
+    assert_ne!(square, margin);
+    assert_eq!(
+        circle,
+        SvgBuilder::default()
+            .shape(Shape::Circle)
+            .options_fingerprint()
+    );
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn png_text_chunk() {
+    use crate::fingerprint::png_with_text;
+
+    // Signature, then IHDR and IEND chunks of a 1x1 grayscale image, IDAT left out
+    let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+    png.extend_from_slice(&[
+        0, 0, 0, 1, 0, 0, 0, 1, 8, 0, 0, 0, 0, 0x3a, 0x7e, 0x9b, 0x55,
+    ]);
+    png.extend_from_slice(b"\0\0\0\0IEND\xae\x42\x60\x82");
+
+    let out = png_with_text(&png, "fingerprint", "0123456789abcdef");
+    assert_eq!(out.len(), png.len() + 12 + 28);
+    assert_eq!(&out[..33], &png[..33]);
+    assert_eq!(&out[33..41], b"\0\0\0\x1ctEXt");
+    assert_eq!(&out[41..69], b"fingerprint\x000123456789abcdef");
+    assert_eq!(&out[69..73], &0xbb6a_afa9_u32.to_be_bytes());
+    assert_eq!(&out[73..], &png[33..]);
+}
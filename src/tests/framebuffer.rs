@@ -0,0 +1,143 @@
+use crate::framebuffer::{PixelFormat, RawRenderer, RenderError};
+use crate::matrix::QRMatrix;
+use crate::QRBuilder;
+
+#[test]
+fn render_into_draws_modules_and_quiet_zone() {
+    let matrix = QRMatrix::parse_ascii(
+        "
+        #.
+        .#
+        ",
+    )
+    .unwrap();
+    let renderer = *RawRenderer::new([10, 20, 30, 40], [200, 210, 220, 230]).margin(1);
+    assert_eq!(renderer.size(&matrix, 2), 8);
+
+    // 10 pixels wide, 2 bytes of padding per row
+    let stride = 10 * 4 + 2;
+    let mut buffer = vec![7; stride * 10];
+    renderer
+        .render_into(
+            &matrix,
+            &mut buffer,
+            stride,
+            PixelFormat::Rgba8888,
+            2,
+            (1, 1),
+        )
+        .unwrap();
+
+    let pixel = |x: usize, y: usize| &buffer[y * stride + x * 4..][..4];
+    let (dark, light) = ([10, 20, 30, 40], [200, 210, 220, 230]);
+    // Untouched around the drawn area
+    assert_eq!(pixel(0, 0), [7; 4]);
+    assert_eq!(pixel(9, 9), [7; 4]);
+    assert_eq!(&buffer[stride - 2..stride], [7, 7]);
+    // Quiet zone, then the modules from (3, 3)
+    assert_eq!(pixel(1, 1), light);
+    assert_eq!(pixel(2, 3), light);
+    for (x, y) in [(3, 3), (4, 4), (5, 5), (6, 6)] {
+        assert_eq!(pixel(x, y), dark);
+    }
+    for (x, y) in [(5, 3), (6, 4), (3, 5), (4, 6), (8, 8)] {
+        assert_eq!(pixel(x, y), light);
+    }
+}
+
+#[test]
+fn render_into_pixel_formats() {
+    let matrix = QRMatrix::parse_ascii("#").unwrap();
+    let mut renderer = RawRenderer::new([255, 128, 0, 255], [0; 4]);
+    renderer.margin(0);
+
+    let cases: [(PixelFormat, &[u8]); 4] = [
+        (PixelFormat::Rgba8888, &[255, 128, 0, 255]),
+        (PixelFormat::Bgra8888, &[0, 128, 255, 255]),
+        (PixelFormat::Rgb888, &[255, 128, 0]),
+        (PixelFormat::Gray8, &[151]),
+    ];
+    for (format, bytes) in cases {
+        let mut buffer = [0; 4];
+        let bpp = format.bytes_per_pixel();
+        renderer
+            .render_into(&matrix, &mut buffer[..bpp], bpp, format, 1, (0, 0))
+            .unwrap();
+        assert_eq!(&buffer[..bpp], bytes);
+    }
+}
+
+#[test]
+fn render_into_checks_bounds() {
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    let renderer = RawRenderer::default();
+    let size = renderer.size(&qrcode, 3);
+    let stride = size * 4;
+    let mut buffer = vec![0; stride * size];
+
+    assert_eq!(
+        renderer.render_into(
+            &qrcode,
+            &mut buffer,
+            stride,
+            PixelFormat::Rgba8888,
+            3,
+            (1, 0)
+        ),
+        Err(RenderError::StrideTooSmall)
+    );
+    assert_eq!(
+        renderer.render_into(
+            &qrcode,
+            &mut buffer,
+            stride,
+            PixelFormat::Rgba8888,
+            3,
+            (0, 1)
+        ),
+        Err(RenderError::BufferTooSmall)
+    );
+    assert_eq!(
+        renderer.render_into(
+            &qrcode,
+            &mut buffer,
+            stride,
+            PixelFormat::Rgba8888,
+            3,
+            (0, usize::MAX)
+        ),
+        Err(RenderError::BufferTooSmall)
+    );
+    assert!(buffer.iter().all(|&byte| byte == 0));
+
+    // The last byte of the code is the last byte of the buffer
+    renderer
+        .render_into(
+            &qrcode,
+            &mut buffer,
+            stride,
+            PixelFormat::Rgba8888,
+            3,
+            (0, 0),
+        )
+        .unwrap();
+    assert_eq!(buffer[buffer.len() - 4..], [255; 4]);
+
+    // Same pixels as the matrix, one byte per module
+    let mut gray = vec![0; qrcode.size * qrcode.size];
+    RawRenderer::default()
+        .margin(0)
+        .render_into(
+            &qrcode,
+            &mut gray,
+            qrcode.size,
+            PixelFormat::Gray8,
+            1,
+            (0, 0),
+        )
+        .unwrap();
+    for (i, &byte) in gray.iter().enumerate() {
+        let module = qrcode[i / qrcode.size][i % qrcode.size];
+        assert_eq!(byte, if module.value() { 0 } else { 255 });
+    }
+}
@@ -0,0 +1,39 @@
+use crate::galois::{self, ecc_codewords, multiply, remainder};
+use crate::{hardcode, polynomials, Version, ECL};
+
+#[test]
+fn remainder_matches_division() {
+    let block: Vec<u8> = (0..122u8).map(|n| n.wrapping_mul(37)).collect();
+
+    for version in Version::iter() {
+        for ecl in [ECL::L, ECL::M, ECL::Q, ECL::H] {
+            let generator = hardcode::get_polynomial(version, ecl);
+            let count = ecc_codewords(version, ecl);
+
+            let division = polynomials::division(&block, generator);
+            let remainder = remainder(&block, version, ecl);
+
+            assert_eq!(remainder[..count], division[255 - count..]);
+            assert!(remainder[count..].iter().all(|&codeword| codeword == 0));
+        }
+    }
+}
+
+#[test]
+fn multiply_field() {
+    for a in 0..=255u8 {
+        assert_eq!(multiply(a, 0), 0);
+        assert_eq!(multiply(a, 1), a);
+        for b in [2, 29, 142, 255] {
+            assert_eq!(multiply(a, b), multiply(b, a));
+        }
+    }
+    // x^7 * x = x^8 = x^4 + x^3 + x^2 + 1
+    assert_eq!(multiply(0x80, 2), 0x1D);
+}
+
+#[test]
+fn remainder_is_const() {
+    const ECC: [u8; galois::MAX_ECC_CODEWORDS] = remainder(&[0; 19], Version::V01, ECL::L);
+    assert!(ECC.iter().all(|&codeword| codeword == 0));
+}
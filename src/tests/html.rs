@@ -0,0 +1,78 @@
+use crate::convert::html::{HtmlBuilder, HtmlLayout};
+use crate::QRBuilder;
+
+/// Widths of the cells of every row, in modules, dark cells negative
+fn rows(html: &str, row: &str, cell: &str) -> Vec<Vec<isize>> {
+    html.split(row)
+        .skip(1)
+        .map(|row| {
+            row.split(cell)
+                .skip(1)
+                .map(|cell| {
+                    let width: isize = cell
+                        .split("width:")
+                        .nth(1)
+                        .unwrap()
+                        .split("px")
+                        .next()
+                        .unwrap()
+                        .parse()
+                        .unwrap();
+                    if cell.contains("background:#000000") {
+                        -width / 2
+                    } else {
+                        width / 2
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn html_cells_match_modules() {
+    let qrcode = QRBuilder::new("html").build().unwrap();
+    let mut builder = HtmlBuilder::default();
+    builder.cell_size(2).margin(1);
+
+    let table = builder.to_str(&qrcode);
+    assert!(table.starts_with(r#"<table role="presentation" width="46" "#));
+    assert!(table.ends_with("</tr></table>"));
+    // The top row of the finder pattern is a single cell
+    assert!(table.contains(r##"<td colspan="7" width="14" height="2" bgcolor="#000000""##));
+
+    let div = builder.layout(HtmlLayout::Div).to_str(&qrcode);
+    assert!(div.starts_with(r#"<div style="width:46px;"#));
+
+    let expected: Vec<Vec<isize>> = (0..23)
+        .map(|y| {
+            let mut row: Vec<isize> = Vec::new();
+            for x in 0..23 {
+                let dark =
+                    (1..22).contains(&y) && (1..22).contains(&x) && qrcode[y - 1][x - 1].value();
+                let step = if dark { -1 } else { 1 };
+                match row.last_mut() {
+                    Some(last) if (*last < 0) == dark => *last += step,
+                    _ => row.push(step),
+                }
+            }
+            row
+        })
+        .collect();
+
+    assert_eq!(rows(&table, "<tr", "<td"), expected);
+    assert_eq!(rows(&div, r#"<div style="height"#, "<span"), expected);
+}
+
+#[test]
+fn html_colors() {
+    let qrcode = QRBuilder::new("html").build().unwrap();
+    let html = HtmlBuilder::default()
+        .module_color("#123456")
+        .background_color([255, 0, 0, 255])
+        .to_str(&qrcode);
+
+    assert!(html.contains(r##"bgcolor="#123456" style="##));
+    assert!(html.contains("background:#ff0000"));
+    assert!(!html.contains("#000000"));
+}
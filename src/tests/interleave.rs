@@ -0,0 +1,66 @@
+use crate::interleave::{data_blocks, ecc_blocks, interleave};
+use crate::{hardcode, polynomials, Version, ECL};
+
+#[test]
+fn codeword_counts_match_tables() {
+    for version in Version::iter() {
+        for ecl in [ECL::L, ECL::M, ECL::Q, ECL::H] {
+            let data_count = hardcode::data_codewords(version, ecl);
+            let data: Vec<u8> = (0..data_count).map(|i| (i * 7) as u8).collect();
+
+            let blocks = data_blocks(&data, version, ecl);
+            let [(g1_count, _), (g2_count, _)] = hardcode::ecc_to_groups(ecl, version);
+            assert_eq!(blocks.len(), g1_count + g2_count);
+            assert_eq!(blocks.iter().map(|b| b.len()).sum::<usize>(), data_count);
+
+            let ecc = ecc_blocks(&blocks, version, ecl);
+            let codewords = interleave(&blocks, &ecc, version);
+            assert_eq!(codewords.len(), version.max_bytes(), "{version:?} {ecl:?}");
+
+            let structure = polynomials::structure(&data, ecl, version);
+            assert_eq!(codewords[..], structure[..version.max_bytes()]);
+        }
+    }
+}
+
+#[test]
+fn interleave_short_blocks_first() {
+    let data = [[1, 2], [3, 4]];
+    let longer = [[5, 6, 7]];
+    let blocks: Vec<&[u8]> = vec![&data[0], &data[1], &longer[0]];
+    let ecc = [[8], [9], [10]];
+
+    assert_eq!(
+        interleave(&blocks, &ecc, Version::V01),
+        [1, 3, 5, 2, 4, 6, 7, 8, 9, 10]
+    );
+}
+
+#[test]
+fn codewords_read_back_from_matrix() {
+    use crate::QRBuilder;
+
+    for version in [Version::V01, Version::V05, Version::V14, Version::V40] {
+        for ecl in [ECL::L, ECL::M, ECL::Q, ECL::H] {
+            let qrcode = QRBuilder::new("fast_qr")
+                .version(version)
+                .ecl(ecl)
+                .build()
+                .unwrap();
+
+            let codewords = qrcode.codewords().unwrap();
+            let blocks = qrcode.blocks().unwrap();
+            let data: Vec<&[u8]> = blocks.iter().map(|block| &block.data[..]).collect();
+            let ecc: Vec<&[u8]> = blocks.iter().map(|block| &block.ecc[..]).collect();
+
+            assert_eq!(interleave(&data, &ecc, version), codewords);
+            assert_eq!(ecc_blocks(&data, version, ecl), ecc);
+            assert_eq!(data.concat().len(), hardcode::data_codewords(version, ecl));
+        }
+    }
+
+    let mut unset = QRBuilder::new("https://example.com/").build().unwrap();
+    unset.mask = None;
+    assert!(unset.codewords().is_none());
+    assert!(unset.blocks().is_none());
+}
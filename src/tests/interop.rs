@@ -0,0 +1,74 @@
+use core::convert::TryFrom;
+
+use crate::{QRCode, Version, ECL};
+
+#[cfg(feature = "qrcode")]
+#[test]
+fn from_qrcode_crate() {
+    use qrcode::{Color, EcLevel};
+
+    let theirs =
+        qrcode::QrCode::with_version(b"interop", qrcode::Version::Normal(7), EcLevel::H).unwrap();
+    let ours = QRCode::try_from(&theirs).unwrap();
+
+    assert_eq!(ours.size, theirs.width());
+    assert!(matches!(ours.version, Some(Version::V07)));
+    assert!(matches!(ours.ecl, Some(ECL::H)));
+    assert_eq!(Vec::<Color>::from(&ours), theirs.to_colors());
+    assert_eq!(
+        ours.count_by_type().total(),
+        ours.size * ours.size,
+        "module types follow the layout"
+    );
+
+    let micro = qrcode::QrCode::with_version(b"1", qrcode::Version::Micro(1), EcLevel::L).unwrap();
+    assert_eq!(
+        QRCode::try_from(&micro).err(),
+        Some(crate::interop::InteropError::MicroQr)
+    );
+}
+
+#[cfg(feature = "qrcodegen")]
+#[test]
+fn qrcodegen_round_trip() {
+    use qrcodegen::QrCodeEcc;
+
+    use crate::QRBuilder;
+
+    for (content, ecl) in [
+        ("HELLO WORLD", ECL::L),
+        ("https://example.com/interop", ECL::Q),
+        ("1234567890", ECL::H),
+    ] {
+        for version in [Version::V03, Version::V10, Version::V27] {
+            let ours = QRBuilder::new(content)
+                .ecl(ecl)
+                .version(version)
+                .build()
+                .unwrap();
+            let theirs = qrcodegen::QrCode::try_from(&ours).unwrap();
+
+            assert_eq!(theirs.size() as usize, ours.size);
+            assert_eq!(theirs.mask().value() as usize, ours.mask.unwrap().pattern());
+            for y in 0..ours.size {
+                for x in 0..ours.size {
+                    assert_eq!(theirs.get_module(x as i32, y as i32), ours[y][x].value());
+                }
+            }
+
+            let back = QRCode::from(&theirs);
+            assert_eq!(back.data[..], ours.data[..]);
+        }
+    }
+
+    // `encode_text` raises the ECL while the version stays the same
+    let theirs = qrcodegen::QrCode::encode_text("Hello", QrCodeEcc::Medium).unwrap();
+    let ours = QRCode::from(&theirs);
+    assert!(matches!(ours.ecl, Some(ECL::H)));
+    assert_eq!(ours.mask.unwrap().pattern(), theirs.mask().value() as usize);
+
+    assert_eq!(
+        qrcodegen::QrCode::try_from(&QRCode::default(21)).err(),
+        Some(crate::interop::InteropError::Incomplete)
+    );
+}
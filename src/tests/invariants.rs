@@ -0,0 +1,82 @@
+use crate::qr::InvariantError;
+use crate::{Mask, QRBuilder, Version, ECL};
+
+#[test]
+fn built_codes_pass_invariants() {
+    for number in 1..=40 {
+        let version = Version::from_number(number).unwrap();
+        for ecl in [ECL::L, ECL::M, ECL::Q, ECL::H] {
+            let qrcode = QRBuilder::new("fast_qr")
+                .version(version)
+                .ecl(ecl)
+                .build()
+                .unwrap();
+            assert_eq!(qrcode.check_invariants(), Ok(()), "{version:?} {ecl:?}");
+        }
+    }
+}
+
+#[test]
+fn broken_codes_fail_invariants() {
+    let qrcode = QRBuilder::new("https://example.com/")
+        .mask(Mask::Diamonds)
+        .build()
+        .unwrap();
+
+    let mut unset = qrcode.clone();
+    unset.mask = None;
+    assert_eq!(unset.check_invariants(), Err(InvariantError::Unset));
+
+    let mut resized = qrcode.clone();
+    resized.size += 4;
+    assert_eq!(resized.check_invariants(), Err(InvariantError::Size));
+
+    // Format information announcing another mask
+    let mut remasked = qrcode.clone();
+    remasked.mask = Some(Mask::Meadow);
+    assert!(matches!(
+        remasked.check_invariants(),
+        Err(InvariantError::Module { .. })
+    ));
+
+    // Timing pattern
+    let mut timing = qrcode.clone();
+    timing[6][9].toggle();
+    assert_eq!(
+        timing.check_invariants(),
+        Err(InvariantError::Module { y: 6, x: 9 })
+    );
+
+    // Data modules are free
+    let mut data = qrcode;
+    let last = data.size - 1;
+    data[last][last].toggle();
+    assert_eq!(data.check_invariants(), Ok(()));
+}
+
+#[cfg(feature = "arbitrary")]
+#[test]
+fn arbitrary_builders_pass_invariants() {
+    use arbitrary::{Arbitrary, Unstructured};
+
+    // Deterministic bytes standing in for a fuzzer corpus
+    let mut state: u32 = 0x1234_5678;
+    let mut bytes = [0; 96];
+    let mut built = 0;
+    for _ in 0..300 {
+        for byte in &mut bytes {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            *byte = state as u8;
+        }
+
+        let builder = QRBuilder::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
+        if let Ok(qrcode) = builder.build() {
+            assert_eq!(qrcode.check_invariants(), Ok(()));
+            built += 1;
+        }
+    }
+
+    assert!(built > 0);
+}
@@ -0,0 +1,158 @@
+use crate::matrix::{AsciiError, Matrix, QRMatrix};
+use crate::{Module, QRBuilder, Version};
+
+#[test]
+fn matrices_of_several_versions() {
+    let matrices: Vec<QRMatrix> = [Version::V01, Version::V07, Version::V40]
+        .iter()
+        .map(|&version| {
+            let qrcode = QRBuilder::new("mixed").version(version).build().unwrap();
+            QRMatrix::from(&qrcode)
+        })
+        .collect();
+
+    let sizes: Vec<usize> = matrices.iter().map(Matrix::size).collect();
+    assert_eq!(sizes, [21, 45, 177]);
+}
+
+#[test]
+fn matrix_iter_is_row_major() {
+    let qrcode = QRBuilder::new("iter").build().unwrap();
+    let matrix = QRMatrix::from(&qrcode);
+
+    let modules: Vec<_> = matrix.iter().collect();
+    assert_eq!(modules.len(), qrcode.size * qrcode.size);
+    assert_eq!(modules[..], qrcode.data[..qrcode.size * qrcode.size]);
+}
+
+#[test]
+fn qrcode_accessors() {
+    let qrcode = QRBuilder::new("accessors").build().unwrap();
+
+    assert_eq!(qrcode.size(), qrcode.size);
+    assert_eq!(qrcode.iter_rows().count(), qrcode.size);
+    assert!(qrcode.iter_rows().all(|row| row.len() == qrcode.size));
+
+    for (y, row) in qrcode.iter_rows().enumerate() {
+        for (x, &module) in row.iter().enumerate() {
+            assert_eq!(qrcode.get(x, y), module);
+            assert_eq!(qrcode.get(x, y), qrcode[y][x]);
+        }
+    }
+
+    let dark: Vec<_> = qrcode.iter_dark_modules().collect();
+    assert!(dark.iter().all(|&(x, y)| qrcode.get(x, y).value()));
+    assert_eq!(
+        dark.len(),
+        qrcode.data[..qrcode.size * qrcode.size]
+            .iter()
+            .filter(|module| module.value())
+            .count()
+    );
+    // Top left finder pattern, (1, 0) is dark but (1, 1) is its light ring
+    assert!(dark.contains(&(1, 0)));
+    assert!(!dark.contains(&(1, 1)));
+}
+
+#[test]
+#[should_panic]
+fn qrcode_get_out_of_bounds() {
+    let qrcode = QRBuilder::new("bounds")
+        .version(Version::V01)
+        .build()
+        .unwrap();
+    let _ = qrcode.get(21, 0);
+}
+
+#[test]
+fn module_type_counts() {
+    use crate::ModuleType;
+
+    let qrcode = QRBuilder::new("counts")
+        .version(Version::V01)
+        .build()
+        .unwrap();
+    let counts = qrcode.count_by_type();
+
+    assert_eq!(counts.total(), 21 * 21);
+    // 26 codewords, no remainder bits
+    assert_eq!(counts.get(ModuleType::Data), 26 * 8);
+    assert_eq!(counts.get(ModuleType::Format), 30);
+    assert_eq!(counts.get(ModuleType::Version), 0);
+    assert_eq!(counts.get(ModuleType::DarkModule), 1);
+
+    for module_type in ModuleType::ALL {
+        let region: Vec<_> = qrcode.region_of(module_type).collect();
+        assert_eq!(region.len(), counts.get(module_type));
+        assert!(region
+            .iter()
+            .all(|&(x, y)| qrcode.get(x, y).module_type() == module_type));
+    }
+
+    // Both blocks of version information from version 7
+    let qrcode = QRBuilder::new("counts")
+        .version(Version::V07)
+        .build()
+        .unwrap();
+    assert_eq!(qrcode.count_by_type().get(ModuleType::Version), 36);
+}
+
+#[test]
+fn ascii_fixture() {
+    let expected = QRMatrix::parse_ascii(
+        "
+        FFFFFFFeI#.##eFFFFFFF
+        FfffffFei.#..eFfffffF
+        FfFFFfFei####eFfFFFfF
+        FfFFFfFei#.#.eFfFFFfF
+        FfFFFfFeI..#.eFfFFFfF
+        FfffffFeI..#.eFfffffF
+        FFFFFFFeTtTtTeFFFFFFF
+        eeeeeeeei...#eeeeeeee
+        iIIIIITIi###.iiIIiiiI
+        #..###t..###.###....#
+        .#####T#..#...##.###.
+        ###..#t...##.##..##.#
+        ##.#.#T.###.#....#.#.
+        eeeeeeeeD#.#...##...#
+        FFFFFFFeI#..##....##.
+        FfffffFeI##....######
+        FfFFFfFeI#.#.#.#.#.#.
+        FfFFFfFeI..####.###..
+        FfFFFfFeI#..#.#...#..
+        FfffffFeI#.####..##..
+        FFFFFFFei#..#....#.#.
+        ",
+    )
+    .unwrap();
+
+    let qrcode = QRBuilder::new("fixture").build().unwrap();
+    let matrix = QRMatrix::from(&qrcode);
+    assert_eq!(matrix, expected);
+    assert_eq!(QRMatrix::parse_ascii(&matrix.to_ascii()), Ok(matrix));
+
+    assert_eq!(expected.module(8, 6), Module::timing(true));
+    assert_eq!(expected.module(13, 8), Module::dark(true));
+    assert_eq!(expected.module(7, 0), Module::empty(false));
+}
+
+#[test]
+fn ascii_errors() {
+    assert_eq!(QRMatrix::parse_ascii(" \n\n"), Err(AsciiError::Empty));
+    assert_eq!(
+        QRMatrix::parse_ascii("#.\n.#\n##"),
+        Err(AsciiError::RowCount)
+    );
+    assert_eq!(
+        QRMatrix::parse_ascii("#.\n.#.\n"),
+        Err(AsciiError::RowLength { row: 1 })
+    );
+    assert_eq!(
+        QRMatrix::parse_ascii("#.\n.x"),
+        Err(AsciiError::Character {
+            row: 1,
+            column: 1,
+            character: 'x'
+        })
+    );
+}
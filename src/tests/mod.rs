@@ -1,11 +1,71 @@
+#[cfg(feature = "animation")]
+mod animation;
+mod baked;
+mod batch;
+mod bitmap;
 mod bytes;
+mod caption;
 mod compact;
+mod compat;
+mod constant_time;
+#[cfg(feature = "svg")]
+mod dark_mode;
 mod datamasking;
+mod decode;
 mod default;
+#[cfg(feature = "dump")]
+mod dump;
+#[cfg(feature = "dxf")]
+mod dxf;
+#[cfg(feature = "embedded-graphics")]
+mod embedded;
 mod encode;
+#[cfg(feature = "eps")]
+mod eps;
 mod error_correction;
+mod exhaustive;
+#[cfg(feature = "mmap")]
+mod export;
+#[cfg(feature = "zip")]
+mod export_zip;
+#[cfg(feature = "ffi")]
+mod ffi;
+mod fingerprint;
+mod framebuffer;
+mod galois;
+mod html;
+mod interleave;
+#[cfg(any(feature = "qrcode", feature = "qrcodegen"))]
+mod interop;
+mod invariants;
+mod matrix;
+mod overlay;
+mod payload;
+#[cfg(feature = "pdf")]
+mod pdf;
+mod physical;
 mod polynomials;
+mod reference;
+mod renderer;
+mod scanner;
 mod score;
+mod segment;
+#[cfg(feature = "serde")]
+mod serialize;
+#[cfg(feature = "simd")]
+mod simd;
+mod sixel;
+#[cfg(feature = "skia")]
+mod skia;
 mod structure;
+mod structured_append;
+#[cfg(feature = "svg")]
+mod style;
 mod svg;
+mod template;
+#[cfg(not(feature = "wasm-bindgen"))]
+mod terminal;
+mod verify;
 mod version;
+#[cfg(feature = "zpl")]
+mod zpl;
@@ -0,0 +1,122 @@
+use crate::overlay::{correction_map, safe_overlay_area, Overlay, OverlayPlanner};
+use crate::{placement, polynomials, Version, ECL};
+
+#[test]
+fn codeword_blocks_match_version_capacity() {
+    for version in [Version::V01, Version::V05, Version::V10, Version::V40] {
+        for ecl in [ECL::L, ECL::M, ECL::Q, ECL::H] {
+            let blocks = polynomials::codeword_blocks(ecl, version);
+            assert_eq!(blocks.len(), version.max_bytes());
+        }
+    }
+}
+
+#[test]
+fn data_positions_cover_every_data_module() {
+    for version in [Version::V01, Version::V07, Version::V40] {
+        let positions = placement::data_positions(version);
+        assert_eq!(
+            positions.len(),
+            version.max_bytes() * 8 + version.missing_bits()
+        );
+    }
+}
+
+#[test]
+fn safe_overlay_area_v01() {
+    // V01-L: 1 block, 7 ECC codewords => 3 correctable
+    assert_eq!(safe_overlay_area(Version::V01, ECL::L), 3 * 8);
+    // V01-H: 1 block, 17 ECC codewords => 8 correctable
+    assert_eq!(safe_overlay_area(Version::V01, ECL::H), 8 * 8);
+}
+
+#[test]
+fn safe_overlay_area_grows_with_ecl() {
+    let l = safe_overlay_area(Version::V10, ECL::L);
+    let m = safe_overlay_area(Version::V10, ECL::M);
+    let q = safe_overlay_area(Version::V10, ECL::Q);
+    let h = safe_overlay_area(Version::V10, ECL::H);
+    assert!(l < m && m < q && q <= h);
+}
+
+#[test]
+fn planner_is_centered_and_within_budget() {
+    let version = Version::V10;
+    let overlay = OverlayPlanner::new(version, ECL::H)
+        .aspect_ratio(2.0)
+        .plan()
+        .unwrap();
+
+    assert_eq!(overlay.x * 2 + overlay.width, version.size());
+    assert_eq!(overlay.y * 2 + overlay.height, version.size());
+    assert!(overlay.width > overlay.height);
+    assert!(overlay.width * overlay.height <= safe_overlay_area(version, ECL::H));
+}
+
+#[test]
+fn planner_verify_is_decodable() {
+    let mut planner = OverlayPlanner::new(Version::V05, ECL::H);
+    planner.verify(true);
+    let overlay = planner.plan().unwrap();
+
+    assert!(planner.is_decodable(&overlay));
+
+    let bigger = Overlay {
+        x: overlay.x - 1,
+        y: overlay.y - 1,
+        width: overlay.width + 2,
+        height: overlay.height + 2,
+    };
+    assert!(!planner.is_decodable(&bigger));
+}
+
+#[test]
+fn planner_avoids_function_patterns() {
+    use crate::default::create_matrix;
+    use crate::module::ModuleType;
+
+    // V07 has an alignment pattern right in the center
+    let version = Version::V07;
+    let qr = create_matrix(version);
+    let center = version.size() / 2;
+    assert_eq!(qr[center][center].module_type(), ModuleType::Alignment);
+
+    let overlay = OverlayPlanner::new(version, ECL::H)
+        .avoid_function_patterns(true)
+        .verify(true)
+        .plan()
+        .unwrap();
+
+    for y in overlay.y..overlay.y + overlay.height {
+        for x in overlay.x..overlay.x + overlay.width {
+            assert_eq!(qr[y][x].module_type(), ModuleType::Data);
+        }
+    }
+    assert!(!overlay.contains(center, center));
+}
+
+#[test]
+fn correction_map_without_damage_is_full() {
+    let map = correction_map(Version::V05, ECL::Q, 0);
+
+    for y in 0..map.size {
+        for x in 0..map.size {
+            if let Some(remaining) = map.get(y, x) {
+                assert_eq!(remaining, map.capacity);
+            }
+        }
+    }
+}
+
+#[test]
+fn correction_map_damage_reduces_capacity() {
+    let map = correction_map(Version::V05, ECL::Q, 5);
+
+    assert_eq!(map.get(0, 0), None);
+    // Bottom right corner is data for every version
+    let corner = map.get(map.size - 1, map.size - 1).unwrap();
+    assert!(corner < map.capacity);
+
+    let large = correction_map(Version::V05, ECL::Q, 9);
+    assert!(large.get(large.size - 1, large.size - 1).unwrap() <= corner);
+}
@@ -0,0 +1,373 @@
+use crate::payload::ndef::{NdefMessage, NdefRecord};
+use crate::payload::{PayloadError, TextPayload, UriPayload};
+
+#[test]
+fn uri_validation() {
+    assert!(UriPayload::new("https://example.com/").is_ok());
+    assert!(UriPayload::new("tel:+33123456789").is_ok());
+    assert_eq!(UriPayload::new(""), Err(PayloadError::Empty));
+    assert_eq!(
+        UriPayload::new("example.com"),
+        Err(PayloadError::InvalidUri)
+    );
+    assert_eq!(
+        UriPayload::new("https://example.com/a b"),
+        Err(PayloadError::InvalidUri)
+    );
+    assert_eq!(UriPayload::new("1http://a"), Err(PayloadError::InvalidUri));
+}
+
+#[test]
+fn uri_feeds_qrcode() {
+    let uri = UriPayload::new("https://example.com/").unwrap();
+    let qrcode = crate::QRBuilder::new(uri).build();
+    assert!(qrcode.is_ok());
+}
+
+#[test]
+fn ndef_uri_record_shortens_prefix() {
+    let uri = UriPayload::new("tel:+33123456789").unwrap();
+    let record = NdefRecord::uri(&uri);
+    assert_eq!(record.payload()[0], 0x05);
+    assert_eq!(&record.payload()[1..], b"+33123456789");
+
+    // No known prefix
+    let uri = UriPayload::new("geo:0,0").unwrap();
+    assert_eq!(NdefRecord::uri(&uri).payload(), b"\x00geo:0,0");
+}
+
+#[test]
+fn ndef_text_record() {
+    let text = TextPayload::new("Bonjour").unwrap().language("fr").unwrap();
+    let bytes = NdefMessage::new()
+        .record(NdefRecord::text(&text))
+        .to_bytes();
+
+    assert_eq!(bytes, b"\xD1\x01\x0AT\x02frBonjour");
+}
+
+#[test]
+fn ndef_language_validation() {
+    let text = TextPayload::new("Hello").unwrap();
+    assert_eq!(
+        text.clone().language(""),
+        Err(PayloadError::InvalidLanguage)
+    );
+    assert_eq!(
+        text.language("a".repeat(64)),
+        Err(PayloadError::InvalidLanguage)
+    );
+}
+
+#[test]
+fn ndef_message_chunks() {
+    let uri = UriPayload::new("https://example.com/").unwrap();
+    let text = TextPayload::new("Hello").unwrap();
+
+    let bytes = NdefMessage::new()
+        .record(NdefRecord::uri(&uri))
+        .record(NdefRecord::text(&text))
+        .chunk_size(6)
+        .to_bytes();
+
+    // URI payload: [0x04, "example.com/"] = 13 bytes => 6 + 6 + 1
+    #[rustfmt::skip]
+    let expected: Vec<u8> = [
+        &[0xB1, 0x01, 0x06, b'U', 0x04][..], b"examp",  // MB | CF | SR
+        &[0x36, 0x00, 0x06][..], b"le.com",             // CF | SR | unchanged
+        &[0x16, 0x00, 0x01][..], b"/",                  // SR | unchanged
+        &[0x31, 0x01, 0x06, b'T', 0x02][..], b"enHel",  // CF | SR
+        &[0x56, 0x00, 0x02][..], b"lo",                 // ME | SR | unchanged
+    ]
+    .concat();
+
+    assert_eq!(bytes, expected);
+}
+
+#[test]
+fn wallet_enforces_https() {
+    use crate::payload::wallet::WalletPass;
+
+    assert!(WalletPass::apple("https://example.com/pass.pkpass").is_ok());
+    assert_eq!(
+        WalletPass::apple("http://example.com/pass.pkpass"),
+        Err(PayloadError::InsecureUri)
+    );
+    assert_eq!(
+        WalletPass::apple("https:///pass.pkpass"),
+        Err(PayloadError::InsecureUri)
+    );
+}
+
+#[test]
+fn wallet_google_save_link() {
+    use crate::payload::wallet::{WalletPass, WalletProvider};
+
+    let pass = WalletPass::google("https://pay.google.com/gp/v/save/eyJhbGciOi").unwrap();
+    assert_eq!(pass.provider(), WalletProvider::Google);
+    assert_eq!(
+        WalletPass::google("https://example.com/save"),
+        Err(PayloadError::InvalidUri)
+    );
+
+    let jwt = "a".repeat(1800);
+    assert_eq!(
+        WalletPass::google(format!("https://pay.google.com/gp/v/save/{jwt}")),
+        Err(PayloadError::TooLong { max: 1663 })
+    );
+}
+
+#[test]
+fn wallet_length_limit_matches_qr_capacity() {
+    use crate::payload::wallet::WalletPass;
+
+    let prefix = "https://example.com/";
+    let fits = format!("{prefix}{}", "a".repeat(1663 - prefix.len()));
+    let too_long = format!("{fits}a");
+
+    let pass = WalletPass::apple(fits).unwrap();
+    let qrcode = pass.qr_builder().build().unwrap();
+    assert!(matches!(qrcode.ecl, Some(crate::ECL::Q)));
+    assert_eq!(
+        WalletPass::apple(too_long),
+        Err(PayloadError::TooLong { max: 1663 })
+    );
+}
+
+#[cfg(feature = "zeroize")]
+#[test]
+fn payload_zeroize() {
+    use zeroize::Zeroize;
+
+    let mut uri = UriPayload::new("otpauth://totp/Example?secret=JBSWY3DPEHPK3PXP").unwrap();
+    uri.zeroize();
+    assert_eq!(uri.as_str(), "");
+
+    let mut text = TextPayload::new("secret").unwrap();
+    text.zeroize();
+    assert_eq!(text.text(), "");
+
+    // Conversions still hand the content over
+    let uri = UriPayload::new("https://example.com/").unwrap();
+    assert_eq!(Vec::from(uri), b"https://example.com/");
+}
+
+#[test]
+fn payload_len_info_modes() {
+    use crate::payload::payload_len_info;
+    use crate::{Mode, Version, ECL};
+
+    let digits = payload_len_info("0123456789");
+    assert_eq!(digits.mode, Mode::Numeric);
+    assert_eq!(digits.numeric_bits, Some(34));
+    assert_eq!(digits.alphanumeric_bits, Some(55));
+    assert_eq!(digits.byte_bits, 80);
+
+    let upper = payload_len_info("HELLO WORLD");
+    assert_eq!(upper.mode, Mode::Alphanumeric);
+    assert_eq!(upper.numeric_bits, None);
+    assert_eq!(upper.alphanumeric_bits, Some(61));
+
+    let emoji = payload_len_info("👍🏽 é");
+    assert_eq!(emoji.chars, 4);
+    assert_eq!(emoji.bytes, 11);
+    assert_eq!(emoji.multibyte_chars, 3);
+    assert_eq!(emoji.alphanumeric_bits, None);
+    assert_eq!(emoji.mode, Mode::Byte);
+
+    // 2953 bytes fit at ECL::L, but not 2953 two-byte characters
+    let accents = payload_len_info(&"é".repeat(2953));
+    assert_eq!(accents.chars, 2953);
+    assert!(accents.version(ECL::L).is_none());
+    let fits = payload_len_info(&"é".repeat(1476));
+    assert_eq!(fits.version(ECL::L), Some(Version::V40));
+}
+
+#[test]
+#[cfg(feature = "kanji")]
+fn payload_len_info_kanji() {
+    use crate::payload::payload_len_info;
+    use crate::{Mode, QRBuilder, ECL};
+
+    let text = "点茗".repeat(60);
+    let info = payload_len_info(&text);
+    assert_eq!(info.mode, Mode::Kanji);
+    assert_eq!(info.bytes, 360);
+    assert_eq!(info.kanji_bits, Some(120 * 13));
+
+    for ecl in [ECL::L, ECL::M, ECL::Q, ECL::H] {
+        let qrcode = QRBuilder::new(text.as_str()).ecl(ecl).build().unwrap();
+        assert_eq!(info.version(ecl), qrcode.version);
+    }
+
+    assert_eq!(payload_len_info("Café").kanji_bits, None);
+}
+
+#[test]
+fn wifi_format() {
+    use crate::payload::{WiFi, WiFiSecurity};
+
+    let open = WiFi::new("Guest").unwrap();
+    assert_eq!(open.to_string(), "WIFI:T:nopass;S:Guest;;");
+
+    let wep = WiFi::new("Old")
+        .unwrap()
+        .password("12345")
+        .security(WiFiSecurity::Wep)
+        .hidden(true);
+    assert_eq!(wep.to_string(), "WIFI:T:WEP;S:Old;P:12345;H:true;;");
+
+    // Password is dropped once the network is open
+    let ignored = WiFi::new("Cafe")
+        .unwrap()
+        .password("unused")
+        .security(WiFiSecurity::NoPass);
+    assert_eq!(ignored.to_string(), "WIFI:T:nopass;S:Cafe;;");
+
+    assert_eq!(WiFi::new(""), Err(PayloadError::Empty));
+}
+
+#[test]
+fn wifi_escaping() {
+    use crate::payload::WiFi;
+
+    let wifi = WiFi::new(r"a;b:c\d,e").unwrap().password(r"p;w:d\");
+    assert_eq!(
+        wifi.to_string(),
+        r"WIFI:T:WPA;S:a\;b\:c\\d\,e;P:p\;w\:d\\;;"
+    );
+
+    let qrcode = crate::QRBuilder::new(wifi).build();
+    assert!(qrcode.is_ok());
+}
+
+#[test]
+fn epc_payment_layout() {
+    use crate::payload::EpcPayment;
+
+    let payment = EpcPayment::new("Red Cross of Belgium", "be72 0000 0000 1616")
+        .unwrap()
+        .bic("BPOTBEB1")
+        .unwrap()
+        .amount(1_00)
+        .unwrap()
+        .text("Urgency fund")
+        .unwrap();
+    assert_eq!(payment.iban(), "BE72000000001616");
+    assert_eq!(
+        payment.to_string(),
+        "BCD\n002\n1\nSCT\nBPOTBEB1\nRed Cross of Belgium\nBE72000000001616\nEUR1.00\n\n\nUrgency fund"
+    );
+
+    // Structured reference replaces the text, trailing lines are omitted
+    let payment = payment.reference("RF18539007547034").unwrap();
+    assert!(payment.to_string().ends_with("EUR1.00\n\nRF18539007547034"));
+    let minimal = EpcPayment::new("Jane Doe", "DE89370400440532013000").unwrap();
+    assert_eq!(
+        minimal.to_string(),
+        "BCD\n002\n1\nSCT\n\nJane Doe\nDE89370400440532013000\n"
+    );
+
+    let qrcode = payment.qr_builder().build().unwrap();
+    assert!(matches!(qrcode.ecl, Some(crate::ECL::M)));
+    assert!(matches!(qrcode.mode, Some(crate::Mode::Byte)));
+}
+
+#[test]
+fn epc_payment_validation() {
+    use crate::payload::EpcPayment;
+
+    let iban = "DE89370400440532013000";
+    assert_eq!(
+        EpcPayment::new("Jane Doe", "DE89370400440532013001"),
+        Err(PayloadError::InvalidField("IBAN"))
+    );
+    assert_eq!(EpcPayment::new("", iban), Err(PayloadError::Empty));
+    assert_eq!(
+        EpcPayment::new("a".repeat(71), iban),
+        Err(PayloadError::TooLong { max: 70 })
+    );
+    assert_eq!(
+        EpcPayment::new("Jane\nDoe", iban),
+        Err(PayloadError::InvalidField("name"))
+    );
+
+    let payment = EpcPayment::new("Jane Doe", iban).unwrap();
+    assert_eq!(
+        payment.clone().bic("COBADEF"),
+        Err(PayloadError::InvalidField("BIC"))
+    );
+    assert!(payment.clone().bic("COBADEFFXXX").is_ok());
+    assert_eq!(
+        payment.clone().amount(0),
+        Err(PayloadError::InvalidField("amount"))
+    );
+    assert!(payment.clone().amount(99_999_999_999).is_ok());
+    assert_eq!(
+        payment.clone().reference("R".repeat(36)),
+        Err(PayloadError::TooLong { max: 35 })
+    );
+
+    // 140 characters, but too many bytes with the 70 character name
+    let payment = EpcPayment::new("é".repeat(70), iban).unwrap();
+    assert_eq!(
+        payment.text("€".repeat(140)),
+        Err(PayloadError::TooLong { max: 331 })
+    );
+}
+
+#[test]
+fn totp_uri() {
+    use crate::payload::{OtpAlgorithm, Totp};
+
+    let totp = Totp::new("Example", "alice@google.com", b"Hello!\xDE\xAD\xBE\xEF")
+        .unwrap()
+        .digits(8)
+        .unwrap()
+        .period(60)
+        .unwrap()
+        .algorithm(OtpAlgorithm::Sha256);
+    assert_eq!(
+        totp.to_string(),
+        "otpauth://totp/Example:alice%40google.com?secret=JBSWY3DPEHPK3PXP&issuer=Example&algorithm=SHA256&digits=8&period=60"
+    );
+
+    // Reserved characters are escaped in the label and the issuer parameter
+    let hotp = Totp::new("Caf\u{e9} & Co/Bar", "a b?c", &[0xFF])
+        .unwrap()
+        .counter(42);
+    assert_eq!(
+        hotp.to_string(),
+        "otpauth://hotp/Caf%C3%A9%20%26%20Co%2FBar:a%20b%3Fc?secret=74&issuer=Caf%C3%A9%20%26%20Co%2FBar&algorithm=SHA1&digits=6&counter=42"
+    );
+
+    let no_issuer = Totp::new("", "bob", &[0]).unwrap();
+    assert!(no_issuer
+        .to_string()
+        .starts_with("otpauth://totp/bob?secret=AA&algorithm"));
+
+    assert!(crate::QRBuilder::new(totp).build().is_ok());
+}
+
+#[test]
+fn totp_validation() {
+    use crate::payload::Totp;
+
+    assert_eq!(Totp::new("Issuer", "", b"k"), Err(PayloadError::Empty));
+    assert_eq!(Totp::new("Issuer", "bob", b""), Err(PayloadError::Empty));
+    assert_eq!(
+        Totp::new("A:B", "bob", b"k"),
+        Err(PayloadError::InvalidField("issuer"))
+    );
+    assert_eq!(
+        Totp::new("Issuer", "b:ob", b"k"),
+        Err(PayloadError::InvalidField("account"))
+    );
+
+    let totp = Totp::new("Issuer", "bob", b"k").unwrap();
+    assert_eq!(
+        totp.clone().digits(7),
+        Err(PayloadError::InvalidField("digits"))
+    );
+    assert_eq!(totp.period(0), Err(PayloadError::InvalidField("period")));
+}
@@ -0,0 +1,136 @@
+use crate::convert::pdf::{PdfBuilder, PdfColor};
+use crate::QRBuilder;
+
+/// Byte offset of the first occurrence of `needle`
+fn find(pdf: &[u8], needle: &str) -> usize {
+    pdf.windows(needle.len())
+        .position(|window| window == needle.as_bytes())
+        .unwrap()
+}
+
+/// Text of the content stream
+fn content(pdf: &[u8]) -> String {
+    let start = find(pdf, "stream\n") + "stream\n".len();
+    let end = find(pdf, "endstream");
+    String::from_utf8(pdf[start..end].to_vec()).unwrap()
+}
+
+#[test]
+fn pdf_structure() {
+    let qrcode = QRBuilder::new("pdf").build().unwrap();
+    let pdf = PdfBuilder::default().to_bytes(&qrcode);
+
+    assert!(pdf.starts_with(b"%PDF-1.4\n"));
+    assert!(pdf.ends_with(b"%%EOF\n"));
+
+    let text = String::from_utf8_lossy(&pdf);
+    let startxref: usize = text
+        .rsplit("startxref\n")
+        .next()
+        .and_then(|tail| tail.lines().next())
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert_eq!(startxref, find(&pdf, "xref\n"));
+
+    // Every cross-reference entry points at its object
+    let entries = text[startxref..].lines().skip(3).take(5);
+    for (i, entry) in entries.enumerate() {
+        assert_eq!(entry.len(), 19);
+        let offset: usize = entry[..10].parse().unwrap();
+        assert!(pdf[offset..].starts_with(format!("{} 0 obj\n", i + 1).as_bytes()));
+    }
+
+    let length: usize = text[find(&pdf, "/Length ") + 8..]
+        .split(' ')
+        .next()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert_eq!(length, content(&pdf).len());
+}
+
+#[test]
+fn pdf_physical_size() {
+    let qrcode = QRBuilder::new("pdf").build().unwrap();
+
+    // 30mm are 85.0394pt
+    let pdf = PdfBuilder::default().to_bytes(&qrcode);
+    assert!(String::from_utf8_lossy(&pdf).contains("/MediaBox [0 0 85.0394 85.0394]"));
+    // 29 modules with a quiet zone of 4, 85.0394 / 29
+    assert!(content(&pdf).starts_with("q\n2.9324 0 0 -2.9324 0 85.0394 cm\n"));
+
+    // A 21mm code centered on an A6 page
+    let pdf = PdfBuilder::default()
+        .width(21.0)
+        .margin(0)
+        .page_size(105.0, 148.0)
+        .to_bytes(&qrcode);
+    assert!(String::from_utf8_lossy(&pdf).contains("/MediaBox [0 0 297.6378 419.5276]"));
+    assert!(content(&pdf).starts_with("q\n2.8346 0 0 -2.8346 119.0551 239.5276 cm\n"));
+}
+
+#[test]
+fn pdf_modules() {
+    let qrcode = QRBuilder::new("pdf").build().unwrap();
+    let pdf = PdfBuilder::default()
+        .module_color(PdfColor::Rgb([255, 0, 0]))
+        .background_color(PdfColor::Cmyk([0, 0, 0, 0]))
+        .to_bytes(&qrcode);
+    let content = content(&pdf);
+
+    assert!(content.contains("0 0 0 0 k\n0 0 29 29 re\nf\n1 0 0 rg\n"));
+
+    // Top row: finder pattern, separator, timing and separator, finder pattern
+    assert!(content.contains("\n4 4 7 1 re\n"));
+    assert!(content.contains("\n18 4 7 1 re\n"));
+
+    let mut dark = 0;
+    for line in content.lines().filter(|line| line.ends_with(" re")) {
+        let values: Vec<usize> = line
+            .split(' ')
+            .take(4)
+            .map(|v| v.parse().unwrap())
+            .collect();
+        if values[0] != 0 {
+            dark += values[2];
+        }
+    }
+    let expected = (0..qrcode.size)
+        .flat_map(|y| (0..qrcode.size).map(move |x| (y, x)))
+        .filter(|&(y, x)| qrcode[y][x].value())
+        .count();
+    assert_eq!(dark, expected);
+}
+
+#[test]
+fn pdf_bleed() {
+    use crate::convert::vector::{dark_rects, is_dark};
+
+    let qrcode = QRBuilder::new("pdf").build().unwrap();
+
+    let grown = content(&PdfBuilder::default().bleed(0.1).to_bytes(&qrcode));
+    assert!(grown.contains("\n3.9 3.9 7.2 1.2 re\n"));
+    // Bleed is kept within half a module
+    let clamped = content(&PdfBuilder::default().bleed(2.0).to_bytes(&qrcode));
+    assert!(clamped.contains("\n3.5 3.5 8 2 re\n"));
+
+    // The top row of the finder pattern does not shrink where it joins
+    // the sides of the pattern
+    let shrunk = content(&PdfBuilder::default().bleed(-0.1).to_bytes(&qrcode));
+    assert!(shrunk.contains("\n4.1 4.1 0.9 0.9 re\n5 4.1 5 0.8 re\n10 4.1 0.9 0.9 re\n"));
+
+    // Pixel renderers sample the same shapes as the rectangles
+    for bleed in [-0.3, -0.1, 0.0, 0.25] {
+        let rects = dark_rects(&qrcode, bleed);
+        for i in 0..(21 * 7) {
+            for j in 0..(21 * 7) {
+                let (y, x) = ((i as f64 + 0.5) / 7.0, (j as f64 + 0.5) / 7.0);
+                let inside = rects
+                    .iter()
+                    .any(|&(rx, ry, w, h)| x >= rx && x < rx + w && y >= ry && y < ry + h);
+                assert_eq!(is_dark(&qrcode, y, x, bleed), inside, "{bleed} {y} {x}");
+            }
+        }
+    }
+}
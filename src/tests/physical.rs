@@ -0,0 +1,81 @@
+use crate::physical::{PrintSize, PrintWarning};
+use crate::{QRBuilder, QRMatrix, Version};
+
+fn number(version: Option<Version>) -> Option<usize> {
+    version.map(Version::number)
+}
+
+#[test]
+fn max_version_for_print_width() {
+    // 37 modules + 8 of quiet zone, 0.333mm each
+    assert_eq!(number(PrintSize::new(15.0).max_version()), Some(5));
+    assert_eq!(number(PrintSize::new(5.0).max_version()), None);
+    assert_eq!(number(PrintSize::new(100.0).max_version()), Some(40));
+
+    let version = PrintSize::new(15.0)
+        .margin(0)
+        .min_module_size(0.5)
+        .max_version();
+    // 29 modules of 0.517mm
+    assert_eq!(number(version), Some(3));
+}
+
+#[test]
+fn warns_below_threshold() {
+    let qrcode = QRBuilder::new("warning")
+        .version(Version::V10)
+        .build()
+        .unwrap();
+    let print = PrintSize::new(20.0);
+
+    let warning = print.check(&qrcode).unwrap();
+    let PrintWarning::ModuleTooSmall {
+        module_size,
+        min_module_size,
+    } = warning;
+    assert!((module_size - 20.0 / 65.0).abs() < 1e-9);
+    assert_eq!(min_module_size, 0.33);
+    assert_eq!(
+        warning.to_string(),
+        "Modules are 0.308mm wide, below the 0.33mm minimum"
+    );
+
+    assert_eq!(print.check(&QRMatrix::from(&qrcode)), Some(warning));
+    assert_eq!(PrintSize::new(25.0).check(&qrcode), None);
+}
+
+#[test]
+fn pixels_per_module_at_dpi() {
+    let qrcode = QRBuilder::new("https://example.com/")
+        .version(Version::V02)
+        .build()
+        .unwrap();
+    // 25 modules + 8 of quiet zone, 1mm each
+    let print = PrintSize::new(33.0);
+
+    // 1mm is 11.81 pixels at 300 dpi
+    assert_eq!(print.pixels_per_module(&qrcode, 300.0), Ok(11));
+    // Exact sizes are not rounded down
+    assert_eq!(print.pixels_per_module(&qrcode, 254.0), Ok(10));
+    assert_eq!(print.pixels_per_module(&qrcode, 25.4), Ok(1));
+
+    // Below one pixel, or rounded down under the minimum
+    assert_eq!(
+        print.pixels_per_module(&qrcode, 20.0),
+        Err(PrintWarning::ModuleTooSmall {
+            module_size: 0.0,
+            min_module_size: 0.33,
+        })
+    );
+    // 1 pixel at 50 dpi is 0.508mm
+    let warning = PrintSize::new(33.0)
+        .min_module_size(0.9)
+        .pixels_per_module(&qrcode, 50.0)
+        .unwrap_err();
+    let PrintWarning::ModuleTooSmall { module_size, .. } = warning;
+    assert!((module_size - 0.508).abs() < 1e-9);
+    assert_eq!(
+        print.pixels_per_module(&qrcode, f64::NAN).map_err(|_| ()),
+        Err(())
+    );
+}
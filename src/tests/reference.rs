@@ -0,0 +1,290 @@
+//! Slow reference placement and masking, written straight from the spec
+//! without any of the tables or bit tricks of the real pipeline, to
+//! differential-test it.
+
+use crate::compat::PadCodewords;
+use crate::{encode, hardcode, polynomials, Mask, QRBuilder, Version, ECL};
+
+struct Reference {
+    size: usize,
+    dark: Vec<Vec<bool>>,
+    function: Vec<Vec<bool>>,
+}
+
+impl Reference {
+    fn new(number: usize) -> Self {
+        let size = number * 4 + 17;
+        Reference {
+            size,
+            dark: vec![vec![false; size]; size],
+            function: vec![vec![false; size]; size],
+        }
+    }
+
+    fn set_function(&mut self, y: usize, x: usize, dark: bool) {
+        self.dark[y][x] = dark;
+        self.function[y][x] = true;
+    }
+
+    /// Section 6.3.3: 7x7 finder pattern centered at (y, x), with its separator
+    fn finder(&mut self, y: isize, x: isize) {
+        for dy in -4..=4_isize {
+            for dx in -4..=4_isize {
+                let (yy, xx) = (y + dy, x + dx);
+                if yy < 0 || xx < 0 || yy >= self.size as isize || xx >= self.size as isize {
+                    continue;
+                }
+                let distance = dy.abs().max(dx.abs());
+                self.set_function(yy as usize, xx as usize, distance != 2 && distance != 4);
+            }
+        }
+    }
+
+    /// Section 6.3.6: 5x5 alignment pattern centered at (y, x)
+    fn alignment(&mut self, y: usize, x: usize) {
+        for dy in -2..=2_isize {
+            for dx in -2..=2_isize {
+                let distance = dy.abs().max(dx.abs());
+                let (yy, xx) = ((y as isize + dy) as usize, (x as isize + dx) as usize);
+                self.set_function(yy, xx, distance != 1);
+            }
+        }
+    }
+
+    /// Annex E: centers are evenly spaced from 6 to `size - 7`, the step
+    /// being even and the first gap taking the remainder
+    fn alignment_positions(number: usize) -> Vec<usize> {
+        if number == 1 {
+            return Vec::new();
+        }
+
+        let count = number / 7 + 2;
+        let size = number * 4 + 17;
+        let step = if number == 32 {
+            26
+        } else {
+            (number * 4 + count * 2 + 1) / (count * 2 - 2) * 2
+        };
+
+        let mut positions = vec![6];
+        positions.extend((0..count - 1).rev().map(|i| size - 7 - i * step));
+        positions
+    }
+
+    fn function_patterns(&mut self, number: usize) {
+        let size = self.size;
+
+        // Timing patterns first, finders overwrite their ends
+        for i in 0..size {
+            self.set_function(6, i, i % 2 == 0);
+            self.set_function(i, 6, i % 2 == 0);
+        }
+
+        self.finder(3, 3);
+        self.finder(3, size as isize - 4);
+        self.finder(size as isize - 4, 3);
+
+        let positions = Self::alignment_positions(number);
+        let last = positions.len().saturating_sub(1);
+        for (i, &y) in positions.iter().enumerate() {
+            for (j, &x) in positions.iter().enumerate() {
+                // Skips the three corners holding finder patterns
+                let corner = (i == 0 && (j == 0 || j == last)) || (i == last && j == 0);
+                if !corner {
+                    self.alignment(y, x);
+                }
+            }
+        }
+
+        // Reserves format areas, written once the mask is known
+        self.format_information(0);
+
+        if number >= 7 {
+            self.version_information(number);
+        }
+    }
+
+    /// Remainder of `value << degree` divided by `generator`, bit by bit
+    fn bch(value: u32, generator: u32, degree: u32) -> u32 {
+        let mut remainder = value << degree;
+        for bit in (degree..32).rev() {
+            if remainder & (1 << bit) != 0 {
+                remainder ^= generator << (bit - degree);
+            }
+        }
+        remainder
+    }
+
+    /// Section 7.9.1, bit 14 is the most significant
+    fn format_information(&mut self, data: u32) {
+        let bits = (data << 10 | Self::bch(data, 0b101_0011_0111, 10)) ^ 0b101_0100_0001_0010;
+        let bit = |i: usize| bits >> i & 1 == 1;
+        let size = self.size;
+
+        for i in 0..=5 {
+            self.set_function(i, 8, bit(i));
+        }
+        self.set_function(7, 8, bit(6));
+        self.set_function(8, 8, bit(7));
+        self.set_function(8, 7, bit(8));
+        for i in 9..15 {
+            self.set_function(8, 14 - i, bit(i));
+        }
+
+        for i in 0..8 {
+            self.set_function(8, size - 1 - i, bit(i));
+        }
+        for i in 8..15 {
+            self.set_function(size - 15 + i, 8, bit(i));
+        }
+
+        // Dark module, section 7.9.1
+        self.set_function(size - 8, 8, true);
+    }
+
+    /// Section 7.10, bit `i` at (i / 3, size - 11 + i % 3) and its transpose
+    fn version_information(&mut self, number: usize) {
+        let number = number as u32;
+        let bits = number << 12 | Self::bch(number, 0b1_1111_0010_0101, 12);
+
+        for i in 0..18 {
+            let dark = bits >> i & 1 == 1;
+            let (a, b) = (self.size - 11 + i % 3, i / 3);
+            self.set_function(b, a, dark);
+            self.set_function(a, b, dark);
+        }
+    }
+
+    /// Section 7.7.3: two-module wide columns, from the bottom right,
+    /// alternating upwards and downwards, skipping the vertical timing pattern
+    fn place(&mut self, codewords: &[u8]) {
+        let size = self.size;
+        let mut i = 0;
+
+        let mut right = size - 1;
+        loop {
+            if right == 6 {
+                right = 5;
+            }
+            let upward = (right + 1) & 2 == 0;
+
+            for vertical in 0..size {
+                let y = if upward {
+                    size - 1 - vertical
+                } else {
+                    vertical
+                };
+                for x in [right, right - 1] {
+                    if self.function[y][x] {
+                        continue;
+                    }
+                    // Remainder bits stay light
+                    if i < codewords.len() * 8 {
+                        self.dark[y][x] = codewords[i / 8] >> (7 - i % 8) & 1 == 1;
+                        i += 1;
+                    }
+                }
+            }
+
+            if right < 2 {
+                break;
+            }
+            right -= 2;
+        }
+    }
+
+    /// Table 10, `i` is the row and `j` the column
+    fn apply_mask(&mut self, pattern: usize) {
+        for i in 0..self.size {
+            for j in 0..self.size {
+                let flip = match pattern {
+                    0 => (i + j) % 2 == 0,
+                    1 => i % 2 == 0,
+                    2 => j % 3 == 0,
+                    3 => (i + j) % 3 == 0,
+                    4 => (i / 2 + j / 3) % 2 == 0,
+                    5 => (i * j) % 2 + (i * j) % 3 == 0,
+                    6 => ((i * j) % 2 + (i * j) % 3) % 2 == 0,
+                    _ => ((i + j) % 2 + (i * j) % 3) % 2 == 0,
+                };
+                if flip && !self.function[i][j] {
+                    self.dark[i][j] = !self.dark[i][j];
+                }
+            }
+        }
+    }
+}
+
+/// Builds the reference matrix of `codewords`, final interleaved sequence included
+fn reference(codewords: &[u8], number: usize, ecl: ECL, pattern: usize) -> Vec<Vec<bool>> {
+    // Table 12, L and H swap their order in the enum
+    let ecl_bits = match ecl {
+        ECL::L => 0b01,
+        ECL::M => 0b00,
+        ECL::Q => 0b11,
+        ECL::H => 0b10,
+    };
+
+    let mut qr = Reference::new(number);
+    qr.function_patterns(number);
+    qr.place(codewords);
+    qr.apply_mask(pattern);
+    qr.format_information(ecl_bits << 3 | pattern as u32);
+    qr.dark
+}
+
+#[test]
+fn alignment_positions_match_table() {
+    for number in 1..=40 {
+        let version = Version::from_number(number).unwrap();
+        assert_eq!(
+            Reference::alignment_positions(number),
+            version.alignment_positions(),
+            "version {number}"
+        );
+    }
+}
+
+#[test]
+fn pipeline_matches_reference() {
+    // Fits version 1 at every level, the padding fills larger versions
+    let input = b"fast_qr";
+
+    for number in 1..=40 {
+        let version = Version::from_number(number).unwrap();
+        for ecl in [ECL::L, ECL::M, ECL::Q, ECL::H] {
+            let mode = encode::best_encoding(input);
+            let data =
+                encode::encode(input, ecl, mode, version, PadCodewords::Alternating).unwrap();
+            let data = &data.get_data()[..hardcode::data_codewords(version, ecl)];
+            let codewords = polynomials::structure(data, ecl, version);
+            let codewords = &codewords[..version.max_bytes()];
+
+            // Every mask on a few versions, one per version otherwise
+            let patterns = if number <= 3 || number >= 38 {
+                0..8
+            } else {
+                number % 8..number % 8 + 1
+            };
+            for pattern in patterns {
+                let qrcode = QRBuilder::new(&input[..])
+                    .ecl(ecl)
+                    .version(version)
+                    .mask(Mask::from_pattern(pattern).unwrap())
+                    .build()
+                    .unwrap();
+                let expected = reference(codewords, number, ecl, pattern);
+
+                for (y, row) in expected.iter().enumerate() {
+                    for (x, &dark) in row.iter().enumerate() {
+                        assert_eq!(
+                            qrcode[y][x].value(),
+                            dark,
+                            "version {number}, {ecl}, mask {pattern}, module ({y}, {x})"
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
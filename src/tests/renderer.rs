@@ -0,0 +1,50 @@
+use crate::convert::html::HtmlBuilder;
+use crate::convert::Renderer;
+use crate::{QRBuilder, QRCode};
+
+/// Counts dark modules, as a third-party backend would
+struct DarkCount;
+
+impl Renderer for DarkCount {
+    type Output = usize;
+
+    fn render(&self, qr: &QRCode) -> usize {
+        qr.iter_dark_modules().count()
+    }
+}
+
+#[test]
+fn renderers_match_builders() {
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+
+    let html = HtmlBuilder::default();
+    assert_eq!(html.render(&qrcode), html.to_str(&qrcode));
+
+    #[cfg(feature = "svg")]
+    {
+        let svg = crate::convert::svg::SvgBuilder::default();
+        let renderers: [&dyn Renderer<Output = String>; 2] = [&svg, &html];
+        assert_eq!(renderers[0].render(&qrcode), svg.to_str(&qrcode));
+    }
+    #[cfg(not(feature = "wasm-bindgen"))]
+    {
+        let terminal = crate::convert::terminal::TerminalBuilder::default();
+        assert_eq!(terminal.render(&qrcode), terminal.to_str(&qrcode));
+    }
+    #[cfg(feature = "pdf")]
+    {
+        let pdf = crate::convert::pdf::PdfBuilder::default();
+        assert_eq!(pdf.render(&qrcode), pdf.to_bytes(&qrcode));
+    }
+    #[cfg(feature = "skia")]
+    {
+        let skia = crate::convert::skia::SkiaBuilder::default();
+        assert_eq!(skia.render(&qrcode), skia.to_bytes(&qrcode));
+    }
+
+    let dark = qrcode.data[..qrcode.size * qrcode.size]
+        .iter()
+        .filter(|module| module.value())
+        .count();
+    assert_eq!(DarkCount.render(&qrcode), dark);
+}
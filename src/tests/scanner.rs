@@ -0,0 +1,52 @@
+use crate::scanner::ScannerSimulation;
+use crate::{Mask, QRBuilder, ECL};
+
+#[test]
+fn scanner_aligned_no_error() {
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+
+    let rate = ScannerSimulation::default()
+        .offsets(vec![(0.0, 0.0)])
+        .bit_error_rate(&qrcode);
+
+    assert_eq!(rate, 0.0);
+}
+
+#[test]
+fn scanner_small_offsets_no_error() {
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+
+    let rate = ScannerSimulation::default().bit_error_rate(&qrcode);
+
+    assert_eq!(rate, 0.0);
+}
+
+#[test]
+fn scanner_degraded_has_errors() {
+    let qrcode = QRBuilder::new("https://example.com/")
+        .mask(Mask::Checkerboard)
+        .build()
+        .unwrap();
+
+    let rate = ScannerSimulation::default()
+        .dot_gain(0.25)
+        .pitch_error(0.05)
+        .bit_error_rate(&qrcode);
+
+    assert!(rate > 0.0);
+    assert!(rate < 1.0);
+}
+
+#[test]
+fn scanner_mask_report_sorted() {
+    let reports = ScannerSimulation::default()
+        .dot_gain(0.2)
+        .pitch_error(0.03)
+        .mask_report(b"https://example.com/", ECL::M)
+        .unwrap();
+
+    assert_eq!(reports.len(), 8);
+    for pair in reports.windows(2) {
+        assert!(pair[0].bit_error_rate <= pair[1].bit_error_rate);
+    }
+}
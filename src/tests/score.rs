@@ -378,3 +378,67 @@ fn pattern() {
 
     assert_eq!(test_score_pattern(&line), 40, "pattern, expected 40");
 }
+
+#[test]
+fn packed_line_across_words() {
+    // 177 modules span three `u64` words
+    let line = [DATA(T); 177];
+    assert_eq!(test_score_line(&line), 175, "line, expected 175");
+
+    // Function modules split runs
+    let mut line = [DATA(T); 177];
+    line[64] = FIND(T);
+    line[128] = FIND(T);
+    assert_eq!(test_score_line(&line), 62 + 61 + 46, "line, expected 169");
+
+    // Pattern straddling the first word boundary
+    let mut line = [DATA(F); 177];
+    for (i, &value) in [T, F, T, T, T, F, T].iter().enumerate() {
+        line[60 + i] = DATA(value);
+    }
+    assert_eq!(test_score_pattern(&line), 40, "pattern, expected 40");
+}
+
+#[test]
+fn penalty_weights() {
+    use crate::datamasking::{MaskScoring, PenaltyWeights};
+    use crate::QRBuilder;
+
+    let pattern = |input: &str, scoring| {
+        QRBuilder::new(input)
+            .mask_scoring(scoring)
+            .build()
+            .unwrap()
+            .mask
+            .map(crate::Mask::pattern)
+    };
+
+    for input in [
+        "https://example.com/",
+        "HELLO WORLD",
+        "0123456789",
+        "fast_qr",
+    ] {
+        assert_eq!(
+            pattern(input, MaskScoring::Spec),
+            pattern(input, MaskScoring::Weighted(PenaltyWeights::default()))
+        );
+
+        let balance_only = PenaltyWeights {
+            runs: 0,
+            squares: 0,
+            finder_patterns: 0,
+            balance: 10,
+        };
+        assert_eq!(
+            pattern(input, MaskScoring::DarkRatio),
+            pattern(input, MaskScoring::Weighted(balance_only))
+        );
+
+        let nothing = PenaltyWeights {
+            balance: 0,
+            ..balance_only
+        };
+        assert_eq!(pattern(input, MaskScoring::Weighted(nothing)), Some(0));
+    }
+}
@@ -0,0 +1,115 @@
+use crate::segment::{encode_segments, estimate_bits, Segment, SegmentError};
+use crate::{QRBuilder, Version, ECL};
+
+#[test]
+fn single_segment_matches_builder() {
+    let cases = [
+        (Segment::numeric("0123456789").unwrap(), "0123456789"),
+        (Segment::alphanumeric("HELLO WORLD").unwrap(), "HELLO WORLD"),
+        (
+            Segment::bytes(b"https://example.com/"),
+            "https://example.com/",
+        ),
+    ];
+
+    for (segment, input) in cases {
+        let segments = encode_segments(&[segment], ECL::Q).unwrap();
+        let builder = QRBuilder::new(input).ecl(ECL::Q).build().unwrap();
+
+        assert_eq!(segments.version, builder.version);
+        assert_eq!(segments.data[..], builder.data[..]);
+    }
+}
+
+#[test]
+fn mixed_segments_are_smaller() {
+    let segments = [
+        Segment::bytes(b"order #"),
+        Segment::numeric("12345678901234567890123456789012").unwrap(),
+    ];
+    let mixed = encode_segments(&segments, ECL::H).unwrap();
+    let bytes = QRBuilder::new("order #12345678901234567890123456789012")
+        .ecl(ECL::H)
+        .build()
+        .unwrap();
+
+    assert!(mixed.version < bytes.version);
+    assert!(mixed.mode.is_none());
+}
+
+#[test]
+fn invalid_segments() {
+    assert_eq!(Segment::numeric("12a"), Err(SegmentError::InvalidNumeric));
+    assert_eq!(
+        Segment::alphanumeric("lowercase"),
+        Err(SegmentError::InvalidAlphanumeric)
+    );
+    assert_eq!(
+        Segment::kanji_sjis(&[0x93]),
+        Err(SegmentError::InvalidKanji)
+    );
+    assert_eq!(Segment::kanji_sjis(b"ab"), Err(SegmentError::InvalidKanji));
+    assert_eq!(Segment::eci(1_000_000), Err(SegmentError::InvalidEci));
+    assert!(Segment::eci(999_999).is_ok());
+}
+
+#[test]
+fn kanji_and_eci() {
+    // "点茗", the example of section 7.4.6
+    let kanji = Segment::kanji_sjis(&[0x93, 0x5F, 0xE4, 0xAA]).unwrap();
+    let segments = [Segment::eci(20).unwrap(), kanji];
+
+    let qrcode = encode_segments(&segments, ECL::H).unwrap();
+    assert_eq!(qrcode.version, Some(Version::V01));
+}
+
+#[cfg(feature = "kanji")]
+#[test]
+fn kanji_from_str() {
+    assert_eq!(
+        Segment::kanji("点茗"),
+        Segment::kanji_sjis(&[0x93, 0x5F, 0xE4, 0xAA])
+    );
+    assert_eq!(Segment::kanji("a"), Err(SegmentError::InvalidKanji));
+}
+
+#[test]
+fn too_long() {
+    let segments = [Segment::bytes(&[0; 3000])];
+    assert!(encode_segments(&segments, ECL::L).is_err());
+}
+
+#[test]
+fn too_long_overflow() {
+    use crate::qr::QRCodeError;
+
+    let segments = [Segment::eci(26).unwrap(), Segment::bytes(&[0; 3000])];
+    match encode_segments(&segments, ECL::L) {
+        // The ECI header takes 12 bits, 2 bytes once rounded up
+        Err(QRCodeError::EncodedData(overflow)) => assert_eq!(overflow.len, 3002),
+        _ => panic!("3000 bytes should not fit"),
+    }
+}
+
+#[test]
+fn estimated_bits_pick_the_version() {
+    let segments = [
+        Segment::structured_append(0, 2, 0x5A).unwrap(),
+        Segment::eci(26).unwrap(),
+        Segment::bytes("Ünïcödé ".repeat(20).as_bytes()),
+        Segment::alphanumeric("INVOICE 42").unwrap(),
+        Segment::numeric("0123456789").unwrap(),
+    ];
+
+    // 20 + 12 + (4 + 8 + 1920) + (4 + 9 + 55) + (4 + 10 + 34) bits
+    assert_eq!(estimate_bits(&segments, Version::V01), 2080);
+    // Wider character count indicators from version 10
+    assert_eq!(estimate_bits(&segments, Version::V10), 2080 + 8 + 2 + 2);
+    assert_eq!(estimate_bits(&[], Version::V40), 0);
+
+    for ecl in [ECL::L, ECL::M, ECL::Q, ECL::H] {
+        let fitting = Version::iter()
+            .find(|&version| estimate_bits(&segments, version) <= version.data_bits(ecl));
+        assert_eq!(encode_segments(&segments, ecl).unwrap().version, fitting);
+    }
+}
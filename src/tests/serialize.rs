@@ -0,0 +1,84 @@
+use crate::datamasking::MaskScoring;
+use crate::qr::QRBuilder;
+use crate::{Mask, QRCode, QRMatrix, Version, ECL};
+
+#[test]
+fn serde_qrcode_round_trip() {
+    let qrcode = QRBuilder::new("https://example.com/")
+        .ecl(ECL::H)
+        .mask(Mask::Diamonds)
+        .build()
+        .unwrap();
+
+    let json = serde_json::to_string(&qrcode).unwrap();
+    assert!(json.contains(r#""ecl":"H""#));
+    assert!(json.contains(r#""mask":"Diamonds""#));
+
+    let cached: QRCode = serde_json::from_str(&json).unwrap();
+    assert_eq!(cached.size, qrcode.size);
+    assert!(matches!(cached.version, Some(Version::V03)));
+    assert!(matches!(cached.mask, Some(Mask::Diamonds)));
+    assert_eq!(cached.mode, qrcode.mode);
+    // Values and module types are both kept
+    assert_eq!(QRMatrix::from(&cached), QRMatrix::from(&qrcode));
+}
+
+#[test]
+fn serde_qrcode_packed() {
+    let qrcode = QRBuilder::new("Hello")
+        .version(Version::V01)
+        .build()
+        .unwrap();
+    let value = serde_json::to_value(&qrcode).unwrap();
+
+    // 21 x 21 modules, two per byte
+    assert_eq!(value["modules"].as_array().unwrap().len(), 221);
+}
+
+#[test]
+fn serde_qrcode_invalid() {
+    let qrcode = QRBuilder::new("Hello").build().unwrap();
+    let mut value = serde_json::to_value(&qrcode).unwrap();
+
+    value["size"] = 25.into();
+    let err = serde_json::from_value::<QRCode>(value.clone()).unwrap_err();
+    assert!(err.to_string().contains("25x25"));
+
+    value["size"] = 1000.into();
+    assert!(serde_json::from_value::<QRCode>(value).is_err());
+}
+
+#[test]
+fn serde_matrix_round_trip() {
+    let qrcode = QRBuilder::new("Hello").build().unwrap();
+    let matrix = QRMatrix::from(&qrcode);
+
+    let json = serde_json::to_string(&matrix).unwrap();
+    let cached: QRMatrix = serde_json::from_str(&json).unwrap();
+    assert_eq!(cached, matrix);
+}
+
+#[test]
+fn serde_builder_round_trip() {
+    let mut builder = QRBuilder::new("https://example.com/");
+    builder
+        .ecl(ECL::L)
+        .max_version(Version::V05)
+        .mask_scoring(MaskScoring::DarkRatio);
+
+    let json = serde_json::to_string(&builder).unwrap();
+    let cached: QRBuilder = serde_json::from_str(&json).unwrap();
+
+    let expected = builder.build().unwrap();
+    let rebuilt = cached.build().unwrap();
+    assert_eq!(rebuilt.to_str(), expected.to_str());
+    assert!(matches!(rebuilt.ecl, Some(ECL::L)));
+}
+
+#[test]
+fn serde_builder_custom_scoring() {
+    let mut builder = QRBuilder::new("Hello");
+    builder.mask_scoring(MaskScoring::Custom(|_| 0));
+
+    assert!(serde_json::to_string(&builder).is_err());
+}
@@ -0,0 +1,38 @@
+use crate::simd::{self, Implementation, SimdError};
+use crate::{Module, QRBuilder};
+
+#[test]
+fn implementations_agree() {
+    let qrcode = QRBuilder::new("a".repeat(1000)).build().unwrap();
+    let modules = &qrcode.data[..qrcode.size * qrcode.size];
+    let expected = modules.iter().filter(|m| m.value() == Module::DARK).count();
+
+    for implementation in [
+        Implementation::Scalar,
+        Implementation::Avx2,
+        Implementation::Neon,
+    ] {
+        if !implementation.is_supported() {
+            assert_eq!(
+                simd::force(implementation),
+                Err(SimdError::Unsupported(implementation))
+            );
+            continue;
+        }
+
+        simd::force(implementation).unwrap();
+        assert_eq!(simd::active(), implementation);
+        // Odd lengths go through the scalar tail
+        for len in [0, 1, 31, 33, modules.len()] {
+            let expected = modules[..len]
+                .iter()
+                .filter(|m| m.value() == Module::DARK)
+                .count();
+            assert_eq!(simd::count_dark(&modules[..len]), expected);
+        }
+        assert_eq!(simd::count_dark(modules), expected);
+    }
+
+    simd::reset();
+    assert_eq!(simd::active(), simd::detected());
+}
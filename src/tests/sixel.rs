@@ -0,0 +1,98 @@
+use crate::convert::sixel::{SixelBuilder, SixelError};
+use crate::convert::Color;
+use crate::QRBuilder;
+
+/// Paints the sixel data of `sixel` on a `width` x `width` grid of color
+/// registers, `None` where nothing is drawn
+fn paint(sixel: &str, width: usize) -> Vec<Vec<Option<usize>>> {
+    let mut grid = vec![vec![None; width]; width];
+    let mut chars = sixel.chars().peekable();
+    let (mut x, mut top, mut register) = (0, 0, 0);
+    let number = |chars: &mut core::iter::Peekable<core::str::Chars>| {
+        let mut n = 0;
+        while let Some(digit) = chars.peek().and_then(|c| c.to_digit(10)) {
+            n = n * 10 + digit as usize;
+            chars.next();
+        }
+        n
+    };
+
+    while let Some(c) = chars.next() {
+        match c {
+            '#' => {
+                register = number(&mut chars);
+                // Color definitions are skipped
+                while chars.peek() == Some(&';') {
+                    chars.next();
+                    number(&mut chars);
+                }
+            }
+            '$' => x = 0,
+            '-' => (x, top) = (0, top + 6),
+            '!' | '?'..='~' => {
+                let (count, c) = if c == '!' {
+                    (number(&mut chars), chars.next().unwrap())
+                } else {
+                    (1, c)
+                };
+                let bits = c as u8 - b'?';
+                for _ in 0..count {
+                    for bit in (0..6).filter(|bit| bits & 1 << bit != 0) {
+                        grid[top + bit][x] = Some(register);
+                    }
+                    x += 1;
+                }
+            }
+            _ => panic!("Unexpected {c:?}"),
+        }
+    }
+    grid
+}
+
+#[test]
+fn sixel_pixels() {
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    let n = qrcode.size;
+
+    for (margin, module_size) in [(4, 4), (1, 3), (0, 1)] {
+        let mut builder = SixelBuilder::default();
+        builder.margin(margin).module_size(module_size);
+        let width = builder.width(&qrcode);
+        assert_eq!(width, (n + margin * 2) * module_size);
+
+        let sixel = builder.to_str(&qrcode).unwrap();
+        let header = format!("\x1bP0;1;0q\"1;1;{width};{width}#0;2;100;100;100#1;2;0;0;0");
+        assert!(sixel.starts_with(&header), "{sixel:?}");
+        assert!(sixel.ends_with("-\x1b\\"));
+
+        let grid = paint(&sixel[header.len()..sixel.len() - 2], width);
+        for (y, row) in grid.iter().enumerate() {
+            for (x, &register) in row.iter().enumerate() {
+                let module = |p: usize| (p / module_size).checked_sub(margin).filter(|&i| i < n);
+                let dark = match (module(y), module(x)) {
+                    (Some(y), Some(x)) => qrcode[y][x].value(),
+                    _ => false,
+                };
+                assert_eq!(register, Some(usize::from(dark)), "({x}, {y})");
+            }
+        }
+    }
+}
+
+#[test]
+fn sixel_colors() {
+    let qrcode = QRBuilder::new("sixel").build().unwrap();
+    let sixel = SixelBuilder::default()
+        .module_color("#1A2B6D80")
+        .background_color([255, 128, 0])
+        .to_str(&qrcode)
+        .unwrap();
+    assert!(sixel.contains("#0;2;100;50;0#1;2;10;17;43#"));
+
+    assert_eq!(
+        SixelBuilder::default().module_color("navy").to_str(&qrcode),
+        Err(SixelError::Color(String::from("navy")))
+    );
+    assert_eq!(Color::from("#12345").to_rgba(), None);
+    assert_eq!(Color::from("#0f0").to_rgba(), Some([0, 255, 0, 255]));
+}
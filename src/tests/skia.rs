@@ -0,0 +1,101 @@
+use crate::convert::skia::{SkiaBuilder, SkiaError};
+use crate::convert::{Gradient, Shape};
+use crate::{QRBuilder, Version, ECL};
+
+#[test]
+fn skia_square_modules_are_pixel_exact() {
+    let qrcode = QRBuilder::new("Test")
+        .ecl(ECL::M)
+        .version(Version::V01)
+        .build()
+        .unwrap();
+
+    let pixmap = SkiaBuilder::default()
+        .margin(1)
+        .module_size(4.0)
+        .to_pixmap(&qrcode)
+        .unwrap();
+    assert_eq!((pixmap.width(), pixmap.height()), (92, 92));
+
+    for y in 0..23 {
+        for x in 0..23 {
+            let dark = (1..22).contains(&y) && (1..22).contains(&x) && qrcode[y - 1][x - 1].value();
+            let expected = if dark { 0 } else { 255 };
+            for (dy, dx) in [(0, 0), (3, 3), (1, 2)] {
+                let pixel = pixmap
+                    .pixel((x * 4 + dx) as u32, (y * 4 + dy) as u32)
+                    .unwrap();
+                assert_eq!(
+                    (pixel.red(), pixel.alpha()),
+                    (expected, 255),
+                    "module ({y}, {x})"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn skia_round_modules_are_anti_aliased() {
+    let qrcode = QRBuilder::new("Test")
+        .version(Version::V01)
+        .build()
+        .unwrap();
+    let gray_pixels = |anti_alias: bool| {
+        let pixmap = SkiaBuilder::default()
+            .module_size(16.0)
+            .shape(Shape::Circle)
+            .anti_alias(anti_alias)
+            .to_pixmap(&qrcode)
+            .unwrap();
+        pixmap
+            .pixels()
+            .iter()
+            .filter(|pixel| (1..255).contains(&pixel.red()))
+            .count()
+    };
+
+    assert!(gray_pixels(true) > 1000);
+    assert_eq!(gray_pixels(false), 0);
+}
+
+#[test]
+fn skia_gradient_and_errors() {
+    let qrcode = QRBuilder::new("Test")
+        .version(Version::V01)
+        .build()
+        .unwrap();
+
+    // Red on the left, blue on the right, the top left finder is dark
+    let pixmap = SkiaBuilder::default()
+        .margin(0)
+        .module_size(10.0)
+        .gradient(Gradient::linear("#FF0000", "#0000FF", 0.0))
+        .to_pixmap(&qrcode)
+        .unwrap();
+    let left = pixmap.pixel(2, 2).unwrap();
+    let right = pixmap.pixel(207, 2).unwrap();
+    assert!(left.red() > 200 && left.blue() < 50);
+    assert!(right.blue() > 200 && right.red() < 50);
+
+    assert!(SkiaBuilder::default()
+        .gradient(Gradient::radial("#000000", "#333333"))
+        .to_bytes(&qrcode)
+        .unwrap()
+        .starts_with(b"\x89PNG"));
+
+    assert_eq!(
+        SkiaBuilder::default()
+            .module_color("black")
+            .to_pixmap(&qrcode)
+            .unwrap_err(),
+        SkiaError::Color(String::from("black"))
+    );
+    assert_eq!(
+        SkiaBuilder::default()
+            .module_size(0.0)
+            .to_pixmap(&qrcode)
+            .unwrap_err(),
+        SkiaError::Size
+    );
+}
@@ -1,3 +1,5 @@
+#![allow(clippy::bool_assert_comparison)]
+
 #[test]
 fn structure_codewords_data() {
     const VERSION: crate::version::Version = crate::version::Version::V05;
@@ -0,0 +1,107 @@
+use crate::decode;
+use crate::segment::{encode_segments, Segment, SegmentError};
+use crate::structured_append::{parity, validate, Header, StructuredAppendError};
+use crate::{QRBuilder, QRCode, ECL};
+
+const PAYLOAD: &[u8] = b"https://example.com/a/rather/long/path?split=over&several=codes";
+
+/// Splits `data` in `total` parts, each announcing `parity`
+fn split(data: &[u8], total: u8, parity: u8) -> Vec<QRCode> {
+    data.chunks((data.len() + usize::from(total) - 1) / usize::from(total))
+        .enumerate()
+        .map(|(i, chunk)| {
+            let header = Segment::structured_append(i as u8, total, parity).unwrap();
+            encode_segments(&[header, Segment::bytes(chunk)], ECL::M).unwrap()
+        })
+        .collect()
+}
+
+#[test]
+fn parity_xors_every_byte() {
+    assert_eq!(parity(b""), 0);
+    assert_eq!(parity(&[0x12]), 0x12);
+    assert_eq!(parity(&[0x0F, 0xF0, 0xFF]), 0);
+    assert_eq!(parity(b"AB"), b'A' ^ b'B');
+}
+
+#[test]
+fn segment_checks_sequence_bounds() {
+    assert!(Segment::structured_append(0, 1, 0).is_ok());
+    assert!(Segment::structured_append(15, 16, 0).is_ok());
+    for (index, total) in [(0, 0), (1, 1), (0, 17), (16, 16)] {
+        assert_eq!(
+            Segment::structured_append(index, total, 0).unwrap_err(),
+            SegmentError::InvalidStructuredAppend
+        );
+    }
+}
+
+#[test]
+fn decode_reads_header() {
+    let parts = split(PAYLOAD, 3, parity(PAYLOAD));
+    for (i, part) in parts.iter().enumerate() {
+        let decoded = decode::decode(part).unwrap();
+        assert_eq!(
+            decoded.structured_append,
+            Some(Header {
+                index: i as u8,
+                total: 3,
+                parity: parity(PAYLOAD),
+            })
+        );
+    }
+
+    let single = QRBuilder::new("no header").build().unwrap();
+    assert_eq!(decode::decode(&single).unwrap().structured_append, None);
+}
+
+#[test]
+fn validate_reassembles_in_any_order() {
+    let mut parts = split(PAYLOAD, 4, parity(PAYLOAD));
+    assert_eq!(validate(&parts).unwrap(), PAYLOAD);
+
+    parts.reverse();
+    parts.swap(0, 1);
+    assert_eq!(validate(&parts).unwrap(), PAYLOAD);
+}
+
+#[test]
+fn validate_rejects_inconsistent_parts() {
+    let parts = split(PAYLOAD, 3, parity(PAYLOAD));
+    let other = split(PAYLOAD, 2, parity(PAYLOAD));
+    let single = QRBuilder::new("no header").build().unwrap();
+
+    assert_eq!(
+        validate::<QRCode>(&[]).unwrap_err(),
+        StructuredAppendError::Empty
+    );
+    assert_eq!(
+        validate(&[parts[0].clone(), single]).unwrap_err(),
+        StructuredAppendError::MissingHeader(1)
+    );
+    assert_eq!(
+        validate(&[parts[0].clone(), other[1].clone()]).unwrap_err(),
+        StructuredAppendError::Total(1)
+    );
+    assert_eq!(
+        validate(&[parts[0].clone(), parts[0].clone(), parts[2].clone()]).unwrap_err(),
+        StructuredAppendError::DuplicateIndex(0)
+    );
+    assert_eq!(
+        validate(&[parts[0].clone(), parts[2].clone()]).unwrap_err(),
+        StructuredAppendError::MissingIndex(1)
+    );
+
+    let wrong = split(PAYLOAD, 3, parity(PAYLOAD) ^ 1);
+    assert_eq!(
+        validate(&[parts[0].clone(), wrong[1].clone(), parts[2].clone()]).unwrap_err(),
+        StructuredAppendError::Parity(1)
+    );
+    assert_eq!(
+        validate(&wrong).unwrap_err(),
+        StructuredAppendError::Checksum {
+            expected: parity(PAYLOAD) ^ 1,
+            found: parity(PAYLOAD),
+        }
+    );
+}
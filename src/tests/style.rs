@@ -0,0 +1,101 @@
+use crate::convert::style::{Severity, StyleIssue, StyleValidator};
+use crate::convert::svg::SvgBuilder;
+use crate::convert::{Builder, Gradient};
+use crate::{QRBuilder, QRCode, Version, ECL};
+
+fn qrcode(ecl: ECL) -> QRCode {
+    QRBuilder::new("https://example.com/")
+        .ecl(ecl)
+        .version(Version::V05)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn default_style_is_valid() {
+    let issues = StyleValidator::default().validate(&SvgBuilder::default(), &qrcode(ECL::M));
+    assert_eq!(issues, Vec::new());
+}
+
+#[test]
+fn style_contrast() {
+    let qrcode = qrcode(ECL::M);
+    let validator = StyleValidator::default();
+
+    // A single light gradient stop is enough
+    let issues = validator.validate(
+        SvgBuilder::default().gradient(Gradient::linear("#000000", "#DDDDDD", 0.0)),
+        &qrcode,
+    );
+    assert!(matches!(issues[..], [StyleIssue::Contrast { ratio, min }] if ratio < min));
+    assert_eq!(issues[0].severity(), Severity::Error);
+
+    // Faded modules
+    let issues = validator.validate(SvgBuilder::default().dark_opacity(0.3), &qrcode);
+    assert!(matches!(issues[..], [StyleIssue::Contrast { .. }]));
+
+    let issues = validator.validate(
+        SvgBuilder::default()
+            .module_color("#FFFFFF")
+            .background_color("#000000"),
+        &qrcode,
+    );
+    assert_eq!(issues, vec![StyleIssue::Inverted]);
+    assert_eq!(issues[0].severity(), Severity::Warning);
+
+    let issues = validator.validate(SvgBuilder::default().module_color("red"), &qrcode);
+    assert_eq!(issues, vec![StyleIssue::Color(String::from("red"))]);
+
+    let issues = validator.validate(
+        SvgBuilder::default().background_image(String::from("photo.png")),
+        &qrcode,
+    );
+    assert_eq!(issues, vec![StyleIssue::BackgroundImage]);
+
+    // Dark blue on white is fine
+    let issues = StyleValidator::default()
+        .min_contrast(7.0)
+        .validate(SvgBuilder::default().module_color("#000080"), &qrcode);
+    assert_eq!(issues, Vec::new());
+}
+
+#[test]
+fn style_logo_coverage() {
+    let validator = StyleValidator::default();
+    let mut builder = SvgBuilder::default();
+    builder
+        .image(String::from("logo.png"))
+        .image_size_percent(20.0);
+
+    assert_eq!(validator.validate(&builder, &qrcode(ECL::H)), Vec::new());
+
+    // Logos on `ECL::L` codes are shrunk to what the code can recover
+    assert_eq!(validator.validate(&builder, &qrcode(ECL::L)), Vec::new());
+
+    builder.image_size_percent(40.0);
+    let issues = validator.validate(&builder, &qrcode(ECL::Q));
+    assert!(
+        matches!(issues[..], [StyleIssue::LogoCoverage { damaged, capacity }] if damaged > capacity)
+    );
+    assert_eq!(issues[0].severity(), Severity::Error);
+
+    // Centered on the top left module
+    builder.image_position(4.5, 4.5);
+    let issues = validator.validate(&builder, &qrcode(ECL::H));
+    assert_eq!(issues[0], StyleIssue::LogoInQuietZone);
+    assert_eq!(issues[1], StyleIssue::LogoOverFinder);
+}
+
+#[test]
+fn style_quiet_zone() {
+    let qrcode = qrcode(ECL::M);
+
+    let issues = StyleValidator::default().validate(SvgBuilder::default().margin(0), &qrcode);
+    assert_eq!(issues, vec![StyleIssue::QuietZone { margin: 0, min: 4 }]);
+    assert_eq!(issues[0].severity(), Severity::Error);
+
+    let issues = StyleValidator::default()
+        .min_margin(2)
+        .validate(SvgBuilder::default().margin(2), &qrcode);
+    assert_eq!(issues, Vec::new());
+}
@@ -49,3 +49,642 @@ fn check_svg_is_not_inverted() {
         }
     }
 }
+
+#[cfg(feature = "svg")]
+#[test]
+fn image_size_percent_overrides_image_size() {
+    use crate::convert::svg::SvgBuilder;
+    use crate::convert::Builder;
+    use crate::{QRBuilder, Version};
+
+    let qrcode = QRBuilder::new("https://example.com/")
+        .version(Version::V05)
+        .image_embedding()
+        .build()
+        .unwrap();
+
+    // V05 is 37 modules wide, 40% => 15 modules
+    let svg = SvgBuilder::default()
+        .image(String::from("logo.png"))
+        .image_size(5f64)
+        .image_size_percent(40f64)
+        .to_str(&qrcode);
+
+    assert!(svg.contains(r#"width="15.00" height="15.00" href="logo.png""#));
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn image_shrinks_on_low_ecl() {
+    use crate::convert::svg::SvgBuilder;
+    use crate::convert::Builder;
+    use crate::{QRBuilder, Version, ECL};
+
+    let mut builder = SvgBuilder::default();
+    builder
+        .image(String::from("logo.png"))
+        .image_size_percent(40f64);
+
+    // 40% of V05 is 15 modules, more than these levels can recover
+    for (ecl, size) in [(ECL::L, 2), (ECL::M, 6)] {
+        let qrcode = QRBuilder::new("https://example.com/")
+            .version(Version::V05)
+            .ecl(ecl)
+            .build()
+            .unwrap();
+        let svg = builder.to_str(&qrcode);
+        let expected = format!(r#"width="{size}.00" height="{size}.00" href="logo.png""#);
+        assert!(svg.contains(&expected));
+    }
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn module_shape_overrides_finder_patterns() {
+    use crate::convert::svg::SvgBuilder;
+    use crate::convert::{Builder, Shape};
+    use crate::{ModuleType, QRBuilder, Version, ECL};
+
+    let qrcode = QRBuilder::new("Test")
+        .ecl(ECL::M)
+        .version(Version::V01)
+        .build()
+        .unwrap();
+
+    let svg = SvgBuilder::default()
+        .margin(0)
+        .shape(Shape::Circle)
+        .module_shape(ModuleType::FinderPattern, Shape::Square)
+        .to_str(&qrcode);
+
+    // Top left corner is a dark finder module, drawn only as a square
+    assert!(svg.contains("M0,0h1v1h-1"));
+    assert!(!svg.contains("M1,0.5a"));
+
+    // Data modules keep the generic circle
+    let paths: Vec<&str> = svg.split("<path").collect();
+    assert_eq!(paths.len(), 3);
+    assert!(paths[1].contains("a.5,.5"));
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn module_shape_only_keeps_default_square() {
+    use crate::convert::svg::SvgBuilder;
+    use crate::convert::Shape;
+    use crate::{ModuleType, QRBuilder, Version, ECL};
+
+    let qrcode = QRBuilder::new("Test")
+        .ecl(ECL::M)
+        .version(Version::V01)
+        .build()
+        .unwrap();
+
+    let plain = SvgBuilder::default().to_str(&qrcode);
+    let svg = SvgBuilder::default()
+        .module_shape(ModuleType::Alignment, Shape::Circle)
+        .to_str(&qrcode);
+
+    // V01 has no alignment pattern, the output only gains an empty path
+    assert_eq!(
+        svg,
+        plain.replace("</svg>", r##"<path d="" fill="#000000"/></svg>"##)
+    );
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn module_type_color_splits_paths() {
+    use crate::convert::svg::SvgBuilder;
+    use crate::convert::{Builder, Shape};
+    use crate::{ModuleType, QRBuilder, Version, ECL};
+
+    let qrcode = QRBuilder::new("Test")
+        .ecl(ECL::M)
+        .version(Version::V01)
+        .build()
+        .unwrap();
+
+    let svg = SvgBuilder::default()
+        .margin(0)
+        .module_type_color(ModuleType::FinderPattern, [255, 0, 0])
+        .module_shape_color(ModuleType::Timing, Shape::Circle, [0, 0, 255])
+        .module_type_color(ModuleType::Timing, [0, 255, 0])
+        .to_str(&qrcode);
+
+    let paths: Vec<&str> = svg.split("<path").skip(1).collect();
+    assert_eq!(paths.len(), 3);
+
+    // Generic squares, without the finder patterns
+    assert!(paths[0].ends_with(r##"fill="#000000"/>"##));
+    assert!(!paths[0].contains(r#""M0,0h1v1h-1"#));
+    // Finder patterns, in red
+    assert!(paths[1].starts_with(r#" d="M0,0h1v1h-1"#));
+    assert!(paths[1].ends_with(r##"fill="#ff0000"/>"##));
+    // Timing patterns keep the color of their dedicated shape
+    assert!(paths[2].contains("a.5,.5"));
+    assert!(paths[2].ends_with(r##"fill="#0000ff"/></svg>"##));
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn gradient_fills_dark_modules() {
+    use crate::convert::svg::SvgBuilder;
+    use crate::convert::Gradient;
+    use crate::{QRBuilder, Version, ECL};
+
+    let qrcode = QRBuilder::new("Test")
+        .ecl(ECL::M)
+        .version(Version::V01)
+        .build()
+        .unwrap();
+
+    let svg = SvgBuilder::default()
+        .gradient(Gradient::linear([255, 0, 0], [0, 0, 255], 45f64))
+        .to_str(&qrcode);
+
+    assert!(svg.contains(r#"<linearGradient id="fast_qr_gradient" gradientUnits="userSpaceOnUse" x1="4" y1="14.5" x2="25" y2="14.5" gradientTransform="rotate(45 14.5 14.5)">"#));
+    assert!(svg.contains(r##"<stop offset="0" stop-color="#ff0000"/>"##));
+    assert!(svg.contains(r#"fill="url(#fast_qr_gradient)"/>"#));
+    // Background is untouched
+    assert!(svg.contains(r##"fill="#ffffff"/>"##));
+
+    let svg = SvgBuilder::default()
+        .gradient(Gradient::radial([255, 0, 0], [0, 0, 255]))
+        .to_str(&qrcode);
+    assert!(svg.contains(r#"<radialGradient id="fast_qr_gradient" gradientUnits="userSpaceOnUse" cx="14.5" cy="14.5" r="14.85">"#));
+}
+
+#[test]
+#[cfg(feature = "svg")]
+fn svg_without_margin() {
+    use crate::convert::{svg::SvgBuilder, Builder};
+    use crate::QRBuilder;
+
+    let qrcode = QRBuilder::new("Test").build().unwrap();
+    let svg = SvgBuilder::default().margin(0).to_str(&qrcode);
+
+    let viewbox = format!(r#"viewBox="0 0 {0} {0}""#, qrcode.size);
+    assert!(svg.contains(&viewbox));
+    assert!(svg.contains(r#"<path d="M0,0h1v1h-1"#));
+}
+
+#[test]
+#[cfg(feature = "svg")]
+fn ecc_map_shades_data_modules() {
+    use crate::convert::ecc_map::EccMapBuilder;
+    use crate::QRBuilder;
+
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    let svg = EccMapBuilder::default().margin(0).to_str(&qrcode);
+
+    assert!(svg.starts_with(&format!(r#"<svg viewBox="0 0 {0} {0}""#, qrcode.size)));
+    // Finder patterns stay black and white, data modules are shaded
+    assert!(svg.contains(r##"fill="#000000""##));
+    assert!(svg.contains("hsl("));
+
+    let intact = EccMapBuilder::default().damage(0).to_str(&qrcode);
+    assert!(intact.contains("hsl(120,80%,30%)"));
+    assert!(!intact.contains("hsl(0,"));
+}
+
+#[test]
+#[cfg(feature = "svg")]
+fn geometry_rerender_matches_to_str() {
+    use crate::convert::{svg::SvgBuilder, Builder, Shape};
+    use crate::{ModuleType, QRBuilder};
+
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+
+    let mut builder = SvgBuilder::default();
+    builder
+        .shape(Shape::RoundedSquare)
+        .module_shape(ModuleType::FinderPattern, Shape::Square);
+    let geometry = builder.geometry(&qrcode);
+    assert_eq!(
+        builder.to_str(&qrcode),
+        builder.to_str_with_geometry(&geometry)
+    );
+
+    // Only the theme changes, the geometry is reused
+    builder
+        .module_color("#FF0000")
+        .background_color("#000000")
+        .module_type_color(ModuleType::Alignment, "#00FF00");
+    assert_eq!(
+        builder.to_str(&qrcode),
+        builder.to_str_with_geometry(&geometry)
+    );
+}
+
+#[test]
+#[cfg(feature = "svg")]
+fn test_sheet_sweeps_combinations() {
+    use crate::convert::test_sheet::TestSheetBuilder;
+    use crate::{Version, ECL};
+
+    let svg = TestSheetBuilder::new("HELLO WORLD")
+        .ecls(vec![ECL::L, ECL::H])
+        .versions(vec![Version::V01, Version::V02, Version::V05])
+        .module_sizes(vec![0.25, 0.5])
+        .to_str();
+
+    // V01 is too small at ECL H, 5 rows of 2 codes
+    assert_eq!(svg.matches("<text").count(), 10);
+    assert_eq!(svg.matches("<svg ").count(), 11);
+    assert!(svg.contains("V01 L 0.25mm"));
+    assert!(svg.contains("V05 H 0.5mm"));
+    assert!(!svg.contains("V01 H"));
+}
+
+#[test]
+#[cfg(feature = "svg")]
+fn test_sheet_default_versions() {
+    use crate::convert::test_sheet::TestSheetBuilder;
+
+    let svg = TestSheetBuilder::new("Test")
+        .module_sizes(vec![1.0])
+        .to_str();
+
+    for ecl in ["L", "M", "Q", "H"] {
+        assert!(svg.contains(&format!("V01 {ecl} 1mm")));
+    }
+}
+
+#[test]
+#[cfg(feature = "svg")]
+fn svg_renders_packed_matrix() {
+    use crate::convert::{svg::SvgBuilder, Builder, Shape};
+    use crate::{QRBuilder, QRMatrix};
+
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    let matrix = QRMatrix::from(&qrcode);
+
+    let mut builder = SvgBuilder::default();
+    builder.shape(Shape::Circle);
+    assert_eq!(builder.to_str(&qrcode), builder.to_str(&matrix));
+}
+
+#[test]
+#[cfg(feature = "svg")]
+fn svg_bleed() {
+    use crate::convert::{svg::SvgBuilder, Builder, Shape};
+    use crate::QRBuilder;
+
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+
+    let svg = SvgBuilder::default().to_str(&qrcode);
+    assert!(!svg.contains("stroke"));
+
+    // Grown modules are stroked in their own color
+    let svg = SvgBuilder::default().bleed(0.1).to_str(&qrcode);
+    assert!(svg.contains(r##"" stroke-width="0.2" stroke="#000000" fill="#000000"/>"##));
+    let svg = SvgBuilder::default()
+        .shape(Shape::RoundedSquare)
+        .bleed(0.1)
+        .to_str(&qrcode);
+    assert!(svg.contains(r##"" stroke-width="0.5" stroke-linejoin="round" stroke="#000000""##));
+
+    // Shrunk modules are eaten by the outlines of light modules and of the code
+    let svg = SvgBuilder::default()
+        .margin(2)
+        .background_color("#FFFFFF")
+        .bleed(-0.1)
+        .to_str(&qrcode);
+    let light = svg.split("<path").nth(2).unwrap();
+    assert!(light.starts_with(r#" d="M2,2h25v25h-25zM9,2h1v1h-1z"#));
+    assert!(light.ends_with(r##"" fill="none" stroke-width="0.2" stroke="#FFFFFF"/></svg>"##));
+    let light_modules = (0..25)
+        .flat_map(|y| (0..25).map(move |x| (y, x)))
+        .filter(|&(y, x)| !qrcode[y][x].value())
+        .count();
+    assert_eq!(light.matches('M').count(), light_modules + 1);
+
+    // The fingerprint only changes once bleed is set
+    let mut builder = SvgBuilder::default();
+    let fingerprint = builder.options_fingerprint();
+    assert_eq!(builder.bleed(0.0).options_fingerprint(), fingerprint);
+    assert_ne!(builder.bleed(0.1).options_fingerprint(), fingerprint);
+}
+
+#[test]
+#[cfg(feature = "svg")]
+fn svg_write_to_streams() {
+    use crate::convert::svg::{SvgBuilder, SvgError};
+    use crate::convert::{Builder, Gradient, Shape};
+    use crate::{ModuleType, QRBuilder, Version, ECL};
+
+    /// Accepts `limit` bytes then fails
+    struct Limited {
+        written: Vec<u8>,
+        limit: usize,
+    }
+
+    impl std::io::Write for Limited {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            if self.written.len() + buf.len() > self.limit {
+                return Err(std::io::Error::new(std::io::ErrorKind::WriteZero, "full"));
+            }
+            self.written.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let qrcode = QRBuilder::new("https://example.com/")
+        .ecl(ECL::H)
+        .version(Version::V10)
+        .build()
+        .unwrap();
+    let mut builder = SvgBuilder::default();
+    builder
+        .gradient(Gradient::radial("#FF0000", "#0000FF"))
+        .shape(Shape::RoundedSquare)
+        .module_type_color(ModuleType::FinderPattern, "#00FF00")
+        .bleed(-0.1)
+        .fingerprint(42);
+    let svg = builder.to_str(&qrcode);
+
+    let mut out = Limited {
+        written: Vec::new(),
+        limit: usize::MAX,
+    };
+    builder.write_to(&qrcode, &mut out).unwrap();
+    assert_eq!(out.written, svg.as_bytes());
+
+    let mut out = Limited {
+        written: Vec::new(),
+        limit: 100,
+    };
+    match builder.write_to(&qrcode, &mut out) {
+        Err(SvgError::IoError(err)) => assert_eq!(err.kind(), std::io::ErrorKind::WriteZero),
+        other => panic!("Unexpected {other:?}"),
+    }
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn eye_shape_draws_whole_finder_patterns() {
+    use crate::convert::svg::SvgBuilder;
+    use crate::convert::{Builder, EyeShape, Shape};
+    use crate::{QRBuilder, Version, ECL};
+
+    let qrcode = QRBuilder::new("Test")
+        .ecl(ECL::M)
+        .version(Version::V01)
+        .build()
+        .unwrap();
+
+    let svg = SvgBuilder::default()
+        .margin(0)
+        .shape(Shape::Circle)
+        .eye_shape(EyeShape::Circle, EyeShape::Square)
+        .to_str(&qrcode);
+
+    // Finder modules are not drawn one by one anymore
+    assert!(!svg.contains("M1,0.5a"));
+    assert!(!svg.contains("M15,0.5a"));
+
+    let eyes = svg
+        .split("<path")
+        .find(|path| path.contains("evenodd"))
+        .unwrap();
+    // Top left ring, its hole and its inner square
+    assert!(eyes.contains("M3.5,0H3.5A3.5,3.5 0 0 1 7,3.5"));
+    assert!(eyes.contains("M3.5,1H3.5A2.5,2.5 0 0 1 6,3.5"));
+    assert!(eyes.contains("M2,2H5V5H2V2z"));
+    // Top right and bottom left eyes
+    assert!(eyes.contains("M17.5,0H17.5A3.5,3.5 0 0 1 21,3.5"));
+    assert!(eyes.contains("M2,16H5V19H2V16z"));
+    assert!(eyes.contains(r##"fill="#000000"/>"##));
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn eye_shape_skips_matrices_smaller_than_finders() {
+    use crate::convert::svg::SvgBuilder;
+    use crate::convert::{Builder, EyeShape};
+    use crate::module::Module;
+    use crate::QRMatrix;
+
+    for size in [0, 1, 3, 6] {
+        let mut matrix = QRMatrix::new(size);
+        if size > 0 {
+            matrix.set(0, 0, Module::dark(true));
+        }
+
+        let plain = SvgBuilder::default().margin(0).to_str(&matrix);
+        let eyes = SvgBuilder::default()
+            .margin(0)
+            .eye_shape(EyeShape::Circle, EyeShape::Square)
+            .to_str(&matrix);
+        // Without room for finder patterns, modules are drawn as they are
+        assert_eq!(eyes, plain);
+        assert_eq!(plain.contains("M0,0h1v1h-1"), size > 0);
+    }
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn eye_shape_leaf_points_to_the_center() {
+    use crate::convert::svg::SvgBuilder;
+    use crate::convert::{Builder, EyeShape};
+    use crate::{ModuleType, QRBuilder, Version, ECL};
+
+    let qrcode = QRBuilder::new("Test")
+        .ecl(ECL::M)
+        .version(Version::V01)
+        .build()
+        .unwrap();
+
+    let svg = SvgBuilder::default()
+        .margin(0)
+        .eye_shape(EyeShape::Leaf, EyeShape::Rounded)
+        .module_type_color(ModuleType::FinderPattern, [255, 0, 0])
+        .to_str(&qrcode);
+
+    let eyes = svg
+        .split("<path")
+        .find(|path| path.contains("evenodd"))
+        .unwrap();
+    // Top left eye: sharp top left and bottom right corners
+    assert!(eyes.contains("M0,0H3.5A3.5,3.5 0 0 1 7,3.5V7H3.5A3.5,3.5 0 0 1 0,3.5V0z"));
+    // Top right eye: sharp top right and bottom left corners
+    assert!(eyes.contains("M17.5,0H21V3.5A3.5,3.5 0 0 1 17.5,7H14V3.5A3.5,3.5 0 0 1 17.5,0z"));
+    // Inner squares are rounded
+    assert!(eyes.contains("M2.75,2H4.25A0.75,0.75 0 0 1 5,2.75"));
+    assert!(eyes.contains(r##"fill="#ff0000"/>"##));
+
+    let plain = SvgBuilder::default().options_fingerprint();
+    let leaf = SvgBuilder::default()
+        .eye_shape(EyeShape::Leaf, EyeShape::Rounded)
+        .options_fingerprint();
+    let circle = SvgBuilder::default()
+        .eye_shape(EyeShape::Circle, EyeShape::Rounded)
+        .options_fingerprint();
+    assert_ne!(plain, leaf);
+    assert_ne!(leaf, circle);
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn background_image_under_translucent_modules() {
+    use crate::convert::svg::SvgBuilder;
+    use crate::{QRBuilder, Version, ECL};
+
+    let qrcode = QRBuilder::new("Test")
+        .ecl(ECL::M)
+        .version(Version::V01)
+        .build()
+        .unwrap();
+
+    let plain = SvgBuilder::default().to_str(&qrcode);
+    let mut builder = SvgBuilder::default();
+    builder
+        .background_image(String::from("photo.png"))
+        .dark_opacity(0.8);
+    let svg = builder.to_str(&qrcode);
+
+    // Background color, then the image, then the modules
+    let background = svg.find("<rect").unwrap();
+    let image = svg
+        .find(r#"<image width="29" height="29" preserveAspectRatio="xMidYMid slice" href="photo.png"/>"#)
+        .unwrap();
+    let modules = svg.find(r#"<g opacity="0.8"><path"#).unwrap();
+    assert!(background < image && image < modules);
+    assert!(svg.ends_with("</g></svg>"));
+
+    // Out of range opacities are clamped, opaque modules are not grouped
+    assert_eq!(
+        SvgBuilder::default().dark_opacity(3.0).to_str(&qrcode),
+        plain
+    );
+    assert!(SvgBuilder::default()
+        .dark_opacity(-1.0)
+        .to_str(&qrcode)
+        .contains(r#"<g opacity="0">"#));
+
+    assert_ne!(
+        builder.options_fingerprint(),
+        SvgBuilder::default().options_fingerprint()
+    );
+}
+
+#[test]
+#[cfg(feature = "svg")]
+fn outline_strokes_without_fill() {
+    use crate::convert::{svg::SvgBuilder, Builder, EyeShape, Shape};
+    use crate::QRBuilder;
+
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+
+    let mut builder = SvgBuilder::default();
+    builder.margin(2).background_color("#FFFFFF").outline(0.05);
+    let svg = builder.to_str(&qrcode);
+    let paths: Vec<_> = svg.split("<path").skip(1).collect();
+    assert_eq!(paths.len(), 1);
+    assert!(paths[0].ends_with(r##"" fill="none" stroke-width="0.05" stroke="#000000"/></svg>"##));
+
+    // Bleed is ignored, light modules are not stroked
+    assert_eq!(builder.bleed(-0.2).to_str(&qrcode), svg);
+
+    let svg = SvgBuilder::default()
+        .shape(Shape::RoundedSquare)
+        .eye_shape(EyeShape::Rounded, EyeShape::Circle)
+        .outline(0.1)
+        .to_str(&qrcode);
+    assert!(svg.contains(
+        r##"" fill="none" stroke-width="0.1" stroke-linejoin="round" stroke="#000000"/>"##
+    ));
+    assert!(svg
+        .contains(r##"" fill-rule="evenodd" fill="none" stroke-width="0.1" stroke="#000000"/>"##));
+    assert!(!svg.contains(r##"fill="#000000""##));
+
+    // A width of 0 fills modules again, the fingerprint only changes once set
+    let mut builder = SvgBuilder::default();
+    let fingerprint = builder.options_fingerprint();
+    assert_eq!(
+        builder.outline(0.1).outline(0.0).to_str(&qrcode),
+        SvgBuilder::default().to_str(&qrcode)
+    );
+    assert_eq!(builder.options_fingerprint(), fingerprint);
+    assert_ne!(builder.outline(0.1).options_fingerprint(), fingerprint);
+}
+
+#[test]
+#[cfg(feature = "svg")]
+fn shape_optimization_merges_square_modules() {
+    use crate::convert::{svg::SvgBuilder, Builder, Shape};
+    use crate::{ModuleType, QRBuilder, Version};
+
+    let qrcode = QRBuilder::new("https://example.com/")
+        .version(Version::V30)
+        .build()
+        .unwrap();
+    let n = qrcode.size;
+
+    let mut builder = SvgBuilder::default();
+    builder.margin(2).shape_optimization(true);
+    let svg = builder.to_str(&qrcode);
+    let plain = SvgBuilder::default().margin(2).to_str(&qrcode);
+    assert!(svg.len() * 2 < plain.len());
+
+    // Rectangles cover every dark module exactly once
+    let path = svg.split(r#"<path d=""#).nth(1).unwrap();
+    let path = &path[..path.find('"').unwrap()];
+    let mut covered = vec![0; n * n];
+    for rect in path.split('M').skip(1) {
+        let numbers: Vec<usize> = rect
+            .split([',', 'h', 'v', '-'])
+            .filter(|part| !part.is_empty())
+            .map(|part| part.parse().unwrap())
+            .collect();
+        let (x, y, width, height) = (numbers[0] - 2, numbers[1] - 2, numbers[2], numbers[3]);
+        assert_eq!(numbers[4], width);
+        for row in y..y + height {
+            for col in x..x + width {
+                covered[row * n + col] += 1;
+            }
+        }
+    }
+    for y in 0..n {
+        for x in 0..n {
+            assert_eq!(covered[y * n + x], u8::from(qrcode[y][x].value()));
+        }
+    }
+
+    // The finder pattern ring is one rectangle per side
+    assert!(path.starts_with("M2,2h7v1h-7M"));
+    assert!(path.contains("M2,3h1v6h-1M"));
+    assert!(path.contains("M8,3h1v6h-1M"));
+    assert!(path.contains("M3,8h5v1h-5M"));
+
+    // Module type colors still get their own path
+    let svg = SvgBuilder::default()
+        .shape_optimization(true)
+        .module_type_color(ModuleType::FinderPattern, "#FF0000")
+        .to_str(&qrcode);
+    let finders = svg
+        .split("<path")
+        .find(|path| path.contains(r##"fill="#FF0000""##))
+        .unwrap();
+    assert!(finders.starts_with(r#" d="M4,4h7v1h-7M"#));
+    assert!(finders.contains("M4,5h1v6h-1M"));
+
+    // Other shapes are left as they are
+    let circles = |merge: bool| {
+        SvgBuilder::default()
+            .shape(Shape::Circle)
+            .shape_optimization(merge)
+            .to_str(&qrcode)
+    };
+    assert_eq!(circles(true), circles(false));
+
+    assert_ne!(
+        builder.options_fingerprint(),
+        SvgBuilder::default().margin(2).options_fingerprint()
+    );
+}
@@ -0,0 +1,105 @@
+use crate::qr::QRCodeError;
+use crate::{Mask, Mode, QRBuilder, Template, Version, ECL};
+
+fn built(input: &str, mode: Mode, version: Version, ecl: ECL, mask: Mask) -> crate::QRCode {
+    QRBuilder::new(input)
+        .mode(mode)
+        .version(version)
+        .ecl(ecl)
+        .mask(mask)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn template_matches_builder() {
+    for mask in Mask::ALL {
+        let template = Template::new(Mode::Numeric, Version::V02, ECL::M, mask);
+        for id in [
+            "00000000000000000000",
+            "12345678901234567890",
+            "99999999999999999999",
+            "7",
+        ] {
+            let qrcode = template.build(id).unwrap();
+            let expected = built(id, Mode::Numeric, Version::V02, ECL::M, mask);
+
+            assert_eq!(qrcode.data[..], expected.data[..]);
+            assert_eq!(qrcode.size, expected.size);
+            assert_eq!(qrcode.version, Some(Version::V02));
+            assert!(matches!(qrcode.ecl, Some(ECL::M)));
+            assert_eq!(qrcode.mask.map(|mask| mask as usize), Some(mask as usize));
+            assert_eq!(qrcode.mode, Some(Mode::Numeric));
+        }
+    }
+}
+
+#[test]
+fn template_matches_builder_with_version_information() {
+    let input = "https://example.com/ticket/1234";
+    for version in [Version::V07, Version::V23, Version::V40] {
+        let template = Template::new(Mode::Byte, version, ECL::H, Mask::Diamonds);
+        let qrcode = template.build(input).unwrap();
+        let expected = built(input, Mode::Byte, version, ECL::H, Mask::Diamonds);
+
+        assert_eq!(qrcode.data[..], expected.data[..]);
+        assert_eq!(qrcode.check_invariants(), Ok(()));
+    }
+}
+
+/// "点茗" in Shift JIS
+const SJIS: [u8; 4] = [0x93, 0x5F, 0xE4, 0xAA];
+
+#[test]
+fn template_matches_builder_in_kanji() {
+    let template = Template::new(Mode::Kanji, Version::V03, ECL::Q, Mask::Fields);
+    let qrcode = template.build(SJIS).unwrap();
+    let expected = QRBuilder::new(SJIS)
+        .mode(Mode::Kanji)
+        .version(Version::V03)
+        .ecl(ECL::Q)
+        .mask(Mask::Fields)
+        .build()
+        .unwrap();
+
+    assert_eq!(qrcode.data[..], expected.data[..]);
+}
+
+#[test]
+#[cfg(feature = "kanji")]
+fn template_converts_kanji() {
+    let template = Template::new(Mode::Kanji, Version::V03, ECL::Q, Mask::Fields);
+    let from_utf8 = template.build("点茗").unwrap();
+    let from_sjis = template.build(SJIS).unwrap();
+
+    assert_eq!(from_utf8.data[..], from_sjis.data[..]);
+}
+
+#[test]
+fn template_rejects_oversized_kanji() {
+    let template = Template::new(Mode::Kanji, Version::V40, ECL::Q, Mask::Fields);
+
+    assert!(matches!(
+        template.build("点".repeat(3000)),
+        Err(QRCodeError::EncodedData(_))
+    ));
+}
+
+#[test]
+fn template_errors() {
+    let template = Template::new(Mode::Numeric, Version::V01, ECL::H, Mask::Checkerboard);
+
+    assert!(matches!(template.build(""), Err(QRCodeError::EmptyPayload)));
+    assert!(matches!(
+        template.build("12a4"),
+        Err(QRCodeError::InvalidMode(_))
+    ));
+    assert!(matches!(
+        template.build("123456789012345678901234567890"),
+        Err(QRCodeError::SpecifiedVersion)
+    ));
+    assert!(matches!(
+        template.build("1".repeat(8000)),
+        Err(QRCodeError::EncodedData(_))
+    ));
+}
@@ -0,0 +1,85 @@
+use crate::convert::terminal::TerminalBuilder;
+use crate::{QRBuilder, Version, ECL};
+
+#[test]
+fn terminal_has_margin_rows() {
+    let qrcode = QRBuilder::new("Test")
+        .ecl(ECL::M)
+        .version(Version::V01)
+        .build()
+        .unwrap();
+
+    const MARGIN: usize = 2;
+    let str = TerminalBuilder::default().margin(MARGIN).to_str(&qrcode);
+
+    let lines: Vec<&str> = str.lines().collect();
+    assert_eq!(lines.len(), qrcode.size + MARGIN * 2);
+    assert_eq!(lines[0], format!("\x1b[47m{}\x1b[0m", "  ".repeat(25)));
+}
+
+#[test]
+fn terminal_invert_swaps_colors() {
+    let qrcode = QRBuilder::new("Test")
+        .ecl(ECL::M)
+        .version(Version::V01)
+        .build()
+        .unwrap();
+
+    let normal = TerminalBuilder::default().margin(0).to_str(&qrcode);
+    let inverted = TerminalBuilder::default()
+        .margin(0)
+        .invert(true)
+        .to_str(&qrcode);
+
+    let swapped = normal
+        .replace("\x1b[40m", "\x1b[X]")
+        .replace("\x1b[47m", "\x1b[40m")
+        .replace("\x1b[X]", "\x1b[47m");
+    assert_eq!(swapped, inverted);
+
+    // Top-left module is part of a finder pattern, always dark
+    assert!(normal.starts_with("\x1b[40m"));
+    assert!(inverted.starts_with("\x1b[47m"));
+}
+
+#[test]
+fn to_str_with_margin_sizes() {
+    let qrcode = QRBuilder::new("Test")
+        .ecl(ECL::M)
+        .version(Version::V01)
+        .build()
+        .unwrap();
+
+    assert_eq!(qrcode.to_str(), qrcode.to_str_with_margin(1));
+
+    // 21 rows + 1 blank row on top, two rows per line
+    let str = qrcode.to_str_with_margin(0);
+    assert_eq!(str.lines().count(), 11);
+    assert!(str.lines().all(|line| line.chars().count() == 21));
+
+    let str = qrcode.to_str_with_margin(4);
+    assert_eq!(str.lines().count(), 15);
+    assert!(str.lines().all(|line| line.chars().count() == 29));
+}
+
+#[test]
+fn terminal_renders_packed_matrix() {
+    use crate::matrix::{Matrix, QRMatrix};
+
+    let qrcode = QRBuilder::new("Test")
+        .ecl(ECL::M)
+        .version(Version::V01)
+        .build()
+        .unwrap();
+    let matrix = QRMatrix::from(&qrcode);
+
+    assert_eq!(matrix.as_bytes().len(), (21 * 21 + 1) / 2);
+    for y in 0..qrcode.size {
+        for x in 0..qrcode.size {
+            assert_eq!(matrix.module(y, x), qrcode[y][x]);
+        }
+    }
+
+    let builder = TerminalBuilder::default();
+    assert_eq!(builder.to_str(&qrcode), builder.to_str(&matrix));
+}
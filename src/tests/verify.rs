@@ -0,0 +1,129 @@
+use crate::decode;
+use crate::qr::QRCodeError;
+use crate::segment::{self, Segment};
+use crate::verify::{VerifiedQRCode, VerifyError};
+use crate::{QRBuilder, Version, ECL};
+
+#[test]
+fn verify_every_mode_and_ecl() {
+    let inputs: [&[u8]; 4] = [
+        b"0123456789012",
+        b"HELLO WORLD $%*+-./:",
+        b"https://example.com/?q=fast_qr",
+        &[0, 255, 128, 7, 42],
+    ];
+
+    for input in inputs {
+        for ecl in [ECL::L, ECL::M, ECL::Q, ECL::H] {
+            let qrcode = QRBuilder::new(input).ecl(ecl).build_verified().unwrap();
+            assert_eq!(decode::decode(&qrcode).unwrap().data, input);
+        }
+    }
+}
+
+#[test]
+fn verify_every_version() {
+    // Version information is only present from version 7
+    for number in 1..=40 {
+        let version = Version::from_number(number).unwrap();
+        for ecl in [ECL::L, ECL::M, ECL::Q, ECL::H] {
+            let qrcode = QRBuilder::new("fast_qr")
+                .version(version)
+                .ecl(ecl)
+                .build_verified()
+                .unwrap();
+            assert_eq!(qrcode.size, number * 4 + 17);
+        }
+    }
+}
+
+#[test]
+fn verify_segments() {
+    let segments = [
+        Segment::eci(26).unwrap(),
+        Segment::numeric("2024").unwrap(),
+        Segment::alphanumeric("-ABC").unwrap(),
+        Segment::kanji_sjis(&[0x93, 0x5F, 0xE4, 0xAA]).unwrap(),
+        Segment::bytes(b"!"),
+    ];
+
+    let qrcode = segment::encode_segments(&segments, ECL::M).unwrap();
+    assert_eq!(
+        decode::decode(&qrcode).unwrap().data,
+        b"2024-ABC\x93\x5F\xE4\xAA!"
+    );
+}
+
+#[test]
+fn verify_detects_errors() {
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    assert!(matches!(
+        VerifiedQRCode::new(qrcode.clone(), b"https://example.org/"),
+        Err(VerifyError::Mismatch)
+    ));
+
+    // Bottom right module is always a data module
+    let mut corrupted = qrcode.clone();
+    let last = corrupted.size - 1;
+    corrupted[last][last].toggle();
+    assert!(matches!(
+        VerifiedQRCode::new(corrupted, b"https://example.com/"),
+        Err(VerifyError::Corrected)
+    ));
+
+    // First copy of the format information
+    let mut corrupted = qrcode.clone();
+    corrupted[8][0].toggle();
+    assert!(matches!(
+        VerifiedQRCode::new(corrupted, b"https://example.com/"),
+        Err(VerifyError::Corrected)
+    ));
+
+    assert!(matches!(
+        QRBuilder::new("a".repeat(3000)).build_verified(),
+        Err(VerifyError::Build(QRCodeError::EncodedData(_)))
+    ));
+}
+
+#[test]
+fn verify_shortened_input() {
+    fn shorten(_: &[u8]) -> Vec<u8> {
+        b"https://ex.co/a1".to_vec()
+    }
+
+    let long_url = format!("https://example.com/?q={}", "a".repeat(200));
+    let qrcode = QRBuilder::new(long_url)
+        .max_version(Version::V02)
+        .shortener(shorten)
+        .build_verified()
+        .unwrap();
+    assert_eq!(decode::decode(&qrcode).unwrap().data, b"https://ex.co/a1");
+}
+
+#[test]
+fn verify_calls_shortener_once() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static CALLS: AtomicUsize = AtomicUsize::new(0);
+    // Like a link-shortening service, a new link on every call
+    fn shorten(_: &[u8]) -> Vec<u8> {
+        let call = CALLS.fetch_add(1, Ordering::SeqCst);
+        format!("https://ex.co/{call}").into_bytes()
+    }
+
+    let long_url = format!("https://example.com/?q={}", "a".repeat(200));
+    let qrcode = QRBuilder::new(long_url)
+        .max_version(Version::V02)
+        .shortener(shorten)
+        .build_verified()
+        .unwrap();
+    assert_eq!(decode::decode(&qrcode).unwrap().data, b"https://ex.co/0");
+    assert_eq!(CALLS.load(Ordering::SeqCst), 1);
+
+    // Not called when the input fits
+    QRBuilder::new("https://example.com/")
+        .shortener(shorten)
+        .build_verified()
+        .unwrap();
+    assert_eq!(CALLS.load(Ordering::SeqCst), 1);
+}
@@ -3874,3 +3874,331 @@ fn version_format_h_mask7_version17() {
         assert_eq!(tmp2.map(|x| x.value()), expected2);
     }
 }
+
+#[test]
+fn max_version_cap() {
+    use crate::qr::QRCodeError;
+    use crate::{QRBuilder, Version};
+
+    let input = "a".repeat(100);
+
+    let qrcode = QRBuilder::new(input.clone())
+        .max_version(Version::V10)
+        .build()
+        .unwrap();
+    assert!(qrcode.version.unwrap() <= Version::V10);
+
+    let capped = QRBuilder::new(input.clone())
+        .max_version(Version::V03)
+        .build();
+    assert!(matches!(capped, Err(QRCodeError::MaxVersion)));
+
+    let forced = QRBuilder::new("small")
+        .version(Version::V05)
+        .max_version(Version::V03)
+        .build();
+    assert!(matches!(forced, Err(QRCodeError::MaxVersion)));
+}
+
+#[test]
+fn max_version_shortener() {
+    use crate::qr::QRCodeError;
+    use crate::{QRBuilder, Version};
+
+    fn shorten(input: &[u8]) -> Vec<u8> {
+        input[..10].to_vec()
+    }
+
+    fn identity(input: &[u8]) -> Vec<u8> {
+        input.to_vec()
+    }
+
+    let input = "a".repeat(100);
+
+    let qrcode = QRBuilder::new(input.clone())
+        .max_version(Version::V01)
+        .shortener(shorten)
+        .build()
+        .unwrap();
+    assert_eq!(qrcode.size, 21);
+
+    let still_too_big = QRBuilder::new(input)
+        .max_version(Version::V01)
+        .shortener(identity)
+        .build();
+    assert!(matches!(still_too_big, Err(QRCodeError::MaxVersion)));
+}
+
+#[test]
+fn shortener_over_version_40() {
+    use crate::qr::QRCodeError;
+    use crate::QRBuilder;
+
+    fn shorten(_: &[u8]) -> Vec<u8> {
+        b"https://ex.co/a1".to_vec()
+    }
+
+    // Over the 2953 bytes a version 40 holds at `ECL::L`
+    let input = "a".repeat(3000);
+
+    let qrcode = QRBuilder::new(input.clone())
+        .shortener(shorten)
+        .build()
+        .unwrap();
+    assert_eq!(qrcode.size, 25);
+
+    let too_big = QRBuilder::new(input).build();
+    assert!(matches!(too_big, Err(QRCodeError::EncodedData(_))));
+}
+
+#[test]
+fn shortener_returns_empty_payload() {
+    use crate::qr::QRCodeError;
+    use crate::{QRBuilder, Version};
+
+    fn empty(_: &[u8]) -> Vec<u8> {
+        Vec::new()
+    }
+
+    let input = "a".repeat(100);
+
+    let qrcode = QRBuilder::new(input.clone())
+        .max_version(Version::V01)
+        .shortener(empty)
+        .build();
+    assert!(matches!(qrcode, Err(QRCodeError::EmptyPayload)));
+
+    let qrcode = QRBuilder::new(input)
+        .max_version(Version::V01)
+        .shortener(empty)
+        .allow_empty(true)
+        .build()
+        .unwrap();
+    assert_eq!(qrcode.size, 21);
+}
+
+#[test]
+fn negotiated_constraints() {
+    use crate::qr::{Conflict, Negotiation, QRCodeError};
+    use crate::{Mode, QRBuilder, Version, ECL};
+
+    /// 40 bytes at `ECL::H` in a version 2 at most
+    fn grow(conflict: &Conflict) -> Negotiation {
+        assert_eq!(conflict.mode, Mode::Byte);
+        assert_eq!(conflict.len, 40);
+        assert_eq!(conflict.min_version, Some(Version::V05));
+        // Version 2 holds 32 bytes at `ECL::L`
+        assert!(conflict.lower_ecl.is_none());
+        assert_eq!(conflict.split_parts, 1);
+        Negotiation::Version(conflict.min_version.unwrap())
+    }
+
+    let input = "a".repeat(40);
+    let qrcode = QRBuilder::new(input.clone())
+        .ecl(ECL::H)
+        .max_version(Version::V02)
+        .negotiator(grow)
+        .build()
+        .unwrap();
+    assert_eq!(qrcode.size, 37);
+    assert!(matches!(qrcode.ecl, Some(ECL::H)));
+
+    fn prefer_ecl(conflict: &Conflict) -> Negotiation {
+        conflict
+            .lower_ecl
+            .map_or(Negotiation::Fail, Negotiation::Ecl)
+    }
+    let qrcode = QRBuilder::new(input.clone())
+        .ecl(ECL::H)
+        .version(Version::V03)
+        .negotiator(prefer_ecl)
+        .build()
+        .unwrap();
+    // Version 3 holds 42 bytes at `ECL::M`
+    assert_eq!(qrcode.size, 29);
+    assert!(matches!(qrcode.ecl, Some(ECL::M)));
+
+    // Too large for any version, the negotiator can only give up
+    fn split(conflict: &Conflict) -> Negotiation {
+        assert!(conflict.min_version.is_none());
+        assert_eq!(conflict.split_parts, 3);
+        Negotiation::Fail
+    }
+    let too_big = QRBuilder::new("a".repeat(3000))
+        .ecl(ECL::H)
+        .negotiator(split)
+        .build();
+    assert!(matches!(too_big, Err(QRCodeError::EncodedData(_))));
+
+    // Not called when the data fits
+    fn unreachable(_: &Conflict) -> Negotiation {
+        panic!("The data fits")
+    }
+    assert!(QRBuilder::new(input)
+        .negotiator(unreachable)
+        .build()
+        .is_ok());
+}
+
+#[test]
+fn version_information_table() {
+    // Annex D of the spec, versions 7 to 40
+    #[rustfmt::skip]
+    const SPEC: [u32; 34] = [
+        0b00_0111_1100_1001_0100,
+        0b00_1000_0101_1011_1100,
+        0b00_1001_1010_1001_1001,
+        0b00_1010_0100_1101_0011,
+        0b00_1011_1011_1111_0110,
+        0b00_1100_0111_0110_0010,
+        0b00_1101_1000_0100_0111,
+        0b00_1110_0110_0000_1101,
+        0b00_1111_1001_0010_1000,
+        0b01_0000_1011_0111_1000,
+        0b01_0001_0100_0101_1101,
+        0b01_0010_1010_0001_0111,
+        0b01_0011_0101_0011_0010,
+        0b01_0100_1001_1010_0110,
+        0b01_0101_0110_1000_0011,
+        0b01_0110_1000_1100_1001,
+        0b01_0111_0111_1110_1100,
+        0b01_1000_1110_1100_0100,
+        0b01_1001_0001_1110_0001,
+        0b01_1010_1111_1010_1011,
+        0b01_1011_0000_1000_1110,
+        0b01_1100_1100_0001_1010,
+        0b01_1101_0011_0011_1111,
+        0b01_1110_1101_0111_0101,
+        0b01_1111_0010_0101_0000,
+        0b10_0000_1001_1101_0101,
+        0b10_0001_0110_1111_0000,
+        0b10_0010_1000_1011_1010,
+        0b10_0011_0111_1001_1111,
+        0b10_0100_1011_0000_1011,
+        0b10_0101_0100_0010_1110,
+        0b10_0110_1010_0110_0100,
+        0b10_0111_0101_0100_0001,
+        0b10_1000_1100_0110_1001,
+    ];
+
+    assert!(crate::hardcode::VERSION_INFORMATION[..6]
+        .iter()
+        .all(|&info| info == 0));
+    assert_eq!(crate::hardcode::VERSION_INFORMATION[6..], SPEC);
+}
+
+#[test]
+fn version_information_placement() {
+    use crate::module::ModuleType;
+    use crate::Version;
+
+    for version in [Version::V06, Version::V07, Version::V21, Version::V40] {
+        let qr = crate::default::create_matrix(version);
+        let size = qr.size;
+        let info = crate::hardcode::VERSION_INFORMATION[version as usize];
+
+        let mut count = 0;
+        for y in 0..size {
+            for x in 0..size {
+                if qr[y][x].module_type() == ModuleType::Version {
+                    count += 1;
+                }
+            }
+        }
+        assert_eq!(count, if version >= Version::V07 { 36 } else { 0 });
+        if version < Version::V07 {
+            continue;
+        }
+
+        // Bit `i` goes to (i / 3, size - 11 + i % 3) and its transpose
+        for i in 0..18 {
+            let bit = info & (1 << i) != 0;
+            assert_eq!(qr[i / 3][size - 11 + i % 3].value(), bit);
+            assert_eq!(qr[size - 11 + i % 3][i / 3].value(), bit);
+        }
+    }
+}
+
+#[test]
+fn overflow_capacities() {
+    use crate::qr::QRCodeError;
+    use crate::{QRBuilder, ECL};
+
+    // Table 7 of the spec, version 40
+    let spec = [
+        ("1".repeat(8000), [7089, 5596, 3993, 3057]),
+        ("A".repeat(5000), [4296, 3391, 2420, 1852]),
+        ("a".repeat(3000), [2953, 2331, 1663, 1273]),
+    ];
+
+    for (input, capacities) in spec {
+        let overflow = match QRBuilder::new(input.clone()).ecl(ECL::L).build() {
+            Err(QRCodeError::EncodedData(overflow)) => overflow,
+            _ => panic!("{} characters should not fit", input.len()),
+        };
+
+        assert_eq!(overflow.len, input.len());
+        assert_eq!(overflow.capacity, capacities[0]);
+        assert_eq!(overflow.excess(), input.len() - capacities[0]);
+        assert!(overflow.lower_ecl().is_none());
+        for (ecl, capacity) in [ECL::L, ECL::M, ECL::Q, ECL::H].into_iter().zip(capacities) {
+            assert_eq!(overflow.capacity_at(ecl), capacity);
+        }
+    }
+}
+
+#[test]
+fn overflow_alternatives() {
+    use crate::qr::QRCodeError;
+    use crate::{QRBuilder, ECL};
+
+    let input = "a".repeat(2000);
+    let overflow = match QRBuilder::new(input).ecl(ECL::H).build() {
+        Err(QRCodeError::EncodedData(overflow)) => overflow,
+        _ => panic!("2000 bytes should not fit at ECL::H"),
+    };
+
+    assert!(matches!(overflow.lower_ecl(), Some(ECL::M)));
+    // 1271 bytes per part once the 20-bit header is added
+    assert_eq!(overflow.structured_append_parts(ECL::H), 2);
+    assert_eq!(overflow.structured_append_parts(ECL::L), 1);
+
+    let parts = match QRBuilder::new("a".repeat(2543)).ecl(ECL::H).build() {
+        Err(QRCodeError::EncodedData(overflow)) => overflow.structured_append_parts(ECL::H),
+        _ => panic!("2543 bytes should not fit at ECL::H"),
+    };
+    assert_eq!(parts, 3);
+}
+
+#[test]
+fn version_numbers_and_sizes() {
+    use crate::{InvalidVersion, Version};
+
+    assert_eq!(Version::iter().len(), 40);
+    for (number, version) in (1..=40u8).zip(Version::iter()) {
+        assert_eq!(version.number(), usize::from(number));
+        assert_eq!(version.size(), 17 + 4 * usize::from(number));
+        assert_eq!(Version::try_from(number), Ok(version));
+        assert_eq!(Version::from_n(version.size()), version);
+    }
+
+    assert!(Version::iter()
+        .zip(Version::iter().skip(1))
+        .all(|(a, b)| a < b));
+    assert_eq!(Version::iter().next_back(), Some(Version::V40));
+    assert_eq!(Version::try_from(0), Err(InvalidVersion(0)));
+    assert_eq!(Version::try_from(41), Err(InvalidVersion(41)));
+}
+
+#[test]
+fn alignment_positions() {
+    use crate::Version;
+
+    assert!(Version::V01.alignment_positions().is_empty());
+    assert_eq!(Version::V02.alignment_positions(), [6, 18]);
+    for version in Version::iter().skip(1) {
+        let positions = version.alignment_positions();
+        assert_eq!(positions.first(), Some(&6));
+        assert_eq!(positions.last(), Some(&(version.size() - 7)));
+    }
+}
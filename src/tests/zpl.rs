@@ -0,0 +1,117 @@
+use crate::convert::zpl::ZplBuilder;
+use crate::matrix::QRMatrix;
+use crate::{QRBuilder, ECL};
+
+/// Expands the compressed `^GF` data into rows of `stride` bytes
+fn decompress(data: &str, stride: usize) -> Vec<Vec<u8>> {
+    let mut rows: Vec<String> = Vec::new();
+    let mut row = String::new();
+    let mut count = 0;
+    for c in data.chars() {
+        match c {
+            'G'..='Y' => count += c as usize - 'F' as usize,
+            'g'..='z' => count += (c as usize - 'f' as usize) * 20,
+            ':' => rows.push(rows.last().unwrap().clone()),
+            ',' | '!' => {
+                let fill = if c == ',' { '0' } else { 'F' };
+                while row.len() < stride * 2 {
+                    row.push(fill);
+                }
+            }
+            _ => {
+                assert!(c.is_ascii_hexdigit(), "{c:?}");
+                (0..count.max(1)).for_each(|_| row.push(c));
+                count = 0;
+            }
+        }
+        assert!(row.len() <= stride * 2);
+        if row.len() == stride * 2 {
+            rows.push(core::mem::take(&mut row));
+        }
+    }
+    assert!(row.is_empty());
+
+    rows.iter()
+        .map(|row| {
+            (0..stride)
+                .map(|i| u8::from_str_radix(&row[i * 2..i * 2 + 2], 16).unwrap())
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn zpl_graphic_field_dots() {
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    let n = qrcode.size;
+
+    for scale in [1, 3, 8] {
+        let zpl = ZplBuilder::default().magnification(scale).to_str(&qrcode);
+        let offset = 4 * scale;
+        let header = format!("^XA^FO{offset},{offset}^GFA,");
+        assert!(zpl.starts_with(&header), "{zpl}");
+        assert!(zpl.ends_with("^FS^XZ\n"));
+
+        let fields: Vec<&str> = zpl[header.len()..zpl.len() - 7].splitn(4, ',').collect();
+        let stride = (n * scale + 7) / 8;
+        let total = (stride * n * scale).to_string();
+        assert_eq!(fields[..3], [&total[..], &total, &stride.to_string()]);
+
+        let rows = decompress(fields[3], stride);
+        assert_eq!(rows.len(), n * scale);
+        for (py, row) in rows.iter().enumerate() {
+            for px in 0..stride * 8 {
+                let dot = row[px / 8] & (0x80 >> (px % 8)) != 0;
+                let dark = px < n * scale && qrcode[py / scale][px / scale].value();
+                assert_eq!(dot, dark, "scale {scale}, dot ({px}, {py})");
+            }
+        }
+    }
+}
+
+#[test]
+fn zpl_compression() {
+    let matrix = QRMatrix::parse_ascii(
+        "
+        ###
+        #..
+        ...
+        ",
+    )
+    .unwrap();
+    let zpl = ZplBuilder::default()
+        .magnification(8)
+        .margin(0)
+        .label(false)
+        .to_str(&matrix);
+
+    // 24 dots are 6 hex digits per row: a run of 6 `F`, then 2 `F` and 4 `0`
+    // filled by `,`, then only `,`. Rows repeat 8 times.
+    assert_eq!(
+        zpl,
+        format!(
+            "^FO0,0^GFA,72,72,3,!{}FF,{},{}^FS\n",
+            ":".repeat(7),
+            ":".repeat(7),
+            ":".repeat(7)
+        )
+    );
+}
+
+#[test]
+fn zpl_barcode_command() {
+    let mut builder = ZplBuilder::default();
+    builder.magnification(20).margin(0).origin(10, 20);
+
+    assert_eq!(
+        builder.to_barcode_command(b"HELLO", ECL::H),
+        "^XA^FO10,20^BQN,2,10^FDHA,HELLO^FS^XZ\n"
+    );
+    assert_eq!(
+        builder
+            .darkness(99)
+            .label(false)
+            .to_barcode_command("a^b~c_d é".as_bytes(), ECL::Q),
+        "~SD30^FO10,20^BQN,2,10^FH^FDQA,a_5Eb_7Ec_5Fd _C3_A9^FS\n"
+    );
+}
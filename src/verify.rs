@@ -0,0 +1,118 @@
+//! Reads a [`QRCode`] back from its modules and compares it with the input,
+//! for payment or ticketing codes which must scan before being printed.
+//!
+//! Codes are read with the [`decode`](crate::decode) module, which only
+//! trusts the dark / light state of the modules. A scanner would correct
+//! errors, a freshly generated code must have none: a code only reading back
+//! once corrected fails the verification.
+//!
+//! ```rust
+//! use fast_qr::QRBuilder;
+//!
+//! let qrcode = QRBuilder::new("https://example.com/").build_verified().unwrap();
+//! assert_eq!(qrcode.size, 25);
+//! ```
+
+#![deny(unsafe_code)]
+#![warn(missing_docs)]
+
+use core::fmt::{Display, Formatter};
+use core::ops::Deref;
+
+use crate::decode::{self, DecodeError};
+use crate::matrix::Matrix;
+use crate::qr::QRCodeError;
+use crate::{Module, QRCode};
+
+/// Contains the different errors when a `QRCode` does not read back as expected
+#[derive(Debug)]
+pub enum VerifyError {
+    /// The `QRCode` could not be built in the first place
+    Build(QRCodeError),
+    /// The `QRCode` cannot be read back
+    Decode(DecodeError),
+    /// The `QRCode` only reads back once errors are corrected
+    Corrected,
+    /// The code reads back, but not as the input
+    Mismatch,
+}
+
+impl std::error::Error for VerifyError {}
+
+impl Display for VerifyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            VerifyError::Build(error) => write!(f, "{error}"),
+            VerifyError::Decode(error) => write!(f, "{error}"),
+            VerifyError::Corrected => f.write_str("QRCode has errors"),
+            VerifyError::Mismatch => f.write_str("QRCode does not read back as its input"),
+        }
+    }
+}
+
+impl From<QRCodeError> for VerifyError {
+    fn from(error: QRCodeError) -> Self {
+        VerifyError::Build(error)
+    }
+}
+
+impl From<DecodeError> for VerifyError {
+    fn from(error: DecodeError) -> Self {
+        VerifyError::Decode(error)
+    }
+}
+
+/// Decodes `qr`, refusing codes which only read back once corrected
+pub(crate) fn read_back(qr: &QRCode) -> Result<Vec<u8>, VerifyError> {
+    let decoded = decode::decode(qr)?;
+    if decoded.corrected_codewords > 0 || decoded.information_errors > 0 {
+        return Err(VerifyError::Corrected);
+    }
+
+    Ok(decoded.data)
+}
+
+/// A [`QRCode`] which was read back and matched its input, derefs to the `QRCode`
+#[derive(Debug, Clone)]
+pub struct VerifiedQRCode(pub(crate) QRCode);
+
+impl VerifiedQRCode {
+    /// Decodes `qr` and compares it with `input`
+    ///
+    /// # Errors
+    /// - `VerifyError::Mismatch` if `qr` decodes to something else than `input`
+    /// - `VerifyError::Corrected` if `qr` has errors
+    /// - `VerifyError::Decode` if `qr` cannot be decoded
+    pub fn new(qr: QRCode, input: &[u8]) -> Result<Self, VerifyError> {
+        if read_back(&qr)? != input {
+            return Err(VerifyError::Mismatch);
+        }
+
+        Ok(VerifiedQRCode(qr))
+    }
+
+    /// Returns the verified `QRCode`
+    #[must_use]
+    pub fn into_inner(self) -> QRCode {
+        self.0
+    }
+}
+
+/// Renderers take a `VerifiedQRCode` as they take a `QRCode`
+impl Matrix for VerifiedQRCode {
+    fn size(&self) -> usize {
+        self.0.size
+    }
+
+    fn module(&self, y: usize, x: usize) -> Module {
+        self.0[y][x]
+    }
+}
+
+impl Deref for VerifiedQRCode {
+    type Target = QRCode;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
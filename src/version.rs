@@ -2,10 +2,14 @@
 
 use crate::ecl::ECL;
 use crate::encode::Mode;
+use crate::hardcode;
+use core::fmt::{Display, Formatter};
 
-/// Enum containing all possible `QRCode` versions
-#[derive(Clone, Copy, Debug)]
+/// Enum containing all possible `QRCode` versions, ordered by number
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "wasm-bindgen", wasm_bindgen::prelude::wasm_bindgen)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum Version {
     /// Version n°01
     V01 = 0,
@@ -623,15 +627,110 @@ impl Version {
                     _ => None,
                 },
             },
+            // Rare enough not to need a table, computed from the data bits
+            Mode::Kanji => {
+                let chars = (len + 1) / 2;
+                let mut number = 1;
+                while number <= 40 {
+                    let version = Version::from_n(number * 4 + 17);
+                    let bits =
+                        hardcode::data_bits(version, ecl) - 4 - hardcode::cci_bits(version, mode);
+                    if chars <= bits / 13 {
+                        return Some(version);
+                    }
+                    number += 1;
+                }
+                None
+            }
         }
     }
 
+    /// Returns the `Version` numbered `number`, `None` if not between 1 and 40
+    ///
+    /// ```rust
+    /// use fast_qr::Version;
+    ///
+    /// assert!(matches!(Version::from_number(7), Some(Version::V07)));
+    /// assert!(Version::from_number(41).is_none());
+    /// ```
+    #[must_use]
+    pub const fn from_number(number: usize) -> Option<Self> {
+        if number >= 1 && number <= 40 {
+            Some(Version::from_n(number * 4 + 17))
+        } else {
+            None
+        }
+    }
+
+    /// Every version, from `V01` to `V40`
+    pub const ALL: [Version; 40] = {
+        let mut all = [Version::V01; 40];
+        let mut i = 0;
+        while i < 40 {
+            all[i] = Version::from_n(i * 4 + 21);
+            i += 1;
+        }
+        all
+    };
+
+    /// Iterates over every version, from `V01` to `V40`
+    ///
+    /// ```rust
+    /// use fast_qr::Version;
+    ///
+    /// let sizes: Vec<usize> = Version::iter().map(Version::size).take(3).collect();
+    /// assert_eq!(sizes, [21, 25, 29]);
+    /// assert_eq!(Version::iter().last(), Some(Version::V40));
+    /// ```
+    pub fn iter() -> impl DoubleEndedIterator<Item = Version> + ExactSizeIterator {
+        Version::ALL.into_iter()
+    }
+
+    /// Returns the number of the version, from 1 to 40
+    #[must_use]
+    pub const fn number(self) -> usize {
+        self as usize + 1
+    }
+
+    /// Returns the width of a `QRCode` in modules, quiet zone excluded:
+    /// `17 + 4 * number`
+    #[must_use]
+    pub const fn size(self) -> usize {
+        self as usize * 4 + 21
+    }
+
+    /// Returns the number of data bits a `QRCode` holds in this version at
+    /// `ecl`, error correction excluded
+    ///
+    /// ```rust
+    /// use fast_qr::{Version, ECL};
+    ///
+    /// assert_eq!(Version::V01.data_bits(ECL::L), 152);
+    /// ```
+    #[must_use]
+    pub const fn data_bits(self, ecl: ECL) -> usize {
+        hardcode::data_bits(self, ecl)
+    }
+
+    /// Returns the row and column coordinates of the alignment patterns
+    /// centers, empty for version 1. A pattern is centered on every pair of
+    /// coordinates, except the three overlapping the finder patterns.
+    ///
+    /// ```rust
+    /// use fast_qr::Version;
+    ///
+    /// assert_eq!(Version::V07.alignment_positions(), &[6, 22, 38]);
+    /// ```
+    #[must_use]
+    pub const fn alignment_positions(self) -> &'static [usize] {
+        hardcode::ALIGNMENT_PATTERNS_GRID[self as usize]
+    }
+
     /// Returns `Version` based on the size of the [`crate::QRCode`]
     ///
     /// # Panics
     /// Function panics if `n` is not included in `(21..=177).step_by(4)`
     #[must_use]
-    #[cfg(any(test, feature = "svg", feature = "image", debug_assertions))]
     pub(crate) const fn from_n(n: usize) -> Self {
         use Version::{
             V01, V02, V03, V04, V05, V06, V07, V08, V09, V10, V11, V12, V13, V14, V15, V16, V17,
@@ -712,108 +811,33 @@ impl Version {
 
         MAX_BYTES[self as usize]
     }
+}
 
-    /// Returns the **version information** we need to put for `QRCode` larger or equal to version 7
-    #[must_use]
-    pub(crate) const fn information(self) -> u32 {
-        const VERSION_INFORMATION: [u32; 40] = [
-            0,
-            0,
-            0,
-            0,
-            0,
-            0,
-            0b00_0111_1100_1001_0100,
-            0b00_1000_0101_1011_1100,
-            0b00_1001_1010_1001_1001,
-            0b00_1010_0100_1101_0011,
-            0b00_1011_1011_1111_0110,
-            0b00_1100_0111_0110_0010,
-            0b00_1101_1000_0100_0111,
-            0b00_1110_0110_0000_1101,
-            0b00_1111_1001_0010_1000,
-            0b01_0000_1011_0111_1000,
-            0b01_0001_0100_0101_1101,
-            0b01_0010_1010_0001_0111,
-            0b01_0011_0101_0011_0010,
-            0b01_0100_1001_1010_0110,
-            0b01_0101_0110_1000_0011,
-            0b01_0110_1000_1100_1001,
-            0b01_0111_0111_1110_1100,
-            0b01_1000_1110_1100_0100,
-            0b01_1001_0001_1110_0001,
-            0b01_1010_1111_1010_1011,
-            0b01_1011_0000_1000_1110,
-            0b01_1100_1100_0001_1010,
-            0b01_1101_0011_0011_1111,
-            0b01_1110_1101_0111_0101,
-            0b01_1111_0010_0101_0000,
-            0b10_0000_1001_1101_0101,
-            0b10_0001_0110_1111_0000,
-            0b10_0010_1000_1011_1010,
-            0b10_0011_0111_1001_1111,
-            0b10_0100_1011_0000_1011,
-            0b10_0101_0100_0010_1110,
-            0b10_0110_1010_0110_0100,
-            0b10_0111_0101_0100_0001,
-            0b10_1000_1100_0110_1001,
-        ];
-
-        VERSION_INFORMATION[self as usize]
-    }
+/// Error of [`Version::try_from`], holding a number not between 1 and 40
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidVersion(pub u8);
 
-    /// Returns **alignments** positions
-    #[must_use]
-    pub(crate) const fn alignment_patterns_grid(self) -> &'static [usize] {
-        const ALIGNMENT_PATTERNS_GRID: [&[usize]; 40] = [
-            &[],
-            &[6, 18],
-            &[6, 22],
-            &[6, 26],
-            &[6, 30],
-            &[6, 34],
-            &[6, 22, 38],
-            &[6, 24, 42],
-            &[6, 26, 46],
-            &[6, 28, 50],
-            &[6, 30, 54],
-            &[6, 32, 58],
-            &[6, 34, 62],
-            &[6, 26, 46, 66],
-            &[6, 26, 48, 70],
-            &[6, 26, 50, 74],
-            &[6, 30, 54, 78],
-            &[6, 30, 56, 82],
-            &[6, 30, 58, 86],
-            &[6, 34, 62, 90],
-            &[6, 28, 50, 72, 94],
-            &[6, 26, 50, 74, 98],
-            &[6, 30, 54, 78, 102],
-            &[6, 28, 54, 80, 106],
-            &[6, 32, 58, 84, 110],
-            &[6, 30, 58, 86, 114],
-            &[6, 34, 62, 90, 118],
-            &[6, 26, 50, 74, 98, 122],
-            &[6, 30, 54, 78, 102, 126],
-            &[6, 26, 52, 78, 104, 130],
-            &[6, 30, 56, 82, 108, 134],
-            &[6, 34, 60, 86, 112, 138],
-            &[6, 30, 58, 86, 114, 142],
-            &[6, 34, 62, 90, 118, 146],
-            &[6, 30, 54, 78, 102, 126, 150],
-            &[6, 24, 50, 76, 102, 128, 154],
-            &[6, 28, 54, 80, 106, 132, 158],
-            &[6, 32, 58, 84, 110, 136, 162],
-            &[6, 26, 54, 82, 110, 138, 166],
-            &[6, 30, 58, 86, 114, 142, 170],
-        ];
+#[cfg(feature = "std")]
+impl std::error::Error for InvalidVersion {}
 
-        ALIGNMENT_PATTERNS_GRID[self as usize]
+impl Display for InvalidVersion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Version {} is not between 1 and 40", self.0)
     }
+}
 
-    /// Returns the size of a `QRCode` for said version.
-    #[must_use]
-    pub(crate) const fn size(self) -> usize {
-        self as usize * 4 + 21
+/// Converts a version number, from 1 to 40
+///
+/// ```rust
+/// use fast_qr::{InvalidVersion, Version};
+///
+/// assert_eq!(Version::try_from(7), Ok(Version::V07));
+/// assert_eq!(Version::try_from(41), Err(InvalidVersion(41)));
+/// ```
+impl TryFrom<u8> for Version {
+    type Error = InvalidVersion;
+
+    fn try_from(number: u8) -> Result<Self, Self::Error> {
+        Version::from_number(usize::from(number)).ok_or(InvalidVersion(number))
     }
 }
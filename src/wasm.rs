@@ -20,12 +20,23 @@ pub fn qr(content: &str) -> Vec<u8> {
     qrcode.map(bool_to_u8).unwrap_or(Vec::new())
 }
 
+/// Generate a QR code matrix from a string, as a flat `Uint8Array` of `size * size`
+/// values (row by row, `1` for dark modules). The size is `Math.sqrt(array.length)`.
+///
+/// Returns an empty array if the content is too big to be encoded.
+#[cfg_attr(feature = "wasm-bindgen", wasm_bindgen)]
+#[must_use]
+pub fn qr_matrix(content: &str) -> Vec<u8> {
+    qr(content)
+}
+
 /// Configuration for the SVG output.
 #[cfg(feature = "svg")]
 #[cfg_attr(feature = "wasm-bindgen", wasm_bindgen)]
 #[derive(Debug, Clone)]
 pub struct SvgOptions {
     shape: convert::Shape,
+    eye_shapes: Option<(convert::EyeShape, convert::EyeShape)>,
     module_color: Vec<u8>,
     margin: usize,
 
@@ -66,6 +77,14 @@ impl SvgOptions {
         Self { shape, ..self }
     }
 
+    /// Updates the shapes of the finder patterns, an outer ring around an inner square.
+    pub fn eye_shape(self, outer: convert::EyeShape, inner: convert::EyeShape) -> Self {
+        Self {
+            eye_shapes: Some((outer, inner)),
+            ..self
+        }
+    }
+
     /// Updates the module color of the QRCode. Tales a string in the format `#RRGGBB[AA]`.
     pub fn module_color(self, module_color: String) -> Self {
         let code = Self::color_to_code(module_color);
@@ -169,6 +188,7 @@ impl SvgOptions {
     pub fn new() -> Self {
         Self {
             shape: convert::Shape::Square,
+            eye_shapes: None,
             module_color: vec![0, 0, 0, 255],
             margin: 4,
 
@@ -192,10 +212,18 @@ impl SvgOptions {
 pub fn qr_svg(content: &str, options: SvgOptions) -> String {
     use crate::convert::svg::SvgBuilder;
     use crate::convert::Builder;
-    let qrcode = QRCode::new(content.as_bytes(), options.ecl, options.version, None, None);
+    // An embedded image hides modules, keep enough redundancy for it
+    let ecl = match options.ecl {
+        Some(ECL::L | ECL::M) | None if !options.image.is_empty() => Some(ECL::Q),
+        ecl => ecl,
+    };
+    let qrcode = QRCode::new(content.as_bytes(), ecl, options.version, None, None);
 
     let mut builder = SvgBuilder::default();
     builder.shape(options.shape);
+    if let Some((outer, inner)) = options.eye_shapes {
+        builder.eye_shape(outer, inner);
+    }
     builder.margin(options.margin);
     builder.background_color(options.background_color);
     builder.module_color(options.module_color);
@@ -213,7 +241,7 @@ pub fn qr_svg(content: &str, options: SvgOptions) -> String {
         builder.image_gap(gap);
     }
 
-    if options.image_size.len() == 2 {
+    if options.image_position.len() == 2 {
         let x = options.image_position[0];
         let y = options.image_position[1];
         builder.image_position(x, y);
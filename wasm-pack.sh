@@ -12,7 +12,7 @@ cargo +nightly build ${CARGO_MODE} \
     --target wasm32-unknown-unknown \
     -Z "build-std=std,panic_abort" \
     -Z "build-std-features=${BUILD_STD_FEATURES}" \
-    --features svg,wasm-bindgen && \
+    --features wasm && \
 
 wasm-bindgen \
     --out-dir ${OUTPUT_DIR} \